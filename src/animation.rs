@@ -0,0 +1,142 @@
+//! Animated icons, such as a spinning fan or a beating heart, made of a sequence of custom character glyphs. An
+//! [`Animation`] prints its custom character once at a fixed position, and then only reprograms the glyph of that
+//! custom character on each [`Animation::tick`], which the display shows right away. This takes far fewer I2C
+//! transactions than printing the next frame every time, and leaves the rest of the display untouched.
+//!
+//! ```rust
+//! use i2c_character_display::animation::Animation;
+//!
+//! const HEART: [[u8; 8]; 2] = [
+//!     [0b00000, 0b01010, 0b11111, 0b11111, 0b01110, 0b00100, 0b00000, 0b00000],
+//!     [0b00000, 0b00000, 0b01010, 0b01110, 0b00100, 0b00000, 0b00000, 0b00000],
+//! ];
+//!
+//! let mut heartbeat = Animation::new(15, 0, 6, &HEART);
+//! loop {
+//!     heartbeat.tick(&mut lcd)?;
+//!     delay.delay_ms(400);
+//! }
+//! ```
+
+use embedded_hal::{delay::DelayNs, i2c};
+
+use crate::{driver::DriverTrait, BaseCharacterDisplay, CharacterDisplayError};
+
+/// Cycles through a sequence of glyphs in one custom character location, shown in the cell at a column and row,
+/// one frame per call to [`Animation::tick`].
+#[derive(Debug, Clone, Copy)]
+pub struct Animation<'a> {
+    col: u8,
+    row: u8,
+    slot: u8,
+    frames: &'a [[u8; 8]],
+    frame: usize,
+    /// The glyph last uploaded to the custom character location
+    uploaded: Option<[u8; 8]>,
+    /// Whether the custom character has been printed in the cell
+    placed: bool,
+}
+
+impl<'a> Animation<'a> {
+    /// Create an animation of `frames` in the cell at `col` and `row`, using the custom character location `slot`.
+    pub const fn new(col: u8, row: u8, slot: u8, frames: &'a [[u8; 8]]) -> Self {
+        Self {
+            col,
+            row,
+            slot: slot & 0x7,
+            frames,
+            frame: 0,
+            uploaded: None,
+            placed: false,
+        }
+    }
+
+    /// Start the animation again from its first frame, uploading the glyph and printing the custom character in
+    /// its cell again on the next tick, such as after the display was cleared or initialized.
+    pub fn reset(&mut self) -> &mut Self {
+        self.frame = 0;
+        self.uploaded = None;
+        self.placed = false;
+        self
+    }
+
+    /// Show the current frame, then advance to the next frame for the next call. The glyph is only uploaded when it
+    /// differs from the one shown, and the custom character is only printed in its cell on the first tick. The
+    /// cursor is put back where it was after uploading a glyph, if its position is known.
+    pub fn tick<I2C, DELAY, DEVICE>(
+        &mut self,
+        lcd: &mut BaseCharacterDisplay<I2C, DELAY, DEVICE>,
+    ) -> Result<&mut Self, CharacterDisplayError<I2C>>
+    where
+        I2C: i2c::I2c,
+        DELAY: DelayNs,
+        DEVICE: DriverTrait<I2C, DELAY>,
+    {
+        let Some(glyph) = self.frames.get(self.frame).copied() else {
+            return Ok(self);
+        };
+        if self.uploaded != Some(glyph) {
+            let cursor = lcd.cursor_position();
+            lcd.create_char(self.slot, glyph)?;
+            self.uploaded = Some(glyph);
+            if let (true, Some((col, row))) = (self.placed, cursor) {
+                lcd.set_cursor(col, row)?;
+            }
+        }
+        if !self.placed {
+            lcd.print_at(
+                self.col,
+                self.row,
+                char::from(self.slot).encode_utf8(&mut [0; 4]),
+            )?;
+            self.placed = true;
+        }
+        self.frame = (self.frame + 1) % self.frames.len();
+        Ok(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+    use super::*;
+    use crate::{
+        testing::{aip31068_command_transaction, aip31068_data_transaction},
+        CharacterDisplayAIP31068, LcdDisplayType,
+    };
+    use embedded_hal_mock::eh1::{delay::NoopDelay, i2c::Mock as I2cMock};
+
+    #[test]
+    fn test_animation_tick() {
+        let i2c_address = 0x3e;
+        let frames = [[0x1F; 8], [0x0E; 8], [0x0E; 8]];
+        let expected_i2c_transactions = std::vec![
+            // the first tick uploads the glyph and prints the custom character in its cell
+            aip31068_command_transaction(i2c_address, 0x40 | 6 << 3),
+            aip31068_data_transaction(i2c_address, &frames[0]),
+            aip31068_command_transaction(i2c_address, 0x80 | 0x4F),
+            aip31068_data_transaction(i2c_address, &[6]),
+            // the application moves the cursor
+            aip31068_command_transaction(i2c_address, 0x80 | 0x03),
+            // later ticks only upload the glyph, and put the cursor back
+            aip31068_command_transaction(i2c_address, 0x40 | 6 << 3),
+            aip31068_data_transaction(i2c_address, &frames[1]),
+            aip31068_command_transaction(i2c_address, 0x80 | 0x03),
+            // the unchanged glyph of the third frame is not uploaded again
+            aip31068_command_transaction(i2c_address, 0x40 | 6 << 3),
+            aip31068_data_transaction(i2c_address, &frames[0]),
+            aip31068_command_transaction(i2c_address, 0x80 | 0x03),
+        ];
+
+        let i2c = I2cMock::new(&expected_i2c_transactions);
+        let mut lcd = CharacterDisplayAIP31068::new(i2c, LcdDisplayType::Lcd16x2, NoopDelay::new());
+        let mut animation = Animation::new(15, 1, 6, &frames);
+        assert!(animation.tick(&mut lcd).is_ok());
+        assert!(lcd.set_cursor(3, 0).is_ok());
+        assert!(animation.tick(&mut lcd).is_ok());
+        assert!(animation.tick(&mut lcd).is_ok());
+        assert!(animation.tick(&mut lcd).is_ok());
+        assert!(Animation::new(0, 0, 6, &[]).tick(&mut lcd).is_ok());
+        lcd.i2c().done();
+    }
+}
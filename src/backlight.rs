@@ -0,0 +1,264 @@
+//! Backlight control that is safe to use from interrupt handlers. An interrupt handler must not use the I2C bus
+//! while the main loop may be in the middle of a transaction, so a [`BacklightHandle`] only records the requested
+//! backlight state, and the main loop applies it to the display with [`BacklightHandle::update`]. The handle is
+//! lock-free and only uses atomic loads and stores, so it also works on targets without atomic read-modify-write
+//! instructions, such as Cortex-M0.
+//!
+//! ```rust
+//! use i2c_character_display::backlight::BacklightState;
+//!
+//! static BACKLIGHT: BacklightState = BacklightState::new();
+//!
+//! #[interrupt]
+//! fn ALARM() {
+//!     static mut ON: bool = false;
+//!     *ON = !*ON;
+//!     BACKLIGHT.handle().set_backlight(*ON);
+//! }
+//!
+//! let backlight = BACKLIGHT.handle();
+//! loop {
+//!     backlight.update(&mut lcd)?;
+//!     // ...
+//! }
+//! ```
+//!
+//! Adapters with an on/off backlight, such as the PCF8574 adapters, can dim the backlight with the software PWM of
+//! [`BacklightPwm`], driven by a periodic ticker of the application:
+//!
+//! ```rust
+//! use i2c_character_display::backlight::BacklightPwm;
+//!
+//! let mut pwm = BacklightPwm::new(64);
+//! loop {
+//!     ticker.wait_ms(1);
+//!     pwm.tick(&mut lcd)?;
+//! }
+//! ```
+
+use core::sync::atomic::{AtomicU8, Ordering};
+
+use embedded_hal::{delay::DelayNs, i2c};
+
+use crate::{driver::DriverTrait, BaseCharacterDisplay, CharacterDisplayError};
+
+/// The bit of a request that holds the requested backlight state. The other bits count the requests, so every
+/// request differs from the one before it.
+const REQUEST_ON: u8 = 0x01;
+
+/// The number of ticks in each period of the software PWM of [`BacklightPwm`], which is the number of brightness
+/// levels it can show.
+pub const PWM_STEPS: u8 = 16;
+
+/// The backlight state requested through the handles, to be placed in a `static`. Create handles with
+/// [`BacklightState::handle`].
+#[derive(Debug)]
+pub struct BacklightState {
+    /// The latest request, written by `set_backlight()`
+    requested: AtomicU8,
+    /// The latest request applied to the display, written by `update()` only
+    applied: AtomicU8,
+}
+
+impl Default for BacklightState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl BacklightState {
+    /// Create the backlight state without a pending request.
+    pub const fn new() -> Self {
+        Self {
+            requested: AtomicU8::new(0),
+            applied: AtomicU8::new(0),
+        }
+    }
+
+    /// Returns a handle to request backlight changes and apply them.
+    pub fn handle(&self) -> BacklightHandle<'_> {
+        BacklightHandle { state: self }
+    }
+}
+
+/// A handle to a [`BacklightState`], which can be copied into interrupt handlers and the main loop.
+#[derive(Debug, Clone, Copy)]
+pub struct BacklightHandle<'a> {
+    state: &'a BacklightState,
+}
+
+impl BacklightHandle<'_> {
+    /// Request the backlight to be turned on or off by the next `update()`. This does not use the I2C bus, so it
+    /// can be called from an interrupt handler. When several requests are made before the next `update()`, the last
+    /// one wins.
+    pub fn set_backlight(&self, on: bool) {
+        let count = self.state.requested.load(Ordering::Acquire) & !REQUEST_ON;
+        let request = count.wrapping_add(REQUEST_ON + 1) | if on { REQUEST_ON } else { 0 };
+        self.state.requested.store(request, Ordering::Release);
+    }
+
+    /// Returns whether a backlight request has been made that `update()` has not applied yet.
+    pub fn is_pending(&self) -> bool {
+        self.state.requested.load(Ordering::Acquire) != self.state.applied.load(Ordering::Acquire)
+    }
+
+    /// Apply the latest backlight request to the display, if there is one that has not been applied yet. Call this
+    /// from the main loop, or wherever else the display is used. A request that fails to be applied stays pending.
+    pub fn update<I2C, DELAY, DEVICE>(
+        &self,
+        lcd: &mut BaseCharacterDisplay<I2C, DELAY, DEVICE>,
+    ) -> Result<(), CharacterDisplayError<I2C>>
+    where
+        I2C: i2c::I2c,
+        DELAY: DelayNs,
+        DEVICE: DriverTrait<I2C, DELAY>,
+    {
+        let request = self.state.requested.load(Ordering::Acquire);
+        if request == self.state.applied.load(Ordering::Acquire) {
+            return Ok(());
+        }
+        lcd.backlight(request & REQUEST_ON != 0)?;
+        self.state.applied.store(request, Ordering::Release);
+        Ok(())
+    }
+}
+
+/// Dims an on/off backlight by switching it on for a share of each PWM period matching the brightness. A period is
+/// [`PWM_STEPS`] calls to [`BacklightPwm::tick`], which the application makes at a steady rate, such as from a 1 ms
+/// ticker for a period of 16 ms. The backlight is only written to when it switches, so a brightness of 0 or 255
+/// does not use the I2C bus after the first tick.
+#[derive(Debug, Clone, Copy)]
+pub struct BacklightPwm {
+    brightness: u8,
+    step: u8,
+    /// The backlight state last written to the display, if any
+    lit: Option<bool>,
+}
+
+impl BacklightPwm {
+    /// Create a software PWM with `brightness` from 0 (dark) to 255 (full brightness).
+    pub const fn new(brightness: u8) -> Self {
+        Self {
+            brightness,
+            step: 0,
+            lit: None,
+        }
+    }
+
+    /// Set the brightness, from 0 (dark) to 255 (full brightness), which takes effect from the next tick.
+    pub fn set_brightness(&mut self, brightness: u8) -> &mut Self {
+        self.brightness = brightness;
+        self
+    }
+
+    /// Returns the brightness.
+    pub fn brightness(&self) -> u8 {
+        self.brightness
+    }
+
+    /// Advance the PWM by one tick, switching the backlight on or off when needed. Forget the backlight state with
+    /// [`BacklightPwm::invalidate`] when something else switched the backlight, such as `init()`.
+    pub fn tick<I2C, DELAY, DEVICE>(
+        &mut self,
+        lcd: &mut BaseCharacterDisplay<I2C, DELAY, DEVICE>,
+    ) -> Result<(), CharacterDisplayError<I2C>>
+    where
+        I2C: i2c::I2c,
+        DELAY: DelayNs,
+        DEVICE: DriverTrait<I2C, DELAY>,
+    {
+        let on_steps = ((self.brightness as u16 * PWM_STEPS as u16 + 0x7F) / 0xFF) as u8;
+        let on = self.step < on_steps;
+        self.step = (self.step + 1) % PWM_STEPS;
+        if self.lit != Some(on) {
+            self.lit = None;
+            lcd.backlight(on)?;
+            self.lit = Some(on);
+        }
+        Ok(())
+    }
+
+    /// Forget the backlight state, so the next tick writes it to the display.
+    pub fn invalidate(&mut self) -> &mut Self {
+        self.lit = None;
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+    use super::*;
+    use crate::{testing::pcf8574_gpio, CharacterDisplayPCF8574T, LcdDisplayType};
+    use embedded_hal_mock::eh1::{
+        delay::NoopDelay,
+        i2c::{Mock as I2cMock, Transaction as I2cTransaction},
+    };
+
+    #[test]
+    fn test_backlight_handle() {
+        let i2c_address = 0x27;
+        let backlight =
+            |on: bool| I2cTransaction::write(i2c_address, pcf8574_gpio(false, on)(0, false));
+        let expected_i2c_transactions = std::vec![
+            backlight(false),
+            // a failed request stays pending
+            backlight(true).with_error(embedded_hal::i2c::ErrorKind::Other),
+            backlight(true),
+        ];
+
+        let i2c = I2cMock::new(&expected_i2c_transactions);
+        let mut lcd = CharacterDisplayPCF8574T::new(i2c, LcdDisplayType::Lcd16x2, NoopDelay::new());
+        let state = BacklightState::new();
+        let handle = state.handle();
+        let isr_handle = handle;
+        assert!(!handle.is_pending());
+        assert!(handle.update(&mut lcd).is_ok());
+
+        // the last request before the update wins
+        isr_handle.set_backlight(true);
+        isr_handle.set_backlight(false);
+        assert!(handle.is_pending());
+        assert!(handle.update(&mut lcd).is_ok());
+        assert!(!handle.is_pending());
+        assert!(handle.update(&mut lcd).is_ok());
+
+        isr_handle.set_backlight(true);
+        assert!(handle.update(&mut lcd).is_err());
+        assert!(handle.is_pending());
+        assert!(handle.update(&mut lcd).is_ok());
+        assert!(!handle.is_pending());
+        lcd.i2c().done();
+    }
+    #[test]
+    fn test_backlight_pwm() {
+        let i2c_address = 0x27;
+        let backlight =
+            |on: bool| I2cTransaction::write(i2c_address, pcf8574_gpio(false, on)(0, false));
+        let expected_i2c_transactions = std::vec![
+            // half brightness is on for 8 of the 16 ticks of each period
+            backlight(true),
+            backlight(false),
+            backlight(true),
+            backlight(false),
+            // full brightness stays on
+            backlight(true),
+        ];
+
+        let i2c = I2cMock::new(&expected_i2c_transactions);
+        let mut lcd = CharacterDisplayPCF8574T::new(i2c, LcdDisplayType::Lcd16x2, NoopDelay::new());
+        let mut pwm = BacklightPwm::new(0x80);
+        for _ in 0..=PWM_STEPS {
+            assert!(pwm.tick(&mut lcd).is_ok());
+        }
+        for _ in 1..PWM_STEPS {
+            assert!(pwm.tick(&mut lcd).is_ok());
+        }
+        pwm.set_brightness(0xFF);
+        for _ in 0..PWM_STEPS * 2 {
+            assert!(pwm.tick(&mut lcd).is_ok());
+        }
+        assert_eq!(pwm.brightness(), 0xFF);
+        lcd.i2c().done();
+    }
+}
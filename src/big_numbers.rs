@@ -0,0 +1,297 @@
+//! Big digits that are 3 columns wide and 2 or 4 rows tall, for clocks, thermometers and other readings that
+//! should be legible from across the room. The digits are drawn from four custom characters, which
+//! [`BigNumbers`] programs into CGRAM the first time a number is printed.
+//!
+//! ```rust
+//! use i2c_character_display::big_numbers::{BigDigitHeight, BigNumbers};
+//!
+//! let mut big_numbers = BigNumbers::new(BigDigitHeight::TwoRows);
+//! big_numbers.print_big_number(&mut lcd, 1234, 0)?;
+//! ```
+
+use embedded_hal::{delay::DelayNs, i2c};
+
+use crate::{driver::DriverTrait, BaseCharacterDisplay, CharacterDisplayError};
+
+/// The number of columns of a big digit. Digits are separated by one blank column.
+pub const BIG_DIGIT_WIDTH: u8 = 3;
+
+/// The most digits of a `u32`
+const MAX_DIGITS: usize = 10;
+
+/// The most columns of a rendered number
+const MAX_ROW_CELLS: usize = MAX_DIGITS * (BIG_DIGIT_WIDTH as usize + 1);
+
+/// The custom characters the digits are drawn from, in the order of their CGRAM slots.
+const SEGMENT_GLYPHS: [[u8; 8]; 4] = [
+    // full block
+    [0x1F, 0x1F, 0x1F, 0x1F, 0x1F, 0x1F, 0x1F, 0x1F],
+    // bar along the top
+    [0x1F, 0x1F, 0x1F, 0x00, 0x00, 0x00, 0x00, 0x00],
+    // bar along the bottom
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x1F, 0x1F, 0x1F],
+    // bars along the top and the bottom
+    [0x1F, 0x1F, 0x00, 0x00, 0x00, 0x00, 0x1F, 0x1F],
+];
+
+/// The cells of a big digit: a blank cell or one of the segment glyphs.
+#[derive(Debug, PartialEq, Clone, Copy)]
+enum Cell {
+    Blank,
+    Full,
+    Top,
+    Bottom,
+    Both,
+}
+
+use Cell::{Blank as SP, Both as BB, Bottom as LO, Full as FF, Top as UP};
+
+/// The cells of each 2 row digit, top row first.
+const DIGITS: [[[Cell; 3]; 2]; 10] = [
+    [[FF, UP, FF], [FF, LO, FF]],
+    [[UP, FF, SP], [LO, FF, LO]],
+    [[BB, BB, FF], [FF, LO, LO]],
+    [[UP, BB, FF], [LO, LO, FF]],
+    [[FF, LO, FF], [SP, SP, FF]],
+    [[FF, BB, BB], [LO, LO, FF]],
+    [[FF, BB, BB], [FF, LO, FF]],
+    [[UP, UP, FF], [SP, SP, FF]],
+    [[FF, BB, FF], [FF, LO, FF]],
+    [[FF, BB, FF], [LO, LO, FF]],
+];
+
+impl Cell {
+    /// The two cells that this cell of a 2 row digit is stretched into for a 4 row digit.
+    const fn stretched(self) -> [Cell; 2] {
+        match self {
+            Cell::Blank => [Cell::Blank, Cell::Blank],
+            Cell::Full => [Cell::Full, Cell::Full],
+            Cell::Top => [Cell::Top, Cell::Blank],
+            Cell::Bottom => [Cell::Blank, Cell::Bottom],
+            Cell::Both => [Cell::Top, Cell::Bottom],
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Clone, Copy, Default)]
+/// How many rows tall the big digits are.
+pub enum BigDigitHeight {
+    /// Digits 2 rows tall, for 2 row displays.
+    #[default]
+    TwoRows,
+    /// Digits 4 rows tall, for 4 row displays.
+    FourRows,
+}
+
+impl BigDigitHeight {
+    /// The number of rows of a digit
+    pub const fn rows(self) -> u8 {
+        match self {
+            BigDigitHeight::TwoRows => 2,
+            BigDigitHeight::FourRows => 4,
+        }
+    }
+}
+
+/// Prints numbers in big digits. The digits use four consecutive CGRAM slots, 0 to 3 unless set with
+/// [`BigNumbers::with_slots`], which are programmed the first time a number is printed.
+#[derive(Debug, Clone, Copy)]
+pub struct BigNumbers {
+    height: BigDigitHeight,
+    row: u8,
+    first_slot: u8,
+    loaded: bool,
+}
+
+impl BigNumbers {
+    /// Create big digits of the given height, drawn from the top row of the display.
+    pub const fn new(height: BigDigitHeight) -> Self {
+        Self {
+            height,
+            row: 0,
+            first_slot: 0,
+            loaded: false,
+        }
+    }
+
+    /// Draw the digits from `row` down rather than from the top row.
+    pub const fn with_row(mut self, row: u8) -> Self {
+        self.row = row;
+        self
+    }
+
+    /// Use the four CGRAM slots starting at `first_slot`, which is at most 4, for the segment glyphs.
+    pub const fn with_slots(mut self, first_slot: u8) -> Self {
+        self.first_slot = if first_slot > 4 { 4 } else { first_slot };
+        self
+    }
+
+    /// The number of columns a number takes up when printed in big digits.
+    pub fn width(value: u32) -> u8 {
+        let digits = value.checked_ilog10().unwrap_or(0) as u8 + 1;
+        digits * (BIG_DIGIT_WIDTH + 1) - 1
+    }
+
+    /// Program the segment glyphs into CGRAM. This is done by the first `print_big_number()`, so only needs to be
+    /// called again when the slots were overwritten, such as by `create_char()`, or the display lost power.
+    pub fn load<I2C, DELAY, DEVICE>(
+        &mut self,
+        lcd: &mut BaseCharacterDisplay<I2C, DELAY, DEVICE>,
+    ) -> Result<(), CharacterDisplayError<I2C>>
+    where
+        I2C: i2c::I2c,
+        DELAY: DelayNs,
+        DEVICE: DriverTrait<I2C, DELAY>,
+    {
+        self.loaded = false;
+        for (slot, glyph) in (self.first_slot..).zip(SEGMENT_GLYPHS) {
+            lcd.create_char(slot, glyph)?;
+        }
+        self.loaded = true;
+        Ok(())
+    }
+
+    /// Print `value` in big digits with its left edge at column `col`. Fails with
+    /// `CharacterDisplayError::ColumnOutOfRange` or `CharacterDisplayError::RowOutOfRange` before anything is drawn
+    /// when the number does not fit on the display.
+    pub fn print_big_number<I2C, DELAY, DEVICE>(
+        &mut self,
+        lcd: &mut BaseCharacterDisplay<I2C, DELAY, DEVICE>,
+        value: u32,
+        col: u8,
+    ) -> Result<(), CharacterDisplayError<I2C>>
+    where
+        I2C: i2c::I2c,
+        DELAY: DelayNs,
+        DEVICE: DriverTrait<I2C, DELAY>,
+    {
+        if col as usize + Self::width(value) as usize > lcd.cols() as usize {
+            return Err(CharacterDisplayError::ColumnOutOfRange { col });
+        }
+        if self.row as usize + self.height.rows() as usize > lcd.rows() as usize {
+            return Err(CharacterDisplayError::RowOutOfRange { row: self.row });
+        }
+        if !self.loaded {
+            self.load(lcd)?;
+        }
+
+        let mut digits = [0u8; MAX_DIGITS];
+        let mut count = 0;
+        let mut remaining = value;
+        loop {
+            digits[count] = (remaining % 10) as u8;
+            count += 1;
+            remaining /= 10;
+            if remaining == 0 {
+                break;
+            }
+        }
+        digits[..count].reverse();
+
+        for row in 0..self.height.rows() {
+            let mut line = [b' '; MAX_ROW_CELLS];
+            for (index, digit) in digits[..count].iter().enumerate() {
+                let start = index * (BIG_DIGIT_WIDTH as usize + 1);
+                for (offset, cell) in self.digit_row(*digit, row).into_iter().enumerate() {
+                    line[start + offset] = self.cell_code(cell);
+                }
+            }
+            // the cells are spaces and CGRAM slot codes, all of which are valid single byte UTF-8
+            let len = Self::width(value) as usize;
+            let text = core::str::from_utf8(&line[..len]).map_err(|_| core::fmt::Error)?;
+            lcd.print_at(col, self.row + row, text)?;
+        }
+        Ok(())
+    }
+
+    /// The cells of one row of a digit.
+    fn digit_row(&self, digit: u8, row: u8) -> [Cell; 3] {
+        let cells = DIGITS[digit as usize];
+        match self.height {
+            BigDigitHeight::TwoRows => cells[row as usize],
+            BigDigitHeight::FourRows => {
+                cells[row as usize / 2].map(|cell| cell.stretched()[row as usize % 2])
+            }
+        }
+    }
+
+    /// The character code printed for a cell, which is a CGRAM slot code for the segment glyphs.
+    fn cell_code(&self, cell: Cell) -> u8 {
+        match cell {
+            Cell::Blank => b' ',
+            Cell::Full => self.first_slot,
+            Cell::Top => self.first_slot + 1,
+            Cell::Bottom => self.first_slot + 2,
+            Cell::Both => self.first_slot + 3,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+    use super::*;
+    use crate::{
+        testing::{aip31068_command_transaction, aip31068_data_transaction},
+        CharacterDisplayAIP31068, LcdDisplayType,
+    };
+    use embedded_hal_mock::eh1::{delay::NoopDelay, i2c::Mock as I2cMock};
+
+    #[test]
+    fn test_width() {
+        assert_eq!(BigNumbers::width(0), 3);
+        assert_eq!(BigNumbers::width(9), 3);
+        assert_eq!(BigNumbers::width(10), 7);
+        assert_eq!(BigNumbers::width(u32::MAX), 39);
+    }
+
+    #[test]
+    fn test_four_row_digits() {
+        let big_numbers = BigNumbers::new(BigDigitHeight::FourRows).with_slots(4);
+        let rows: std::vec::Vec<[u8; 3]> = (0..4)
+            .map(|row| {
+                big_numbers
+                    .digit_row(2, row)
+                    .map(|cell| big_numbers.cell_code(cell))
+            })
+            .collect();
+        assert_eq!(rows, [[5, 5, 4], [6, 6, 4], [4, b' ', b' '], [4, 6, 6]]);
+    }
+
+    #[test]
+    fn test_print_big_number() {
+        let i2c_address = 0x3e;
+        let mut expected_i2c_transactions = std::vec::Vec::new();
+        // the segment glyphs are programmed the first time only
+        for (slot, glyph) in SEGMENT_GLYPHS.iter().enumerate() {
+            expected_i2c_transactions.push(aip31068_command_transaction(
+                i2c_address,
+                0x40 | (slot as u8) << 3,
+            ));
+            expected_i2c_transactions.push(aip31068_data_transaction(i2c_address, glyph));
+        }
+        for _ in 0..2 {
+            expected_i2c_transactions.extend([
+                aip31068_command_transaction(i2c_address, 0x80 | 0x05),
+                aip31068_data_transaction(i2c_address, &[1, 0, b' ', b' ', 0, 1, 0]),
+                aip31068_command_transaction(i2c_address, 0x80 | 0x45),
+                aip31068_data_transaction(i2c_address, &[2, 0, 2, b' ', 0, 2, 0]),
+            ]);
+        }
+
+        let i2c = I2cMock::new(&expected_i2c_transactions);
+        let mut lcd = CharacterDisplayAIP31068::new(i2c, LcdDisplayType::Lcd16x2, NoopDelay::new());
+        let mut big_numbers = BigNumbers::new(BigDigitHeight::TwoRows);
+        assert!(big_numbers.print_big_number(&mut lcd, 10, 5).is_ok());
+        assert!(big_numbers.print_big_number(&mut lcd, 10, 5).is_ok());
+        assert!(matches!(
+            big_numbers.print_big_number(&mut lcd, 10, 10),
+            Err(CharacterDisplayError::ColumnOutOfRange { .. })
+        ));
+        assert!(matches!(
+            BigNumbers::new(BigDigitHeight::FourRows).print_big_number(&mut lcd, 1, 0),
+            Err(CharacterDisplayError::RowOutOfRange { .. })
+        ));
+        lcd.i2c().done();
+    }
+}
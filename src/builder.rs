@@ -0,0 +1,216 @@
+use core::marker::PhantomData;
+use embedded_hal::{delay::DelayNs, i2c};
+
+use crate::{
+    charset::CharacterRom, driver, Addressing, BaseCharacterDisplay, BusyPollStrategy,
+    CharacterDisplayError, CommandCompletion, DropBehavior, EntryMode, FontSize, InitProfile,
+    LcdDisplayType, Timings,
+};
+
+/// Builds a character display with the options that are fixed when the display is initialized. Create one with
+/// `builder()` on any of the character display types, set the options, and finish with `build()`, or with `init()`
+/// to also initialize the display.
+///
+/// ```rust
+/// let mut lcd = CharacterDisplayPCF8574T::builder(i2c, LcdDisplayType::Lcd16x2, delay)
+///     .with_address(0x3F)
+///     .with_backlight(false)
+///     .with_entry_mode(EntryMode { left_to_right: true, autoscroll: true })
+///     .init()?;
+/// ```
+pub struct CharacterDisplayBuilder<I2C, DELAY, DEVICE>
+where
+    I2C: i2c::I2c,
+    DELAY: DelayNs,
+    DEVICE: driver::DriverTrait<I2C, DELAY>,
+{
+    i2c: I2C,
+    delay: DELAY,
+    lcd_type: LcdDisplayType,
+    address: u8,
+    font_size: FontSize,
+    init_profile: InitProfile,
+    backlight: bool,
+    entry_mode: EntryMode,
+    addressing: Addressing,
+    character_rom: Option<CharacterRom>,
+    busy_poll_strategy: BusyPollStrategy,
+    command_completion: CommandCompletion,
+    ready_signal: Option<fn() -> bool>,
+    i2c_retries: (u8, u32),
+    timings: Option<Timings>,
+    nibble_delay_us: u32,
+    i2c_frequency_hz: u32,
+    drop_behavior: DropBehavior,
+    _marker: PhantomData<DEVICE>,
+}
+
+impl<I2C, DELAY, DEVICE> CharacterDisplayBuilder<I2C, DELAY, DEVICE>
+where
+    I2C: i2c::I2c,
+    DELAY: DelayNs,
+    DEVICE: driver::DriverTrait<I2C, DELAY>,
+{
+    /// Create a builder using the default I2C address for the adapter and the default options.
+    pub fn new(i2c: I2C, lcd_type: LcdDisplayType, delay: DELAY) -> Self {
+        Self {
+            i2c,
+            delay,
+            lcd_type,
+            address: DEVICE::default_i2c_address(),
+            font_size: FontSize::default(),
+            init_profile: InitProfile::default(),
+            backlight: true,
+            entry_mode: EntryMode::default(),
+            addressing: Addressing::default(),
+            character_rom: None,
+            busy_poll_strategy: BusyPollStrategy::default(),
+            command_completion: CommandCompletion::default(),
+            ready_signal: None,
+            i2c_retries: (0, 0),
+            timings: None,
+            nibble_delay_us: 0,
+            i2c_frequency_hz: 0,
+            drop_behavior: DropBehavior::default(),
+            _marker: PhantomData,
+        }
+    }
+
+    /// Set the I2C address of the adapter.
+    pub fn with_address(mut self, address: u8) -> Self {
+        self.address = address;
+        self
+    }
+
+    /// Set the type of display.
+    pub fn with_display_type(mut self, lcd_type: LcdDisplayType) -> Self {
+        self.lcd_type = lcd_type;
+        self
+    }
+
+    /// Set the character font size. See `BaseCharacterDisplay::with_font_size()`.
+    pub fn with_font_size(mut self, font_size: FontSize) -> Self {
+        self.font_size = font_size;
+        self
+    }
+
+    /// Set the power supply specific setup done by initialization. See `BaseCharacterDisplay::with_init_profile()`.
+    pub fn with_init_profile(mut self, profile: InitProfile) -> Self {
+        self.init_profile = profile;
+        self
+    }
+
+    /// Set whether the backlight is on after initialization. See `BaseCharacterDisplay::with_backlight()`.
+    pub fn with_backlight(mut self, on: bool) -> Self {
+        self.backlight = on;
+        self
+    }
+
+    /// Set the entry mode set up by initialization. See `BaseCharacterDisplay::with_entry_mode()`.
+    pub fn with_entry_mode(mut self, entry_mode: EntryMode) -> Self {
+        self.entry_mode = entry_mode;
+        self
+    }
+
+    /// Set where printed text continues past the end of a row. See `BaseCharacterDisplay::with_addressing()`.
+    pub fn with_addressing(mut self, addressing: Addressing) -> Self {
+        self.addressing = addressing;
+        self
+    }
+
+    /// Set the character ROM that printed text is encoded for. See `BaseCharacterDisplay::with_character_rom()`.
+    pub fn with_character_rom(mut self, character_rom: CharacterRom) -> Self {
+        self.character_rom = Some(character_rom);
+        self
+    }
+
+    /// Set how the busy flag is polled. See `BaseCharacterDisplay::with_busy_poll_strategy()`.
+    pub fn with_busy_poll_strategy(mut self, strategy: BusyPollStrategy) -> Self {
+        self.busy_poll_strategy = strategy;
+        self
+    }
+
+    /// Set how the completion of slow commands is waited for. See
+    /// `BaseCharacterDisplay::with_command_completion()`.
+    pub fn with_command_completion(mut self, completion: CommandCompletion) -> Self {
+        self.command_completion = completion;
+        self
+    }
+
+    /// Set the signal that indicates whether the controller is ready. See `BaseCharacterDisplay::with_ready_signal()`.
+    pub fn with_ready_signal(mut self, is_ready: fn() -> bool) -> Self {
+        self.ready_signal = Some(is_ready);
+        self
+    }
+
+    /// Set how often a failed I2C write is tried again. See `BaseCharacterDisplay::with_i2c_retries()`.
+    pub fn with_i2c_retries(mut self, retries: u8, interval_us: u32) -> Self {
+        self.i2c_retries = (retries, interval_us);
+        self
+    }
+
+    /// Set the waits the driver makes for the controller. See `BaseCharacterDisplay::with_timings()`.
+    pub fn with_timings(mut self, timings: Timings) -> Self {
+        self.timings = Some(timings);
+        self
+    }
+
+    /// Set the wait after each nibble written to the controller. See `BaseCharacterDisplay::with_nibble_delay()`.
+    pub fn with_nibble_delay(mut self, delay_us: u32) -> Self {
+        self.nibble_delay_us = delay_us;
+        self
+    }
+
+    /// Declare the speed of the I2C bus. See `BaseCharacterDisplay::with_i2c_frequency()`.
+    pub fn with_i2c_frequency(mut self, frequency_hz: u32) -> Self {
+        self.i2c_frequency_hz = frequency_hz;
+        self
+    }
+
+    /// Set what happens to the display when the display object is dropped. See
+    /// `BaseCharacterDisplay::with_drop_behavior()`.
+    pub fn with_drop_behavior(mut self, drop_behavior: DropBehavior) -> Self {
+        self.drop_behavior = drop_behavior;
+        self
+    }
+
+    /// Build the display object. The options are applied when the display is initialized with `init()`.
+    pub fn build(self) -> BaseCharacterDisplay<I2C, DELAY, DEVICE> {
+        let mut lcd = BaseCharacterDisplay::new_with_address(
+            self.i2c,
+            self.address,
+            self.lcd_type,
+            self.delay,
+        )
+        .with_font_size(self.font_size)
+        .with_init_profile(self.init_profile)
+        .with_backlight(self.backlight)
+        .with_entry_mode(self.entry_mode)
+        .with_addressing(self.addressing)
+        .with_busy_poll_strategy(self.busy_poll_strategy)
+        .with_command_completion(self.command_completion)
+        .with_i2c_retries(self.i2c_retries.0, self.i2c_retries.1)
+        .with_nibble_delay(self.nibble_delay_us)
+        .with_i2c_frequency(self.i2c_frequency_hz)
+        .with_drop_behavior(self.drop_behavior);
+        if let Some(is_ready) = self.ready_signal {
+            lcd = lcd.with_ready_signal(is_ready);
+        }
+        if let Some(character_rom) = self.character_rom {
+            lcd = lcd.with_character_rom(character_rom);
+        }
+        if let Some(timings) = self.timings {
+            lcd = lcd.with_timings(timings);
+        }
+        lcd
+    }
+
+    /// Build the display object and initialize the display.
+    pub fn init(
+        self,
+    ) -> Result<BaseCharacterDisplay<I2C, DELAY, DEVICE>, CharacterDisplayError<I2C>> {
+        let mut lcd = self.build();
+        lcd.init()?;
+        Ok(lcd)
+    }
+}
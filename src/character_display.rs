@@ -0,0 +1,125 @@
+use embedded_hal::{delay::DelayNs, i2c};
+
+use crate::{driver::DriverTrait, BaseCharacterDisplay, CharacterDisplayErrorKind};
+
+/// The basic operations of a character display, as an object safe trait that does not depend on the types of the
+/// I2C bus, the delay or the driver. Displays of different types can be kept together as `&mut dyn
+/// CharacterDisplay`, or passed to code that works with any display. Errors are returned as their
+/// `CharacterDisplayErrorKind`. The methods of the display types themselves remain available for everything else.
+pub trait CharacterDisplay {
+    /// Returns the number of rows of the display.
+    fn rows(&self) -> u8;
+
+    /// Returns the number of columns of the display.
+    fn cols(&self) -> u8;
+
+    /// Clear the display.
+    fn clear(&mut self) -> Result<(), CharacterDisplayErrorKind>;
+
+    /// Move the cursor to the top left corner of the display.
+    fn home(&mut self) -> Result<(), CharacterDisplayErrorKind>;
+
+    /// Set the cursor position at the specified column and row. Columns and rows are zero-indexed.
+    fn set_cursor(&mut self, col: u8, row: u8) -> Result<(), CharacterDisplayErrorKind>;
+
+    /// Print a string at the cursor position.
+    fn print(&mut self, text: &str) -> Result<(), CharacterDisplayErrorKind>;
+
+    /// Turn the backlight on or off.
+    fn backlight(&mut self, on: bool) -> Result<(), CharacterDisplayErrorKind>;
+}
+
+impl<I2C, DELAY, DEVICE> CharacterDisplay for BaseCharacterDisplay<I2C, DELAY, DEVICE>
+where
+    I2C: i2c::I2c,
+    DELAY: DelayNs,
+    DEVICE: DriverTrait<I2C, DELAY>,
+{
+    fn rows(&self) -> u8 {
+        BaseCharacterDisplay::rows(self)
+    }
+
+    fn cols(&self) -> u8 {
+        BaseCharacterDisplay::cols(self)
+    }
+
+    fn clear(&mut self) -> Result<(), CharacterDisplayErrorKind> {
+        BaseCharacterDisplay::clear(self)?;
+        Ok(())
+    }
+
+    fn home(&mut self) -> Result<(), CharacterDisplayErrorKind> {
+        BaseCharacterDisplay::home(self)?;
+        Ok(())
+    }
+
+    fn set_cursor(&mut self, col: u8, row: u8) -> Result<(), CharacterDisplayErrorKind> {
+        BaseCharacterDisplay::set_cursor(self, col, row)?;
+        Ok(())
+    }
+
+    fn print(&mut self, text: &str) -> Result<(), CharacterDisplayErrorKind> {
+        BaseCharacterDisplay::print(self, text)?;
+        Ok(())
+    }
+
+    fn backlight(&mut self, on: bool) -> Result<(), CharacterDisplayErrorKind> {
+        BaseCharacterDisplay::backlight(self, on)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+    use super::*;
+    use crate::{
+        testing::{
+            aip31068_command_transaction, aip31068_data_transaction, hd44780_byte_transactions,
+            hd44780_packed_data_transactions, pcf8574_gpio,
+        },
+        CharacterDisplayAIP31068, CharacterDisplayPCF8574T, LcdDisplayType,
+    };
+    use embedded_hal_mock::eh1::{delay::NoopDelay, i2c::Mock as I2cMock};
+
+    #[test]
+    fn test_character_display_trait() {
+        let mut expected_i2c_transactions = std::vec![
+            aip31068_command_transaction(0x3e, 0x80 | 0x41),
+            aip31068_data_transaction(0x3e, b"ok"),
+        ];
+        expected_i2c_transactions.extend(hd44780_byte_transactions(
+            0x27,
+            0x80 | 0x41,
+            pcf8574_gpio(false, false),
+        ));
+        expected_i2c_transactions.extend(hd44780_packed_data_transactions(
+            0x27,
+            b"ok",
+            pcf8574_gpio(true, false),
+        ));
+
+        let mut i2c = I2cMock::new(&expected_i2c_transactions);
+        let mut aip31068 =
+            CharacterDisplayAIP31068::new(i2c.clone(), LcdDisplayType::Lcd16x2, NoopDelay::new());
+        let mut pcf8574t =
+            CharacterDisplayPCF8574T::new(i2c.clone(), LcdDisplayType::Lcd20x4, NoopDelay::new());
+        let displays: [&mut dyn CharacterDisplay; 2] = [&mut aip31068, &mut pcf8574t];
+        for display in displays {
+            assert!(display.set_cursor(1, 1).is_ok());
+            assert!(display.print("ok").is_ok());
+            assert_eq!(
+                display.set_cursor(0, display.rows()),
+                Err(CharacterDisplayErrorKind::RowOutOfRange {
+                    row: display.rows()
+                })
+            );
+        }
+        // the AIP31068 has no backlight control
+        assert_eq!(
+            CharacterDisplay::backlight(&mut aip31068, true),
+            Err(CharacterDisplayErrorKind::UnsupportedOperation)
+        );
+        i2c.done();
+    }
+}
@@ -0,0 +1,190 @@
+//! Sharing the 8 custom character locations of the display between widgets. A [`CharmapManager`] hands out the
+//! character code of a glyph, creating the glyph in CGRAM when it is not there yet. A glyph that is already in CGRAM
+//! is reused rather than created again, and when all locations are taken the glyph used least recently is replaced.
+//!
+//! ```rust
+//! use i2c_character_display::charmap::CharmapManager;
+//!
+//! let mut charmap_manager = CharmapManager::new();
+//! let heart = charmap_manager.char_code(&mut lcd, HEART)?;
+//! let bell = charmap_manager.char_code(&mut lcd, BELL)?;
+//! write!(lcd, "{}{}", heart as char, bell as char)?;
+//! ```
+
+use embedded_hal::{delay::DelayNs, i2c};
+
+use crate::{driver::DriverTrait, BaseCharacterDisplay, CharacterDisplayError};
+
+/// The number of custom character locations in CGRAM
+const CGRAM_LOCATIONS: usize = 8;
+
+/// A glyph held in a custom character location.
+#[derive(Debug, Clone, Copy)]
+struct Slot {
+    charmap: [u8; 8],
+    last_used: u32,
+}
+
+/// Tracks the glyphs in the custom character locations of a display. All glyphs a manager hands out codes for
+/// should be created through it, as it does not know about glyphs created with `create_char()`. Restrict it to some
+/// of the locations with [`CharmapManager::with_locations`] to leave the others for `create_char()` or widgets that
+/// use fixed locations, such as `progress_bar()`.
+///
+/// Replacing the glyph in a location changes every character on the display that shows it, so glyphs that are on
+/// the display should be fetched with [`CharmapManager::char_code`] each time they are drawn, to mark them as used.
+#[derive(Debug, Clone, Copy)]
+pub struct CharmapManager {
+    slots: [Option<Slot>; CGRAM_LOCATIONS],
+    first_location: u8,
+    end_location: u8,
+    clock: u32,
+}
+
+impl Default for CharmapManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CharmapManager {
+    /// Create a manager of all 8 custom character locations, all of which are free.
+    pub const fn new() -> Self {
+        Self {
+            slots: [None; CGRAM_LOCATIONS],
+            first_location: 0,
+            end_location: CGRAM_LOCATIONS as u8,
+            clock: 0,
+        }
+    }
+
+    /// Only manage the custom character locations in `locations`, which is limited to locations 0 to 7.
+    pub fn with_locations(mut self, locations: core::ops::Range<u8>) -> Self {
+        self.first_location = locations.start.min(CGRAM_LOCATIONS as u8);
+        self.end_location = locations.end.min(CGRAM_LOCATIONS as u8);
+        self
+    }
+
+    /// Returns the character code to print for `charmap`. A glyph that is already in a location is reused. Otherwise
+    /// it is created in a free location, or in the location of the glyph used least recently when none are free.
+    /// Fails with `CharacterDisplayError::UnsupportedOperation` when the manager has no locations.
+    pub fn char_code<I2C, DELAY, DEVICE>(
+        &mut self,
+        lcd: &mut BaseCharacterDisplay<I2C, DELAY, DEVICE>,
+        charmap: [u8; 8],
+    ) -> Result<u8, CharacterDisplayError<I2C>>
+    where
+        I2C: i2c::I2c,
+        DELAY: DelayNs,
+        DEVICE: DriverTrait<I2C, DELAY>,
+    {
+        self.clock = self.clock.wrapping_add(1);
+        if let Some(location) = self.location_of(&charmap) {
+            if let Some(slot) = self.slots[location as usize].as_mut() {
+                slot.last_used = self.clock;
+            }
+            return Ok(location);
+        }
+
+        let location = self
+            .free_location()
+            .or_else(|| self.least_recently_used())
+            .ok_or(CharacterDisplayError::UnsupportedOperation)?;
+        // forget the old glyph first, so a failed upload does not leave it looking like it is still there
+        self.slots[location as usize] = None;
+        lcd.create_char(location, charmap)?;
+        self.slots[location as usize] = Some(Slot {
+            charmap,
+            last_used: self.clock,
+        });
+        Ok(location)
+    }
+
+    /// Returns the character code of `charmap` if it is in a location, without marking it as used.
+    pub fn location_of(&self, charmap: &[u8; 8]) -> Option<u8> {
+        self.locations().find(|location| {
+            self.slots[*location as usize].is_some_and(|slot| slot.charmap == *charmap)
+        })
+    }
+
+    /// Free the location holding `charmap`, so it can be reused for another glyph before any others are replaced.
+    pub fn release(&mut self, charmap: &[u8; 8]) -> &mut Self {
+        if let Some(location) = self.location_of(charmap) {
+            self.slots[location as usize] = None;
+        }
+        self
+    }
+
+    /// Forget all glyphs, such as after the custom characters were overwritten by `init()` or a widget.
+    pub fn clear(&mut self) -> &mut Self {
+        self.slots = [None; CGRAM_LOCATIONS];
+        self
+    }
+
+    /// The managed locations.
+    fn locations(&self) -> core::ops::Range<u8> {
+        self.first_location..self.end_location
+    }
+
+    /// The first managed location without a glyph.
+    fn free_location(&self) -> Option<u8> {
+        self.locations()
+            .find(|location| self.slots[*location as usize].is_none())
+    }
+
+    /// The managed location of the glyph that was used least recently.
+    fn least_recently_used(&self) -> Option<u8> {
+        self.locations().min_by_key(|location| {
+            self.slots[*location as usize]
+                .map(|slot| self.clock.wrapping_sub(slot.last_used))
+                .map(core::cmp::Reverse)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+    use super::*;
+    use crate::{
+        testing::{aip31068_command_transaction, aip31068_data_transaction},
+        CharacterDisplayAIP31068, LcdDisplayType,
+    };
+    use embedded_hal_mock::eh1::{delay::NoopDelay, i2c::Mock as I2cMock};
+
+    #[test]
+    fn test_char_code() {
+        let i2c_address = 0x3e;
+        let create = |location: u8, charmap: [u8; 8]| {
+            [
+                aip31068_command_transaction(i2c_address, 0x40 | location << 3),
+                aip31068_data_transaction(i2c_address, &charmap),
+            ]
+        };
+        let mut expected_i2c_transactions = std::vec::Vec::new();
+        expected_i2c_transactions.extend(create(2, [0x01; 8]));
+        expected_i2c_transactions.extend(create(3, [0x02; 8]));
+        // both locations are taken, so the glyph used least recently is replaced
+        expected_i2c_transactions.extend(create(3, [0x03; 8]));
+        // a released location is reused
+        expected_i2c_transactions.extend(create(2, [0x04; 8]));
+
+        let i2c = I2cMock::new(&expected_i2c_transactions);
+        let mut lcd = CharacterDisplayAIP31068::new(i2c, LcdDisplayType::Lcd16x2, NoopDelay::new());
+        let mut charmap_manager = CharmapManager::new().with_locations(2..4);
+        assert_eq!(charmap_manager.char_code(&mut lcd, [0x01; 8]).ok(), Some(2));
+        assert_eq!(charmap_manager.char_code(&mut lcd, [0x02; 8]).ok(), Some(3));
+        // identical glyphs share a location
+        assert_eq!(charmap_manager.char_code(&mut lcd, [0x01; 8]).ok(), Some(2));
+        assert_eq!(charmap_manager.char_code(&mut lcd, [0x03; 8]).ok(), Some(3));
+        assert_eq!(charmap_manager.location_of(&[0x02; 8]), None);
+        charmap_manager.release(&[0x01; 8]);
+        assert_eq!(charmap_manager.char_code(&mut lcd, [0x04; 8]).ok(), Some(2));
+        assert!(matches!(
+            CharmapManager::new()
+                .with_locations(8..10)
+                .char_code(&mut lcd, [0x01; 8]),
+            Err(CharacterDisplayError::UnsupportedOperation)
+        ));
+        lcd.i2c().done();
+    }
+}
@@ -0,0 +1,243 @@
+//! Encoding text for the character ROM of the display controller. HD44780 compatible controllers come with one of
+//! two character ROMs: A00, which has Japanese katakana and some Greek and math symbols, and A02, which has the
+//! accented letters of western European languages. Select the ROM of a display with `with_character_rom()`, and
+//! `print()` encodes each character to the code of its glyph in that ROM, or to `?` when the ROM has no glyph for it.
+//! Without a character ROM, text is written to the display as it is.
+//!
+//! ```rust
+//! use i2c_character_display::charset::CharacterRom;
+//!
+//! let mut lcd = CharacterDisplayPCF8574T::new(i2c, LcdDisplayType::Lcd16x2, delay)
+//!     .with_character_rom(CharacterRom::A00);
+//! lcd.init()?;
+//! lcd.print("21.5°C ｵﾝﾄﾞ")?;
+//! ```
+
+/// The code printed for a character the ROM has no glyph for.
+const UNMAPPED: u8 = b'?';
+
+/// The characters of the A00 ROM outside of ASCII and half-width katakana, with their codes. Together with ASCII
+/// and half-width katakana, this is the mapping table used by `CharacterRom::A00` in both directions.
+pub const A00_SYMBOLS: [(char, u8); 25] = [
+    ('¥', 0x5C),
+    ('→', 0x7E),
+    ('←', 0x7F),
+    ('°', 0xDF),
+    ('α', 0xE0),
+    ('ä', 0xE1),
+    ('β', 0xE2),
+    ('ε', 0xE3),
+    ('μ', 0xE4),
+    ('µ', 0xE4),
+    ('σ', 0xE5),
+    ('ρ', 0xE6),
+    ('√', 0xE8),
+    ('¢', 0xEC),
+    ('ñ', 0xEE),
+    ('ö', 0xEF),
+    ('θ', 0xF2),
+    ('∞', 0xF3),
+    ('Ω', 0xF4),
+    ('ü', 0xF5),
+    ('Σ', 0xF6),
+    ('π', 0xF7),
+    ('千', 0xFA),
+    ('万', 0xFB),
+    ('円', 0xFC),
+];
+
+/// The built-in glyphs that `with_glyph_fallback()` shows with custom characters, for characters that are missing
+/// from the character ROM.
+const FALLBACK_GLYPHS: [(char, [u8; 8]); 24] = [
+    ('°', [0x0C, 0x12, 0x12, 0x0C, 0x00, 0x00, 0x00, 0x00]),
+    ('µ', [0x00, 0x00, 0x11, 0x11, 0x11, 0x13, 0x1D, 0x10]),
+    ('μ', [0x00, 0x00, 0x11, 0x11, 0x11, 0x13, 0x1D, 0x10]),
+    ('→', [0x00, 0x04, 0x02, 0x1F, 0x02, 0x04, 0x00, 0x00]),
+    ('←', [0x00, 0x04, 0x08, 0x1F, 0x08, 0x04, 0x00, 0x00]),
+    ('↑', [0x04, 0x0E, 0x15, 0x04, 0x04, 0x04, 0x00, 0x00]),
+    ('↓', [0x04, 0x04, 0x04, 0x15, 0x0E, 0x04, 0x00, 0x00]),
+    ('±', [0x04, 0x04, 0x1F, 0x04, 0x04, 0x00, 0x1F, 0x00]),
+    ('Ω', [0x00, 0x0E, 0x11, 0x11, 0x11, 0x0A, 0x1B, 0x00]),
+    ('€', [0x07, 0x08, 0x1E, 0x08, 0x1E, 0x08, 0x07, 0x00]),
+    ('£', [0x06, 0x09, 0x08, 0x1C, 0x08, 0x08, 0x1F, 0x00]),
+    ('à', [0x08, 0x04, 0x0E, 0x01, 0x0F, 0x11, 0x0F, 0x00]),
+    ('â', [0x04, 0x0A, 0x0E, 0x01, 0x0F, 0x11, 0x0F, 0x00]),
+    ('ä', [0x0A, 0x00, 0x0E, 0x01, 0x0F, 0x11, 0x0F, 0x00]),
+    ('ç', [0x00, 0x00, 0x0E, 0x10, 0x10, 0x11, 0x0E, 0x04]),
+    ('è', [0x08, 0x04, 0x0E, 0x11, 0x1F, 0x10, 0x0E, 0x00]),
+    ('é', [0x02, 0x04, 0x0E, 0x11, 0x1F, 0x10, 0x0E, 0x00]),
+    ('ê', [0x04, 0x0A, 0x0E, 0x11, 0x1F, 0x10, 0x0E, 0x00]),
+    ('ñ', [0x0D, 0x12, 0x00, 0x16, 0x19, 0x11, 0x11, 0x00]),
+    ('ö', [0x0A, 0x00, 0x0E, 0x11, 0x11, 0x11, 0x0E, 0x00]),
+    ('ü', [0x0A, 0x00, 0x11, 0x11, 0x11, 0x13, 0x0D, 0x00]),
+    ('Ä', [0x0A, 0x00, 0x0E, 0x11, 0x1F, 0x11, 0x11, 0x00]),
+    ('Ö', [0x0A, 0x00, 0x0E, 0x11, 0x11, 0x11, 0x0E, 0x00]),
+    ('Ü', [0x0A, 0x00, 0x11, 0x11, 0x11, 0x11, 0x0E, 0x00]),
+];
+
+/// Returns the built-in glyph that `with_glyph_fallback()` shows for `c` when the character ROM has no glyph for it,
+/// if there is one. The glyphs cover common symbols, such as `°`, `µ` and arrows, and common accented letters.
+pub fn fallback_glyph(c: char) -> Option<[u8; 8]> {
+    FALLBACK_GLYPHS
+        .iter()
+        .find(|(glyph_char, _)| *glyph_char == c)
+        .map(|(_, charmap)| *charmap)
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+/// The character ROM of an HD44780 compatible controller, which is usually given by the part number suffix, such
+/// as HD44780UA00.
+pub enum CharacterRom {
+    /// The Japanese standard font, with ASCII except for `\` and `~`, half-width katakana, and the Greek letters and
+    /// symbols of [`A00_SYMBOLS`].
+    A00,
+    /// The European standard font, with ASCII and the ISO 8859-1 (Latin-1) characters from `U+00A0` to `U+00FF`.
+    A02,
+    /// The ROM of a panel variant that differs from one of the standard ROMs in a few glyphs, such as a clone
+    /// controller. The characters of `extra` and their codes take precedence over those of `rom`.
+    ///
+    /// ```rust
+    /// static CLONE_ROM: CharacterRom = CharacterRom::Extended {
+    ///     rom: &CharacterRom::A00,
+    ///     extra: &[('\\', 0x5C), ('~', 0x7E)],
+    /// };
+    /// ```
+    Extended {
+        /// The standard ROM the variant is based on
+        rom: &'static CharacterRom,
+        /// The characters that the variant adds or places at different codes
+        extra: &'static [(char, u8)],
+    },
+}
+
+impl CharacterRom {
+    /// Returns the ROM code of the glyph for `c`, or the code of `?` when the ROM has no glyph for it. Control
+    /// characters, which include the codes of the custom characters, are passed through unchanged.
+    pub fn encode(self, c: char) -> u8 {
+        if c < ' ' {
+            return c as u8;
+        }
+        self.code_of(c).unwrap_or(UNMAPPED)
+    }
+
+    /// Returns the character of the glyph at `code` in the ROM, or `None` when the ROM has no glyph there. This is
+    /// the reverse of `encode()`, for turning text read back from the display into a string. Control codes, which
+    /// include the codes of the custom characters, are passed through unchanged. When the ROM has the same glyph for
+    /// more than one character, such as `°` and `ﾟ` in the A00 ROM, the first one in the mapping table is returned.
+    pub fn decode(self, code: u8) -> Option<char> {
+        if code < b' ' {
+            return Some(char::from(code));
+        }
+        match self {
+            CharacterRom::A00 => A00_SYMBOLS
+                .iter()
+                .find(|(_, symbol_code)| *symbol_code == code)
+                .map(|(symbol, _)| *symbol)
+                .or(match code {
+                    b'\\' | b'~' => None,
+                    b' '..=b'}' => Some(char::from(code)),
+                    0xA1..=0xDF => char::from_u32(code as u32 - 0xA1 + 0xFF61),
+                    _ => None,
+                }),
+            CharacterRom::A02 => match code {
+                b' '..=b'~' | 0xA0..=0xFF => Some(char::from(code)),
+                _ => None,
+            },
+            CharacterRom::Extended { rom, extra } => extra
+                .iter()
+                .find(|(_, extra_code)| *extra_code == code)
+                .map(|(c, _)| *c)
+                .or_else(|| rom.decode(code)),
+        }
+    }
+
+    /// Returns whether the ROM has a glyph for `c`. Control characters are treated as being in the ROM.
+    pub fn contains(self, c: char) -> bool {
+        c < ' ' || self.code_of(c).is_some()
+    }
+
+    /// The ROM code of the glyph for `c`, which is not a control character.
+    fn code_of(self, c: char) -> Option<u8> {
+        match self {
+            CharacterRom::A00 => match c {
+                // the A00 ROM has the yen sign and arrows in place of these
+                '\\' | '~' => None,
+                ' '..='}' => Some(c as u8),
+                // half-width katakana are in the order of JIS X 0201
+                '\u{FF61}'..='\u{FF9F}' => Some((c as u32 - 0xFF61 + 0xA1) as u8),
+                _ => A00_SYMBOLS
+                    .iter()
+                    .find(|(symbol, _)| *symbol == c)
+                    .map(|(_, code)| *code),
+            },
+            CharacterRom::A02 => match c {
+                ' '..='~' | '\u{A0}'..='\u{FF}' => Some(c as u8),
+                _ => None,
+            },
+            CharacterRom::Extended { rom, extra } => extra
+                .iter()
+                .find(|(extra_char, _)| *extra_char == c)
+                .map(|(_, code)| *code)
+                .or_else(|| rom.code_of(c)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encode() {
+        assert_eq!(CharacterRom::A00.encode('A'), b'A');
+        assert_eq!(CharacterRom::A00.encode('\u{01}'), 0x01);
+        assert_eq!(CharacterRom::A00.encode('\\'), b'?');
+        assert_eq!(CharacterRom::A00.encode('¥'), 0x5C);
+        assert_eq!(CharacterRom::A00.encode('ｱ'), 0xB1);
+        assert_eq!(CharacterRom::A00.encode('ﾟ'), 0xDF);
+        assert_eq!(CharacterRom::A00.encode('°'), 0xDF);
+        assert_eq!(CharacterRom::A00.encode('Ω'), 0xF4);
+        assert_eq!(CharacterRom::A00.encode('é'), b'?');
+        assert_eq!(CharacterRom::A02.encode('~'), b'~');
+        assert_eq!(CharacterRom::A02.encode('é'), 0xE9);
+        assert_eq!(CharacterRom::A02.encode('°'), 0xB0);
+        assert_eq!(CharacterRom::A02.encode('ｱ'), b'?');
+        assert!(CharacterRom::A00.contains('?'));
+        assert!(!CharacterRom::A00.contains('é'));
+        assert!(CharacterRom::A02.contains('é'));
+        assert_eq!(fallback_glyph('é').map(|g| g[0]), Some(0x02));
+        assert_eq!(fallback_glyph('ｱ'), None);
+    }
+
+    #[test]
+    fn test_decode() {
+        // every character that can be encoded decodes back to itself, except for shared glyphs
+        for c in (' '..='~')
+            .chain('\u{A0}'..='\u{FF}')
+            .chain('\u{FF61}'..='\u{FF9E}')
+        {
+            for rom in [CharacterRom::A00, CharacterRom::A02] {
+                if rom.contains(c) && c != 'µ' {
+                    assert_eq!(rom.decode(rom.encode(c)), Some(c), "{rom:?} {c}");
+                }
+            }
+        }
+        assert_eq!(CharacterRom::A00.decode(0xDF), Some('°'));
+        assert_eq!(CharacterRom::A00.decode(0x5C), Some('¥'));
+        assert_eq!(CharacterRom::A00.decode(0xB1), Some('ｱ'));
+        assert_eq!(CharacterRom::A00.decode(0x80), None);
+        assert_eq!(CharacterRom::A02.decode(0xE9), Some('é'));
+        assert_eq!(CharacterRom::A02.decode(0x02), Some('\u{02}'));
+
+        let extended = CharacterRom::Extended {
+            rom: &CharacterRom::A00,
+            extra: &[('\\', 0x5C), ('é', 0x80)],
+        };
+        assert_eq!(extended.encode('\\'), 0x5C);
+        assert_eq!(extended.encode('é'), 0x80);
+        assert_eq!(extended.encode('ｱ'), 0xB1);
+        assert_eq!(extended.decode(0x5C), Some('\\'));
+        assert_eq!(extended.decode(0x80), Some('é'));
+        assert!(!extended.contains('~'));
+    }
+}
@@ -0,0 +1,26 @@
+//! A millisecond clock source that lets widgets time their own animations and limit how often they update the
+//! display, so the application does not have to track elapsed time itself. `embedded-hal` 1.0 has no clock trait,
+//! so implement [`Clock`] on top of the HAL's monotonic timer, or pass a closure returning the current time:
+//!
+//! ```rust
+//! use i2c_character_display::dashboard::{Dashboard, TEMPERATURE_HUMIDITY_FIELDS};
+//!
+//! let clock = || timer.now().duration_since_epoch().to_millis();
+//! let mut dashboard = Dashboard::new(TEMPERATURE_HUMIDITY_FIELDS);
+//! dashboard.set_update_interval(&clock, 500);
+//! ```
+
+/// A monotonic clock with millisecond resolution.
+pub trait Clock {
+    /// Returns the current time in milliseconds. The value must never decrease.
+    fn now_ms(&self) -> u64;
+}
+
+impl<F> Clock for F
+where
+    F: Fn() -> u64,
+{
+    fn now_ms(&self) -> u64 {
+        self()
+    }
+}
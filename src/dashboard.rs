@@ -0,0 +1,588 @@
+//! A ready-made dashboard layout for the most common hobbyist use case: showing a handful of sensor readings,
+//! such as temperature and humidity, each with a label, a right-aligned numeric value, a trend arrow and a unit.
+//!
+//! A [`Dashboard`] is built from a registry of [`DashboardField`] definitions. The static parts of the layout
+//! (labels, units and the trend arrow glyphs) are drawn once with [`Dashboard::render`], after which only the
+//! numeric value and trend arrow of a field are rewritten by [`Dashboard::update`].
+//!
+//! ```rust
+//! use i2c_character_display::dashboard::{Dashboard, TEMPERATURE_HUMIDITY_FIELDS};
+//!
+//! let mut dashboard = Dashboard::new(TEMPERATURE_HUMIDITY_FIELDS);
+//! dashboard.render(&mut lcd)?;
+//! dashboard.update(&mut lcd, 0, 21.5)?; // temperature
+//! dashboard.update(&mut lcd, 1, 48.0)?; // humidity
+//! ```
+//!
+//! Sensors that report faster than the values can be read are rate limited with
+//! [`Dashboard::set_update_interval`], which takes a [`Clock`](crate::clock::Clock). Each field is then redrawn at
+//! most once per interval, and [`Dashboard::refresh`] draws the latest value of any field whose interval has since
+//! passed.
+use core::fmt::Write;
+
+use embedded_hal::{delay::DelayNs, i2c};
+
+use crate::{clock::Clock, driver::DriverTrait, BaseCharacterDisplay, CharacterDisplayError};
+
+/// The maximum number of cells a field's numeric value can occupy.
+const MAX_VALUE_WIDTH: usize = 16;
+
+/// Custom character for the rising trend arrow
+const TREND_UP_GLYPH: [u8; 8] = [
+    0b00100, 0b01110, 0b10101, 0b00100, 0b00100, 0b00100, 0b00100, 0b00000,
+];
+
+/// Custom character for the falling trend arrow
+const TREND_DOWN_GLYPH: [u8; 8] = [
+    0b00100, 0b00100, 0b00100, 0b00100, 0b10101, 0b01110, 0b00100, 0b00000,
+];
+
+/// Default CGRAM slots used for the rising and falling trend arrows
+const DEFAULT_TREND_SLOTS: [u8; 2] = [6, 7];
+
+/// Temperature and humidity on a two row display. The `\u{DF}` in the temperature unit is the degree-like
+/// symbol found in the standard A00 character ROM.
+pub const TEMPERATURE_HUMIDITY_FIELDS: [DashboardField<'static>; 2] = [
+    DashboardField::new("Temp", "\u{DF}C", 0, 0),
+    DashboardField::new("Hum ", "%", 0, 1),
+];
+
+/// Temperature, humidity, dew point and pressure on a four row display.
+pub const CLIMATE_FIELDS: [DashboardField<'static>; 4] = [
+    DashboardField::new("Temp", "\u{DF}C", 0, 0),
+    DashboardField::new("Hum ", "%", 0, 1),
+    DashboardField::new("Dew ", "\u{DF}C", 0, 2),
+    DashboardField::new("Pres", "hPa", 0, 3)
+        .with_width(6)
+        .with_precision(0),
+];
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+/// The direction a field's value has moved since its previous update.
+pub enum Trend {
+    /// The value increased by more than the field's trend threshold
+    Rising,
+    /// The value decreased by more than the field's trend threshold
+    Falling,
+    /// The value changed by no more than the field's trend threshold
+    Steady,
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+/// Definition of one labeled numeric field on a dashboard. The field is laid out on a single row starting at
+/// `col` as: the label, the value right-aligned in `width` cells, the trend arrow (when enabled), then the unit.
+pub struct DashboardField<'a> {
+    /// Text printed before the value
+    pub label: &'a str,
+    /// Text printed after the value and trend arrow
+    pub unit: &'a str,
+    /// Column of the first character of the label
+    pub col: u8,
+    /// Row of the field
+    pub row: u8,
+    /// Number of cells reserved for the value
+    pub width: u8,
+    /// Number of digits printed after the decimal point
+    pub precision: u8,
+    /// Whether a trend arrow cell is shown after the value
+    pub show_trend: bool,
+    /// The minimum change between updates that is reported as a rising or falling trend
+    pub trend_threshold: f32,
+}
+
+impl<'a> DashboardField<'a> {
+    /// Create a field with a five cell value, one decimal place and a trend arrow.
+    pub const fn new(label: &'a str, unit: &'a str, col: u8, row: u8) -> Self {
+        Self {
+            label,
+            unit,
+            col,
+            row,
+            width: 5,
+            precision: 1,
+            show_trend: true,
+            trend_threshold: 0.0,
+        }
+    }
+
+    /// Set the number of cells reserved for the value.
+    pub const fn with_width(mut self, width: u8) -> Self {
+        self.width = width;
+        self
+    }
+
+    /// Set the number of digits printed after the decimal point.
+    pub const fn with_precision(mut self, precision: u8) -> Self {
+        self.precision = precision;
+        self
+    }
+
+    /// Enable or disable the trend arrow cell.
+    pub const fn with_trend(mut self, show_trend: bool) -> Self {
+        self.show_trend = show_trend;
+        self
+    }
+
+    /// Set the minimum change between updates that is reported as a rising or falling trend.
+    pub const fn with_trend_threshold(mut self, threshold: f32) -> Self {
+        self.trend_threshold = threshold;
+        self
+    }
+
+    /// The column where the value starts. The column math is done in `usize` so a long label or a large `col`
+    /// cannot overflow.
+    fn value_col(&self) -> usize {
+        self.col as usize + self.label.chars().count()
+    }
+
+    /// The column where the trend arrow is drawn
+    fn trend_col(&self) -> usize {
+        self.value_col() + self.width as usize
+    }
+
+    /// The column where the unit starts
+    fn unit_col(&self) -> usize {
+        self.trend_col() + self.show_trend as usize
+    }
+
+    /// The column one past the last cell used by the field
+    fn end_col(&self) -> usize {
+        self.unit_col() + self.unit.chars().count()
+    }
+
+    /// Convert a column of the field to a display column, returning `CharacterDisplayError::ColumnOutOfRange` when
+    /// it is past the widest display.
+    fn display_col<I2C: i2c::I2c>(&self, col: usize) -> Result<u8, CharacterDisplayError<I2C>> {
+        u8::try_from(col).map_err(|_| CharacterDisplayError::ColumnOutOfRange { col: self.col })
+    }
+
+    /// Determine the trend between the previous and current value
+    fn trend(&self, last: f32, value: f32) -> Trend {
+        if value > last + self.trend_threshold {
+            Trend::Rising
+        } else if value < last - self.trend_threshold {
+            Trend::Falling
+        } else {
+            Trend::Steady
+        }
+    }
+}
+
+/// Fixed size buffer used to format a value without allocation.
+struct ValueBuffer {
+    data: [u8; MAX_VALUE_WIDTH],
+    len: usize,
+}
+
+impl Write for ValueBuffer {
+    fn write_str(&mut self, s: &str) -> core::fmt::Result {
+        let bytes = s.as_bytes();
+        if self.len + bytes.len() > self.data.len() {
+            return Err(core::fmt::Error);
+        }
+        self.data[self.len..self.len + bytes.len()].copy_from_slice(bytes);
+        self.len += bytes.len();
+        Ok(())
+    }
+}
+
+/// Format `value` right-aligned into exactly `width` cells with `precision` decimal places. If the value
+/// does not fit, the cells are filled with `#` so an overflowing reading is never mistaken for a valid one.
+fn format_value(value: f32, width: u8, precision: u8, out: &mut [u8]) -> usize {
+    let width = (width as usize).min(MAX_VALUE_WIDTH).min(out.len());
+    let mut buffer = ValueBuffer {
+        data: [b' '; MAX_VALUE_WIDTH],
+        len: 0,
+    };
+    let fits = write!(
+        buffer,
+        "{:>width$.precision$}",
+        value,
+        width = width,
+        precision = precision as usize
+    )
+    .is_ok()
+        && buffer.len <= width;
+    if fits {
+        out[..width].copy_from_slice(&buffer.data[..width]);
+    } else {
+        out[..width].fill(b'#');
+    }
+    width
+}
+
+/// A sensor dashboard made of `N` registered fields.
+pub struct Dashboard<'a, const N: usize> {
+    fields: [DashboardField<'a>; N],
+    last_values: [Option<f32>; N],
+    trend_slots: [u8; 2],
+    /// The clock and the minimum time in milliseconds between redraws of a field, when rate limited
+    update_interval: Option<(&'a dyn Clock, u32)>,
+    /// When each field was last drawn, in milliseconds from the clock
+    drawn_ms: [Option<u64>; N],
+    /// The latest value of each field that has not been drawn yet because of the update interval
+    pending_values: [Option<f32>; N],
+}
+
+impl<'a, const N: usize> Dashboard<'a, N> {
+    /// Create a dashboard from a registry of field definitions. The trend arrows use CGRAM slots 6 and 7.
+    pub fn new(fields: [DashboardField<'a>; N]) -> Self {
+        Self {
+            fields,
+            last_values: [None; N],
+            trend_slots: DEFAULT_TREND_SLOTS,
+            update_interval: None,
+            drawn_ms: [None; N],
+            pending_values: [None; N],
+        }
+    }
+
+    /// Redraw each field at most once every `interval_ms` milliseconds of `clock`. Updates that arrive sooner are
+    /// held back, and the latest of them is drawn by a later `update()` or `refresh()` once the interval has passed.
+    pub fn set_update_interval(&mut self, clock: &'a dyn Clock, interval_ms: u32) -> &mut Self {
+        self.update_interval = Some((clock, interval_ms));
+        self
+    }
+
+    /// Set the CGRAM slots used for the rising and falling trend arrow glyphs.
+    pub fn set_trend_slots(&mut self, rising: u8, falling: u8) -> &mut Self {
+        self.trend_slots = [rising & 0x7, falling & 0x7];
+        self
+    }
+
+    /// Returns the registered field definitions.
+    pub fn fields(&self) -> &[DashboardField<'a>; N] {
+        &self.fields
+    }
+
+    /// Returns whether the field at `index` has a value waiting to be drawn because of the update interval.
+    pub fn is_pending(&self, index: usize) -> bool {
+        matches!(self.pending_values.get(index), Some(Some(_)))
+    }
+
+    /// Returns the last value shown for the field at `index`, if any.
+    pub fn value(&self, index: usize) -> Option<f32> {
+        self.last_values.get(index).copied().flatten()
+    }
+
+    /// Draw the static parts of the dashboard: the trend arrow glyphs, and each field's label and unit.
+    /// Values that were already set are redrawn too, so this can be used to restore the dashboard after
+    /// the display was cleared.
+    pub fn render<I2C, DELAY, DEVICE>(
+        &mut self,
+        lcd: &mut BaseCharacterDisplay<I2C, DELAY, DEVICE>,
+    ) -> Result<(), CharacterDisplayError<I2C>>
+    where
+        I2C: i2c::I2c,
+        DELAY: DelayNs,
+        DEVICE: DriverTrait<I2C, DELAY>,
+    {
+        let cols = lcd.display_type().cols() as usize;
+        if let Some(field) = self.fields.iter().find(|field| field.end_col() > cols) {
+            return Err(CharacterDisplayError::ColumnOutOfRange { col: field.col });
+        }
+        if self.fields.iter().any(|field| field.show_trend) {
+            lcd.create_char(self.trend_slots[0], TREND_UP_GLYPH)?
+                .create_char(self.trend_slots[1], TREND_DOWN_GLYPH)?;
+        }
+        for index in 0..N {
+            let field = self.fields[index];
+            lcd.print_at(field.col, field.row, field.label)?;
+            match self.last_values[index] {
+                Some(value) => self.draw_value(lcd, index, value, None)?,
+                None => self.draw_blank_value(lcd, index)?,
+            }
+            lcd.print_at(field.display_col(field.unit_col())?, field.row, field.unit)?;
+        }
+        Ok(())
+    }
+
+    /// Show a new value for the field at `index`. The trend arrow is derived from the previously shown value.
+    /// When an update interval is set and the field was drawn less than the interval ago, the value is held back
+    /// until the interval has passed, and the returned trend is relative to the shown value.
+    pub fn update<I2C, DELAY, DEVICE>(
+        &mut self,
+        lcd: &mut BaseCharacterDisplay<I2C, DELAY, DEVICE>,
+        index: usize,
+        value: f32,
+    ) -> Result<Trend, CharacterDisplayError<I2C>>
+    where
+        I2C: i2c::I2c,
+        DELAY: DelayNs,
+        DEVICE: DriverTrait<I2C, DELAY>,
+    {
+        if index >= N {
+            return Err(CharacterDisplayError::IndexOutOfRange { index });
+        }
+        let trend = match self.last_values[index] {
+            Some(last) => self.fields[index].trend(last, value),
+            None => Trend::Steady,
+        };
+        let now_ms = match self.update_interval {
+            Some((clock, interval_ms)) => {
+                let now_ms = clock.now_ms();
+                if let Some(drawn_ms) = self.drawn_ms[index] {
+                    if now_ms.saturating_sub(drawn_ms) < interval_ms as u64 {
+                        self.pending_values[index] = Some(value);
+                        return Ok(trend);
+                    }
+                }
+                Some(now_ms)
+            }
+            None => None,
+        };
+        let shown_trend = self.last_values[index].map(|_| trend);
+        self.draw_value(lcd, index, value, shown_trend)?;
+        self.last_values[index] = Some(value);
+        self.pending_values[index] = None;
+        self.drawn_ms[index] = now_ms;
+        Ok(trend)
+    }
+
+    /// Draw the held back values of the fields whose update interval has passed. Call this regularly, such as
+    /// once per main loop iteration, when an update interval is set. Returns the number of fields drawn.
+    pub fn refresh<I2C, DELAY, DEVICE>(
+        &mut self,
+        lcd: &mut BaseCharacterDisplay<I2C, DELAY, DEVICE>,
+    ) -> Result<usize, CharacterDisplayError<I2C>>
+    where
+        I2C: i2c::I2c,
+        DELAY: DelayNs,
+        DEVICE: DriverTrait<I2C, DELAY>,
+    {
+        let mut drawn = 0;
+        for index in 0..N {
+            if let Some(value) = self.pending_values[index] {
+                self.update(lcd, index, value)?;
+                if self.pending_values[index].is_none() {
+                    drawn += 1;
+                }
+            }
+        }
+        Ok(drawn)
+    }
+
+    fn draw_value<I2C, DELAY, DEVICE>(
+        &self,
+        lcd: &mut BaseCharacterDisplay<I2C, DELAY, DEVICE>,
+        index: usize,
+        value: f32,
+        trend: Option<Trend>,
+    ) -> Result<(), CharacterDisplayError<I2C>>
+    where
+        I2C: i2c::I2c,
+        DELAY: DelayNs,
+        DEVICE: DriverTrait<I2C, DELAY>,
+    {
+        let field = self.fields[index];
+        let mut cells = [b' '; MAX_VALUE_WIDTH + 1];
+        let mut len = format_value(value, field.width, field.precision, &mut cells);
+        if field.show_trend {
+            cells[len] = match trend {
+                Some(Trend::Rising) => self.trend_slots[0],
+                Some(Trend::Falling) => self.trend_slots[1],
+                Some(Trend::Steady) => b'=',
+                None => b' ',
+            };
+            len += 1;
+        }
+        self.draw_cells(lcd, field, &cells[..len])
+    }
+
+    fn draw_blank_value<I2C, DELAY, DEVICE>(
+        &self,
+        lcd: &mut BaseCharacterDisplay<I2C, DELAY, DEVICE>,
+        index: usize,
+    ) -> Result<(), CharacterDisplayError<I2C>>
+    where
+        I2C: i2c::I2c,
+        DELAY: DelayNs,
+        DEVICE: DriverTrait<I2C, DELAY>,
+    {
+        let field = self.fields[index];
+        let len = (field.width as usize).min(MAX_VALUE_WIDTH) + field.show_trend as usize;
+        let mut cells = [b' '; MAX_VALUE_WIDTH + 1];
+        cells[len / 2] = b'-';
+        self.draw_cells(lcd, field, &cells[..len])
+    }
+
+    fn draw_cells<I2C, DELAY, DEVICE>(
+        &self,
+        lcd: &mut BaseCharacterDisplay<I2C, DELAY, DEVICE>,
+        field: DashboardField<'a>,
+        cells: &[u8],
+    ) -> Result<(), CharacterDisplayError<I2C>>
+    where
+        I2C: i2c::I2c,
+        DELAY: DelayNs,
+        DEVICE: DriverTrait<I2C, DELAY>,
+    {
+        // the cells are ASCII digits, spaces and CGRAM slot codes, all of which are valid single byte UTF-8
+        let text = core::str::from_utf8(cells).map_err(|_| core::fmt::Error)?;
+        lcd.print_at(field.display_col(field.value_col())?, field.row, text)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+    use super::*;
+    use crate::{CharacterDisplayAIP31068, LcdDisplayType};
+    use embedded_hal_mock::eh1::{
+        delay::NoopDelay,
+        i2c::{Mock as I2cMock, Transaction as I2cTransaction},
+    };
+
+    #[test]
+    fn test_format_value() {
+        let mut out = [0u8; MAX_VALUE_WIDTH];
+        assert_eq!(format_value(21.46, 5, 1, &mut out), 5);
+        assert_eq!(&out[..5], b" 21.5");
+        assert_eq!(format_value(-3.0, 4, 0, &mut out), 4);
+        assert_eq!(&out[..4], b"  -3");
+        assert_eq!(format_value(12345.6, 5, 1, &mut out), 5);
+        assert_eq!(&out[..5], b"#####");
+    }
+
+    #[test]
+    fn test_field_layout() {
+        let field = DashboardField::new("Temp", "\u{DF}C", 2, 1);
+        assert_eq!(field.value_col(), 6);
+        assert_eq!(field.trend_col(), 11);
+        assert_eq!(field.unit_col(), 12);
+        assert_eq!(field.end_col(), 14);
+
+        let field = field.with_trend(false).with_width(3);
+        assert_eq!(field.unit_col(), 9);
+    }
+
+    #[test]
+    fn test_field_past_last_column() {
+        let field = DashboardField::new("Temperature", "\u{DF}C", 250, 0).with_width(16);
+        assert_eq!(field.value_col(), 261);
+        assert_eq!(field.end_col(), 280);
+
+        let i2c = I2cMock::new(&[]);
+        let mut lcd = CharacterDisplayAIP31068::new(i2c, LcdDisplayType::Lcd16x2, NoopDelay);
+        let mut dashboard = Dashboard::new([field]);
+        assert!(matches!(
+            dashboard.render(&mut lcd),
+            Err(CharacterDisplayError::ColumnOutOfRange { col: 250 })
+        ));
+        assert!(matches!(
+            dashboard.update(&mut lcd, 0, 21.5),
+            Err(CharacterDisplayError::ColumnOutOfRange { col: 250 })
+        ));
+
+        lcd.i2c().done();
+    }
+
+    #[test]
+    fn test_trend() {
+        let field = DashboardField::new("T", "", 0, 0).with_trend_threshold(0.5);
+        assert_eq!(field.trend(20.0, 20.4), Trend::Steady);
+        assert_eq!(field.trend(20.0, 20.6), Trend::Rising);
+        assert_eq!(field.trend(20.0, 19.4), Trend::Falling);
+    }
+
+    #[test]
+    fn test_dashboard_update() {
+        let i2c_address = 0x3e;
+        let expected_i2c_transactions = std::vec![
+            // first update of the temperature: cursor to column 4, row 0, value with no trend yet
+            I2cTransaction::write(i2c_address, std::vec![0b0000_0000, 0x80 | 0x04]),
+            I2cTransaction::write(
+                i2c_address,
+                std::vec![0b0100_0000, b' ', b'2', b'1', b'.', b'5', b' ']
+            ),
+            // second update of the temperature, which is now rising
+            I2cTransaction::write(i2c_address, std::vec![0b0000_0000, 0x80 | 0x04]),
+            I2cTransaction::write(
+                i2c_address,
+                std::vec![0b0100_0000, b' ', b'2', b'2', b'.', b'0', 6]
+            ),
+        ];
+
+        let i2c = I2cMock::new(&expected_i2c_transactions);
+        let mut lcd = CharacterDisplayAIP31068::new(i2c, LcdDisplayType::Lcd16x2, NoopDelay);
+        let mut dashboard = Dashboard::new(TEMPERATURE_HUMIDITY_FIELDS);
+
+        assert!(matches!(
+            dashboard.update(&mut lcd, 0, 21.5),
+            Ok(Trend::Steady)
+        ));
+        assert!(matches!(
+            dashboard.update(&mut lcd, 0, 22.0),
+            Ok(Trend::Rising)
+        ));
+        assert_eq!(dashboard.value(0), Some(22.0));
+        assert_eq!(dashboard.value(1), None);
+        assert!(matches!(
+            dashboard.update(&mut lcd, 2, 1.0),
+            Err(CharacterDisplayError::IndexOutOfRange { index: 2 })
+        ));
+
+        lcd.i2c().done();
+    }
+
+    #[test]
+    fn test_dashboard_does_not_fit() {
+        let i2c = I2cMock::new(&[]);
+        let mut lcd = CharacterDisplayAIP31068::new(i2c, LcdDisplayType::Lcd8x2, NoopDelay);
+        let mut dashboard = Dashboard::new(TEMPERATURE_HUMIDITY_FIELDS);
+
+        assert!(matches!(
+            dashboard.render(&mut lcd),
+            Err(CharacterDisplayError::ColumnOutOfRange { .. })
+        ));
+
+        lcd.i2c().done();
+    }
+
+    #[test]
+    fn test_dashboard_update_interval() {
+        use core::cell::Cell;
+
+        let i2c_address = 0x3e;
+        let expected_i2c_transactions = std::vec![
+            // first update is drawn right away
+            I2cTransaction::write(i2c_address, std::vec![0b0000_0000, 0x80 | 0x04]),
+            I2cTransaction::write(
+                i2c_address,
+                std::vec![0b0100_0000, b' ', b'2', b'1', b'.', b'5', b' ']
+            ),
+            // the latest held back value is drawn once the interval has passed
+            I2cTransaction::write(i2c_address, std::vec![0b0000_0000, 0x80 | 0x04]),
+            I2cTransaction::write(
+                i2c_address,
+                std::vec![0b0100_0000, b' ', b'2', b'0', b'.', b'0', 7]
+            ),
+        ];
+
+        let now_ms = Cell::new(1000_u64);
+        let clock = || now_ms.get();
+        let i2c = I2cMock::new(&expected_i2c_transactions);
+        let mut lcd = CharacterDisplayAIP31068::new(i2c, LcdDisplayType::Lcd16x2, NoopDelay);
+        let mut dashboard = Dashboard::new(TEMPERATURE_HUMIDITY_FIELDS);
+        dashboard.set_update_interval(&clock, 500);
+
+        assert!(dashboard.update(&mut lcd, 0, 21.5).is_ok());
+        now_ms.set(1200);
+        assert!(matches!(
+            dashboard.update(&mut lcd, 0, 22.0),
+            Ok(Trend::Rising)
+        ));
+        assert!(dashboard.update(&mut lcd, 0, 20.0).is_ok());
+        assert!(dashboard.is_pending(0));
+        assert_eq!(dashboard.value(0), Some(21.5));
+        assert!(matches!(dashboard.refresh(&mut lcd), Ok(0)));
+
+        now_ms.set(1500);
+        assert!(matches!(dashboard.refresh(&mut lcd), Ok(1)));
+        assert!(!dashboard.is_pending(0));
+        assert_eq!(dashboard.value(0), Some(20.0));
+
+        lcd.i2c().done();
+    }
+}
@@ -0,0 +1,198 @@
+//! Detection of a character display on an I2C bus, for bringing up unknown hardware. [`detect`] probes the I2C
+//! addresses commonly used by character display adapters and tells the kinds of adapters apart where it can, so the
+//! matching display type can be chosen.
+//!
+//! ```rust
+//! use i2c_character_display::{detect::{detect, DetectedAdapter}, CharacterDisplayPCF8574T, LcdDisplayType};
+//!
+//! match detect(&mut i2c, &mut delay) {
+//!     Some(DetectedAdapter::PCF8574 { address }) => {
+//!         let mut lcd = CharacterDisplayPCF8574T::new_with_address(i2c, address, LcdDisplayType::Lcd16x2, delay);
+//!         lcd.init()?;
+//!     }
+//!     Some(other) => defmt::info!("found a display adapter at {:#x}", other.address()),
+//!     None => defmt::warn!("no display found"),
+//! }
+//! ```
+
+use embedded_hal::{delay::DelayNs, i2c};
+
+use crate::driver::hd44780::adapter::{
+    generic_pcf8574t::GenericPCF8574TAdapter, HD44780AdapterTrait,
+};
+
+/// The address of controllers with a built-in I2C interface, such as the AiP31068 and the ST7032, which is also a
+/// PCF8574A address.
+const BUILT_IN_CONTROLLER_ADDRESS: u8 = 0x3E;
+
+/// The addresses probed by `detect()`, in order: the default addresses of the PCF8574T, PCF8574AT and AiP31068
+/// adapters first, then the rest of the PCF8574 (0x20 to 0x27) and PCF8574A (0x38 to 0x3F) address ranges.
+const PROBED_ADDRESSES: [u8; 16] = [
+    0x27, 0x3F, 0x3E, 0x20, 0x21, 0x22, 0x23, 0x24, 0x25, 0x26, 0x38, 0x39, 0x3A, 0x3B, 0x3C, 0x3D,
+];
+
+/// The time to wait for the display to be ready after power up before probing, in milliseconds.
+const POWER_UP_DELAY_MS: u32 = 50;
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+/// A display adapter found by `detect()`.
+pub enum DetectedAdapter {
+    /// A PCF8574 adapter driving an HD44780 controller whose busy flag could be read, which is used with
+    /// `CharacterDisplayPCF8574T`.
+    PCF8574 {
+        /// The I2C address of the adapter
+        address: u8,
+    },
+    /// A device at 0x3E which did not answer like a PCF8574 adapter, taken to be a controller with a built-in I2C
+    /// interface, such as the AiP31068 or ST7032, which is used with `CharacterDisplayAIP31068`.
+    AIP31068 {
+        /// The I2C address of the controller
+        address: u8,
+    },
+    /// A device in the address range of the I2C GPIO expanders used by display adapters, which did not answer like a
+    /// PCF8574 adapter with a readable HD44780 controller. This can be an MCP23017 adapter, a PCF8574 adapter that
+    /// ties the display's RW pin to ground, or a device that is not a display adapter.
+    GpioExpander {
+        /// The I2C address of the device
+        address: u8,
+    },
+}
+
+impl DetectedAdapter {
+    /// Returns the I2C address the adapter was found at.
+    pub fn address(&self) -> u8 {
+        match self {
+            DetectedAdapter::PCF8574 { address }
+            | DetectedAdapter::AIP31068 { address }
+            | DetectedAdapter::GpioExpander { address } => *address,
+        }
+    }
+}
+
+/// Probes the I2C addresses commonly used by character display adapters and returns the first adapter found, or
+/// `None` when no device answers at any of them. An address answers when a write of no bytes is acknowledged, which
+/// leaves the outputs of any device there untouched. The busy flag of an HD44780 controller is then read as through
+/// a PCF8574 adapter: a controller drives the busy flag low when it is idle, while the pins of a PCF8574 without a
+/// controller, or of other devices, read high. A device at 0x3E that does not answer like a PCF8574 adapter is
+/// taken to be a controller with a built-in I2C interface, which takes the single byte writes of the busy flag read
+/// as control bytes without data, and so is left unchanged by them. Waits for the display to power up before
+/// probing, so it can be called right after the display is powered on.
+pub fn detect<I2C, DELAY>(i2c: &mut I2C, delay: &mut DELAY) -> Option<DetectedAdapter>
+where
+    I2C: i2c::I2c,
+    DELAY: DelayNs,
+{
+    delay.delay_ms(POWER_UP_DELAY_MS);
+    let adapter = GenericPCF8574TAdapter::<I2C>::default();
+    let address = PROBED_ADDRESSES
+        .into_iter()
+        .find(|address| i2c.write(*address, &[]).is_ok())?;
+    if matches!(adapter.is_busy(i2c, address), Ok(false)) {
+        Some(DetectedAdapter::PCF8574 { address })
+    } else if address == BUILT_IN_CONTROLLER_ADDRESS {
+        Some(DetectedAdapter::AIP31068 { address })
+    } else {
+        Some(DetectedAdapter::GpioExpander { address })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+    use super::*;
+    use embedded_hal::i2c::{ErrorKind, NoAcknowledgeSource};
+    use embedded_hal_mock::eh1::{
+        delay::NoopDelay,
+        i2c::{Mock as I2cMock, Transaction as I2cTransaction},
+    };
+
+    /// The transactions of probing an address where nothing answers.
+    fn no_answer(address: u8) -> I2cTransaction {
+        I2cTransaction::write(address, std::vec![])
+            .with_error(ErrorKind::NoAcknowledge(NoAcknowledgeSource::Address))
+    }
+
+    /// The transactions of reading the busy flag through a PCF8574 adapter, reading `high_nibble` as the port.
+    fn busy_check(address: u8, high_nibble: u8) -> [I2cTransaction; 6] {
+        [
+            I2cTransaction::write(address, std::vec![0b1111_0010]),
+            I2cTransaction::write(address, std::vec![0b1111_0110]),
+            I2cTransaction::read(address, std::vec![(high_nibble << 4) | 0b0110]),
+            I2cTransaction::write(address, std::vec![0b1111_0010]),
+            I2cTransaction::write(address, std::vec![0b1111_0110]),
+            I2cTransaction::write(address, std::vec![0b1111_0010]),
+        ]
+    }
+
+    #[test]
+    fn test_detect_pcf8574() {
+        let mut expected_i2c_transactions = std::vec![I2cTransaction::write(0x27, std::vec![])];
+        // the idle controller drives the busy flag low
+        expected_i2c_transactions.extend(busy_check(0x27, 0b0010));
+        let mut i2c = I2cMock::new(&expected_i2c_transactions);
+        let detected = detect(&mut i2c, &mut NoopDelay::new());
+        assert_eq!(detected, Some(DetectedAdapter::PCF8574 { address: 0x27 }));
+        assert_eq!(detected.map(|adapter| adapter.address()), Some(0x27));
+        i2c.done();
+    }
+
+    #[test]
+    fn test_detect_built_in_controller() {
+        // the read of the busy flag is not acknowledged by a controller with a built-in I2C interface
+        let expected_i2c_transactions = [
+            no_answer(0x27),
+            no_answer(0x3F),
+            I2cTransaction::write(0x3E, std::vec![]),
+            I2cTransaction::write(0x3E, std::vec![0b1111_0010]),
+            I2cTransaction::write(0x3E, std::vec![0b1111_0110]),
+            I2cTransaction::read(0x3E, std::vec![0])
+                .with_error(ErrorKind::NoAcknowledge(NoAcknowledgeSource::Address)),
+        ];
+        let mut i2c = I2cMock::new(&expected_i2c_transactions);
+        assert_eq!(
+            detect(&mut i2c, &mut NoopDelay::new()),
+            Some(DetectedAdapter::AIP31068 { address: 0x3E })
+        );
+        i2c.done();
+    }
+
+    #[test]
+    fn test_detect_pcf8574a_at_built_in_controller_address() {
+        // a PCF8574AT adapter strapped to 0x3E
+        let mut expected_i2c_transactions = std::vec![
+            no_answer(0x27),
+            no_answer(0x3F),
+            I2cTransaction::write(0x3E, std::vec![]),
+        ];
+        expected_i2c_transactions.extend(busy_check(0x3E, 0b0010));
+        let mut i2c = I2cMock::new(&expected_i2c_transactions);
+        assert_eq!(
+            detect(&mut i2c, &mut NoopDelay::new()),
+            Some(DetectedAdapter::PCF8574 { address: 0x3E })
+        );
+        i2c.done();
+    }
+
+    #[test]
+    fn test_detect_gpio_expander_and_nothing() {
+        // pins that read high are not the busy flag of a controller
+        let mut expected_i2c_transactions = std::vec![
+            no_answer(0x27),
+            no_answer(0x3F),
+            no_answer(0x3E),
+            I2cTransaction::write(0x20, std::vec![]),
+        ];
+        expected_i2c_transactions.extend(busy_check(0x20, 0b1111));
+        let mut i2c = I2cMock::new(&expected_i2c_transactions);
+        assert_eq!(
+            detect(&mut i2c, &mut NoopDelay::new()),
+            Some(DetectedAdapter::GpioExpander { address: 0x20 })
+        );
+        i2c.done();
+
+        let expected_i2c_transactions = PROBED_ADDRESSES.map(no_answer);
+        let mut i2c = I2cMock::new(&expected_i2c_transactions);
+        assert_eq!(detect(&mut i2c, &mut NoopDelay::new()), None);
+        i2c.done();
+    }
+}
@@ -0,0 +1,151 @@
+//! Utilities for finding what changed between two versions of a row of display text, so that only the changed
+//! characters need to be sent to the display.
+//!
+//! ```rust
+//! use i2c_character_display::diff::diff_rows;
+//!
+//! let old = b"T= 21.5C H=40%";
+//! let new = b"T= 22.0C H=41%";
+//! for span in diff_rows(old, new) {
+//!     lcd.set_cursor(span.start as u8, row)?;
+//!     lcd.print(core::str::from_utf8(&new[span.range()]).unwrap())?;
+//! }
+//! ```
+
+use core::ops::Range;
+
+/// A run of changed characters within a row, from column `start` up to but not including column `end`.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Span {
+    /// The first changed column
+    pub start: usize,
+    /// The column after the last changed column
+    pub end: usize,
+}
+
+impl Span {
+    /// The number of characters in the span
+    pub const fn len(&self) -> usize {
+        self.end - self.start
+    }
+
+    /// Returns `true` if the span has no characters
+    pub const fn is_empty(&self) -> bool {
+        self.start == self.end
+    }
+
+    /// The columns of the span as a range, for slicing the new row data
+    pub const fn range(&self) -> Range<usize> {
+        self.start..self.end
+    }
+}
+
+/// Iterator over the changed spans of a row, returned by [`diff_rows`].
+#[derive(Debug, Clone)]
+pub struct RowDiff<'a> {
+    old: &'a [u8],
+    new: &'a [u8],
+    col: usize,
+    max_gap: usize,
+}
+
+impl RowDiff<'_> {
+    /// Merge spans that are separated by no more than `max_gap` unchanged characters. Rewriting a few unchanged
+    /// characters is often cheaper than the command to move the cursor past them.
+    pub const fn merge_gaps(mut self, max_gap: usize) -> Self {
+        self.max_gap = max_gap;
+        self
+    }
+
+    fn is_changed(&self, col: usize) -> bool {
+        self.old.get(col) != Some(&self.new[col])
+    }
+
+    /// Find the next changed column at or after `col`.
+    fn next_change(&self, mut col: usize) -> Option<usize> {
+        while col < self.new.len() {
+            if self.is_changed(col) {
+                return Some(col);
+            }
+            col += 1;
+        }
+        None
+    }
+}
+
+impl Iterator for RowDiff<'_> {
+    type Item = Span;
+
+    fn next(&mut self) -> Option<Span> {
+        let start = self.next_change(self.col)?;
+        let mut end = start + 1;
+        loop {
+            while end < self.new.len() && self.is_changed(end) {
+                end += 1;
+            }
+            match self.next_change(end) {
+                Some(next) if next - end <= self.max_gap => end = next,
+                _ => break,
+            }
+        }
+        self.col = end;
+        Some(Span { start, end })
+    }
+}
+
+/// Compare the `old` and `new` contents of a row and iterate over the spans of columns that changed. Columns
+/// beyond the end of `old` are treated as changed, and columns beyond the end of `new` are ignored since there
+/// is nothing to write to them.
+pub fn diff_rows<'a>(old: &'a [u8], new: &'a [u8]) -> RowDiff<'a> {
+    RowDiff {
+        old,
+        new,
+        col: 0,
+        max_gap: 0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+    use super::*;
+    use std::vec::Vec;
+
+    #[test]
+    fn test_diff_rows() {
+        let spans: Vec<Span> = diff_rows(b"T= 21.5C H=40%", b"T= 22.0C H=41%").collect();
+        assert_eq!(
+            spans,
+            [
+                Span { start: 4, end: 5 },
+                Span { start: 6, end: 7 },
+                Span { start: 12, end: 13 }
+            ]
+        );
+        assert_eq!(spans[0].len(), 1);
+        assert_eq!(diff_rows(b"same", b"same").count(), 0);
+        assert_eq!(
+            diff_rows(b"", b"new").collect::<Vec<Span>>(),
+            [Span { start: 0, end: 3 }]
+        );
+        assert_eq!(
+            diff_rows(b"abcdef", b"abXd").collect::<Vec<Span>>(),
+            [Span { start: 2, end: 3 }]
+        );
+    }
+
+    #[test]
+    fn test_diff_rows_merge_gaps() {
+        let spans: Vec<Span> = diff_rows(b"T= 21.5C H=40%", b"T= 22.0C H=41%")
+            .merge_gaps(1)
+            .collect();
+        assert_eq!(
+            spans,
+            [Span { start: 4, end: 7 }, Span { start: 12, end: 13 }]
+        );
+        let spans: Vec<Span> = diff_rows(b"T= 21.5C H=40%", b"T= 22.0C H=41%")
+            .merge_gaps(5)
+            .collect();
+        assert_eq!(spans, [Span { start: 4, end: 13 }]);
+    }
+}
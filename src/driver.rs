@@ -1,9 +1,16 @@
 pub mod hd44780;
 pub mod aip31068;
+pub mod aip31068_rgb;
+pub mod pcf211x;
+pub mod us2066;
 
 use embedded_hal::{delay::DelayNs, i2c};
 
-use crate::{CharacterDisplayError, DeviceSetupConfig};
+use crate::{
+    BatchCommand, BusyPollStrategy, CharacterDisplayError, CommandCompletion, ContrastPreset,
+    DeviceSetupConfig, DoubleHeightMode, FontSize, I2cPacing, InitProfile, InitProgress,
+    LcdDisplayType, Timings,
+};
 
 pub trait DriverTrait<I2C, DELAY>: Default
 where
@@ -13,15 +20,106 @@ where
     /// returns the default I2C address for the device
     fn default_i2c_address() -> u8;
 
+    /// returns the alternate I2C address to try when the device does not respond at `address`, if any
+    fn alternate_i2c_address(_address: u8) -> Option<u8> {
+        None
+    }
+
     /// returns whether reads are supported by the device
     fn supports_reads() -> bool;
 
+    /// returns the number of controllers used to drive the display
+    fn controller_count(&self) -> usize {
+        1
+    }
+
+    /// returns the controller that drives the given row and the row within that controller, as
+    /// `( controller, row )`
+    fn controller_row(&self, row: u8) -> (usize, u8) {
+        (0, row)
+    }
+
+    /// returns the DDRAM address of the first column of each row within its controller
+    fn row_offsets(&self, display_type: LcdDisplayType) -> [u8; 4] {
+        display_type.row_offsets()
+    }
+
+    /// sets how the busy flag is polled on devices that support reads
+    fn set_busy_poll_strategy(&mut self, _strategy: BusyPollStrategy) {}
+
+    /// sets how the completion of slow commands is waited for on devices that support reads
+    fn set_command_completion(&mut self, _completion: CommandCompletion) {}
+
+    /// sets the signal checked before polling the busy flag on devices that support reads
+    fn set_ready_signal(&mut self, _is_ready: Option<fn() -> bool>) {}
+
+    /// sets how many times a failed I2C write is tried again, and the delay before each retry, on devices that
+    /// support retries
+    fn set_i2c_retries(&mut self, _retries: u8, _interval_us: u32) {}
+
+    /// returns the waits the driver makes for the controller
+    fn timings(&self) -> Timings;
+
+    /// sets the waits the driver makes for the controller
+    fn set_timings(&mut self, timings: Timings);
+
+    /// sets the wait after each nibble written through the 4 bit interface, on devices that use it
+    fn set_nibble_delay(&mut self, _delay_us: u32) {}
+
+    /// sets the speed of the I2C bus, for devices that pace their writes to meet the controller timing
+    fn set_i2c_frequency(&mut self, _frequency_hz: u32) {}
+
+    /// returns how the writes are paced for the speed of the I2C bus
+    fn i2c_pacing(&self) -> I2cPacing {
+        I2cPacing::FullSpeed
+    }
+
+    /// returns the number of I2C writes that were tried again since the last call, and resets the count
+    fn take_retried_writes(&mut self) -> u32 {
+        0
+    }
+
+    /// sets the table of `( controller, row )` that display rows are mapped to on devices with a configurable row
+    /// layout
+    fn set_controller_rows(&mut self, _table: &'static [(usize, u8)]) {}
+
+    /// sets the character font size selected when the display is initialized
+    fn set_font_size(&mut self, _font_size: FontSize) {}
+
+    /// sets the power supply specific setup done when the display is initialized
+    fn set_init_profile(&mut self, _profile: InitProfile) {}
+
+    /// returns the tracked display control and entry mode flags, as `( display_control, display_mode )`
+    fn control_flags(&self) -> (u8, u8);
+
+    /// replaces the tracked display control and entry mode flags, which are written to the display by the next
+    /// `soft_reset()`
+    fn set_control_flags(&mut self, display_control: u8, display_mode: u8);
+
     /// Initialize the display
     fn init(
         &mut self,
         device: &mut DeviceSetupConfig<I2C, DELAY>,
     ) -> Result<(), CharacterDisplayError<I2C>>;
 
+    /// Run step `step` of the display initialization, counting from 0, and return how long to wait before the next
+    /// step or that the initialization is done. The default runs all of `init()` in the first step.
+    fn init_step(
+        &mut self,
+        device: &mut DeviceSetupConfig<I2C, DELAY>,
+        _step: usize,
+    ) -> Result<InitProgress, CharacterDisplayError<I2C>> {
+        self.init(device)?;
+        Ok(InitProgress::Done)
+    }
+
+    /// Reset the display controller(s) without the power on delays, restoring the tracked display state
+    /// (function set, display control and entry mode). The display contents are not cleared.
+    fn soft_reset(
+        &mut self,
+        device: &mut DeviceSetupConfig<I2C, DELAY>,
+    ) -> Result<(), CharacterDisplayError<I2C>>;
+
     /// Clear the display
     fn clear(
         &mut self,
@@ -42,6 +140,14 @@ where
         row: u8,
     ) -> Result<(), CharacterDisplayError<I2C>>;
 
+    /// Set the DDRAM address of the controller that drives the given row, making it the active controller.
+    fn set_ddram_address(
+        &mut self,
+        device: &mut DeviceSetupConfig<I2C, DELAY>,
+        row: u8,
+        address: u8,
+    ) -> Result<(), CharacterDisplayError<I2C>>;
+
     /// Set the cursor visibility.
     fn show_cursor(
         &mut self,
@@ -101,6 +207,35 @@ where
         text: &str,
     ) -> Result<(), CharacterDisplayError<I2C>>;
 
+    /// Writes raw data bytes to the LCD at the current cursor position of the active device.
+    fn write_data(
+        &mut self,
+        device: &mut DeviceSetupConfig<I2C, DELAY>,
+        data: &[u8],
+    ) -> Result<(), CharacterDisplayError<I2C>>;
+
+    /// Writes the low 4 bits of `value` to a controller over the 4-bit interface, on adapters with one
+    fn write_nibble(
+        &mut self,
+        _device: &mut DeviceSetupConfig<I2C, DELAY>,
+        _controller: usize,
+        _rs_setting: bool,
+        _value: u8,
+    ) -> Result<(), CharacterDisplayError<I2C>> {
+        Err(CharacterDisplayError::UnsupportedOperation)
+    }
+
+    /// Writes a byte to a controller, as a command when `rs_setting` is `false` and as data otherwise
+    fn write_byte(
+        &mut self,
+        _device: &mut DeviceSetupConfig<I2C, DELAY>,
+        _controller: usize,
+        _rs_setting: bool,
+        _value: u8,
+    ) -> Result<(), CharacterDisplayError<I2C>> {
+        Err(CharacterDisplayError::UnsupportedOperation)
+    }
+
     /// Sets the backlight on or off
     fn backlight(
         &mut self,
@@ -108,6 +243,84 @@ where
         on: bool,
     ) -> Result<(), CharacterDisplayError<I2C>>;
 
+    /// Sets the backlight color on hardware with an RGB backlight controller
+    fn set_backlight_rgb(
+        &mut self,
+        _device: &mut DeviceSetupConfig<I2C, DELAY>,
+        _red: u8,
+        _green: u8,
+        _blue: u8,
+    ) -> Result<(), CharacterDisplayError<I2C>> {
+        Err(CharacterDisplayError::UnsupportedOperation)
+    }
+
+    /// Sets the backlight brightness on hardware that can dim the backlight
+    fn set_backlight_brightness(
+        &mut self,
+        _device: &mut DeviceSetupConfig<I2C, DELAY>,
+        _brightness: u8,
+    ) -> Result<(), CharacterDisplayError<I2C>> {
+        Err(CharacterDisplayError::UnsupportedOperation)
+    }
+
+    /// Serializes display commands into a single I2C write held in `buffer`, on controllers that accept a mix of
+    /// commands and data in one write, and returns the length of the write
+    fn serialize_batch(
+        &mut self,
+        _device: &mut DeviceSetupConfig<I2C, DELAY>,
+        _commands: &[BatchCommand<'_>],
+        _buffer: &mut [u8],
+    ) -> Result<usize, CharacterDisplayError<I2C>> {
+        Err(CharacterDisplayError::UnsupportedOperation)
+    }
+
+    /// Selects one of the contrast presets on hardware that supports switching between them
+    fn set_contrast_preset(
+        &mut self,
+        _device: &mut DeviceSetupConfig<I2C, DELAY>,
+        _preset: ContrastPreset,
+    ) -> Result<(), CharacterDisplayError<I2C>> {
+        Err(CharacterDisplayError::UnsupportedOperation)
+    }
+
+    /// Returns the index of the controller the driver last sent a command or data to, or read from, which is the
+    /// controller an operation failed on when it returns an error
+    fn last_controller(&self) -> usize {
+        0
+    }
+
+    /// Returns the mask of the I2C expander pins not used by the display that can be read as inputs
+    fn input_pins(&self) -> u8 {
+        0
+    }
+
+    /// Reads the levels of the I2C expander pins not used by the display on hardware that has them
+    fn read_input_pins(
+        &mut self,
+        _device: &mut DeviceSetupConfig<I2C, DELAY>,
+    ) -> Result<u8, CharacterDisplayError<I2C>> {
+        Err(CharacterDisplayError::UnsupportedOperation)
+    }
+
+    /// Sets the contrast on controllers with software contrast control, such as OLED controllers
+    fn set_contrast(
+        &mut self,
+        _device: &mut DeviceSetupConfig<I2C, DELAY>,
+        _contrast: u8,
+    ) -> Result<(), CharacterDisplayError<I2C>> {
+        Err(CharacterDisplayError::UnsupportedOperation)
+    }
+
+    /// Selects the rows shown in double height characters on controllers that support them. The rows passed to
+    /// `set_cursor()` are then numbered as shown.
+    fn set_double_height(
+        &mut self,
+        _device: &mut DeviceSetupConfig<I2C, DELAY>,
+        _mode: DoubleHeightMode,
+    ) -> Result<(), CharacterDisplayError<I2C>> {
+        Err(CharacterDisplayError::UnsupportedOperation)
+    }
+
     /// creates a new custom character
     fn create_char(
         &mut self,
@@ -116,6 +329,21 @@ where
         charmap: [u8; 8],
     ) -> Result<(), CharacterDisplayError<I2C>>;
 
+    /// creates a new custom character in the CGRAM of only the given controller. Devices with a
+    /// single controller only accept controller `0`.
+    fn create_char_for_controller(
+        &mut self,
+        device: &mut DeviceSetupConfig<I2C, DELAY>,
+        controller: usize,
+        location: u8,
+        charmap: [u8; 8],
+    ) -> Result<(), CharacterDisplayError<I2C>> {
+        if controller != 0 {
+            return Err(CharacterDisplayError::BadDeviceId);
+        }
+        self.create_char(device, location, charmap)
+    }
+
     /// read bytes from the active controller of the device. The size of the buffer is the number of bytes to read.
     fn read_device_data(
         &self,
@@ -132,3 +360,22 @@ where
         unimplemented!("Reads are not supported for device");
     }
 }
+
+/// Run all the steps of `DriverTrait::init_step()`, waiting between them, for drivers that implement `init()` that
+/// way.
+pub(crate) fn init_in_steps<I2C, DELAY, DRIVER>(
+    driver: &mut DRIVER,
+    device: &mut DeviceSetupConfig<I2C, DELAY>,
+) -> Result<(), CharacterDisplayError<I2C>>
+where
+    I2C: i2c::I2c,
+    DELAY: DelayNs,
+    DRIVER: DriverTrait<I2C, DELAY>,
+{
+    let mut step = 0;
+    while let InitProgress::WaitUs(us) = driver.init_step(device, step)? {
+        device.delay.delay_us(us);
+        step += 1;
+    }
+    Ok(())
+}
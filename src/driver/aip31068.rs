@@ -3,8 +3,9 @@ use core::marker::PhantomData;
 use embedded_hal::{delay::DelayNs, i2c};
 
 use crate::{
-    driver::DriverTrait,
-    CharacterDisplayError, DeviceSetupConfig,
+    driver::{init_in_steps, DriverTrait},
+    BatchCommand, CharacterDisplayError, DeviceSetupConfig, FontSize, InitProfile, InitProgress,
+    LcdBias, Timings,
 };
 
 // commands
@@ -17,6 +18,12 @@ const LCD_CMD_FUNCTIONSET: u8 = 0x20; //  Used to send the function to set to th
 const LCD_CMD_SETCGRAMADDR: u8 = 0x40; //  Used to set the CGRAM (character generator RAM) with characters
 const LCD_CMD_SETDDRAMADDR: u8 = 0x80; //  Used to set the DDRAM (Display Data RAM)
 
+// extended commands, available when the IS bit of the function set is 1
+const LCD_CMD_OSCFREQUENCY: u8 = 0x10; //  Sets the bias and the internal oscillator frequency
+const LCD_CMD_CONTRASTSET: u8 = 0x70; //  Sets the low 4 bits of the contrast
+const LCD_CMD_POWERCONTROL: u8 = 0x50; //  Sets the booster and the high 2 bits of the contrast
+const LCD_CMD_FOLLOWERCONTROL: u8 = 0x60; //  Sets the voltage follower and its amplification ratio
+
 // flags for display entry mode
 const LCD_FLAG_ENTRYRIGHT: u8 = 0x00; //  Used to set text to flow from right to left
 const LCD_FLAG_ENTRYLEFT: u8 = 0x02; //  Uset to set text to flow from left to right
@@ -42,8 +49,26 @@ const LCD_FLAG_2LINE: u8 = 0x08; //  LCD 2 line mode
 const LCD_FLAG_1LINE: u8 = 0x00; //  LCD 1 line mode
 const LCD_FLAG_5x10_DOTS: u8 = 0x04; //  10 pixel high font mode
 const LCD_FLAG_5x8_DOTS: u8 = 0x00; //  8 pixel high font mode
+const LCD_FLAG_EXTENDED: u8 = 0x01; //  Selects the extended instruction set (IS = 1)
+
+// flags for the extended commands
+const LCD_FLAG_BIAS_1_5: u8 = 0x00; //  1/5 bias
+const LCD_FLAG_BIAS_1_4: u8 = 0x08; //  1/4 bias
+const LCD_FLAG_BOOSTERON: u8 = 0x04; //  Turns the voltage booster on
+const LCD_FLAG_BOOSTEROFF: u8 = 0x00; //  Turns the voltage booster off
+const LCD_FLAG_FOLLOWERON: u8 = 0x08; //  Turns the voltage follower on
+const LCD_FLAG_FOLLOWEROFF: u8 = 0x00; //  Turns the voltage follower off
 
 const MAX_BUFFER_SIZE: usize = 82;      // 80 bytes of data + 2 control bytes.
+
+/// The datasheet times of the AIP31068. Commands take 39 us and data writes 43 us, so the longer covers both.
+const DEFAULT_TIMINGS: Timings = Timings {
+    power_on_us: 15_000,
+    clear_home_us: 1530,
+    command_us: 43,
+    enable_pulse_us: 0,
+};
+
 pub struct AIP31068<I2C>
 where
     I2C: i2c::I2c,
@@ -52,6 +77,9 @@ where
     display_control: u8,
     display_mode: u8,
     buffer: [u8; MAX_BUFFER_SIZE],  // buffer for I2C data
+    font_size: FontSize,
+    init_profile: InitProfile,
+    timings: Timings,
     _marker: PhantomData<I2C>,
 }
 
@@ -65,6 +93,9 @@ where
             display_control: 0,
             display_mode: 0,
             buffer: [0; MAX_BUFFER_SIZE],
+            font_size: FontSize::Dots5x8,
+            init_profile: InitProfile::Standard,
+            timings: DEFAULT_TIMINGS,
             _marker: PhantomData,
         }
     }
@@ -83,40 +114,92 @@ where
         false
     }
 
-    fn init(
-        &mut self,
-        device: &mut DeviceSetupConfig<I2C, DELAY>,
-    ) -> Result<(), CharacterDisplayError<I2C>> {
+    fn set_font_size(&mut self, font_size: FontSize) {
+        self.font_size = font_size;
+    }
 
-        #[cfg(feature = "defmt")]
-        defmt::debug!("Initializing AIP31068");
-        // wait 15 ms for power on
-        device.delay.delay_ms(15);
+    fn set_init_profile(&mut self, profile: InitProfile) {
+        self.init_profile = profile;
+    }
 
-        // send function set command
-        self.display_function = LCD_FLAG_2LINE | LCD_FLAG_5x8_DOTS;
-        self.write_bytes(device, false, &[LCD_CMD_FUNCTIONSET | self.display_function])?;
+    fn timings(&self) -> Timings {
+        self.timings
+    }
 
-        // wait 39 us
-        device.delay.delay_us(39);
+    fn set_timings(&mut self, timings: Timings) {
+        self.timings = timings;
+    }
 
-        // display on/off control
-        self.display_control = LCD_FLAG_DISPLAYON | LCD_FLAG_CURSOROFF | LCD_FLAG_BLINKOFF;
-        self.write_bytes(device, false, &[LCD_CMD_DISPLAYCONTROL | self.display_control])?;
+    fn control_flags(&self) -> (u8, u8) {
+        (self.display_control, self.display_mode)
+    }
 
-        // wait 39 us
-        device.delay.delay_us(39);
+    fn set_control_flags(&mut self, display_control: u8, display_mode: u8) {
+        self.display_control = display_control;
+        self.display_mode = display_mode;
+    }
 
-        // clear display
-        self.write_bytes(device, false, &[LCD_CMD_CLEARDISPLAY])?;
+    fn init(
+        &mut self,
+        device: &mut DeviceSetupConfig<I2C, DELAY>,
+    ) -> Result<(), CharacterDisplayError<I2C>> {
+        init_in_steps(self, device)
+    }
 
-        // wait 1.53 ms
-        device.delay.delay_us(1530);
+    fn init_step(
+        &mut self,
+        device: &mut DeviceSetupConfig<I2C, DELAY>,
+        step: usize,
+    ) -> Result<InitProgress, CharacterDisplayError<I2C>> {
+        match step {
+            0 => {
+                #[cfg(feature = "defmt")]
+                defmt::debug!("Initializing AIP31068");
+                // wait for power on
+                Ok(InitProgress::WaitUs(self.timings.power_on_us))
+            }
+            1 => self.init_function_and_voltage(device),
+            2 => {
+                if self.init_profile.voltage_config().is_some() {
+                    // back to the normal instruction set once the LCD voltage is stable
+                    let command = LCD_CMD_FUNCTIONSET | self.display_function;
+                    self.write_bytes(device, false, &[command])?;
+                    device.delay.delay_us(self.timings.command_us);
+                }
+
+                // display on/off control
+                self.display_control = LCD_FLAG_DISPLAYON | LCD_FLAG_CURSOROFF | LCD_FLAG_BLINKOFF;
+                self.write_bytes(device, false, &[LCD_CMD_DISPLAYCONTROL | self.display_control])?;
+
+                // wait for the command to execute
+                device.delay.delay_us(self.timings.command_us);
+
+                // clear display
+                self.write_bytes(device, false, &[LCD_CMD_CLEARDISPLAY])?;
+                Ok(InitProgress::WaitUs(self.timings.clear_home_us))
+            }
+            _ => {
+                // entry mode set
+                self.display_mode = LCD_FLAG_ENTRYLEFT | LCD_FLAG_ENTRYSHIFTDECREMENT;
+                self.write_bytes(device, false, &[LCD_CMD_ENTRYMODESET | self.display_mode])?;
+                Ok(InitProgress::Done)
+            }
+        }
+    }
 
-        // entry mode set
-        self.display_mode = LCD_FLAG_ENTRYLEFT | LCD_FLAG_ENTRYSHIFTDECREMENT;
+    fn soft_reset(
+        &mut self,
+        device: &mut DeviceSetupConfig<I2C, DELAY>,
+    ) -> Result<(), CharacterDisplayError<I2C>> {
+        #[cfg(feature = "defmt")]
+        defmt::debug!("Soft resetting AIP31068");
+        // same sequence as init, without the power on wait and the clear display
+        self.write_bytes(device, false, &[LCD_CMD_FUNCTIONSET | self.display_function])?;
+        device.delay.delay_us(self.timings.command_us);
+        self.write_bytes(device, false, &[LCD_CMD_DISPLAYCONTROL | self.display_control])?;
+        device.delay.delay_us(self.timings.command_us);
         self.write_bytes(device, false, &[LCD_CMD_ENTRYMODESET | self.display_mode])?;
-
+        device.delay.delay_us(self.timings.command_us);
         Ok(())
     }
 
@@ -126,7 +209,7 @@ where
     ) -> Result<(), CharacterDisplayError<I2C>> {
         self.write_bytes(device, false, &[LCD_CMD_CLEARDISPLAY])?;
         // wait for command to complete
-        device.delay.delay_us(1530);
+        device.delay.delay_us(self.timings.clear_home_us);
         Ok(())
     }
 
@@ -136,7 +219,7 @@ where
     ) -> Result<(), CharacterDisplayError<I2C>> {
         self.write_bytes(device, false, &[LCD_CMD_RETURNHOME])?;
         // wait for command to complete
-        device.delay.delay_us(1530);
+        device.delay.delay_us(self.timings.clear_home_us);
         Ok(())
     }
 
@@ -147,19 +230,39 @@ where
         row: u8,
     ) -> Result<(), CharacterDisplayError<I2C>> {
         if row >= device.lcd_type.rows() {
-            return Err(CharacterDisplayError::RowOutOfRange);
+            return Err(CharacterDisplayError::RowOutOfRange { row });
         }
         if col >= device.lcd_type.cols() {
-            return Err(CharacterDisplayError::ColumnOutOfRange);
+            return Err(CharacterDisplayError::ColumnOutOfRange { col });
         }
+        // a single controller can address at most 4 rows
+        let row_offset = match device.lcd_type.row_offsets().get(row as usize) {
+            Some(offset) => *offset,
+            None => return Err(CharacterDisplayError::UnsupportedDisplayType),
+        };
 
         self.write_bytes(
             device,
             false,
-            &[LCD_CMD_SETDDRAMADDR | (col + device.lcd_type.row_offsets()[row as usize])],
+            &[LCD_CMD_SETDDRAMADDR | (col + row_offset)],
         )?;
         // wait for command to complete
-        device.delay.delay_us(39);
+        device.delay.delay_us(self.timings.command_us);
+        Ok(())
+    }
+
+    fn set_ddram_address(
+        &mut self,
+        device: &mut DeviceSetupConfig<I2C, DELAY>,
+        row: u8,
+        address: u8,
+    ) -> Result<(), CharacterDisplayError<I2C>> {
+        if row >= device.lcd_type.rows() {
+            return Err(CharacterDisplayError::RowOutOfRange { row });
+        }
+        self.write_bytes(device, false, &[LCD_CMD_SETDDRAMADDR | address])?;
+        // wait for command to complete
+        device.delay.delay_us(self.timings.command_us);
         Ok(())
     }
 
@@ -175,7 +278,7 @@ where
         }
         self.write_bytes(device, false, &[LCD_CMD_DISPLAYCONTROL | self.display_control])?;
         // wait for command to complete
-        device.delay.delay_us(39);
+        device.delay.delay_us(self.timings.command_us);
         Ok(())
     }
 
@@ -191,7 +294,7 @@ where
         }
         self.write_bytes(device, false, &[LCD_CMD_DISPLAYCONTROL | self.display_control])?;
         // wait for command to complete
-        device.delay.delay_us(39);
+        device.delay.delay_us(self.timings.command_us);
         Ok(())
     }
 
@@ -207,7 +310,7 @@ where
         }
         self.write_bytes(device, false, &[LCD_CMD_DISPLAYCONTROL | self.display_control])?;
         // wait for command to complete
-        device.delay.delay_us(39);
+        device.delay.delay_us(self.timings.command_us);
         Ok(())
     }
 
@@ -217,7 +320,7 @@ where
     ) -> Result<(), CharacterDisplayError<I2C>> {
         self.write_bytes(device, false, &[LCD_CMD_CURSORSHIFT | LCD_FLAG_DISPLAYMOVE | LCD_FLAG_MOVELEFT])?;
         // wait for command to complete
-        device.delay.delay_us(39);
+        device.delay.delay_us(self.timings.command_us);
         Ok(())
     }
 
@@ -227,7 +330,7 @@ where
     ) -> Result<(), CharacterDisplayError<I2C>> {
         self.write_bytes(device, false, &[LCD_CMD_CURSORSHIFT | LCD_FLAG_DISPLAYMOVE | LCD_FLAG_MOVERIGHT])?;
         // wait for command to complete
-        device.delay.delay_us(39);
+        device.delay.delay_us(self.timings.command_us);
         Ok(())
     }
 
@@ -243,7 +346,7 @@ where
             &[LCD_CMD_ENTRYMODESET | self.display_mode],
         )?;
         // wait for command to complete
-        device.delay.delay_us(39);
+        device.delay.delay_us(self.timings.command_us);
         Ok(())
     }
 
@@ -251,15 +354,14 @@ where
         &mut self,
         device: &mut DeviceSetupConfig<I2C, DELAY>,
     ) -> Result<(), CharacterDisplayError<I2C>> {
-        // TODO revisit this function's logic
-        self.display_mode |= LCD_FLAG_ENTRYRIGHT;
+        self.display_mode &= !LCD_FLAG_ENTRYLEFT;
         self.write_bytes(
             device,
             false,
             &[LCD_CMD_ENTRYMODESET | self.display_mode],
         )?;
         // wait for command to complete
-        device.delay.delay_us(39);
+        device.delay.delay_us(self.timings.command_us);
         Ok(())
     }
 
@@ -279,7 +381,7 @@ where
             &[LCD_CMD_ENTRYMODESET | self.display_mode],
         )?;
         // wait for command to complete
-        device.delay.delay_us(39);
+        device.delay.delay_us(self.timings.command_us);
         Ok(())
     }
 
@@ -290,7 +392,18 @@ where
     ) -> Result<(), CharacterDisplayError<I2C>> {
         self.write_bytes(device, true, text.as_bytes())?;
         // wait for command to complete
-        device.delay.delay_us(43);
+        device.delay.delay_us(self.timings.command_us);
+        Ok(())
+    }
+
+    fn write_data(
+        &mut self,
+        device: &mut DeviceSetupConfig<I2C, DELAY>,
+        data: &[u8],
+    ) -> Result<(), CharacterDisplayError<I2C>> {
+        self.write_bytes(device, true, data)?;
+        // wait for command to complete
+        device.delay.delay_us(self.timings.command_us);
         Ok(())
     }
 
@@ -311,7 +424,7 @@ where
         self.write_bytes(device, false, &[LCD_CMD_SETCGRAMADDR | ((location & 0x7) << 3)])?;
         self.write_bytes(device, true, &charmap)?;
         // wait for command to complete
-        device.delay.delay_us(39);
+        device.delay.delay_us(self.timings.command_us);
         Ok(())
     }
 
@@ -333,6 +446,46 @@ where
     ) -> Result<u8, CharacterDisplayError<I2C>> {
         Err(CharacterDisplayError::UnsupportedOperation)
     }
+
+    fn serialize_batch(
+        &mut self,
+        device: &mut DeviceSetupConfig<I2C, DELAY>,
+        commands: &[BatchCommand<'_>],
+        buffer: &mut [u8],
+    ) -> Result<usize, CharacterDisplayError<I2C>> {
+        // Every byte but the last run of data gets its own control byte, so each command is at least two bytes long
+        // on the bus, which at up to 400 kHz is longer than the controller needs to execute it.
+        let mut idx: usize = 0;
+        for (n, command) in commands.iter().enumerate() {
+            let last = n + 1 == commands.len();
+            match command {
+                BatchCommand::SetCursor(col, row) => {
+                    if *row >= device.lcd_type.rows() {
+                        return Err(CharacterDisplayError::RowOutOfRange { row: *row });
+                    }
+                    if *col >= device.lcd_type.cols() {
+                        return Err(CharacterDisplayError::ColumnOutOfRange { col: *col });
+                    }
+                    let row_offset = match device.lcd_type.row_offsets().get(*row as usize) {
+                        Some(offset) => *offset,
+                        None => return Err(CharacterDisplayError::UnsupportedDisplayType),
+                    };
+                    let control_byte = if last { Self::CONTROL_LAST_BYTE } else { Self::CONTROL_NOT_LAST_BYTE };
+                    idx = Self::serialize_bytes(buffer, idx, &[control_byte | Self::CONTROL_RS_COMMAND, LCD_CMD_SETDDRAMADDR | (col + row_offset)])?;
+                }
+                BatchCommand::Print(text) if last && !text.is_empty() => {
+                    idx = Self::serialize_bytes(buffer, idx, &[Self::CONTROL_LAST_BYTE | Self::CONTROL_RS_DATA])?;
+                    idx = Self::serialize_bytes(buffer, idx, text.as_bytes())?;
+                }
+                BatchCommand::Print(text) => {
+                    for byte in text.as_bytes() {
+                        idx = Self::serialize_bytes(buffer, idx, &[Self::CONTROL_NOT_LAST_BYTE | Self::CONTROL_RS_DATA, *byte])?;
+                    }
+                }
+            }
+        }
+        Ok(idx)
+    }
 }
 
 impl<I2C> AIP31068<I2C>
@@ -344,6 +497,47 @@ where
     const CONTROL_RS_DATA: u8 = 0b0100_0000;
     const CONTROL_RS_COMMAND: u8 = 0b0000_0000;
 
+    /// Send the function set command, and set up the oscillator and the LCD voltage for the supply voltage if the
+    /// init profile does, returning the 200 ms the LCD voltage then takes to stabilize.
+    fn init_function_and_voltage<DELAY: DelayNs>(
+        &mut self,
+        device: &mut DeviceSetupConfig<I2C, DELAY>,
+    ) -> Result<InitProgress, CharacterDisplayError<I2C>> {
+        // send function set command
+        // the 5x10 font is only available in one line mode
+        self.display_function = match self.font_size {
+            FontSize::Dots5x8 => LCD_FLAG_2LINE | LCD_FLAG_5x8_DOTS,
+            FontSize::Dots5x10 => LCD_FLAG_1LINE | LCD_FLAG_5x10_DOTS,
+        };
+        self.write_bytes(device, false, &[LCD_CMD_FUNCTIONSET | self.display_function])?;
+
+        // wait for the command to execute
+        device.delay.delay_us(self.timings.command_us);
+
+        // set up the oscillator and the LCD voltage for the supply voltage
+        let Some(config) = self.init_profile.voltage_config() else {
+            return Ok(InitProgress::WaitUs(0));
+        };
+        let bias = match config.bias {
+            LcdBias::OneFifth => LCD_FLAG_BIAS_1_5,
+            LcdBias::OneFourth => LCD_FLAG_BIAS_1_4,
+        };
+        let booster = if config.booster { LCD_FLAG_BOOSTERON } else { LCD_FLAG_BOOSTEROFF };
+        let follower = if config.follower { LCD_FLAG_FOLLOWERON } else { LCD_FLAG_FOLLOWEROFF };
+        for command in [
+            LCD_CMD_FUNCTIONSET | self.display_function | LCD_FLAG_EXTENDED,
+            LCD_CMD_OSCFREQUENCY | bias | (config.osc_frequency & 0x07),
+            LCD_CMD_CONTRASTSET | (config.contrast & 0x0F),
+            LCD_CMD_POWERCONTROL | booster | ((config.contrast >> 4) & 0x03),
+            LCD_CMD_FOLLOWERCONTROL | follower | (config.follower_ratio & 0x07),
+        ] {
+            self.write_bytes(device, false, &[command])?;
+            device.delay.delay_us(self.timings.command_us);
+        }
+        // wait 200 ms for the LCD voltage to stabilize
+        Ok(InitProgress::WaitUs(200_000))
+    }
+
     /// write one or more bytes to the display.
     /// The `rs_setting` parameter indcate if the data is a command or data. `true` for data, `false` for command.
     fn write_bytes<DELAY: DelayNs>(
@@ -377,10 +571,21 @@ where
 
         Ok(())
     }
+
+    /// copy `bytes` into `buffer` at `idx`, returning the index following them.
+    fn serialize_bytes(buffer: &mut [u8], idx: usize, bytes: &[u8]) -> Result<usize, CharacterDisplayError<I2C>> {
+        let end = idx + bytes.len();
+        if end > buffer.len() {
+            return Err(CharacterDisplayError::BufferTooSmall);
+        }
+        buffer[idx..end].copy_from_slice(bytes);
+        Ok(end)
+    }
 }
 
 
 #[cfg(test)]
+#[allow(clippy::redundant_field_names)]
 mod lib_tests {
     extern crate std;
     use crate::LcdDisplayType;
@@ -517,4 +722,92 @@ mod lib_tests {
         device.i2c.done();
     }
 
+    #[test]
+    fn test_soft_reset() {
+        let i2c_address = 0x3e;
+        let expected_i2c_transactions = std::vec![
+            // init
+            I2cTransaction::write(i2c_address, std::vec![0b0000_0000, 0x20 | 0x08]),
+            I2cTransaction::write(i2c_address, std::vec![0b0000_0000, 0x08 | 0x04]),
+            I2cTransaction::write(i2c_address, std::vec![0b0000_0000, 0x01]),
+            I2cTransaction::write(i2c_address, std::vec![0b0000_0000, 0x04 | 0x02]),
+            // show cursor
+            I2cTransaction::write(i2c_address, std::vec![0b0000_0000, 0x08 | 0x04 | 0x02]),
+            // soft reset restores the function set, display control and entry mode without clearing
+            I2cTransaction::write(i2c_address, std::vec![0b0000_0000, 0x20 | 0x08]),
+            I2cTransaction::write(i2c_address, std::vec![0b0000_0000, 0x08 | 0x04 | 0x02]),
+            I2cTransaction::write(i2c_address, std::vec![0b0000_0000, 0x04 | 0x02]),
+        ];
+        let i2c = I2cMock::new(&expected_i2c_transactions);
+        let mut driver = AIP31068::default();
+        let mut device = DeviceSetupConfig {
+            i2c,
+            address: i2c_address,
+            lcd_type: LcdDisplayType::Lcd16x2,
+            delay: NoopDelay,
+        };
+
+        assert!(driver.init(&mut device).is_ok());
+        assert!(driver.show_cursor(&mut device, true).is_ok());
+        assert!(driver.soft_reset(&mut device).is_ok());
+        device.i2c.done();
+    }
+
+    #[test]
+    fn test_init_profile() {
+        let i2c_address = 0x3e;
+        let expected_i2c_transactions = std::vec![
+            I2cTransaction::write(i2c_address, std::vec![0b0000_0000, 0x20 | 0x08]),
+            // extended instruction set: oscillator, contrast, booster on and follower
+            I2cTransaction::write(i2c_address, std::vec![0b0000_0000, 0x29]),
+            I2cTransaction::write(i2c_address, std::vec![0b0000_0000, 0x14]),
+            I2cTransaction::write(i2c_address, std::vec![0b0000_0000, 0x78]),
+            I2cTransaction::write(i2c_address, std::vec![0b0000_0000, 0x56]),
+            I2cTransaction::write(i2c_address, std::vec![0b0000_0000, 0x6D]),
+            I2cTransaction::write(i2c_address, std::vec![0b0000_0000, 0x28]),
+            // the rest of the standard initialization
+            I2cTransaction::write(i2c_address, std::vec![0b0000_0000, 0x08 | 0x04]),
+            I2cTransaction::write(i2c_address, std::vec![0b0000_0000, 0x01]),
+            I2cTransaction::write(i2c_address, std::vec![0b0000_0000, 0x04 | 0x02]),
+        ];
+        let i2c = I2cMock::new(&expected_i2c_transactions);
+        let mut driver = AIP31068::default();
+        let mut device = DeviceSetupConfig {
+            i2c,
+            address: i2c_address,
+            lcd_type: LcdDisplayType::Lcd16x2,
+            delay: NoopDelay,
+        };
+
+        <AIP31068<_> as DriverTrait<_, NoopDelay>>::set_init_profile(&mut driver, InitProfile::Supply3V3);
+        assert!(driver.init(&mut device).is_ok());
+        device.i2c.done();
+
+        // a custom configuration with 1/4 bias, the booster off and the follower off
+        let expected_i2c_transactions = std::vec![
+            I2cTransaction::write(i2c_address, std::vec![0b0000_0000, 0x20 | 0x08]),
+            I2cTransaction::write(i2c_address, std::vec![0b0000_0000, 0x29]),
+            I2cTransaction::write(i2c_address, std::vec![0b0000_0000, 0x1A]),
+            I2cTransaction::write(i2c_address, std::vec![0b0000_0000, 0x7F]),
+            I2cTransaction::write(i2c_address, std::vec![0b0000_0000, 0x53]),
+            I2cTransaction::write(i2c_address, std::vec![0b0000_0000, 0x60]),
+            I2cTransaction::write(i2c_address, std::vec![0b0000_0000, 0x28]),
+            I2cTransaction::write(i2c_address, std::vec![0b0000_0000, 0x08 | 0x04]),
+            I2cTransaction::write(i2c_address, std::vec![0b0000_0000, 0x01]),
+            I2cTransaction::write(i2c_address, std::vec![0b0000_0000, 0x04 | 0x02]),
+        ];
+        device.i2c = I2cMock::new(&expected_i2c_transactions);
+        let config = crate::VoltageConfig {
+            bias: LcdBias::OneFourth,
+            osc_frequency: 2,
+            booster: false,
+            follower: false,
+            follower_ratio: 0,
+            contrast: 0x3F,
+        };
+        <AIP31068<_> as DriverTrait<_, NoopDelay>>::set_init_profile(&mut driver, InitProfile::Custom(config));
+        assert!(driver.init(&mut device).is_ok());
+        device.i2c.done();
+    }
+
 }
\ No newline at end of file
@@ -0,0 +1,421 @@
+use embedded_hal::{delay::DelayNs, i2c};
+
+use crate::{
+    driver::{aip31068::AIP31068, DriverTrait},
+    BatchCommand, CharacterDisplayError, DeviceSetupConfig, FontSize, InitProfile, InitProgress,
+    Timings,
+};
+
+/// The I2C address of the PCA9633 RGB backlight controller on Grove 16x2 RGB LCD boards
+pub const PCA9633_I2C_ADDRESS: u8 = 0x62;
+
+// PCA9633 registers
+const PCA9633_REG_MODE1: u8 = 0x00; //  Mode register 1, controls sleep mode
+const PCA9633_REG_PWM0: u8 = 0x02; //  Brightness of LED0, wired to the blue LED
+const PCA9633_REG_LEDOUT: u8 = 0x08; //  LED output state
+
+// PCA9633 flags
+const PCA9633_FLAG_AUTOINCREMENT: u8 = 0x80; //  Auto-increment through all registers after each byte written
+const PCA9633_FLAG_MODE1_AWAKE: u8 = 0x00; //  Normal mode with the oscillator on
+const PCA9633_FLAG_MODE2_DMBLNK: u8 = 0x20; //  Group control is blinking rather than dimming
+const PCA9633_FLAG_LEDOUT_PWM: u8 = 0xAA; //  All LEDs driven by their individual PWM registers
+
+/// AIP31068 character display with a PCA9633 RGB backlight controller on the same I2C bus, as found on Grove
+/// 16x2 RGB LCD boards (up to v4). The text is sent to the AIP31068 at the display's I2C address, and the backlight
+/// color to the PCA9633 at `PCA9633_I2C_ADDRESS`. The backlight is initialized to white.
+pub struct AIP31068RGB<I2C>
+where
+    I2C: i2c::I2c,
+{
+    lcd: AIP31068<I2C>,
+    rgb: (u8, u8, u8),
+    brightness: u8,
+    backlight_on: bool,
+}
+
+impl<I2C> Default for AIP31068RGB<I2C>
+where
+    I2C: i2c::I2c,
+{
+    fn default() -> Self {
+        AIP31068RGB {
+            lcd: AIP31068::default(),
+            rgb: (0xFF, 0xFF, 0xFF),
+            brightness: 0xFF,
+            backlight_on: true,
+        }
+    }
+}
+
+impl<I2C, DELAY> DriverTrait<I2C, DELAY> for AIP31068RGB<I2C>
+where
+    I2C: i2c::I2c,
+    DELAY: DelayNs,
+{
+    fn default_i2c_address() -> u8 {
+        <AIP31068<I2C> as DriverTrait<I2C, DELAY>>::default_i2c_address()
+    }
+
+    fn supports_reads() -> bool {
+        false
+    }
+
+    fn set_font_size(&mut self, font_size: FontSize) {
+        <AIP31068<I2C> as DriverTrait<I2C, DELAY>>::set_font_size(&mut self.lcd, font_size);
+    }
+
+    fn set_init_profile(&mut self, profile: InitProfile) {
+        <AIP31068<I2C> as DriverTrait<I2C, DELAY>>::set_init_profile(&mut self.lcd, profile);
+    }
+
+    fn timings(&self) -> Timings {
+        <AIP31068<I2C> as DriverTrait<I2C, DELAY>>::timings(&self.lcd)
+    }
+
+    fn set_timings(&mut self, timings: Timings) {
+        <AIP31068<I2C> as DriverTrait<I2C, DELAY>>::set_timings(&mut self.lcd, timings);
+    }
+
+    fn control_flags(&self) -> (u8, u8) {
+        <AIP31068<I2C> as DriverTrait<I2C, DELAY>>::control_flags(&self.lcd)
+    }
+
+    fn set_control_flags(&mut self, display_control: u8, display_mode: u8) {
+        <AIP31068<I2C> as DriverTrait<I2C, DELAY>>::set_control_flags(
+            &mut self.lcd,
+            display_control,
+            display_mode,
+        );
+    }
+
+    fn init(
+        &mut self,
+        device: &mut DeviceSetupConfig<I2C, DELAY>,
+    ) -> Result<(), CharacterDisplayError<I2C>> {
+        self.lcd.init(device)?;
+        self.init_rgb(device)
+    }
+
+    fn init_step(
+        &mut self,
+        device: &mut DeviceSetupConfig<I2C, DELAY>,
+        step: usize,
+    ) -> Result<InitProgress, CharacterDisplayError<I2C>> {
+        let progress = self.lcd.init_step(device, step)?;
+        if progress == InitProgress::Done {
+            self.init_rgb(device)?;
+        }
+        Ok(progress)
+    }
+
+    fn soft_reset(
+        &mut self,
+        device: &mut DeviceSetupConfig<I2C, DELAY>,
+    ) -> Result<(), CharacterDisplayError<I2C>> {
+        self.lcd.soft_reset(device)
+    }
+
+    fn clear(
+        &mut self,
+        device: &mut DeviceSetupConfig<I2C, DELAY>,
+    ) -> Result<(), CharacterDisplayError<I2C>> {
+        self.lcd.clear(device)
+    }
+
+    fn home(
+        &mut self,
+        device: &mut DeviceSetupConfig<I2C, DELAY>,
+    ) -> Result<(), CharacterDisplayError<I2C>> {
+        self.lcd.home(device)
+    }
+
+    fn set_cursor(
+        &mut self,
+        device: &mut DeviceSetupConfig<I2C, DELAY>,
+        col: u8,
+        row: u8,
+    ) -> Result<(), CharacterDisplayError<I2C>> {
+        self.lcd.set_cursor(device, col, row)
+    }
+
+    fn set_ddram_address(
+        &mut self,
+        device: &mut DeviceSetupConfig<I2C, DELAY>,
+        row: u8,
+        address: u8,
+    ) -> Result<(), CharacterDisplayError<I2C>> {
+        self.lcd.set_ddram_address(device, row, address)
+    }
+
+    fn show_cursor(
+        &mut self,
+        device: &mut DeviceSetupConfig<I2C, DELAY>,
+        show_cursor: bool,
+    ) -> Result<(), CharacterDisplayError<I2C>> {
+        self.lcd.show_cursor(device, show_cursor)
+    }
+
+    fn blink_cursor(
+        &mut self,
+        device: &mut DeviceSetupConfig<I2C, DELAY>,
+        blink_cursor: bool,
+    ) -> Result<(), CharacterDisplayError<I2C>> {
+        self.lcd.blink_cursor(device, blink_cursor)
+    }
+
+    fn show_display(
+        &mut self,
+        device: &mut DeviceSetupConfig<I2C, DELAY>,
+        show_display: bool,
+    ) -> Result<(), CharacterDisplayError<I2C>> {
+        self.lcd.show_display(device, show_display)
+    }
+
+    fn scroll_left(
+        &mut self,
+        device: &mut DeviceSetupConfig<I2C, DELAY>,
+    ) -> Result<(), CharacterDisplayError<I2C>> {
+        self.lcd.scroll_left(device)
+    }
+
+    fn scroll_right(
+        &mut self,
+        device: &mut DeviceSetupConfig<I2C, DELAY>,
+    ) -> Result<(), CharacterDisplayError<I2C>> {
+        self.lcd.scroll_right(device)
+    }
+
+    fn left_to_right(
+        &mut self,
+        device: &mut DeviceSetupConfig<I2C, DELAY>,
+    ) -> Result<(), CharacterDisplayError<I2C>> {
+        self.lcd.left_to_right(device)
+    }
+
+    fn right_to_left(
+        &mut self,
+        device: &mut DeviceSetupConfig<I2C, DELAY>,
+    ) -> Result<(), CharacterDisplayError<I2C>> {
+        self.lcd.right_to_left(device)
+    }
+
+    fn autoscroll(
+        &mut self,
+        device: &mut DeviceSetupConfig<I2C, DELAY>,
+        autoscroll: bool,
+    ) -> Result<(), CharacterDisplayError<I2C>> {
+        self.lcd.autoscroll(device, autoscroll)
+    }
+
+    fn print(
+        &mut self,
+        device: &mut DeviceSetupConfig<I2C, DELAY>,
+        text: &str,
+    ) -> Result<(), CharacterDisplayError<I2C>> {
+        self.lcd.print(device, text)
+    }
+
+    fn write_data(
+        &mut self,
+        device: &mut DeviceSetupConfig<I2C, DELAY>,
+        data: &[u8],
+    ) -> Result<(), CharacterDisplayError<I2C>> {
+        self.lcd.write_data(device, data)
+    }
+
+    /// Turns the RGB backlight off, or back on with the last color set with `set_backlight_rgb()`.
+    fn backlight(
+        &mut self,
+        device: &mut DeviceSetupConfig<I2C, DELAY>,
+        on: bool,
+    ) -> Result<(), CharacterDisplayError<I2C>> {
+        self.backlight_on = on;
+        self.update_rgb(device)
+    }
+
+    fn set_backlight_rgb(
+        &mut self,
+        device: &mut DeviceSetupConfig<I2C, DELAY>,
+        red: u8,
+        green: u8,
+        blue: u8,
+    ) -> Result<(), CharacterDisplayError<I2C>> {
+        self.rgb = (red, green, blue);
+        self.update_rgb(device)
+    }
+
+    fn set_backlight_brightness(
+        &mut self,
+        device: &mut DeviceSetupConfig<I2C, DELAY>,
+        brightness: u8,
+    ) -> Result<(), CharacterDisplayError<I2C>> {
+        self.brightness = brightness;
+        self.update_rgb(device)
+    }
+
+    fn create_char(
+        &mut self,
+        device: &mut DeviceSetupConfig<I2C, DELAY>,
+        location: u8,
+        charmap: [u8; 8],
+    ) -> Result<(), CharacterDisplayError<I2C>> {
+        self.lcd.create_char(device, location, charmap)
+    }
+
+    /// Read the device data into the buffer.
+    /// This function is not supported by the AIP31068 driver.
+    fn read_device_data(
+        &self,
+        _device: &mut DeviceSetupConfig<I2C, DELAY>,
+        _buffer: &mut [u8],
+    ) -> Result<(), CharacterDisplayError<I2C>> {
+        Err(CharacterDisplayError::UnsupportedOperation)
+    }
+
+    /// Read the address counter.
+    /// This function is not supported by the AIP31068 driver.
+    fn read_address_counter(
+        &mut self,
+        _device: &mut DeviceSetupConfig<I2C, DELAY>,
+    ) -> Result<u8, CharacterDisplayError<I2C>> {
+        Err(CharacterDisplayError::UnsupportedOperation)
+    }
+
+    fn serialize_batch(
+        &mut self,
+        device: &mut DeviceSetupConfig<I2C, DELAY>,
+        commands: &[BatchCommand<'_>],
+        buffer: &mut [u8],
+    ) -> Result<usize, CharacterDisplayError<I2C>> {
+        self.lcd.serialize_batch(device, commands, buffer)
+    }
+}
+
+impl<I2C> AIP31068RGB<I2C>
+where
+    I2C: i2c::I2c,
+{
+    /// wake up the PCA9633 and set its outputs to PWM control, then show the current color
+    fn init_rgb<DELAY: DelayNs>(
+        &mut self,
+        device: &mut DeviceSetupConfig<I2C, DELAY>,
+    ) -> Result<(), CharacterDisplayError<I2C>> {
+        #[cfg(feature = "defmt")]
+        defmt::debug!("Initializing PCA9633 RGB backlight");
+        Self::write_rgb_registers(
+            device,
+            PCA9633_REG_MODE1,
+            &[PCA9633_FLAG_MODE1_AWAKE, PCA9633_FLAG_MODE2_DMBLNK],
+        )?;
+        Self::write_rgb_registers(device, PCA9633_REG_LEDOUT, &[PCA9633_FLAG_LEDOUT_PWM])?;
+        self.update_rgb(device)
+    }
+
+    /// write the current color scaled by the brightness to the PWM registers, or black if the backlight is off.
+    /// The PWM registers are ordered blue, green, red on the Grove boards.
+    fn update_rgb<DELAY: DelayNs>(
+        &self,
+        device: &mut DeviceSetupConfig<I2C, DELAY>,
+    ) -> Result<(), CharacterDisplayError<I2C>> {
+        let scale = |level: u8| (level as u16 * self.brightness as u16 / 0xFF) as u8;
+        let (red, green, blue) = if self.backlight_on {
+            (scale(self.rgb.0), scale(self.rgb.1), scale(self.rgb.2))
+        } else {
+            (0, 0, 0)
+        };
+        Self::write_rgb_registers(device, PCA9633_REG_PWM0, &[blue, green, red])
+    }
+
+    /// write consecutive PCA9633 registers, starting at the register `register`
+    fn write_rgb_registers<DELAY: DelayNs>(
+        device: &mut DeviceSetupConfig<I2C, DELAY>,
+        register: u8,
+        values: &[u8],
+    ) -> Result<(), CharacterDisplayError<I2C>> {
+        let mut buffer = [0u8; 4];
+        if values.len() >= buffer.len() {
+            return Err(CharacterDisplayError::BufferTooSmall);
+        }
+        buffer[0] = PCA9633_FLAG_AUTOINCREMENT | register;
+        buffer[1..=values.len()].copy_from_slice(values);
+        device
+            .i2c
+            .write(PCA9633_I2C_ADDRESS, &buffer[..=values.len()])
+            .map_err(CharacterDisplayError::I2cError)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+    use super::*;
+    use crate::LcdDisplayType;
+    use embedded_hal_mock::eh1::{
+        delay::NoopDelay,
+        i2c::{Mock as I2cMock, Transaction as I2cTransaction},
+    };
+
+    #[test]
+    fn test_aip31068_rgb_init() {
+        let i2c_address = 0x3e;
+        let expected_i2c_transactions = std::vec![
+            // AIP31068 initialization
+            I2cTransaction::write(i2c_address, std::vec![0x00, 0x28]),
+            I2cTransaction::write(i2c_address, std::vec![0x00, 0x0C]),
+            I2cTransaction::write(i2c_address, std::vec![0x00, 0x01]),
+            I2cTransaction::write(i2c_address, std::vec![0x00, 0x06]),
+            // PCA9633 mode registers, LED output state and white backlight
+            I2cTransaction::write(PCA9633_I2C_ADDRESS, std::vec![0x80, 0x00, 0x20]),
+            I2cTransaction::write(PCA9633_I2C_ADDRESS, std::vec![0x88, 0xAA]),
+            I2cTransaction::write(PCA9633_I2C_ADDRESS, std::vec![0x82, 0xFF, 0xFF, 0xFF]),
+        ];
+
+        let i2c = I2cMock::new(&expected_i2c_transactions);
+        let mut driver = AIP31068RGB::default();
+        let mut device = DeviceSetupConfig {
+            i2c,
+            address: i2c_address,
+            lcd_type: LcdDisplayType::Lcd16x2,
+            delay: NoopDelay,
+        };
+
+        assert!(driver.init(&mut device).is_ok());
+        device.i2c.done();
+    }
+
+    #[test]
+    fn test_aip31068_rgb_set_backlight_rgb() {
+        let expected_i2c_transactions = std::vec![
+            // PWM registers are ordered blue, green, red
+            I2cTransaction::write(PCA9633_I2C_ADDRESS, std::vec![0x82, 0x30, 0x20, 0x10]),
+            // backlight off, then back on with the same color
+            I2cTransaction::write(PCA9633_I2C_ADDRESS, std::vec![0x82, 0x00, 0x00, 0x00]),
+            I2cTransaction::write(PCA9633_I2C_ADDRESS, std::vec![0x82, 0x30, 0x20, 0x10]),
+        ];
+
+        let i2c = I2cMock::new(&expected_i2c_transactions);
+        let mut driver = AIP31068RGB::default();
+        let mut device = DeviceSetupConfig {
+            i2c,
+            address: 0x3e,
+            lcd_type: LcdDisplayType::Lcd16x2,
+            delay: NoopDelay,
+        };
+
+        assert!(driver
+            .set_backlight_rgb(&mut device, 0x10, 0x20, 0x30)
+            .is_ok());
+        assert!(driver.backlight(&mut device, false).is_ok());
+        assert!(driver.backlight(&mut device, true).is_ok());
+        device.i2c.done();
+
+        // the brightness scales the color
+        let expected_i2c_transactions = std::vec![I2cTransaction::write(
+            PCA9633_I2C_ADDRESS,
+            std::vec![0x82, 0x18, 0x10, 0x08]
+        )];
+        device.i2c = I2cMock::new(&expected_i2c_transactions);
+        assert!(driver.set_backlight_brightness(&mut device, 0x80).is_ok());
+        device.i2c.done();
+    }
+}
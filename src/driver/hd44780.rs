@@ -1,23 +1,35 @@
-mod adapter;
+pub mod adapter;
 
 use core::marker::PhantomData;
 use embedded_hal::{delay::DelayNs, i2c};
 
+use adapter::{AdapterI2c, AdapterWrites};
+
 use crate::{
     driver::{
         hd44780::adapter::{
             adafruit_lcd_backpack::AdafruitLCDBackpackAdapter,
+            buttons_pcf8574t::ButtonsPCF8574TAdapter,
+            contrast_pcf8574t::ContrastPCF8574TAdapter,
             dual_controller_pcf8574t::DualHD44780_PCF8574TAdapter,
-            generic_pcf8574t::GenericPCF8574TAdapter, HD44780AdapterTrait,
+            generic_pcf8574t::GenericPCF8574TAdapter,
+            mcp23017::MCP23017Adapter,
+            multi_controller_pcf8575::MultiHD44780_PCF8575Adapter, HD44780AdapterTrait,
         },
-        DriverTrait,
+        init_in_steps, DriverTrait,
     },
-    CharacterDisplayError, DeviceSetupConfig,
+    BusyPollStrategy, CharacterDisplayError, CommandCompletion, ContrastPreset, DeviceSetupConfig,
+    FontSize, I2cPacing, InitProgress, Timings,
 };
 
 pub type GenericHD44780PCF8574T<I2C> = HD44780<GenericPCF8574TAdapter<I2C>, I2C>;
 pub type AdafruitLCDBackpack<I2C> = HD44780<AdafruitLCDBackpackAdapter<I2C>, I2C>;
+pub type ContrastHD44780PCF8574T<I2C> = HD44780<ContrastPCF8574TAdapter<I2C>, I2C>;
+pub type ButtonsHD44780PCF8574T<I2C> = HD44780<ButtonsPCF8574TAdapter<I2C>, I2C>;
+pub type MCP23017HD44780<I2C> = HD44780<MCP23017Adapter<I2C>, I2C>;
 pub type DualHD44780PCF8574T<I2C> = HD44780<DualHD44780_PCF8574TAdapter<I2C>, I2C>;
+pub type MultiHD44780PCF8575<I2C, const N: usize> =
+    HD44780<MultiHD44780_PCF8575Adapter<I2C, N>, I2C>;
 
 // commands
 const LCD_CMD_CLEARDISPLAY: u8 = 0x01; //  Clear display, set cursor position to zero
@@ -58,7 +70,32 @@ const LCD_FLAG_5x10_DOTS: u8 = 0x04; //  10 pixel high font mode
 const LCD_FLAG_5x8_DOTS: u8 = 0x00; //  8 pixel high font mode
 
 /// The number of HD44780 controllers that can be supported on one device
-const MAX_CONTROLLER_COUNT: usize = 2;
+pub const MAX_CONTROLLER_COUNT: usize = 8;
+
+/// The delays after each of the writes that put a controller into 8 bit mode, in microseconds
+const SYNC_DELAYS_US: [u32; 3] = [5000, 5000, 150];
+/// The number of `init_step()` steps that initialize each controller: the three writes that put it into 8 bit mode,
+/// setting up its interface and state and clearing it, and returning it home
+const INIT_STEPS_PER_CONTROLLER: usize = 5;
+
+/// The time the controller takes to execute a data write or most commands, which must pass before the next byte
+/// reaches it, in nanoseconds
+const EXECUTION_TIME_NS: u64 = 37_000;
+/// The I2C bit times between the enable pulse falling edges of the low nibble of a byte and of the high nibble of the
+/// next byte, the two GPIO updates of the high nibble, when the GPIO updates are packed into one write at 9 bits each
+const PACKED_BITS_PER_BYTE: u64 = 18;
+/// The I2C bit times between the enable pulse falling edges of the low nibble of a byte and of the high nibble of the
+/// next byte when each GPIO update is its own write, at 20 bits each with the start, address and stop
+const UNPACKED_BITS_PER_BYTE: u64 = 40;
+
+/// The datasheet times of the HD44780. There is no power on wait, as the display is powered well before it is
+/// initialized, and no wait after commands or between enable pulses, as the I2C writes to the adapter take longer.
+const DEFAULT_TIMINGS: Timings = Timings {
+    power_on_us: 0,
+    clear_home_us: 2000,
+    command_us: 0,
+    enable_pulse_us: 0,
+};
 
 pub struct HD44780<ADAPTER, I2C>
 where
@@ -70,6 +107,18 @@ where
     display_control: [u8; MAX_CONTROLLER_COUNT],
     display_mode: [u8; MAX_CONTROLLER_COUNT],
     active_controller: usize,
+    /// The controller last sent a command or data to, or read from
+    last_controller: usize,
+    busy_poll_strategy: BusyPollStrategy,
+    command_completion: CommandCompletion,
+    ready_signal: Option<fn() -> bool>,
+    font_size: FontSize,
+    writes: AdapterWrites,
+    timings: Timings,
+    /// The wait after each nibble written through the 4 bit interface, in microseconds
+    nibble_delay_us: u32,
+    /// The pacing of the writes needed at the declared I2C bus speed
+    pacing: I2cPacing,
     _marker: PhantomData<I2C>,
 }
 
@@ -86,6 +135,15 @@ where
                 MAX_CONTROLLER_COUNT],
             display_mode: [LCD_FLAG_ENTRYLEFT | LCD_FLAG_ENTRYSHIFTDECREMENT; MAX_CONTROLLER_COUNT],
             active_controller: 0,
+            last_controller: 0,
+            busy_poll_strategy: BusyPollStrategy::default(),
+            command_completion: CommandCompletion::FixedDelay,
+            ready_signal: None,
+            font_size: FontSize::Dots5x8,
+            writes: AdapterWrites::default(),
+            timings: DEFAULT_TIMINGS,
+            nibble_delay_us: 0,
+            pacing: I2cPacing::FullSpeed,
             _marker: PhantomData,
         }
     }
@@ -101,83 +159,171 @@ where
         ADAPTER::default_i2c_address()
     }
 
+    fn alternate_i2c_address(address: u8) -> Option<u8> {
+        ADAPTER::alternate_i2c_address(address)
+    }
+
     fn supports_reads() -> bool {
         ADAPTER::supports_reads()
     }
 
+    fn controller_count(&self) -> usize {
+        self.adapter.controller_count()
+    }
+
+    fn last_controller(&self) -> usize {
+        self.last_controller
+    }
+
+    fn set_busy_poll_strategy(&mut self, strategy: BusyPollStrategy) {
+        self.busy_poll_strategy = strategy;
+    }
+
+    fn set_command_completion(&mut self, completion: CommandCompletion) {
+        self.command_completion = completion;
+    }
+
+    fn set_i2c_retries(&mut self, retries: u8, interval_us: u32) {
+        self.writes.retries = retries;
+        self.writes.interval_us = interval_us;
+    }
+
+    fn take_retried_writes(&mut self) -> u32 {
+        core::mem::take(&mut self.writes.retried)
+    }
+
+    fn set_nibble_delay(&mut self, delay_us: u32) {
+        self.nibble_delay_us = delay_us;
+    }
+
+    fn set_i2c_frequency(&mut self, frequency_hz: u32) {
+        self.pacing = Self::pacing_for_frequency(frequency_hz);
+        #[cfg(feature = "defmt")]
+        if self.pacing != I2cPacing::FullSpeed {
+            defmt::warn!("HD44780 writes are paced for a {} Hz I2C bus", frequency_hz);
+        }
+    }
+
+    fn i2c_pacing(&self) -> I2cPacing {
+        self.pacing
+    }
+
+    fn timings(&self) -> Timings {
+        self.timings
+    }
+
+    fn set_timings(&mut self, timings: Timings) {
+        self.timings = timings;
+        self.writes.spacing_us = timings.enable_pulse_us;
+    }
+
+    fn set_ready_signal(&mut self, is_ready: Option<fn() -> bool>) {
+        self.ready_signal = is_ready;
+    }
+
+    fn set_font_size(&mut self, font_size: FontSize) {
+        self.font_size = font_size;
+    }
+
+    fn controller_row(&self, row: u8) -> (usize, u8) {
+        self.adapter.row_to_controller_row(row)
+    }
+
+    fn set_controller_rows(&mut self, table: &'static [(usize, u8)]) {
+        self.adapter.set_row_table(table);
+    }
+
+    fn control_flags(&self) -> (u8, u8) {
+        // only the active controller shows the cursor, so the flags of all controllers are combined
+        let display_control = self.display_control[..self.adapter.controller_count()]
+            .iter()
+            .fold(0, |flags, control| flags | control);
+        (display_control, self.display_mode[0])
+    }
+
+    fn set_control_flags(&mut self, display_control: u8, display_mode: u8) {
+        // `soft_reset()` makes the first controller the active one, which is the one showing the cursor
+        for controller in 0..MAX_CONTROLLER_COUNT {
+            self.display_control[controller] = if controller == 0 {
+                display_control
+            } else {
+                display_control & !(LCD_FLAG_CURSORON | LCD_FLAG_BLINKON)
+            };
+            self.display_mode[controller] = display_mode;
+        }
+    }
+
     fn init(
         &mut self,
         device: &mut DeviceSetupConfig<I2C, DELAY>,
     ) -> Result<(), CharacterDisplayError<I2C>> {
-        if !ADAPTER::is_supported(device.lcd_type) {
-            return Err(CharacterDisplayError::UnsupportedDisplayType);
+        init_in_steps(self, device)
+    }
+
+    fn init_step(
+        &mut self,
+        device: &mut DeviceSetupConfig<I2C, DELAY>,
+        step: usize,
+    ) -> Result<InitProgress, CharacterDisplayError<I2C>> {
+        if step == 0 {
+            if !ADAPTER::is_supported(device.lcd_type) {
+                return Err(CharacterDisplayError::UnsupportedDisplayType);
+            }
+            self.adapter
+                .init(&mut device.i2c, device.address)
+                .map_err(CharacterDisplayError::I2cError)?;
+            // wait for the controllers to come out of their power on reset
+            return Ok(InitProgress::WaitUs(self.timings.power_on_us));
         }
 
-        self.adapter
-            .init(&mut device.i2c, device.address)
-            .map_err(CharacterDisplayError::I2cError)?;
+        // each controller is initialized in turn, followed by a last step that sets up the display
+        let step = step - 1;
+        let controller = step / INIT_STEPS_PER_CONTROLLER;
+        if controller == self.adapter.controller_count() {
+            self.backlight(device, true)?;
+            self.active_controller = 0;
+            return Ok(InitProgress::Done);
+        }
+        if controller >= MAX_CONTROLLER_COUNT {
+            return Err(CharacterDisplayError::BadDeviceId);
+        }
+        match step % INIT_STEPS_PER_CONTROLLER {
+            sync @ 0..=2 => {
+                if sync == 0 {
+                    let interface = if ADAPTER::uses_8bit_interface() {
+                        LCD_FLAG_8BITMODE
+                    } else {
+                        LCD_FLAG_4BITMODE
+                    };
+                    // the 5x10 font is only available in one line mode
+                    self.display_function[controller] = match self.font_size {
+                        FontSize::Dots5x8 => interface | LCD_FLAG_5x8_DOTS | LCD_FLAG_2LINE,
+                        FontSize::Dots5x10 => interface | LCD_FLAG_5x10_DOTS | LCD_FLAG_1LINE,
+                    };
+                }
+                self.write_sync(device, controller)?;
+                Ok(InitProgress::WaitUs(SYNC_DELAYS_US[sync]))
+            }
+            3 => {
+                self.finish_sync(device, controller)?;
+                self.restore_controller_state(device, controller)?;
+                self.send_slow_init_command(device, controller, LCD_CMD_CLEARDISPLAY)
+            }
+            _ => self.send_slow_init_command(device, controller, LCD_CMD_RETURNHOME),
+        }
+    }
 
+    fn soft_reset(
+        &mut self,
+        device: &mut DeviceSetupConfig<I2C, DELAY>,
+    ) -> Result<(), CharacterDisplayError<I2C>> {
         for controller in 0..self.adapter.controller_count() {
             if controller >= MAX_CONTROLLER_COUNT {
                 return Err(CharacterDisplayError::BadDeviceId);
             }
-
-            self.display_function[controller] =
-                LCD_FLAG_4BITMODE | LCD_FLAG_5x8_DOTS | LCD_FLAG_2LINE;
-
-            // Put LCD into 4 bit mode, device starts in 8 bit mode
-            self.adapter.write_nibble_to_controller(
-                &mut device.i2c,
-                device.address,
-                controller,
-                false,
-                0x03,
-            )?;
-            device.delay.delay_ms(5);
-            self.adapter.write_nibble_to_controller(
-                &mut device.i2c,
-                device.address,
-                controller,
-                false,
-                0x03,
-            )?;
-            device.delay.delay_ms(5);
-            self.adapter.write_nibble_to_controller(
-                &mut device.i2c,
-                device.address,
-                controller,
-                false,
-                0x03,
-            )?;
-            device.delay.delay_us(150);
-            self.adapter.write_nibble_to_controller(
-                &mut device.i2c,
-                device.address,
-                controller,
-                false,
-                0x02,
-            )?;
-
-            self.send_command_to_controller(
-                device,
-                controller,
-                LCD_CMD_FUNCTIONSET | self.display_function[controller],
-            )?;
-            self.send_command_to_controller(
-                device,
-                controller,
-                LCD_CMD_DISPLAYCONTROL | self.display_control[controller],
-            )?;
-            self.send_command_to_controller(
-                device,
-                controller,
-                LCD_CMD_ENTRYMODESET | self.display_mode[controller],
-            )?;
-            self.clear_controller(device, controller)?;
-            self.home_controller(device, controller)?;
+            self.sync_controller_interface(device, controller)?;
+            self.restore_controller_state(device, controller)?;
         }
-        // set up the display
-        self.backlight(device, true)?;
         self.active_controller = 0;
         Ok(())
     }
@@ -189,6 +335,8 @@ where
         for controller in 0..self.adapter.controller_count() {
             self.clear_controller(device, controller)?;
         }
+        // like `home()`, continue at the top left of the display
+        self.active_controller = 0;
         Ok(())
     }
 
@@ -196,7 +344,10 @@ where
         &mut self,
         device: &mut DeviceSetupConfig<I2C, DELAY>,
     ) -> Result<(), CharacterDisplayError<I2C>> {
-        self.home_controller(device, 0)?;
+        // returning home also undoes any display shift, which must happen on every controller
+        for controller in 0..self.adapter.controller_count() {
+            self.home_controller(device, controller)?;
+        }
         self.active_controller = 0;
         Ok(())
     }
@@ -208,10 +359,10 @@ where
         row: u8,
     ) -> Result<(), CharacterDisplayError<I2C>> {
         if row >= device.lcd_type.rows() {
-            return Err(CharacterDisplayError::RowOutOfRange);
+            return Err(CharacterDisplayError::RowOutOfRange { row });
         }
         if col >= device.lcd_type.cols() {
-            return Err(CharacterDisplayError::ColumnOutOfRange);
+            return Err(CharacterDisplayError::ColumnOutOfRange { col });
         }
 
         let (controller, controller_row) = self.adapter.row_to_controller_row(row);
@@ -219,6 +370,20 @@ where
         self.set_cursor_controller(device, self.active_controller, col, controller_row)
     }
 
+    fn set_ddram_address(
+        &mut self,
+        device: &mut DeviceSetupConfig<I2C, DELAY>,
+        row: u8,
+        address: u8,
+    ) -> Result<(), CharacterDisplayError<I2C>> {
+        if row >= device.lcd_type.rows() {
+            return Err(CharacterDisplayError::RowOutOfRange { row });
+        }
+        let (controller, _) = self.adapter.row_to_controller_row(row);
+        self.active_controller = controller;
+        self.send_command_to_controller(device, controller, LCD_CMD_SETDDRAMADDR | address)
+    }
+
     fn show_cursor(
         &mut self,
         device: &mut DeviceSetupConfig<I2C, DELAY>,
@@ -321,14 +486,75 @@ where
         self.print_controller(device, self.active_controller, text)
     }
 
+    fn write_data(
+        &mut self,
+        device: &mut DeviceSetupConfig<I2C, DELAY>,
+        data: &[u8],
+    ) -> Result<(), CharacterDisplayError<I2C>> {
+        self.write_bytes_to_controller(
+            device,
+            self.active_controller,
+            data.iter().map(|byte| (true, *byte)),
+        )
+    }
+
+    fn write_nibble(
+        &mut self,
+        device: &mut DeviceSetupConfig<I2C, DELAY>,
+        controller: usize,
+        rs_setting: bool,
+        value: u8,
+    ) -> Result<(), CharacterDisplayError<I2C>> {
+        if ADAPTER::uses_8bit_interface() {
+            return Err(CharacterDisplayError::UnsupportedOperation);
+        }
+        self.write_nibble_to_controller(device, controller, rs_setting, value)
+    }
+
+    fn write_byte(
+        &mut self,
+        device: &mut DeviceSetupConfig<I2C, DELAY>,
+        controller: usize,
+        rs_setting: bool,
+        value: u8,
+    ) -> Result<(), CharacterDisplayError<I2C>> {
+        self.write_byte_to_controller(device, controller, rs_setting, value)
+    }
+
     fn backlight(
         &mut self,
         device: &mut DeviceSetupConfig<I2C, DELAY>,
         on: bool,
     ) -> Result<(), CharacterDisplayError<I2C>> {
         self.adapter.set_backlight(on);
+        self.adapter.write_bits_to_gpio(
+            &mut AdapterI2c::new(&mut device.i2c, &mut device.delay, &mut self.writes),
+            device.address,
+        )
+    }
+
+    fn set_contrast_preset(
+        &mut self,
+        device: &mut DeviceSetupConfig<I2C, DELAY>,
+        preset: ContrastPreset,
+    ) -> Result<(), CharacterDisplayError<I2C>> {
+        self.adapter.set_contrast_preset(preset)?;
+        self.adapter.write_bits_to_gpio(
+            &mut AdapterI2c::new(&mut device.i2c, &mut device.delay, &mut self.writes),
+            device.address,
+        )
+    }
+
+    fn input_pins(&self) -> u8 {
+        self.adapter.input_pins()
+    }
+
+    fn read_input_pins(
+        &mut self,
+        device: &mut DeviceSetupConfig<I2C, DELAY>,
+    ) -> Result<u8, CharacterDisplayError<I2C>> {
         self.adapter
-            .write_bits_to_gpio(&mut device.i2c, device.address)
+            .read_input_pins(&mut device.i2c, device.address)
     }
 
     fn create_char(
@@ -343,6 +569,20 @@ where
         Ok(())
     }
 
+    fn create_char_for_controller(
+        &mut self,
+        device: &mut DeviceSetupConfig<I2C, DELAY>,
+        controller: usize,
+        location: u8,
+        charmap: [u8; 8],
+    ) -> Result<(), CharacterDisplayError<I2C>> {
+        if controller >= self.adapter.controller_count() {
+            return Err(CharacterDisplayError::BadDeviceId);
+        }
+        self.create_char_controller(device, controller, location, charmap)?;
+        Ok(())
+    }
+
     fn read_device_data(
         &self,
         device: &mut DeviceSetupConfig<I2C, DELAY>,
@@ -352,6 +592,13 @@ where
             return Err(CharacterDisplayError::ReadNotSupported);
         }
 
+        self.adapter.wait_until_ready(
+            &mut device.i2c,
+            device.address,
+            self.busy_poll_strategy,
+            self.ready_signal,
+            &mut device.delay,
+        )?;
         self.adapter.read_bytes_from_controller(
             &mut device.i2c,
             device.address,
@@ -369,7 +616,15 @@ where
             return Err(CharacterDisplayError::ReadNotSupported);
         }
         let mut buffer = [0];
+        self.last_controller = self.active_controller;
 
+        self.adapter.wait_until_ready(
+            &mut device.i2c,
+            device.address,
+            self.busy_poll_strategy,
+            self.ready_signal,
+            &mut device.delay,
+        )?;
         self.adapter.read_bytes_from_controller(
             &mut device.i2c,
             device.address,
@@ -393,12 +648,234 @@ where
         controller: usize,
         command: u8,
     ) -> Result<(), CharacterDisplayError<I2C>> {
-        self.adapter.write_byte_to_controller(
-            &mut device.i2c,
+        self.write_byte_to_controller(device, controller, false, command)?;
+        if self.timings.command_us > 0 {
+            device.delay.delay_us(self.timings.command_us);
+        }
+        Ok(())
+    }
+
+    /// The pacing of the writes that keeps the bytes from reaching the controller faster than it executes them on an
+    /// I2C bus running at `frequency_hz`. Adapters using the 8 bit interface write each GPIO update to a register of
+    /// their expander, which takes long enough at any speed the expander supports.
+    fn pacing_for_frequency(frequency_hz: u32) -> I2cPacing {
+        if frequency_hz == 0 || ADAPTER::uses_8bit_interface() {
+            return I2cPacing::FullSpeed;
+        }
+        let byte_time_ns = |bits: u64| bits * 1_000_000_000 / frequency_hz as u64;
+        if ADAPTER::supports_packed_writes()
+            && byte_time_ns(PACKED_BITS_PER_BYTE) >= EXECUTION_TIME_NS
+        {
+            return I2cPacing::FullSpeed;
+        }
+        let unpacked_time_ns = byte_time_ns(UNPACKED_BITS_PER_BYTE);
+        if unpacked_time_ns >= EXECUTION_TIME_NS {
+            return if ADAPTER::supports_packed_writes() {
+                I2cPacing::Unpacked
+            } else {
+                I2cPacing::FullSpeed
+            };
+        }
+        // the rest of the execution time is waited for after the low nibble of each byte, the only nibble delay
+        // between its falling edge and that of the high nibble of the next byte
+        let nibble_delay_us = (EXECUTION_TIME_NS - unpacked_time_ns).div_ceil(1_000) as u32;
+        I2cPacing::Delayed { nibble_delay_us }
+    }
+
+    /// The wait after each nibble written through the 4 bit interface, the longer of the one set with
+    /// `set_nibble_delay()` and the one needed at the declared I2C bus speed.
+    fn nibble_delay(&self) -> u32 {
+        match self.pacing {
+            I2cPacing::Delayed { nibble_delay_us } => nibble_delay_us.max(self.nibble_delay_us),
+            _ => self.nibble_delay_us,
+        }
+    }
+
+    /// Write a byte to a controller. Through the 4 bit interface, a byte is written as two nibbles when the nibbles are
+    /// paced with a delay.
+    fn write_byte_to_controller<DELAY: DelayNs>(
+        &mut self,
+        device: &mut DeviceSetupConfig<I2C, DELAY>,
+        controller: usize,
+        rs_setting: bool,
+        value: u8,
+    ) -> Result<(), CharacterDisplayError<I2C>> {
+        if self.nibble_delay() == 0 || ADAPTER::uses_8bit_interface() {
+            self.last_controller = controller;
+            return self.adapter.write_byte_to_controller(
+                &mut AdapterI2c::new(&mut device.i2c, &mut device.delay, &mut self.writes),
+                device.address,
+                controller,
+                rs_setting,
+                value,
+            );
+        }
+        self.write_nibble_to_controller(device, controller, rs_setting, value >> 4)?;
+        self.write_nibble_to_controller(device, controller, rs_setting, value & 0x0F)
+    }
+
+    /// Write the lower nibble of `value` to a controller through the 4 bit interface, followed by the nibble delay.
+    fn write_nibble_to_controller<DELAY: DelayNs>(
+        &mut self,
+        device: &mut DeviceSetupConfig<I2C, DELAY>,
+        controller: usize,
+        rs_setting: bool,
+        value: u8,
+    ) -> Result<(), CharacterDisplayError<I2C>> {
+        self.last_controller = controller;
+        self.adapter.write_nibble_to_controller(
+            &mut AdapterI2c::new(&mut device.i2c, &mut device.delay, &mut self.writes),
             device.address,
             controller,
-            false,
-            command,
+            rs_setting,
+            value,
+        )?;
+        let nibble_delay_us = self.nibble_delay();
+        if nibble_delay_us > 0 {
+            device.delay.delay_us(nibble_delay_us);
+        }
+        Ok(())
+    }
+
+    /// Write bytes to a controller, each given as `( rs_setting, value )`, in as few I2C writes as the adapter allows,
+    /// unless the writes are paced, which takes a write for each GPIO update.
+    fn write_bytes_to_controller<DELAY, BYTES>(
+        &mut self,
+        device: &mut DeviceSetupConfig<I2C, DELAY>,
+        controller: usize,
+        bytes: BYTES,
+    ) -> Result<(), CharacterDisplayError<I2C>>
+    where
+        DELAY: DelayNs,
+        BYTES: IntoIterator<Item = (bool, u8)>,
+    {
+        if self.timings.enable_pulse_us == 0
+            && self.nibble_delay() == 0
+            && self.pacing == I2cPacing::FullSpeed
+        {
+            self.last_controller = controller;
+            return self.adapter.write_bytes_to_controller(
+                &mut AdapterI2c::new(&mut device.i2c, &mut device.delay, &mut self.writes),
+                device.address,
+                controller,
+                bytes,
+            );
+        }
+        for (rs_setting, value) in bytes {
+            self.write_byte_to_controller(device, controller, rs_setting, value)?;
+        }
+        Ok(())
+    }
+
+    /// Wait for a slow command to complete, either for `delay_us` microseconds or by polling the busy flag as set by
+    /// the busy poll strategy, depending on the command completion setting.
+    fn wait_for_slow_command<DELAY: DelayNs>(
+        &self,
+        device: &mut DeviceSetupConfig<I2C, DELAY>,
+        delay_us: u32,
+    ) -> Result<(), CharacterDisplayError<I2C>> {
+        match self.command_completion {
+            CommandCompletion::BusyFlag if ADAPTER::supports_reads() => {
+                self.adapter.wait_until_ready(
+                    &mut device.i2c,
+                    device.address,
+                    self.busy_poll_strategy,
+                    self.ready_signal,
+                    &mut device.delay,
+                )
+            }
+            _ => {
+                device.delay.delay_us(delay_us);
+                Ok(())
+            }
+        }
+    }
+
+    /// Put a controller into the interface mode used by the adapter, whatever mode it was in. For the 4 bit
+    /// interface, this works even when the controller has lost track of which nibble comes next, because the
+    /// controller is first put in 8 bit mode.
+    fn sync_controller_interface<DELAY: DelayNs>(
+        &mut self,
+        device: &mut DeviceSetupConfig<I2C, DELAY>,
+        controller: usize,
+    ) -> Result<(), CharacterDisplayError<I2C>> {
+        for delay_us in SYNC_DELAYS_US {
+            self.write_sync(device, controller)?;
+            device.delay.delay_us(delay_us);
+        }
+        self.finish_sync(device, controller)
+    }
+
+    /// Send one of the writes that put a controller into 8 bit mode, each of which must be followed by its delay in
+    /// `SYNC_DELAYS_US`.
+    fn write_sync<DELAY: DelayNs>(
+        &mut self,
+        device: &mut DeviceSetupConfig<I2C, DELAY>,
+        controller: usize,
+    ) -> Result<(), CharacterDisplayError<I2C>> {
+        if ADAPTER::uses_8bit_interface() {
+            self.write_byte_to_controller(
+                device,
+                controller,
+                false,
+                LCD_CMD_FUNCTIONSET | LCD_FLAG_8BITMODE,
+            )
+        } else {
+            self.write_nibble_to_controller(device, controller, false, 0x03)
+        }
+    }
+
+    /// Switch a controller put into 8 bit mode by `write_sync()` to 4 bit mode, if the adapter uses the 4 bit
+    /// interface.
+    fn finish_sync<DELAY: DelayNs>(
+        &mut self,
+        device: &mut DeviceSetupConfig<I2C, DELAY>,
+        controller: usize,
+    ) -> Result<(), CharacterDisplayError<I2C>> {
+        if ADAPTER::uses_8bit_interface() {
+            return Ok(());
+        }
+        self.write_nibble_to_controller(device, controller, false, 0x02)
+    }
+
+    /// Send the clear or home command during a stepped initialization, returning the time the command takes rather
+    /// than waiting for it, unless its completion is signaled by the busy flag, which is then polled right away.
+    fn send_slow_init_command<DELAY: DelayNs>(
+        &mut self,
+        device: &mut DeviceSetupConfig<I2C, DELAY>,
+        controller: usize,
+        command: u8,
+    ) -> Result<InitProgress, CharacterDisplayError<I2C>> {
+        self.send_command_to_controller(device, controller, command)?;
+        match self.command_completion {
+            CommandCompletion::BusyFlag if ADAPTER::supports_reads() => {
+                self.wait_for_slow_command(device, self.timings.clear_home_us)?;
+                Ok(InitProgress::WaitUs(0))
+            }
+            _ => Ok(InitProgress::WaitUs(self.timings.clear_home_us)),
+        }
+    }
+
+    /// Send the tracked function set, display control and entry mode state to a controller.
+    fn restore_controller_state<DELAY: DelayNs>(
+        &mut self,
+        device: &mut DeviceSetupConfig<I2C, DELAY>,
+        controller: usize,
+    ) -> Result<(), CharacterDisplayError<I2C>> {
+        self.send_command_to_controller(
+            device,
+            controller,
+            LCD_CMD_FUNCTIONSET | self.display_function[controller],
+        )?;
+        self.send_command_to_controller(
+            device,
+            controller,
+            LCD_CMD_DISPLAYCONTROL | self.display_control[controller],
+        )?;
+        self.send_command_to_controller(
+            device,
+            controller,
+            LCD_CMD_ENTRYMODESET | self.display_mode[controller],
         )
     }
 
@@ -408,8 +885,7 @@ where
         controller: usize,
     ) -> Result<(), CharacterDisplayError<I2C>> {
         self.send_command_to_controller(device, controller, LCD_CMD_CLEARDISPLAY)?;
-        device.delay.delay_ms(2);
-        Ok(())
+        self.wait_for_slow_command(device, self.timings.clear_home_us)
     }
 
     /// Set the cursor to the home position on a specific HD44780 controller device
@@ -419,8 +895,7 @@ where
         controller: usize,
     ) -> Result<(), CharacterDisplayError<I2C>> {
         self.send_command_to_controller(device, controller, LCD_CMD_RETURNHOME)?;
-        device.delay.delay_ms(2);
-        Ok(())
+        self.wait_for_slow_command(device, self.timings.clear_home_us)
     }
 
     /// Set the cursor position at specified column and row on a specific HD44780 controller device.
@@ -433,10 +908,10 @@ where
         row: u8,
     ) -> Result<(), CharacterDisplayError<I2C>> {
         if row >= device.lcd_type.rows() {
-            return Err(CharacterDisplayError::RowOutOfRange);
+            return Err(CharacterDisplayError::RowOutOfRange { row });
         }
         if col >= device.lcd_type.cols() {
-            return Err(CharacterDisplayError::ColumnOutOfRange);
+            return Err(CharacterDisplayError::ColumnOutOfRange { col });
         }
 
         self.send_command_to_controller(
@@ -546,8 +1021,7 @@ where
         device: &mut DeviceSetupConfig<I2C, DELAY>,
         controller: usize,
     ) -> Result<(), CharacterDisplayError<I2C>> {
-        // TODO revisit this function's logic
-        self.display_mode[controller] |= LCD_FLAG_ENTRYRIGHT;
+        self.display_mode[controller] &= !LCD_FLAG_ENTRYLEFT;
         self.send_command_to_controller(
             device,
             controller,
@@ -581,20 +1055,12 @@ where
         location: u8,
         charmap: [u8; 8],
     ) -> Result<&mut Self, CharacterDisplayError<I2C>> {
-        self.send_command_to_controller(
+        let command = LCD_CMD_SETCGRAMADDR | ((location & 0x7) << 3);
+        self.write_bytes_to_controller(
             device,
             controller,
-            LCD_CMD_SETCGRAMADDR | ((location & 0x7) << 3),
+            core::iter::once((false, command)).chain(charmap.iter().map(|value| (true, *value))),
         )?;
-        for &charmap_byte in charmap.iter() {
-            self.adapter.write_byte_to_controller(
-                &mut device.i2c,
-                device.address,
-                controller,
-                true,
-                charmap_byte,
-            )?;
-        }
         Ok(self)
     }
 
@@ -604,20 +1070,12 @@ where
         controller: usize,
         text: &str,
     ) -> Result<(), CharacterDisplayError<I2C>> {
-        for c in text.chars() {
-            self.adapter.write_byte_to_controller(
-                &mut device.i2c,
-                device.address,
-                controller,
-                true,
-                c as u8,
-            )?;
-        }
-        Ok(())
+        self.write_bytes_to_controller(device, controller, text.chars().map(|c| (true, c as u8)))
     }
 }
 
 #[cfg(test)]
+#[allow(clippy::redundant_field_names)]
 mod lib_tests {
     extern crate std;
     use crate::LcdDisplayType;
@@ -694,6 +1152,55 @@ mod lib_tests {
         device.i2c.done();
     }
 
+    #[test]
+    fn test_generic_hd44780_pcf8574t_soft_reset() {
+        let i2c_address = 0x27_u8;
+        let expected_i2c_transactions = std::vec![
+            // write low nibble of 0x03 3 times to get back into 8 bit mode
+            I2cTransaction::write(i2c_address, std::vec![0b0011_0100]), // low nibble, rw=0, enable=1
+            I2cTransaction::write(i2c_address, std::vec![0b0011_0000]), // low nibble, rw=0, enable=0
+            I2cTransaction::write(i2c_address, std::vec![0b0011_0100]), // low nibble, rw=0, enable=1
+            I2cTransaction::write(i2c_address, std::vec![0b0011_0000]), // low nibble, rw=0, enable=0
+            I2cTransaction::write(i2c_address, std::vec![0b0011_0100]), // low nibble, rw=0, enable=1
+            I2cTransaction::write(i2c_address, std::vec![0b0011_0000]), // low nibble, rw=0, enable=0
+            // write high nibble of 0x02 one time
+            I2cTransaction::write(i2c_address, std::vec![0b0010_0100]), // high nibble, rw=0, enable=1
+            I2cTransaction::write(i2c_address, std::vec![0b0010_0000]), // high nibble, rw=0, enable=0
+            // LCD_CMD_FUNCTIONSET with the tracked function state = 0x20 | 0x08 = 0x28
+            I2cTransaction::write(i2c_address, std::vec![0b0010_0100]), // high nibble, rw=0, enable=1
+            I2cTransaction::write(i2c_address, std::vec![0b0010_0000]), // high nibble, rw=0, enable=0
+            I2cTransaction::write(i2c_address, std::vec![0b1000_0100]), // low nibble, rw=0, enable=1
+            I2cTransaction::write(i2c_address, std::vec![0b1000_0000]), // low nibble, rw=0, enable=0
+            // LCD_CMD_DISPLAYCONTROL with the tracked control state, blinking cursor
+            // = 0x08 | 0x04 | 0x00 | 0x01 = 0x0D
+            I2cTransaction::write(i2c_address, std::vec![0b0000_0100]), // high nibble, rw=0, enable=1
+            I2cTransaction::write(i2c_address, std::vec![0b0000_0000]), // high nibble, rw=0, enable=0
+            I2cTransaction::write(i2c_address, std::vec![0b1101_0100]), // low nibble, rw=0, enable=1
+            I2cTransaction::write(i2c_address, std::vec![0b1101_0000]), // low nibble, rw=0, enable=0
+            // LCD_CMD_ENTRYMODESET with the tracked mode state = 0x04 | 0x02 | 0x00 = 0x06
+            I2cTransaction::write(i2c_address, std::vec![0b0000_0100]), // high nibble, rw=0, enable=1
+            I2cTransaction::write(i2c_address, std::vec![0b0000_0000]), // high nibble, rw=0, enable=0
+            I2cTransaction::write(i2c_address, std::vec![0b0110_0100]), // low nibble, rw=0, enable=1
+            I2cTransaction::write(i2c_address, std::vec![0b0110_0000]), // low nibble, rw=0, enable=0
+        ];
+
+        let i2c = I2cMock::new(&expected_i2c_transactions);
+        let mut driver = GenericHD44780PCF8574T::default();
+        let mut device = DeviceSetupConfig {
+            i2c,
+            address: i2c_address,
+            lcd_type: LcdDisplayType::Lcd16x4,
+            delay: NoopDelay,
+        };
+        // the state that init would have set up, with the cursor blinking
+        driver.display_function[0] = LCD_FLAG_4BITMODE | LCD_FLAG_5x8_DOTS | LCD_FLAG_2LINE;
+        driver.display_control[0] |= LCD_FLAG_BLINKON;
+        assert!(driver.soft_reset(&mut device).is_ok());
+
+        // finish the i2c mock
+        device.i2c.done();
+    }
+
     #[test]
     fn test_generic_hd44780_pcf8574t_set_backlight() {
         let i2c_address = 0x27_u8;
@@ -723,27 +1230,32 @@ mod lib_tests {
     fn test_generic_hd44780_pcf8574t_print() {
         let i2c_address = 0x27_u8;
         let expected_i2c_transactions = std::vec![
-            // print "hello" to the display
-            I2cTransaction::write(i2c_address, std::vec![0b0110_0101]), // 'h' 0x68 - high nibble, rw=0, enable=1
-            I2cTransaction::write(i2c_address, std::vec![0b0110_0001]), // 'h' 0x68 - high nibble, rw=0, enable=0
-            I2cTransaction::write(i2c_address, std::vec![0b1000_0101]), // 'h' 0x68 - low nibble, rw=0, enable=1
-            I2cTransaction::write(i2c_address, std::vec![0b1000_0001]), // 'h' 0x68 - low nibble, rw=0, enable=0
-            I2cTransaction::write(i2c_address, std::vec![0b0110_0101]), // 'e' 0x65 - high nibble, rw=0, enable=1
-            I2cTransaction::write(i2c_address, std::vec![0b0110_0001]), // 'e' 0x65 - high nibble, rw=0, enable=0
-            I2cTransaction::write(i2c_address, std::vec![0b0101_0101]), // 'e' 0x65 - low nibble, rw=0, enable=1
-            I2cTransaction::write(i2c_address, std::vec![0b0101_0001]), // 'e' 0x65 - low nibble, rw=0, enable=0
-            I2cTransaction::write(i2c_address, std::vec![0b0110_0101]), // 'l' 0x6C - high nibble, rw=0, enable=1
-            I2cTransaction::write(i2c_address, std::vec![0b0110_0001]), // 'l' 0x6C - high nibble, rw=0, enable=0
-            I2cTransaction::write(i2c_address, std::vec![0b1100_0101]), // 'l' 0x6C - low nibble, rw=0, enable=1
-            I2cTransaction::write(i2c_address, std::vec![0b1100_0001]), // 'l' 0x6C - low nibble, rw=0, enable=0
-            I2cTransaction::write(i2c_address, std::vec![0b0110_0101]), // 'l' 0x6C - high nibble, rw=0, enable=1
-            I2cTransaction::write(i2c_address, std::vec![0b0110_0001]), // 'l' 0x6C - high nibble, rw=0, enable=0
-            I2cTransaction::write(i2c_address, std::vec![0b1100_0101]), // 'l' 0x6C - low nibble, rw=0, enable=1
-            I2cTransaction::write(i2c_address, std::vec![0b1100_0001]), // 'l' 0x6C - low nibble, rw=0, enable=0
-            I2cTransaction::write(i2c_address, std::vec![0b0110_0101]), // 'o' 0x6F - high nibble, rw=0, enable=1
-            I2cTransaction::write(i2c_address, std::vec![0b0110_0001]), // 'o' 0x6F - high nibble, rw=0, enable=0
-            I2cTransaction::write(i2c_address, std::vec![0b1111_0101]), // 'o' 0x6F - low nibble, rw=0, enable=1
-            I2cTransaction::write(i2c_address, std::vec![0b1111_0001]), // 'o' 0x6F - low nibble, rw=0, enable=0
+            // print "hello" to the display, in a single packed write
+            I2cTransaction::write(
+                i2c_address,
+                std::vec![
+                    0b0110_0101, // 'h' 0x68 - high nibble, rw=0, enable=1
+                    0b0110_0001, // 'h' 0x68 - high nibble, rw=0, enable=0
+                    0b1000_0101, // 'h' 0x68 - low nibble, rw=0, enable=1
+                    0b1000_0001, // 'h' 0x68 - low nibble, rw=0, enable=0
+                    0b0110_0101, // 'e' 0x65 - high nibble, rw=0, enable=1
+                    0b0110_0001, // 'e' 0x65 - high nibble, rw=0, enable=0
+                    0b0101_0101, // 'e' 0x65 - low nibble, rw=0, enable=1
+                    0b0101_0001, // 'e' 0x65 - low nibble, rw=0, enable=0
+                    0b0110_0101, // 'l' 0x6C - high nibble, rw=0, enable=1
+                    0b0110_0001, // 'l' 0x6C - high nibble, rw=0, enable=0
+                    0b1100_0101, // 'l' 0x6C - low nibble, rw=0, enable=1
+                    0b1100_0001, // 'l' 0x6C - low nibble, rw=0, enable=0
+                    0b0110_0101, // 'l' 0x6C - high nibble, rw=0, enable=1
+                    0b0110_0001, // 'l' 0x6C - high nibble, rw=0, enable=0
+                    0b1100_0101, // 'l' 0x6C - low nibble, rw=0, enable=1
+                    0b1100_0001, // 'l' 0x6C - low nibble, rw=0, enable=0
+                    0b0110_0101, // 'o' 0x6F - high nibble, rw=0, enable=1
+                    0b0110_0001, // 'o' 0x6F - high nibble, rw=0, enable=0
+                    0b1111_0101, // 'o' 0x6F - low nibble, rw=0, enable=1
+                    0b1111_0001, // 'o' 0x6F - low nibble, rw=0, enable=0
+                ],
+            ),
         ];
 
         let i2c = I2cMock::new(&expected_i2c_transactions);
@@ -762,6 +1274,43 @@ mod lib_tests {
         device.i2c.done();
     }
 
+    #[test]
+    fn test_generic_hd44780_pcf8574t_create_char_packed() {
+        let i2c_address = 0x27_u8;
+        let mut packed = std::vec![
+            // LCD_CMD_SETCGRAMADDR | (2 << 3) = 0x50
+            0b0101_0100, // high nibble, rs=0, enable=1
+            0b0101_0000, // high nibble, rs=0, enable=0
+            0b0000_0100, // low nibble, rs=0, enable=1
+            0b0000_0000, // low nibble, rs=0, enable=0
+        ];
+        for _ in 0..8 {
+            packed.extend([
+                0b0001_0101, // 0x1F - high nibble, rs=1, enable=1
+                0b0001_0001, // 0x1F - high nibble, rs=1, enable=0
+                0b1111_0101, // 0x1F - low nibble, rs=1, enable=1
+                0b1111_0001, // 0x1F - low nibble, rs=1, enable=0
+            ]);
+        }
+        // the whole upload is a single I2C write
+        let expected_i2c_transactions = std::vec![I2cTransaction::write(i2c_address, packed)];
+
+        let i2c = I2cMock::new(&expected_i2c_transactions);
+        let mut driver = GenericHD44780PCF8574T::default();
+
+        let mut device = DeviceSetupConfig {
+            i2c,
+            address: i2c_address,
+            lcd_type: LcdDisplayType::Lcd16x2,
+            delay: NoopDelay,
+        };
+
+        assert!(driver.create_char(&mut device, 2, [0x1F; 8]).is_ok());
+
+        // finish the i2c mock
+        device.i2c.done();
+    }
+
     #[test]
     fn test_set_cursor_out_of_range() {
         let i2c_address = 0x27_u8;
@@ -817,4 +1366,94 @@ mod lib_tests {
         // finish the i2c mock
         device.i2c.done();
     }
+
+    #[test]
+    fn test_set_cursor_multi_controller() {
+        let i2c_address = 0x20_u8;
+        let i2c = I2cMock::new(&[
+            // set cursor to (3,5), which is row 1 of the third controller
+            // byte to send = LCD_CMD_SETDDRAMADDR | (3 + 0x40) = 0xC3
+            // each write sets both ports of the PCF8575, the enable pins are on the second port
+            I2cTransaction::write(i2c_address, std::vec![0b1100_0000, 0b0000_0100]), // high nibble 0xC, enable3=1
+            I2cTransaction::write(i2c_address, std::vec![0b1100_0000, 0b0000_0000]), // high nibble 0xC, enable3=0
+            I2cTransaction::write(i2c_address, std::vec![0b0011_0000, 0b0000_0100]), // low nibble 0x3, enable3=1
+            I2cTransaction::write(i2c_address, std::vec![0b0011_0000, 0b0000_0000]), // low nibble 0x3, enable3=0
+        ]);
+        let mut driver = MultiHD44780PCF8575::<I2cMock, 4>::default();
+        let mut device = DeviceSetupConfig {
+            i2c,
+            address: i2c_address,
+            lcd_type: LcdDisplayType::Lcd40x8,
+            delay: NoopDelay,
+        };
+        assert!(driver.set_cursor(&mut device, 3, 5).is_ok());
+        assert!(driver.set_cursor(&mut device, 3, 8).is_err());
+        assert_eq!(
+            DriverTrait::<I2cMock, NoopDelay>::controller_row(&driver, 6),
+            (3, 0)
+        );
+
+        // finish the i2c mock
+        device.i2c.done();
+    }
+
+    #[test]
+    fn test_clear_busy_flag_completion() {
+        let i2c_address = 0x27_u8;
+        let busy_check = |busy: bool| {
+            [
+                I2cTransaction::write(i2c_address, std::vec![0b1111_0010]),
+                I2cTransaction::write(i2c_address, std::vec![0b1111_0110]),
+                I2cTransaction::read(i2c_address, std::vec![((busy as u8) << 7) | 0b0010_0110]),
+                I2cTransaction::write(i2c_address, std::vec![0b1111_0010]),
+                I2cTransaction::write(i2c_address, std::vec![0b1111_0110]),
+                I2cTransaction::write(i2c_address, std::vec![0b1111_0010]),
+            ]
+        };
+        let clear_command = [
+            I2cTransaction::write(i2c_address, std::vec![0b0000_0100]),
+            I2cTransaction::write(i2c_address, std::vec![0b0000_0000]),
+            I2cTransaction::write(i2c_address, std::vec![0b0001_0100]),
+            I2cTransaction::write(i2c_address, std::vec![0b0001_0000]),
+        ];
+        let mut expected_i2c_transactions = std::vec::Vec::new();
+        // the busy flag is polled until the clear completes
+        expected_i2c_transactions.extend(clear_command.clone());
+        expected_i2c_transactions.extend(busy_check(true));
+        expected_i2c_transactions.extend(busy_check(false));
+        // and the clear times out if the controller stays busy
+        expected_i2c_transactions.extend(clear_command);
+        expected_i2c_transactions.extend(busy_check(true));
+        expected_i2c_transactions.extend(busy_check(true));
+
+        let i2c = I2cMock::new(&expected_i2c_transactions);
+        let mut driver = GenericHD44780PCF8574T::default();
+        DriverTrait::<I2cMock, NoopDelay>::set_command_completion(
+            &mut driver,
+            CommandCompletion::BusyFlag,
+        );
+        DriverTrait::<I2cMock, NoopDelay>::set_busy_poll_strategy(
+            &mut driver,
+            BusyPollStrategy::Timeout {
+                max_polls: 2,
+                interval_us: 0,
+            },
+        );
+
+        let mut device = DeviceSetupConfig {
+            i2c,
+            address: i2c_address,
+            lcd_type: LcdDisplayType::Lcd16x2,
+            delay: NoopDelay,
+        };
+
+        assert!(driver.clear(&mut device).is_ok());
+        assert!(matches!(
+            driver.clear(&mut device),
+            Err(CharacterDisplayError::Timeout)
+        ));
+
+        // finish the i2c mock
+        device.i2c.done();
+    }
 }
@@ -1,13 +1,113 @@
 pub mod adafruit_lcd_backpack;
+pub mod buttons_pcf8574t;
+pub mod contrast_pcf8574t;
 pub mod dual_controller_pcf8574t;
 pub mod generic_pcf8574t;
+pub mod mcp23017;
+pub mod multi_controller_pcf8575;
 
-use crate::{CharacterDisplayError, LcdDisplayType};
-use embedded_hal::i2c;
+use crate::{BusyPollStrategy, CharacterDisplayError, ContrastPreset, LcdDisplayType};
+use embedded_hal::{
+    delay::DelayNs,
+    i2c::{self, Error, ErrorKind, NoAcknowledgeSource},
+};
+
+/// The size of the buffer for packed writes, enough for a command and 8 data bytes at 4 GPIO updates per byte.
+pub(crate) const PACKED_WRITE_BUFFER_SIZE: usize = 36;
+
+/// How the I2C writes to an adapter are made: how often a failed write is tried again, and the number of writes that
+/// were, and the wait after each write.
+#[derive(Debug, Default, Clone, Copy)]
+pub(crate) struct AdapterWrites {
+    /// The number of times a failed write is tried again
+    pub retries: u8,
+    /// The delay before each retry, in microseconds
+    pub interval_us: u32,
+    /// The delay after each successful write, in microseconds, which spaces the enable pulses
+    pub spacing_us: u32,
+    /// The number of writes tried again since the count was last taken
+    pub retried: u32,
+}
+
+/// The I2C bus of an adapter, making the writes as set by `AdapterWrites`. A write whose address was not acknowledged
+/// is tried again after a delay, which is safe as the GPIO expander pins are then left untouched. Any other error is
+/// returned at once, since the expander may already have applied some of the bytes of the write, and writing them
+/// again would repeat enable pulses to the controller. Reads and other transactions are passed through as they are.
+pub(crate) struct AdapterI2c<'a, I2C, DELAY> {
+    i2c: &'a mut I2C,
+    delay: &'a mut DELAY,
+    writes: &'a mut AdapterWrites,
+}
+
+impl<'a, I2C, DELAY> AdapterI2c<'a, I2C, DELAY> {
+    pub(crate) fn new(
+        i2c: &'a mut I2C,
+        delay: &'a mut DELAY,
+        writes: &'a mut AdapterWrites,
+    ) -> Self {
+        Self { i2c, delay, writes }
+    }
+}
+
+impl<I2C, DELAY> i2c::ErrorType for AdapterI2c<'_, I2C, DELAY>
+where
+    I2C: i2c::I2c,
+{
+    type Error = I2C::Error;
+}
+
+impl<I2C, DELAY> i2c::I2c for AdapterI2c<'_, I2C, DELAY>
+where
+    I2C: i2c::I2c,
+    DELAY: DelayNs,
+{
+    fn read(&mut self, address: u8, read: &mut [u8]) -> Result<(), Self::Error> {
+        self.i2c.read(address, read)
+    }
+
+    fn write(&mut self, address: u8, write: &[u8]) -> Result<(), Self::Error> {
+        let mut retries = self.writes.retries;
+        loop {
+            match self.i2c.write(address, write) {
+                Err(e)
+                    if retries > 0
+                        && e.kind() == ErrorKind::NoAcknowledge(NoAcknowledgeSource::Address) =>
+                {
+                    retries -= 1;
+                    self.writes.retried = self.writes.retried.saturating_add(1);
+                    self.delay.delay_us(self.writes.interval_us);
+                }
+                Ok(()) if self.writes.spacing_us > 0 => {
+                    self.delay.delay_us(self.writes.spacing_us);
+                    return Ok(());
+                }
+                result => return result,
+            }
+        }
+    }
+
+    fn write_read(
+        &mut self,
+        address: u8,
+        write: &[u8],
+        read: &mut [u8],
+    ) -> Result<(), Self::Error> {
+        self.i2c.write_read(address, write, read)
+    }
+
+    fn transaction(
+        &mut self,
+        address: u8,
+        operations: &mut [i2c::Operation<'_>],
+    ) -> Result<(), Self::Error> {
+        self.i2c.transaction(address, operations)
+    }
+}
 
 /// Trait for implementing an I2C adapter for a specific HD44780 device. Assumes the connection
 /// to the HD44780 controller from the adapter is via a 4 bit interface and the adapter has
-/// 8 GPIO pins available for the 4 bit data interface, RS, RW, and enable pins.
+/// 8 GPIO pins available for the 4 bit data interface, RS, RW, and enable pins, unless the adapter
+/// reports that it uses the 8 bit interface with `uses_8bit_interface()`.
 pub trait HD44780AdapterTrait<I2C>: Default
 where
     I2C: i2c::I2c,
@@ -15,11 +115,36 @@ where
     /// Returns the default I2C address for the adapter
     fn default_i2c_address() -> u8;
 
+    /// Returns the other I2C address commonly used by this adapter family when the adapter does not respond at
+    /// `address`, such as the PCF8574AT variant of PCF8574T backpacks, or `None` if there is no common alternate.
+    fn alternate_i2c_address(address: u8) -> Option<u8> {
+        match address {
+            0x27 => Some(0x3F),
+            0x3F => Some(0x27),
+            0x20 => Some(0x21),
+            0x21 => Some(0x20),
+            _ => None,
+        }
+    }
+
     /// Determines if reading from device is supported by this adapter
     fn supports_reads() -> bool {
         false
     }
 
+    /// Determines if the adapter drives the controller through the 8 bit interface, in which case
+    /// `set_data()` takes a whole byte and `write_byte_to_controller()` must write it in one enable pulse.
+    fn uses_8bit_interface() -> bool {
+        false
+    }
+
+    /// Determines if the adapter's GPIO expander applies each byte of a multi-byte I2C write to its pins in turn, as
+    /// the PCF8574 does, so that a stream of GPIO updates can be sent in a single I2C transaction. The GPIO state
+    /// must be fully described by `bits()`.
+    fn supports_packed_writes() -> bool {
+        false
+    }
+
     /// Determines of display type is supported by this adapter
     fn is_supported(display_type: LcdDisplayType) -> bool;
 
@@ -55,11 +180,48 @@ where
 
     fn set_data(&mut self, value: u8);
 
-    fn write_bits_to_gpio(
+    /// Selects one of the contrast presets on adapters that gate the contrast through a GPIO pin. Most adapters
+    /// have no such pin, in which case `CharacterDisplayError::UnsupportedOperation` is returned.
+    fn set_contrast_preset(
+        &mut self,
+        _preset: ContrastPreset,
+    ) -> Result<(), CharacterDisplayError<I2C>> {
+        Err(CharacterDisplayError::UnsupportedOperation)
+    }
+
+    /// Returns the mask of the expander pins not used by the display, such as pins broken out to pushbuttons. The
+    /// adapter keeps these pins high in `bits()`, so that the quasi-bidirectional pins of the PCF8574 can be read as
+    /// inputs. Most adapters have no such pins.
+    fn input_pins(&self) -> u8 {
+        0
+    }
+
+    /// Reads the levels of the expander pins in `input_pins()`, with the other bits cleared. Adapters without such
+    /// pins return `CharacterDisplayError::UnsupportedOperation`.
+    fn read_input_pins(
         &self,
         i2c: &mut I2C,
         i2c_address: u8,
-    ) -> Result<(), CharacterDisplayError<I2C>> {
+    ) -> Result<u8, CharacterDisplayError<I2C>> {
+        if self.input_pins() == 0 {
+            return Err(CharacterDisplayError::UnsupportedOperation);
+        }
+        let mut pins = [0];
+        i2c.read(i2c_address, &mut pins)
+            .map_err(CharacterDisplayError::I2cError)?;
+        Ok(pins[0] & self.input_pins())
+    }
+
+    /// writes the GPIO state of `bits()` to the adapter. Like the other write methods, this takes the I2C bus as any
+    /// bus with the same error type, so that the driver can pass it wrapped to try failed writes again.
+    fn write_bits_to_gpio<BUS>(
+        &self,
+        i2c: &mut BUS,
+        i2c_address: u8,
+    ) -> Result<(), CharacterDisplayError<I2C>>
+    where
+        BUS: i2c::I2c<Error = I2C::Error>,
+    {
         let data = [self.bits()];
         i2c.write(i2c_address, &data)
             .map_err(CharacterDisplayError::I2cError)?;
@@ -69,14 +231,17 @@ where
     /// writes a full byte to the indicated controller on device. If `rs_setting` is `true`, the data is written to the data register,
     /// either the CGRAM or DDRAM, depending on prior command sent. If `rs_setting` is `false`, the data is written to
     /// command register.
-    fn write_byte_to_controller(
+    fn write_byte_to_controller<BUS>(
         &mut self,
-        i2c: &mut I2C,
+        i2c: &mut BUS,
         i2c_address: u8,
         controller: usize,
         rs_setting: bool,
         value: u8,
-    ) -> Result<(), CharacterDisplayError<I2C>> {
+    ) -> Result<(), CharacterDisplayError<I2C>>
+    where
+        BUS: i2c::I2c<Error = I2C::Error>,
+    {
         self.write_nibble_to_controller(i2c, i2c_address, controller, rs_setting, value >> 4)
             .and_then(|_| {
                 self.write_nibble_to_controller(
@@ -89,18 +254,88 @@ where
             })
     }
 
+    /// writes a command byte followed by data bytes to the indicated controller on device, such as setting the CGRAM
+    /// address and then writing a character map. See `write_bytes_to_controller()`.
+    fn write_command_and_data_to_controller<BUS>(
+        &mut self,
+        i2c: &mut BUS,
+        i2c_address: u8,
+        controller: usize,
+        command: u8,
+        data: &[u8],
+    ) -> Result<(), CharacterDisplayError<I2C>>
+    where
+        BUS: i2c::I2c<Error = I2C::Error>,
+    {
+        let bytes =
+            core::iter::once((false, command)).chain(data.iter().map(|value| (true, *value)));
+        self.write_bytes_to_controller(i2c, i2c_address, controller, bytes)
+    }
+
+    /// writes a sequence of bytes to the indicated controller on device, each given as `( rs_setting, value )`, such
+    /// as the characters of a string. When the adapter supports packed writes, the enable pin toggling for all the
+    /// bytes is streamed in as few I2C transactions as possible, rather than one transaction per GPIO update. Even at
+    /// 400 kHz, the two bytes on the bus between the enable pin falling edges take longer than the 37 µs the
+    /// controller needs to execute each byte.
+    fn write_bytes_to_controller<BUS, BYTES>(
+        &mut self,
+        i2c: &mut BUS,
+        i2c_address: u8,
+        controller: usize,
+        bytes: BYTES,
+    ) -> Result<(), CharacterDisplayError<I2C>>
+    where
+        BUS: i2c::I2c<Error = I2C::Error>,
+        BYTES: IntoIterator<Item = (bool, u8)>,
+    {
+        if !Self::supports_packed_writes() {
+            for (rs_setting, value) in bytes {
+                self.write_byte_to_controller(i2c, i2c_address, controller, rs_setting, value)?;
+            }
+            return Ok(());
+        }
+
+        let mut buffer = [0_u8; PACKED_WRITE_BUFFER_SIZE];
+        let mut idx = 0;
+        for (rs_setting, value) in bytes {
+            if idx == buffer.len() {
+                i2c.write(i2c_address, &buffer[..idx])
+                    .map_err(CharacterDisplayError::I2cError)?;
+                idx = 0;
+            }
+            self.set_rs(rs_setting);
+            self.set_rw(false);
+            for nibble in [value >> 4, value & 0x0F] {
+                self.set_data(nibble);
+                self.set_enable(true, controller)?;
+                buffer[idx] = self.bits();
+                self.set_enable(false, controller)?;
+                buffer[idx + 1] = self.bits();
+                idx += 2;
+            }
+        }
+        if idx > 0 {
+            i2c.write(i2c_address, &buffer[..idx])
+                .map_err(CharacterDisplayError::I2cError)?;
+        }
+        Ok(())
+    }
+
     /// writes the lower nibble of a `value` byte to the indicated controller on device. Typically only used for device initialization in 4 bit mode.
     /// If `rs_setting` is `true`, the data is written to the data register,
     /// either the CGRAM or DDRAM, depending on prior command sent. If `rs_setting` is `false`, the data is written to
     /// command register.
-    fn write_nibble_to_controller(
+    fn write_nibble_to_controller<BUS>(
         &mut self,
-        i2c: &mut I2C,
+        i2c: &mut BUS,
         i2c_address: u8,
         controller: usize,
         rs_setting: bool,
         value: u8,
-    ) -> Result<(), CharacterDisplayError<I2C>> {
+    ) -> Result<(), CharacterDisplayError<I2C>>
+    where
+        BUS: i2c::I2c<Error = I2C::Error>,
+    {
         self.set_rs(rs_setting);
         self.set_rw(false);
 
@@ -139,6 +374,67 @@ where
         Ok(false)
     }
 
+    /// Polls whether the controller is busy. When a `ready_signal` is set and it indicates the controller is ready,
+    /// the busy flag is not read.
+    fn poll_busy(
+        &self,
+        i2c: &mut I2C,
+        i2c_address: u8,
+        ready_signal: Option<fn() -> bool>,
+    ) -> Result<bool, CharacterDisplayError<I2C>> {
+        if ready_signal.is_some_and(|is_ready| is_ready()) {
+            return Ok(false);
+        }
+        self.is_busy(i2c, i2c_address)
+    }
+
+    /// Waits until the busy flag of the controller is clear, polling it as directed by `strategy`. Returns
+    /// `CharacterDisplayError::Timeout` when `strategy` is `BusyPollStrategy::Timeout` and the controller is still
+    /// busy after the last poll. Only used on adapters that support reads.
+    fn wait_until_ready<DELAY: DelayNs>(
+        &self,
+        i2c: &mut I2C,
+        i2c_address: u8,
+        strategy: BusyPollStrategy,
+        ready_signal: Option<fn() -> bool>,
+        delay: &mut DELAY,
+    ) -> Result<(), CharacterDisplayError<I2C>> {
+        match strategy {
+            BusyPollStrategy::Continuous => {
+                while self.poll_busy(i2c, i2c_address, ready_signal)? {}
+            }
+            BusyPollStrategy::Interval { interval_us } => {
+                while self.poll_busy(i2c, i2c_address, ready_signal)? {
+                    delay.delay_us(interval_us);
+                }
+            }
+            BusyPollStrategy::Bounded {
+                max_polls,
+                fallback_us,
+            } => {
+                for _ in 0..max_polls {
+                    if !self.poll_busy(i2c, i2c_address, ready_signal)? {
+                        return Ok(());
+                    }
+                }
+                delay.delay_us(fallback_us);
+            }
+            BusyPollStrategy::Timeout {
+                max_polls,
+                interval_us,
+            } => {
+                for _ in 0..max_polls {
+                    if !self.poll_busy(i2c, i2c_address, ready_signal)? {
+                        return Ok(());
+                    }
+                    delay.delay_us(interval_us);
+                }
+                return Err(CharacterDisplayError::Timeout);
+            }
+        }
+        Ok(())
+    }
+
     fn controller_count(&self) -> usize {
         1
     }
@@ -148,4 +444,8 @@ where
     fn row_to_controller_row(&self, row: u8) -> (usize, u8) {
         (0, row)
     }
+
+    /// Set a table of `( controller, row )` indexed by the display row for `row_to_controller_row()`, on adapters
+    /// with a configurable row layout. Adapters with a fixed row layout ignore the table.
+    fn set_row_table(&mut self, _table: &'static [(usize, u8)]) {}
 }
@@ -51,6 +51,8 @@ where
 
     fn is_supported(display_type: LcdDisplayType) -> bool {
         display_type != LcdDisplayType::Lcd40x4
+            && display_type != LcdDisplayType::Lcd40x6
+            && display_type != LcdDisplayType::Lcd40x8
     }
 
     fn init(&self, i2c: &mut I2C, i2c_address: u8) -> Result<(), I2C::Error> {
@@ -91,11 +93,14 @@ where
         self.bits.set_data(value);
     }
 
-    fn write_bits_to_gpio(
+    fn write_bits_to_gpio<BUS>(
         &self,
-        i2c: &mut I2C,
+        i2c: &mut BUS,
         i2c_address: u8,
-    ) -> Result<(), CharacterDisplayError<I2C>> {
+    ) -> Result<(), CharacterDisplayError<I2C>>
+    where
+        BUS: i2c::I2c<Error = I2C::Error>,
+    {
         // first byte is GPIO register address
         let data = [0x09, self.bits()];
         i2c.write(i2c_address, &data)
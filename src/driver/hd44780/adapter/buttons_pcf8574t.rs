@@ -0,0 +1,135 @@
+use bitfield::bitfield;
+use core::marker::PhantomData;
+use embedded_hal::i2c;
+
+use crate::{CharacterDisplayError, LcdDisplayType};
+
+use super::HD44780AdapterTrait;
+
+// Configuration for PCF8574T backpacks that tie the display's RW pin to ground and break out P1 to a pushbutton.
+bitfield! {
+    pub struct ButtonsPCF8574TBitField(u8);
+    impl Debug;
+    impl BitAnd;
+    pub rs, set_rs: 0, 0;
+    pub button, set_button: 1, 1;
+    pub enable, set_enable: 2, 2;
+    pub backlight, set_backlight: 3, 3;
+    pub data, set_data: 7, 4;
+}
+
+impl Clone for ButtonsPCF8574TBitField {
+    fn clone(&self) -> Self {
+        Self(self.0)
+    }
+}
+
+/// The expander pin broken out to a pushbutton.
+const BUTTON_PINS: u8 = 0b0000_0010;
+
+/// Adapter based on the PCF8574T I2C GPIO expander found on backpacks where the display's RW pin is tied to
+/// ground and P1 is instead broken out to a pushbutton, which pulls the pin low when pressed. P1 is written high
+/// with every GPIO update so it can be read as an input. Reads from the display are not supported since the
+/// display is always in write mode.
+#[derive(Clone)]
+pub struct ButtonsPCF8574TAdapter<I2C> {
+    bits: ButtonsPCF8574TBitField,
+    _marker: PhantomData<I2C>,
+}
+
+impl<I2C> Default for ButtonsPCF8574TAdapter<I2C> {
+    fn default() -> Self {
+        Self {
+            bits: ButtonsPCF8574TBitField(BUTTON_PINS),
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<I2C> HD44780AdapterTrait<I2C> for ButtonsPCF8574TAdapter<I2C>
+where
+    I2C: i2c::I2c,
+{
+    fn bits(&self) -> u8 {
+        self.bits.0
+    }
+
+    fn default_i2c_address() -> u8 {
+        0x27
+    }
+
+    fn supports_packed_writes() -> bool {
+        true
+    }
+
+    fn supports_reads() -> bool {
+        false
+    }
+
+    fn set_enable(
+        &mut self,
+        value: bool,
+        controller: usize,
+    ) -> Result<(), CharacterDisplayError<I2C>> {
+        if controller != 0 {
+            return Err(CharacterDisplayError::BadDeviceId);
+        }
+        self.bits.set_enable(value as u8);
+        Ok(())
+    }
+
+    fn set_rs(&mut self, value: bool) {
+        self.bits.set_rs(value as u8);
+    }
+
+    fn set_rw(&mut self, _value: bool) {
+        // does nothing, RW is tied to ground
+    }
+
+    fn set_backlight(&mut self, value: bool) {
+        self.bits.set_backlight(value as u8);
+    }
+
+    fn set_data(&mut self, value: u8) {
+        self.bits.set_data(value);
+    }
+
+    fn input_pins(&self) -> u8 {
+        BUTTON_PINS
+    }
+
+    fn is_supported(display_type: LcdDisplayType) -> bool {
+        display_type != LcdDisplayType::Lcd40x4
+            && display_type != LcdDisplayType::Lcd40x6
+            && display_type != LcdDisplayType::Lcd40x8
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+    use super::*;
+    use embedded_hal_mock::eh1::i2c::{Mock as I2cMock, Transaction as I2cTransaction};
+
+    #[test]
+    fn test_buttons_pcf8574t_write_byte_keeps_button_pin_high() {
+        let expected_transactions = [
+            // write byte 0xDE with RS = 1, with P1 high for the button
+            I2cTransaction::write(0x27, std::vec![0b1101_0111]), // enable = 1, rs = 1
+            I2cTransaction::write(0x27, std::vec![0b1101_0011]), // enable = 0, rs = 1
+            I2cTransaction::write(0x27, std::vec![0b1110_0111]), // enable = 1, rs = 1
+            I2cTransaction::write(0x27, std::vec![0b1110_0011]), // enable = 0, rs = 1
+            // the button is pressed, pulling P1 low
+            I2cTransaction::read(0x27, std::vec![0b1110_0001]),
+        ];
+        let mut i2c = I2cMock::new(&expected_transactions);
+        let mut config = ButtonsPCF8574TAdapter::<I2cMock>::default();
+        config.set_rw(true);
+        assert!(config
+            .write_byte_to_controller(&mut i2c, 0x27, 0, true, 0xDE)
+            .is_ok());
+        assert_eq!(config.input_pins(), 0b0000_0010);
+        assert_eq!(config.read_input_pins(&mut i2c, 0x27).ok(), Some(0));
+        i2c.done();
+    }
+}
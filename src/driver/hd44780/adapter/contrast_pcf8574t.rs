@@ -0,0 +1,155 @@
+use bitfield::bitfield;
+use core::marker::PhantomData;
+use embedded_hal::i2c;
+
+use crate::{CharacterDisplayError, ContrastPreset, LcdDisplayType};
+
+use super::HD44780AdapterTrait;
+
+// Configuration for the HW-061 clone PCF8574T backpacks that tie the display's RW pin to ground and
+// use P1 to switch the contrast between two presets.
+bitfield! {
+    pub struct ContrastPCF8574TBitField(u8);
+    impl Debug;
+    impl BitAnd;
+    pub rs, set_rs: 0, 0;
+    pub contrast, set_contrast: 1, 1;
+    pub enable, set_enable: 2, 2;
+    pub backlight, set_backlight: 3, 3;
+    pub data, set_data: 7, 4;
+}
+
+impl Clone for ContrastPCF8574TBitField {
+    fn clone(&self) -> Self {
+        Self(self.0)
+    }
+}
+
+/// Adapter based on the PCF8574T I2C GPIO expander found on HW-061 clone backpacks where the
+/// display's RW pin is tied to ground and P1 instead gates the contrast. P1 low selects the
+/// `ContrastPreset::Primary` contrast, and P1 high selects `ContrastPreset::Secondary`. Reads are
+/// not supported since the display is always in write mode.
+#[derive(Clone)]
+pub struct ContrastPCF8574TAdapter<I2C> {
+    bits: ContrastPCF8574TBitField,
+    _marker: PhantomData<I2C>,
+}
+
+impl<I2C> Default for ContrastPCF8574TAdapter<I2C> {
+    fn default() -> Self {
+        Self {
+            bits: ContrastPCF8574TBitField(0),
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<I2C> HD44780AdapterTrait<I2C> for ContrastPCF8574TAdapter<I2C>
+where
+    I2C: i2c::I2c,
+{
+    fn bits(&self) -> u8 {
+        self.bits.0
+    }
+
+    fn default_i2c_address() -> u8 {
+        0x27
+    }
+
+    fn supports_packed_writes() -> bool {
+        true
+    }
+
+    fn supports_reads() -> bool {
+        false
+    }
+
+    fn set_enable(
+        &mut self,
+        value: bool,
+        controller: usize,
+    ) -> Result<(), CharacterDisplayError<I2C>> {
+        if controller != 0 {
+            return Err(CharacterDisplayError::BadDeviceId);
+        }
+        self.bits.set_enable(value as u8);
+        Ok(())
+    }
+
+    fn set_rs(&mut self, value: bool) {
+        self.bits.set_rs(value as u8);
+    }
+
+    fn set_rw(&mut self, _value: bool) {
+        // does nothing, RW is tied to ground
+    }
+
+    fn set_backlight(&mut self, value: bool) {
+        self.bits.set_backlight(value as u8);
+    }
+
+    fn set_data(&mut self, value: u8) {
+        self.bits.set_data(value);
+    }
+
+    fn set_contrast_preset(
+        &mut self,
+        preset: ContrastPreset,
+    ) -> Result<(), CharacterDisplayError<I2C>> {
+        self.bits
+            .set_contrast((preset == ContrastPreset::Secondary) as u8);
+        Ok(())
+    }
+
+    fn is_supported(display_type: LcdDisplayType) -> bool {
+        display_type != LcdDisplayType::Lcd40x4
+            && display_type != LcdDisplayType::Lcd40x6
+            && display_type != LcdDisplayType::Lcd40x8
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+    use super::*;
+    use embedded_hal_mock::eh1::i2c::{Mock as I2cMock, Transaction as I2cTransaction};
+
+    #[test]
+    fn test_contrast_pcf8574t_bits() {
+        let mut config = ContrastPCF8574TAdapter::<I2cMock>::default();
+        config.set_rs(true);
+        config.set_rw(true);
+        assert!(config.set_enable(true, 0).is_ok());
+        assert!(config.set_enable(true, 1).is_err());
+        config.set_backlight(true);
+        config.set_data(0b1010);
+        assert_eq!(config.bits(), 0b1010_1101);
+
+        assert!(config
+            .set_contrast_preset(ContrastPreset::Secondary)
+            .is_ok());
+        assert_eq!(config.bits(), 0b1010_1111);
+        assert!(config.set_contrast_preset(ContrastPreset::Primary).is_ok());
+        assert_eq!(config.bits(), 0b1010_1101);
+    }
+
+    #[test]
+    fn test_contrast_pcf8574t_write_byte_keeps_contrast() {
+        let expected_transactions = [
+            // write byte 0xDE with RS = 1 and the secondary contrast preset selected
+            I2cTransaction::write(0x27, std::vec![0b1101_0111]), // enable = 1, contrast = 1, rs = 1
+            I2cTransaction::write(0x27, std::vec![0b1101_0011]), // enable = 0, contrast = 1, rs = 1
+            I2cTransaction::write(0x27, std::vec![0b1110_0111]), // enable = 1, contrast = 1, rs = 1
+            I2cTransaction::write(0x27, std::vec![0b1110_0011]), // enable = 0, contrast = 1, rs = 1
+        ];
+        let mut i2c = I2cMock::new(&expected_transactions);
+        let mut config = ContrastPCF8574TAdapter::<I2cMock>::default();
+        assert!(config
+            .set_contrast_preset(ContrastPreset::Secondary)
+            .is_ok());
+        assert!(config
+            .write_byte_to_controller(&mut i2c, 0x27, 0, true, 0xDE)
+            .is_ok());
+        i2c.done();
+    }
+}
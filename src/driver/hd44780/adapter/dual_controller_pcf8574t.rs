@@ -53,6 +53,10 @@ where
         0x27
     }
 
+    fn supports_packed_writes() -> bool {
+        true
+    }
+
     fn supports_reads() -> bool {
         false
     }
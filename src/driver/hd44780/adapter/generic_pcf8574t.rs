@@ -53,6 +53,10 @@ where
         0x27
     }
 
+    fn supports_packed_writes() -> bool {
+        true
+    }
+
     fn supports_reads() -> bool {
         true
     }
@@ -68,12 +72,8 @@ where
         if controller != 0 {
             return Err(CharacterDisplayError::BadDeviceId);
         }
-        // wait for the BUSY flag to clear
-        while self.is_busy(i2c, i2c_address)? {
-            // wait
-        }
-
-        // now we can read the data. Set up PCF8574T to read data
+        // the driver has already waited for the BUSY flag to clear with `wait_until_ready()`, so we can read
+        // the data now. Set up PCF8574T to read data
         let mut data_cntl = self.bits.clone();
         data_cntl.set_data(0b1111);
         data_cntl.set_enable(0);
@@ -174,10 +174,13 @@ where
 
     fn is_supported(display_type: LcdDisplayType) -> bool {
         display_type != LcdDisplayType::Lcd40x4
+            && display_type != LcdDisplayType::Lcd40x6
+            && display_type != LcdDisplayType::Lcd40x8
     }
 }
 
 #[cfg(test)]
+#[allow(clippy::bool_assert_comparison, clippy::nonminimal_bool)]
 mod tests {
     extern crate std;
     use super::*;
@@ -198,6 +201,9 @@ mod tests {
         assert!(!GenericPCF8574TAdapter::<I2cMock>::is_supported(
             LcdDisplayType::Lcd40x4
         ));
+        assert!(!GenericPCF8574TAdapter::<I2cMock>::is_supported(
+            LcdDisplayType::Lcd40x8
+        ));
     }
 
     #[test]
@@ -274,24 +280,6 @@ mod tests {
     #[test]
     fn test_generic_pcf8574t_config_read_bytes() {
         let expected_transactions = [
-            // set up PCF8574T to read data for is busy check - true
-            I2cTransaction::write(0x27, std::vec![0b11110010]),
-            // read high nibble
-            I2cTransaction::write(0x27, std::vec![0b11110110]),
-            I2cTransaction::read(0x27, std::vec![0b10100110]),
-            I2cTransaction::write(0x27, std::vec![0b11110010]),
-            // read low nibble
-            I2cTransaction::write(0x27, std::vec![0b11110110]),
-            I2cTransaction::write(0x27, std::vec![0b11110010]),
-            // set up PCF8574T to read data for is busy check - false
-            I2cTransaction::write(0x27, std::vec![0b11110010]),
-            // read high nibble
-            I2cTransaction::write(0x27, std::vec![0b11110110]),
-            I2cTransaction::read(0x27, std::vec![0b00100110]),
-            I2cTransaction::write(0x27, std::vec![0b11110010]),
-            // read low nibble
-            I2cTransaction::write(0x27, std::vec![0b11110110]),
-            I2cTransaction::write(0x27, std::vec![0b11110010]),
             // set up PCF8574T to read data for data read
             I2cTransaction::write(0x27, std::vec![0b11110010]),
             // Byte 0 = $DE
@@ -348,6 +336,124 @@ mod tests {
         i2c.done();
     }
 
+    fn busy_check_transactions(busy: bool) -> [I2cTransaction; 6] {
+        [
+            // set up PCF8574T to read data for is busy check
+            I2cTransaction::write(0x27, std::vec![0b11110010]),
+            // read high nibble
+            I2cTransaction::write(0x27, std::vec![0b11110110]),
+            I2cTransaction::read(0x27, std::vec![((busy as u8) << 7) | 0b00100110]),
+            I2cTransaction::write(0x27, std::vec![0b11110010]),
+            // read low nibble
+            I2cTransaction::write(0x27, std::vec![0b11110110]),
+            I2cTransaction::write(0x27, std::vec![0b11110010]),
+        ]
+    }
+
+    #[test]
+    fn test_generic_pcf8574t_wait_until_ready() {
+        use crate::BusyPollStrategy;
+        use embedded_hal_mock::eh1::delay::NoopDelay;
+
+        let config = GenericPCF8574TAdapter::<I2cMock>::default();
+        let mut delay = NoopDelay::new();
+
+        // continuous and interval polling poll until the controller is not busy
+        for strategy in [
+            BusyPollStrategy::Continuous,
+            BusyPollStrategy::Interval { interval_us: 10 },
+        ] {
+            let mut expected_transactions = std::vec::Vec::new();
+            expected_transactions.extend(busy_check_transactions(true));
+            expected_transactions.extend(busy_check_transactions(true));
+            expected_transactions.extend(busy_check_transactions(false));
+            let mut i2c = I2cMock::new(&expected_transactions);
+            assert!(config
+                .wait_until_ready(&mut i2c, 0x27, strategy, None, &mut delay)
+                .is_ok());
+            i2c.done();
+        }
+
+        // bounded polling gives up after the maximum number of polls
+        let strategy = BusyPollStrategy::Bounded {
+            max_polls: 2,
+            fallback_us: 100,
+        };
+        let mut expected_transactions = std::vec::Vec::new();
+        expected_transactions.extend(busy_check_transactions(true));
+        expected_transactions.extend(busy_check_transactions(true));
+        let mut i2c = I2cMock::new(&expected_transactions);
+        assert!(config
+            .wait_until_ready(&mut i2c, 0x27, strategy, None, &mut delay)
+            .is_ok());
+        i2c.done();
+
+        // and stops polling once the controller is not busy
+        let mut i2c = I2cMock::new(&busy_check_transactions(false));
+        assert!(config
+            .wait_until_ready(&mut i2c, 0x27, strategy, None, &mut delay)
+            .is_ok());
+        i2c.done();
+
+        // polling with a timeout fails once the controller is still busy after the maximum number of polls
+        let strategy = BusyPollStrategy::Timeout {
+            max_polls: 2,
+            interval_us: 100,
+        };
+        let mut i2c = I2cMock::new(&expected_transactions);
+        assert!(matches!(
+            config.wait_until_ready(&mut i2c, 0x27, strategy, None, &mut delay),
+            Err(CharacterDisplayError::Timeout)
+        ));
+        i2c.done();
+        let mut expected_transactions = std::vec::Vec::new();
+        expected_transactions.extend(busy_check_transactions(true));
+        expected_transactions.extend(busy_check_transactions(false));
+        let mut i2c = I2cMock::new(&expected_transactions);
+        assert!(config
+            .wait_until_ready(&mut i2c, 0x27, strategy, None, &mut delay)
+            .is_ok());
+        i2c.done();
+    }
+
+    #[test]
+    fn test_generic_pcf8574t_wait_until_ready_signal() {
+        use crate::BusyPollStrategy;
+        use embedded_hal_mock::eh1::delay::NoopDelay;
+
+        let config = GenericPCF8574TAdapter::<I2cMock>::default();
+        let mut delay = NoopDelay::new();
+
+        // a ready signal skips reading the busy flag
+        let mut i2c = I2cMock::new(&[]);
+        assert!(config
+            .wait_until_ready(
+                &mut i2c,
+                0x27,
+                BusyPollStrategy::Continuous,
+                Some(|| true),
+                &mut delay
+            )
+            .is_ok());
+        i2c.done();
+
+        // and the busy flag is polled while the signal indicates the controller is busy
+        let mut expected_transactions = std::vec::Vec::new();
+        expected_transactions.extend(busy_check_transactions(true));
+        expected_transactions.extend(busy_check_transactions(false));
+        let mut i2c = I2cMock::new(&expected_transactions);
+        assert!(config
+            .wait_until_ready(
+                &mut i2c,
+                0x27,
+                BusyPollStrategy::Continuous,
+                Some(|| false),
+                &mut delay
+            )
+            .is_ok());
+        i2c.done();
+    }
+
     #[test]
     fn test_set_enable_controllor_out_of_range() {
         let mut config = GenericPCF8574TAdapter::<I2cMock>::default();
@@ -0,0 +1,190 @@
+use bitfield::bitfield;
+use core::marker::PhantomData;
+use embedded_hal::i2c;
+
+use crate::{CharacterDisplayError, LcdDisplayType};
+
+use super::HD44780AdapterTrait;
+
+// MCP23017 registers, with the power on register layout (IOCON.BANK = 0)
+const MCP23017_REG_IODIRA: u8 = 0x00; //  I/O direction of port A, followed by port B
+const MCP23017_REG_GPIOA: u8 = 0x12; //  GPIO port A, followed by port B
+
+// Configuration for the MCP23017 based 8-bit LCD interface. Port A (GPA0-GPA7) drives the display's
+// D0-D7 data pins, and port B drives the control pins.
+bitfield! {
+    pub struct MCP23017BitField(u16);
+    impl Debug;
+    impl BitAnd;
+    pub data, set_data: 7, 0;
+    pub rs, set_rs: 8, 8;
+    pub rw, set_rw: 9, 9;
+    pub enable, set_enable: 10, 10;
+    pub backlight, set_backlight: 11, 11;
+}
+
+impl Clone for MCP23017BitField {
+    fn clone(&self) -> Self {
+        Self(self.0)
+    }
+}
+
+/// Adapter based on the MCP23017 16-bit I2C GPIO expander interfacing with an HD44780 LCD controller via
+/// the full 8-bit interface. Port A drives the data pins D0-D7, and port B drives RS on GPB0, RW on GPB1,
+/// enable on GPB2 and the backlight on GPB3. Each byte is written with one I2C transaction per enable pin
+/// edge, half the transactions of the 4-bit PCF8574 adapters. Reads are not supported.
+#[derive(Clone)]
+pub struct MCP23017Adapter<I2C> {
+    bits: MCP23017BitField,
+    _marker: PhantomData<I2C>,
+}
+
+impl<I2C> Default for MCP23017Adapter<I2C> {
+    fn default() -> Self {
+        Self {
+            bits: MCP23017BitField(0),
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<I2C> HD44780AdapterTrait<I2C> for MCP23017Adapter<I2C>
+where
+    I2C: i2c::I2c,
+{
+    fn default_i2c_address() -> u8 {
+        0x20
+    }
+
+    fn uses_8bit_interface() -> bool {
+        true
+    }
+
+    fn is_supported(display_type: LcdDisplayType) -> bool {
+        display_type != LcdDisplayType::Lcd40x4
+            && display_type != LcdDisplayType::Lcd40x6
+            && display_type != LcdDisplayType::Lcd40x8
+    }
+
+    /// Sets both ports of the MCP23017 to outputs.
+    fn init(&self, i2c: &mut I2C, i2c_address: u8) -> Result<(), I2C::Error> {
+        i2c.write(i2c_address, &[MCP23017_REG_IODIRA, 0x00, 0x00])
+    }
+
+    /// Returns the data pins on port A. The control pins are on port B.
+    fn bits(&self) -> u8 {
+        self.bits.0 as u8
+    }
+
+    fn set_enable(
+        &mut self,
+        value: bool,
+        controller: usize,
+    ) -> Result<(), CharacterDisplayError<I2C>> {
+        if controller != 0 {
+            return Err(CharacterDisplayError::BadDeviceId);
+        }
+        self.bits.set_enable(value as u16);
+        Ok(())
+    }
+
+    fn set_rs(&mut self, value: bool) {
+        self.bits.set_rs(value as u16);
+    }
+
+    fn set_rw(&mut self, value: bool) {
+        self.bits.set_rw(value as u16);
+    }
+
+    fn set_backlight(&mut self, value: bool) {
+        self.bits.set_backlight(value as u16);
+    }
+
+    /// Sets all 8 data pins.
+    fn set_data(&mut self, value: u8) {
+        self.bits.set_data(value as u16);
+    }
+
+    /// Writes both ports of the MCP23017 in one transaction, first port A and then port B.
+    fn write_bits_to_gpio<BUS>(
+        &self,
+        i2c: &mut BUS,
+        i2c_address: u8,
+    ) -> Result<(), CharacterDisplayError<I2C>>
+    where
+        BUS: i2c::I2c<Error = I2C::Error>,
+    {
+        let [port_a, port_b] = self.bits.0.to_le_bytes();
+        i2c.write(i2c_address, &[MCP23017_REG_GPIOA, port_a, port_b])
+            .map_err(CharacterDisplayError::I2cError)?;
+        Ok(())
+    }
+
+    /// Writes the whole byte at once over the 8-bit interface.
+    fn write_byte_to_controller<BUS>(
+        &mut self,
+        i2c: &mut BUS,
+        i2c_address: u8,
+        controller: usize,
+        rs_setting: bool,
+        value: u8,
+    ) -> Result<(), CharacterDisplayError<I2C>>
+    where
+        BUS: i2c::I2c<Error = I2C::Error>,
+    {
+        self.set_rs(rs_setting);
+        self.set_rw(false);
+        self.set_data(value);
+        self.set_enable(true, controller)?;
+        self.write_bits_to_gpio(i2c, i2c_address)?;
+        self.set_enable(false, controller)?;
+        self.write_bits_to_gpio(i2c, i2c_address)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+    use super::*;
+    use embedded_hal_mock::eh1::i2c::{Mock as I2cMock, Transaction as I2cTransaction};
+
+    #[test]
+    fn test_mcp23017_bits() {
+        let mut config = MCP23017Adapter::<I2cMock>::default();
+        config.set_rs(true);
+        config.set_rw(true);
+        assert!(config.set_enable(true, 0).is_ok());
+        assert!(config.set_enable(true, 1).is_err());
+        config.set_backlight(true);
+        config.set_data(0xA5);
+        assert_eq!(config.bits.0, 0b0000_1111_1010_0101);
+        assert_eq!(config.bits(), 0xA5);
+    }
+
+    #[test]
+    fn test_mcp23017_init() {
+        let config = MCP23017Adapter::<I2cMock>::default();
+        let expected_transactions = [I2cTransaction::write(0x20, std::vec![0x00, 0x00, 0x00])];
+        let mut i2c = I2cMock::new(&expected_transactions);
+
+        config.init(&mut i2c, 0x20).unwrap();
+        i2c.done();
+    }
+
+    #[test]
+    fn test_mcp23017_write_byte() {
+        let mut config = MCP23017Adapter::<I2cMock>::default();
+        config.set_backlight(true);
+        let expected_transactions = [
+            // write byte 0xDE with RS = 1, one transaction per enable pin edge
+            I2cTransaction::write(0x20, std::vec![0x12, 0xDE, 0b0000_1101]), // enable = 1
+            I2cTransaction::write(0x20, std::vec![0x12, 0xDE, 0b0000_1001]), // enable = 0
+        ];
+        let mut i2c = I2cMock::new(&expected_transactions);
+
+        assert!(config
+            .write_byte_to_controller(&mut i2c, 0x20, 0, true, 0xDE)
+            .is_ok());
+        i2c.done();
+    }
+}
@@ -0,0 +1,259 @@
+use bitfield::bitfield;
+use core::marker::PhantomData;
+use embedded_hal::i2c;
+
+use crate::{driver::hd44780::MAX_CONTROLLER_COUNT, CharacterDisplayError, LcdDisplayType};
+
+use super::HD44780AdapterTrait;
+
+/// The number of rows driven by each HD44780 controller.
+pub const ROWS_PER_CONTROLLER: u8 = 2;
+
+// Configuration for the PCF8575 based 4-bit LCD interface to up to 8 HD44780 controllers. The first
+// port (P0-P7) has the same pinout as the common PCF8574T adapters, and the second port (P10-P17)
+// has one enable pin per controller.
+bitfield! {
+    pub struct MultiHD44780_PCF8575BitField(u16);
+    impl Debug;
+    impl BitAnd;
+    pub rs, set_rs: 0, 0;
+    pub rw, set_rw: 1, 1;
+    pub backlight, set_backlight: 3, 3;
+    pub data, set_data: 7, 4;
+    pub enable, set_enable: 15, 8;
+}
+
+impl Clone for MultiHD44780_PCF8575BitField {
+    fn clone(&self) -> Self {
+        Self(self.0)
+    }
+}
+
+/// Adapter based on the PCF8575 16-bit I2C GPIO expander interfacing with `N` HD44780 LCD controllers
+/// via a 4-bit interface. The controllers share the RS, RW and data pins, and each controller has its own
+/// enable pin on the second port of the PCF8575, with controller 0 on P10. Each controller drives two
+/// rows, so logical rows 0 and 1 are on controller 0, rows 2 and 3 on controller 1, and so on, unless a
+/// row table is set with `set_row_table()`. This enables large custom builds such as a 40x8 display made
+/// from four 40x2 panels.
+#[derive(Clone)]
+pub struct MultiHD44780_PCF8575Adapter<I2C, const N: usize> {
+    bits: MultiHD44780_PCF8575BitField,
+    row_table: Option<&'static [(usize, u8)]>,
+    _marker: PhantomData<I2C>,
+}
+
+impl<I2C, const N: usize> Default for MultiHD44780_PCF8575Adapter<I2C, N> {
+    fn default() -> Self {
+        let () = Self::CONTROLLER_COUNT_SUPPORTED;
+        Self {
+            bits: MultiHD44780_PCF8575BitField(0),
+            row_table: None,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<I2C, const N: usize> MultiHD44780_PCF8575Adapter<I2C, N> {
+    /// Fails the build when the adapter is used with more controllers than the driver tracks the state of.
+    const CONTROLLER_COUNT_SUPPORTED: () = assert!(
+        N <= MAX_CONTROLLER_COUNT,
+        "more HD44780 controllers than MAX_CONTROLLER_COUNT"
+    );
+
+    /// Convert a logical row of the display to the controller driving it and the row within that
+    /// controller. The return tuple is `( controller, row )`.
+    pub const fn logical_row_to_controller_row(row: u8) -> (usize, u8) {
+        (
+            (row / ROWS_PER_CONTROLLER) as usize,
+            row % ROWS_PER_CONTROLLER,
+        )
+    }
+
+    /// Convert a controller and a row within that controller to the logical row of the display.
+    pub const fn controller_row_to_logical_row(controller: usize, row: u8) -> u8 {
+        controller as u8 * ROWS_PER_CONTROLLER + row
+    }
+}
+
+impl<I2C, const N: usize> HD44780AdapterTrait<I2C> for MultiHD44780_PCF8575Adapter<I2C, N>
+where
+    I2C: i2c::I2c,
+{
+    /// Returns the bits of the first port. The enable pins are on the second port.
+    fn bits(&self) -> u8 {
+        self.bits.0 as u8
+    }
+
+    fn default_i2c_address() -> u8 {
+        0x20
+    }
+
+    fn supports_reads() -> bool {
+        false
+    }
+
+    fn set_enable(
+        &mut self,
+        value: bool,
+        controller: usize,
+    ) -> Result<(), CharacterDisplayError<I2C>> {
+        if controller >= N || controller >= 8 {
+            return Err(CharacterDisplayError::BadDeviceId);
+        }
+        let mut enable = self.bits.enable();
+        if value {
+            enable |= 1 << controller;
+        } else {
+            enable &= !(1 << controller);
+        }
+        self.bits.set_enable(enable);
+        Ok(())
+    }
+
+    fn set_rs(&mut self, value: bool) {
+        self.bits.set_rs(value as u16);
+    }
+
+    fn set_rw(&mut self, value: bool) {
+        self.bits.set_rw(value as u16);
+    }
+
+    fn set_backlight(&mut self, value: bool) {
+        self.bits.set_backlight(value as u16);
+    }
+
+    fn set_data(&mut self, value: u8) {
+        self.bits.set_data(value as u16);
+    }
+
+    /// Writes both ports of the PCF8575, first P0-P7 and then P10-P17.
+    fn write_bits_to_gpio<BUS>(
+        &self,
+        i2c: &mut BUS,
+        i2c_address: u8,
+    ) -> Result<(), CharacterDisplayError<I2C>>
+    where
+        BUS: i2c::I2c<Error = I2C::Error>,
+    {
+        let data = self.bits.0.to_le_bytes();
+        i2c.write(i2c_address, &data)
+            .map_err(CharacterDisplayError::I2cError)?;
+        Ok(())
+    }
+
+    fn is_supported(display_type: LcdDisplayType) -> bool {
+        display_type.cols() == 40
+            && display_type.rows() as usize == N * ROWS_PER_CONTROLLER as usize
+    }
+
+    fn controller_count(&self) -> usize {
+        N
+    }
+
+    fn row_to_controller_row(&self, row: u8) -> (usize, u8) {
+        match self.row_table.and_then(|table| table.get(row as usize)) {
+            Some(&(controller, controller_row))
+                if controller < N && controller_row < ROWS_PER_CONTROLLER =>
+            {
+                (controller, controller_row)
+            }
+            _ => Self::logical_row_to_controller_row(row),
+        }
+    }
+
+    fn set_row_table(&mut self, table: &'static [(usize, u8)]) {
+        self.row_table = Some(table);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+    use super::*;
+    use embedded_hal_mock::eh1::i2c::{Mock as I2cMock, Transaction as I2cTransaction};
+
+    #[test]
+    fn test_multi_hd44780_adapter_enable() {
+        let mut config = MultiHD44780_PCF8575Adapter::<I2cMock, 4>::default();
+        assert!(config.set_enable(true, 4).is_err());
+        assert!(config.set_enable(true, 3).is_ok());
+        assert_eq!(config.bits.0, 0b0000_1000_0000_0000);
+        assert!(config.set_enable(true, 0).is_ok());
+        assert_eq!(config.bits.0, 0b0000_1001_0000_0000);
+        assert!(config.set_enable(false, 3).is_ok());
+        assert_eq!(config.bits.0, 0b0000_0001_0000_0000);
+        // the enable pins are not on the first port
+        assert_eq!(config.bits(), 0);
+    }
+
+    #[test]
+    fn test_multi_hd44780_adapter_write_bits_to_gpio() {
+        let mut config = MultiHD44780_PCF8575Adapter::<I2cMock, 4>::default();
+        config.set_rs(true);
+        config.set_rw(false);
+        config.set_backlight(true);
+        config.set_data(0b1010);
+        assert!(config.set_enable(true, 2).is_ok());
+
+        let expected_transactions = [I2cTransaction::write(
+            0x20,
+            std::vec![0b1010_1001, 0b0000_0100],
+        )];
+        let mut i2c = I2cMock::new(&expected_transactions);
+
+        config.write_bits_to_gpio(&mut i2c, 0x20).unwrap();
+        i2c.done();
+    }
+
+    #[test]
+    fn test_multi_hd44780_compatible_lcd_types() {
+        assert!(MultiHD44780_PCF8575Adapter::<I2cMock, 4>::is_supported(
+            LcdDisplayType::Lcd40x8
+        ));
+        assert!(!MultiHD44780_PCF8575Adapter::<I2cMock, 4>::is_supported(
+            LcdDisplayType::Lcd40x4
+        ));
+        assert!(MultiHD44780_PCF8575Adapter::<I2cMock, 2>::is_supported(
+            LcdDisplayType::Lcd40x4
+        ));
+        assert!(!MultiHD44780_PCF8575Adapter::<I2cMock, 2>::is_supported(
+            LcdDisplayType::Lcd20x4
+        ));
+        assert!(MultiHD44780_PCF8575Adapter::<I2cMock, 3>::is_supported(
+            LcdDisplayType::Lcd40x6
+        ));
+    }
+
+    #[test]
+    fn test_row_mapping() {
+        let config = MultiHD44780_PCF8575Adapter::<I2cMock, 4>::default();
+        assert_eq!(config.row_to_controller_row(0), (0, 0));
+        assert_eq!(config.row_to_controller_row(3), (1, 1));
+        assert_eq!(config.row_to_controller_row(4), (2, 0));
+        assert_eq!(config.row_to_controller_row(7), (3, 1));
+        for row in 0..8 {
+            let (controller, controller_row) =
+                MultiHD44780_PCF8575Adapter::<I2cMock, 4>::logical_row_to_controller_row(row);
+            assert_eq!(
+                MultiHD44780_PCF8575Adapter::<I2cMock, 4>::controller_row_to_logical_row(
+                    controller,
+                    controller_row
+                ),
+                row
+            );
+        }
+    }
+
+    #[test]
+    fn test_row_table() {
+        let mut config = MultiHD44780_PCF8575Adapter::<I2cMock, 3>::default();
+        // the top panel is wired to controller 2, and the last entry names a controller that does not exist
+        config.set_row_table(&[(2, 0), (2, 1), (0, 0), (0, 1), (3, 0)]);
+        assert_eq!(config.row_to_controller_row(0), (2, 0));
+        assert_eq!(config.row_to_controller_row(1), (2, 1));
+        assert_eq!(config.row_to_controller_row(3), (0, 1));
+        // rows without a valid entry keep the default mapping
+        assert_eq!(config.row_to_controller_row(4), (2, 0));
+        assert_eq!(config.row_to_controller_row(5), (2, 1));
+    }
+}
@@ -0,0 +1,711 @@
+use core::marker::PhantomData;
+use embedded_hal::{delay::DelayNs, i2c};
+
+use crate::{
+    driver::DriverTrait, CharacterDisplayError, DeviceSetupConfig, LcdDisplayType, Timings,
+};
+
+// standard commands, shared with the HD44780 (H = 0)
+const LCD_CMD_CLEARDISPLAY: u8 = 0x01; //  Clear display, set cursor position to zero
+const LCD_CMD_RETURNHOME: u8 = 0x02; //  Set cursor position to zero
+const LCD_CMD_ENTRYMODESET: u8 = 0x04; //  Sets the entry mode
+const LCD_CMD_DISPLAYCONTROL: u8 = 0x08; //  Controls the display; does stuff like turning it off and on
+const LCD_CMD_CURSORSHIFT: u8 = 0x10; //  Lets you move the cursor
+const LCD_CMD_FUNCTIONSET: u8 = 0x20; //  Used to send the function to set to the display
+const LCD_CMD_SETCGRAMADDR: u8 = 0x40; //  Used to set the CGRAM (character generator RAM) with characters
+const LCD_CMD_SETDDRAMADDR: u8 = 0x80; //  Used to set the DDRAM (Display Data RAM)
+
+// extended commands of the PCF2119, available when the H bit of the function set is 1
+const LCD_CMD_DISPLAYCONFIG: u8 = 0x04; //  Sets the column and row scan directions
+const LCD_CMD_TEMPCOEFFICIENT: u8 = 0x10; //  Sets the temperature coefficient of the LCD voltage
+const LCD_CMD_HVGENCONFIG: u8 = 0x40; //  Sets the number of stages of the voltage multiplier
+const LCD_CMD_SETVLCD: u8 = 0x80; //  Sets the LCD voltage
+
+// flags for display entry mode
+const LCD_FLAG_ENTRYRIGHT: u8 = 0x00; //  Used to set text to flow from right to left
+const LCD_FLAG_ENTRYLEFT: u8 = 0x02; //  Uset to set text to flow from left to right
+const LCD_FLAG_ENTRYSHIFTINCREMENT: u8 = 0x01; //  Used to 'right justify' text from the cursor
+const LCD_FLAG_ENTRYSHIFTDECREMENT: u8 = 0x00; //  Used to 'left justify' text from the cursor
+
+// flags for display on/off control
+const LCD_FLAG_DISPLAYON: u8 = 0x04; //  Turns the display on
+const LCD_FLAG_DISPLAYOFF: u8 = 0x00; //  Turns the display off
+const LCD_FLAG_CURSORON: u8 = 0x02; //  Turns the cursor on
+const LCD_FLAG_CURSOROFF: u8 = 0x00; //  Turns the cursor off
+const LCD_FLAG_BLINKON: u8 = 0x01; //  Turns on the blinking cursor
+const LCD_FLAG_BLINKOFF: u8 = 0x00; //  Turns off the blinking cursor
+
+// flags for display/cursor shift
+const LCD_FLAG_DISPLAYMOVE: u8 = 0x08; //  Flag for moving the display
+const LCD_FLAG_CURSORMOVE: u8 = 0x00; //  Flag for moving the cursor
+const LCD_FLAG_MOVERIGHT: u8 = 0x04; //  Flag for moving right
+const LCD_FLAG_MOVELEFT: u8 = 0x00; //  Flag for moving left
+
+// flags for function set
+const LCD_FLAG_8BITMODE: u8 = 0x10; //  8 bit mode, which is the only mode used over I2C
+const PCF2116_FLAG_2LINE: u8 = 0x08; //  PCF2116 2 line mode, or 4 line mode with the 4 line flag
+const PCF2116_FLAG_4LINE: u8 = 0x04; //  PCF2116 4 line mode
+const PCF2119_FLAG_2LINE: u8 = 0x04; //  PCF2119 2 line mode
+const PCF2119_FLAG_EXTENDED: u8 = 0x01; //  Selects the PCF2119 extended instruction set (H = 1)
+
+// the settings of the PCF2119 LCD voltage generator, for a 5V or 3.3V supply
+const DISPLAY_CONFIG_NORMAL: u8 = 0x00; //  Columns left to right, rows top to bottom
+const TEMP_COEFFICIENT_0: u8 = 0x00;
+const HV_GEN_3_STAGES: u8 = 0x02; //  Voltage multiplier with 3 stages
+const VLCD_VA: u8 = 0x1F; //  The LCD voltage of the character mode (V = 0)
+
+/// The size of each of the two DDRAM lines, which are cleared by writing spaces on character sets without a
+/// space at 0x20.
+const DDRAM_LINE_SIZE: usize = 40;
+
+const MAX_BUFFER_SIZE: usize = 81; // 80 bytes of data + 1 control byte.
+
+/// The datasheet times of the PCF2116 and PCF2119.
+const DEFAULT_TIMINGS: Timings = Timings {
+    power_on_us: 2000,
+    clear_home_us: 2000,
+    command_us: 39,
+    enable_pulse_us: 0,
+};
+
+/// The differences between the controllers of the NXP PCF2116 and PCF2119 families, which are selected with the
+/// model type parameter of [`PCF211x`].
+pub trait PCF211xModel {
+    /// The name of the controller, used in log messages
+    const NAME: &'static str;
+    /// The offset from ASCII of the printable characters (0x20 to 0x7F) in the character set of the controller
+    const ASCII_OFFSET: u8;
+    /// Whether the controller has the extended instruction set that configures the LCD voltage generator
+    const EXTENDED_INSTRUCTIONS: bool;
+
+    /// Determines if the display type can be driven by the controller.
+    fn is_supported(display_type: LcdDisplayType) -> bool;
+
+    /// The function set flags for the number of rows of the display type.
+    fn line_mode(display_type: LcdDisplayType) -> u8;
+}
+
+/// The PCF2116, which drives 1 or 2 rows of up to 24 characters, or 4 rows of up to 12 characters, and has a
+/// character set with ASCII at the usual codes.
+pub struct PCF2116;
+
+impl PCF211xModel for PCF2116 {
+    const NAME: &'static str = "PCF2116";
+    const ASCII_OFFSET: u8 = 0x00;
+    const EXTENDED_INSTRUCTIONS: bool = false;
+
+    fn is_supported(display_type: LcdDisplayType) -> bool {
+        match display_type.rows() {
+            1 | 2 => display_type.cols() <= 24,
+            4 => display_type.cols() <= 12,
+            _ => false,
+        }
+    }
+
+    fn line_mode(display_type: LcdDisplayType) -> u8 {
+        match display_type.rows() {
+            4 => PCF2116_FLAG_2LINE | PCF2116_FLAG_4LINE,
+            2 => PCF2116_FLAG_2LINE,
+            _ => 0,
+        }
+    }
+}
+
+/// The PCF2119 with a character set that has ASCII at the usual codes, such as character sets A, D and F. It drives
+/// 1 or 2 rows of up to 16 characters.
+pub struct PCF2119;
+
+impl PCF211xModel for PCF2119 {
+    const NAME: &'static str = "PCF2119";
+    const ASCII_OFFSET: u8 = 0x00;
+    const EXTENDED_INSTRUCTIONS: bool = true;
+
+    fn is_supported(display_type: LcdDisplayType) -> bool {
+        display_type.rows() <= 2 && display_type.cols() <= 16
+    }
+
+    fn line_mode(display_type: LcdDisplayType) -> u8 {
+        if display_type.rows() > 1 {
+            PCF2119_FLAG_2LINE
+        } else {
+            0
+        }
+    }
+}
+
+/// The PCF2119 with character set R, which has ASCII at 0xA0 to 0xFF rather than at 0x20 to 0x7F. Printed text is
+/// translated to this character set, and the display is cleared by writing spaces, since the clear display command
+/// fills it with the character at 0x20, which is not a space.
+pub struct PCF2119R;
+
+impl PCF211xModel for PCF2119R {
+    const NAME: &'static str = "PCF2119R";
+    const ASCII_OFFSET: u8 = 0x80;
+    const EXTENDED_INSTRUCTIONS: bool = true;
+
+    fn is_supported(display_type: LcdDisplayType) -> bool {
+        PCF2119::is_supported(display_type)
+    }
+
+    fn line_mode(display_type: LcdDisplayType) -> u8 {
+        PCF2119::line_mode(display_type)
+    }
+}
+
+/// Driver for the NXP PCF2116 and PCF2119 character display controllers with built-in I2C interface.
+pub struct PCF211x<I2C, MODEL>
+where
+    I2C: i2c::I2c,
+    MODEL: PCF211xModel,
+{
+    display_function: u8,
+    display_control: u8,
+    display_mode: u8,
+    buffer: [u8; MAX_BUFFER_SIZE], // buffer for I2C data
+    timings: Timings,
+    _marker: PhantomData<(I2C, MODEL)>,
+}
+
+impl<I2C, MODEL> Default for PCF211x<I2C, MODEL>
+where
+    I2C: i2c::I2c,
+    MODEL: PCF211xModel,
+{
+    fn default() -> Self {
+        PCF211x {
+            display_function: 0,
+            display_control: 0,
+            display_mode: 0,
+            buffer: [0; MAX_BUFFER_SIZE],
+            timings: DEFAULT_TIMINGS,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<I2C, DELAY, MODEL> DriverTrait<I2C, DELAY> for PCF211x<I2C, MODEL>
+where
+    I2C: i2c::I2c,
+    DELAY: DelayNs,
+    MODEL: PCF211xModel,
+{
+    fn default_i2c_address() -> u8 {
+        0x3a
+    }
+
+    fn alternate_i2c_address(address: u8) -> Option<u8> {
+        // selected with the SA0 pin
+        match address {
+            0x3a => Some(0x3b),
+            0x3b => Some(0x3a),
+            _ => None,
+        }
+    }
+
+    fn supports_reads() -> bool {
+        false
+    }
+
+    fn timings(&self) -> Timings {
+        self.timings
+    }
+
+    fn set_timings(&mut self, timings: Timings) {
+        self.timings = timings;
+    }
+
+    fn control_flags(&self) -> (u8, u8) {
+        (self.display_control, self.display_mode)
+    }
+
+    fn set_control_flags(&mut self, display_control: u8, display_mode: u8) {
+        self.display_control = display_control;
+        self.display_mode = display_mode;
+    }
+
+    fn init(
+        &mut self,
+        device: &mut DeviceSetupConfig<I2C, DELAY>,
+    ) -> Result<(), CharacterDisplayError<I2C>> {
+        if !MODEL::is_supported(device.lcd_type) {
+            return Err(CharacterDisplayError::UnsupportedDisplayType);
+        }
+        #[cfg(feature = "defmt")]
+        defmt::debug!("Initializing {}", MODEL::NAME);
+        // wait for the power on reset to complete
+        device.delay.delay_us(self.timings.power_on_us);
+
+        self.display_function = LCD_FLAG_8BITMODE | MODEL::line_mode(device.lcd_type);
+        self.display_control = LCD_FLAG_DISPLAYON | LCD_FLAG_CURSOROFF | LCD_FLAG_BLINKOFF;
+        self.display_mode = LCD_FLAG_ENTRYLEFT | LCD_FLAG_ENTRYSHIFTDECREMENT;
+
+        self.write_commands(device, &[LCD_CMD_FUNCTIONSET | self.display_function])?;
+        if MODEL::EXTENDED_INSTRUCTIONS {
+            // set up the LCD voltage generator, then return to the standard instruction set
+            self.write_commands(
+                device,
+                &[
+                    LCD_CMD_FUNCTIONSET | self.display_function | PCF2119_FLAG_EXTENDED,
+                    LCD_CMD_DISPLAYCONFIG | DISPLAY_CONFIG_NORMAL,
+                    LCD_CMD_TEMPCOEFFICIENT | TEMP_COEFFICIENT_0,
+                    LCD_CMD_HVGENCONFIG | HV_GEN_3_STAGES,
+                    LCD_CMD_SETVLCD | VLCD_VA,
+                    LCD_CMD_FUNCTIONSET | self.display_function,
+                ],
+            )?;
+        }
+        self.write_commands(
+            device,
+            &[
+                LCD_CMD_DISPLAYCONTROL | self.display_control,
+                LCD_CMD_ENTRYMODESET | self.display_mode,
+            ],
+        )?;
+        // wait for the command to execute
+        device.delay.delay_us(self.timings.command_us);
+
+        self.clear(device)
+    }
+
+    fn soft_reset(
+        &mut self,
+        device: &mut DeviceSetupConfig<I2C, DELAY>,
+    ) -> Result<(), CharacterDisplayError<I2C>> {
+        #[cfg(feature = "defmt")]
+        defmt::debug!("Soft resetting {}", MODEL::NAME);
+        // same sequence as init, without the power on wait, the voltage generator setup and the clear display
+        self.write_commands(
+            device,
+            &[
+                LCD_CMD_FUNCTIONSET | self.display_function,
+                LCD_CMD_DISPLAYCONTROL | self.display_control,
+                LCD_CMD_ENTRYMODESET | self.display_mode,
+            ],
+        )?;
+        device.delay.delay_us(self.timings.command_us);
+        Ok(())
+    }
+
+    fn clear(
+        &mut self,
+        device: &mut DeviceSetupConfig<I2C, DELAY>,
+    ) -> Result<(), CharacterDisplayError<I2C>> {
+        self.write_commands(device, &[LCD_CMD_CLEARDISPLAY])?;
+        // wait for command to complete
+        device.delay.delay_us(self.timings.clear_home_us);
+        if MODEL::ASCII_OFFSET == 0 {
+            return Ok(());
+        }
+
+        // the clear display command filled the display with the character at 0x20, so overwrite it with spaces,
+        // without shifting the display while doing so
+        self.write_commands(device, &[LCD_CMD_ENTRYMODESET | LCD_FLAG_ENTRYLEFT])?;
+        for line_address in [0x00, 0x40] {
+            self.write_commands(device, &[LCD_CMD_SETDDRAMADDR | line_address])?;
+            self.write_text(device, &[b' '; DDRAM_LINE_SIZE])?;
+        }
+        self.write_commands(
+            device,
+            &[LCD_CMD_RETURNHOME, LCD_CMD_ENTRYMODESET | self.display_mode],
+        )?;
+        // wait for command to complete
+        device.delay.delay_us(self.timings.clear_home_us);
+        Ok(())
+    }
+
+    fn home(
+        &mut self,
+        device: &mut DeviceSetupConfig<I2C, DELAY>,
+    ) -> Result<(), CharacterDisplayError<I2C>> {
+        self.write_commands(device, &[LCD_CMD_RETURNHOME])?;
+        // wait for command to complete
+        device.delay.delay_us(self.timings.clear_home_us);
+        Ok(())
+    }
+
+    fn set_cursor(
+        &mut self,
+        device: &mut DeviceSetupConfig<I2C, DELAY>,
+        col: u8,
+        row: u8,
+    ) -> Result<(), CharacterDisplayError<I2C>> {
+        if row >= device.lcd_type.rows() {
+            return Err(CharacterDisplayError::RowOutOfRange { row });
+        }
+        if col >= device.lcd_type.cols() {
+            return Err(CharacterDisplayError::ColumnOutOfRange { col });
+        }
+        let row_offset = device.lcd_type.row_offsets()[row as usize];
+        self.write_commands(device, &[LCD_CMD_SETDDRAMADDR | (col + row_offset)])?;
+        // wait for command to complete
+        device.delay.delay_us(self.timings.command_us);
+        Ok(())
+    }
+
+    fn set_ddram_address(
+        &mut self,
+        device: &mut DeviceSetupConfig<I2C, DELAY>,
+        row: u8,
+        address: u8,
+    ) -> Result<(), CharacterDisplayError<I2C>> {
+        if row >= device.lcd_type.rows() {
+            return Err(CharacterDisplayError::RowOutOfRange { row });
+        }
+        self.write_commands(device, &[LCD_CMD_SETDDRAMADDR | address])?;
+        // wait for command to complete
+        device.delay.delay_us(self.timings.command_us);
+        Ok(())
+    }
+
+    fn show_cursor(
+        &mut self,
+        device: &mut DeviceSetupConfig<I2C, DELAY>,
+        show_cursor: bool,
+    ) -> Result<(), CharacterDisplayError<I2C>> {
+        if show_cursor {
+            self.display_control |= LCD_FLAG_CURSORON;
+        } else {
+            self.display_control &= !LCD_FLAG_CURSORON;
+        }
+        self.write_commands(device, &[LCD_CMD_DISPLAYCONTROL | self.display_control])?;
+        // wait for command to complete
+        device.delay.delay_us(self.timings.command_us);
+        Ok(())
+    }
+
+    fn blink_cursor(
+        &mut self,
+        device: &mut DeviceSetupConfig<I2C, DELAY>,
+        blink_cursor: bool,
+    ) -> Result<(), CharacterDisplayError<I2C>> {
+        if blink_cursor {
+            self.display_control |= LCD_FLAG_BLINKON;
+        } else {
+            self.display_control &= !LCD_FLAG_BLINKON;
+        }
+        self.write_commands(device, &[LCD_CMD_DISPLAYCONTROL | self.display_control])?;
+        // wait for command to complete
+        device.delay.delay_us(self.timings.command_us);
+        Ok(())
+    }
+
+    fn show_display(
+        &mut self,
+        device: &mut DeviceSetupConfig<I2C, DELAY>,
+        show_display: bool,
+    ) -> Result<(), CharacterDisplayError<I2C>> {
+        if show_display {
+            self.display_control |= LCD_FLAG_DISPLAYON;
+        } else {
+            self.display_control &= !LCD_FLAG_DISPLAYON;
+        }
+        self.write_commands(device, &[LCD_CMD_DISPLAYCONTROL | self.display_control])?;
+        // wait for command to complete
+        device.delay.delay_us(self.timings.command_us);
+        Ok(())
+    }
+
+    fn scroll_left(
+        &mut self,
+        device: &mut DeviceSetupConfig<I2C, DELAY>,
+    ) -> Result<(), CharacterDisplayError<I2C>> {
+        self.write_commands(
+            device,
+            &[LCD_CMD_CURSORSHIFT | LCD_FLAG_DISPLAYMOVE | LCD_FLAG_MOVELEFT],
+        )?;
+        // wait for command to complete
+        device.delay.delay_us(self.timings.command_us);
+        Ok(())
+    }
+
+    fn scroll_right(
+        &mut self,
+        device: &mut DeviceSetupConfig<I2C, DELAY>,
+    ) -> Result<(), CharacterDisplayError<I2C>> {
+        self.write_commands(
+            device,
+            &[LCD_CMD_CURSORSHIFT | LCD_FLAG_DISPLAYMOVE | LCD_FLAG_MOVERIGHT],
+        )?;
+        // wait for command to complete
+        device.delay.delay_us(self.timings.command_us);
+        Ok(())
+    }
+
+    fn left_to_right(
+        &mut self,
+        device: &mut DeviceSetupConfig<I2C, DELAY>,
+    ) -> Result<(), CharacterDisplayError<I2C>> {
+        self.display_mode |= LCD_FLAG_ENTRYLEFT;
+        self.write_commands(device, &[LCD_CMD_ENTRYMODESET | self.display_mode])?;
+        // wait for command to complete
+        device.delay.delay_us(self.timings.command_us);
+        Ok(())
+    }
+
+    fn right_to_left(
+        &mut self,
+        device: &mut DeviceSetupConfig<I2C, DELAY>,
+    ) -> Result<(), CharacterDisplayError<I2C>> {
+        self.display_mode &= !LCD_FLAG_ENTRYLEFT;
+        self.write_commands(device, &[LCD_CMD_ENTRYMODESET | self.display_mode])?;
+        // wait for command to complete
+        device.delay.delay_us(self.timings.command_us);
+        Ok(())
+    }
+
+    fn autoscroll(
+        &mut self,
+        device: &mut DeviceSetupConfig<I2C, DELAY>,
+        autoscroll: bool,
+    ) -> Result<(), CharacterDisplayError<I2C>> {
+        if autoscroll {
+            self.display_mode |= LCD_FLAG_ENTRYSHIFTINCREMENT;
+        } else {
+            self.display_mode &= !LCD_FLAG_ENTRYSHIFTINCREMENT;
+        }
+        self.write_commands(device, &[LCD_CMD_ENTRYMODESET | self.display_mode])?;
+        // wait for command to complete
+        device.delay.delay_us(self.timings.command_us);
+        Ok(())
+    }
+
+    fn print(
+        &mut self,
+        device: &mut DeviceSetupConfig<I2C, DELAY>,
+        text: &str,
+    ) -> Result<(), CharacterDisplayError<I2C>> {
+        self.write_text(device, text.as_bytes())
+    }
+
+    /// Writes the data with printable ASCII translated to the character set of the controller, so that framebuffer
+    /// flushes and other text written as data show the same characters as `print()`. On character sets that move
+    /// ASCII, the characters at 0x20 to 0x7F of the character set can not be written.
+    fn write_data(
+        &mut self,
+        device: &mut DeviceSetupConfig<I2C, DELAY>,
+        data: &[u8],
+    ) -> Result<(), CharacterDisplayError<I2C>> {
+        self.write_text(device, data)
+    }
+
+    fn backlight(
+        &mut self,
+        _device: &mut DeviceSetupConfig<I2C, DELAY>,
+        _on: bool,
+    ) -> Result<(), CharacterDisplayError<I2C>> {
+        Err(CharacterDisplayError::UnsupportedOperation)
+    }
+
+    fn create_char(
+        &mut self,
+        device: &mut DeviceSetupConfig<I2C, DELAY>,
+        location: u8,
+        charmap: [u8; 8],
+    ) -> Result<(), CharacterDisplayError<I2C>> {
+        self.write_commands(device, &[LCD_CMD_SETCGRAMADDR | ((location & 0x7) << 3)])?;
+        self.write_bytes(device, Self::CONTROL_RS_DATA, &charmap)?;
+        // wait for command to complete
+        device.delay.delay_us(self.timings.command_us);
+        Ok(())
+    }
+
+    /// Read the device data into the buffer.
+    /// This function is not supported by the PCF211x driver.
+    fn read_device_data(
+        &self,
+        _device: &mut DeviceSetupConfig<I2C, DELAY>,
+        _buffer: &mut [u8],
+    ) -> Result<(), CharacterDisplayError<I2C>> {
+        Err(CharacterDisplayError::UnsupportedOperation)
+    }
+
+    /// Read the address counter.
+    /// This function is not supported by the PCF211x driver.
+    fn read_address_counter(
+        &mut self,
+        _device: &mut DeviceSetupConfig<I2C, DELAY>,
+    ) -> Result<u8, CharacterDisplayError<I2C>> {
+        Err(CharacterDisplayError::UnsupportedOperation)
+    }
+}
+
+impl<I2C, MODEL> PCF211x<I2C, MODEL>
+where
+    I2C: i2c::I2c,
+    MODEL: PCF211xModel,
+{
+    const CONTROL_RS_DATA: u8 = 0b0100_0000;
+    const CONTROL_RS_COMMAND: u8 = 0b0000_0000;
+
+    /// The code of a character in the character set of the controller. Only printable ASCII is moved, so custom
+    /// characters keep their codes.
+    fn translate(byte: u8) -> u8 {
+        match byte {
+            0x20..=0x7F => byte.wrapping_add(MODEL::ASCII_OFFSET),
+            _ => byte,
+        }
+    }
+
+    /// write text to the display, translated to the character set of the controller.
+    fn write_text<DELAY: DelayNs>(
+        &mut self,
+        device: &mut DeviceSetupConfig<I2C, DELAY>,
+        text: &[u8],
+    ) -> Result<(), CharacterDisplayError<I2C>> {
+        if MODEL::ASCII_OFFSET == 0 {
+            return self.write_bytes(device, Self::CONTROL_RS_DATA, text);
+        }
+        if text.is_empty() {
+            return Ok(());
+        }
+        if text.len() >= MAX_BUFFER_SIZE {
+            return Err(CharacterDisplayError::BufferTooSmall);
+        }
+        self.buffer[0] = Self::CONTROL_RS_DATA;
+        for (code, byte) in self.buffer[1..=text.len()].iter_mut().zip(text) {
+            *code = Self::translate(*byte);
+        }
+        device
+            .i2c
+            .write(device.address, &self.buffer[..=text.len()])
+            .map_err(CharacterDisplayError::I2cError)
+    }
+
+    /// write a sequence of commands to the display.
+    fn write_commands<DELAY: DelayNs>(
+        &mut self,
+        device: &mut DeviceSetupConfig<I2C, DELAY>,
+        commands: &[u8],
+    ) -> Result<(), CharacterDisplayError<I2C>> {
+        self.write_bytes(device, Self::CONTROL_RS_COMMAND, commands)
+    }
+
+    /// write one or more bytes to the display, preceded by the control byte that selects whether they are commands
+    /// or data.
+    fn write_bytes<DELAY: DelayNs>(
+        &mut self,
+        device: &mut DeviceSetupConfig<I2C, DELAY>,
+        control_byte: u8,
+        data: &[u8],
+    ) -> Result<(), CharacterDisplayError<I2C>> {
+        if data.is_empty() {
+            return Ok(());
+        }
+        if data.len() >= MAX_BUFFER_SIZE {
+            return Err(CharacterDisplayError::BufferTooSmall);
+        }
+        self.buffer[0] = control_byte;
+        self.buffer[1..=data.len()].copy_from_slice(data);
+        device
+            .i2c
+            .write(device.address, &self.buffer[..=data.len()])
+            .map_err(CharacterDisplayError::I2cError)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+    use super::*;
+    use embedded_hal_mock::eh1::{
+        delay::NoopDelay,
+        i2c::{Mock as I2cMock, Transaction as I2cTransaction},
+    };
+
+    fn command_transaction(address: u8, commands: &[u8]) -> I2cTransaction {
+        let mut bytes = std::vec![0b0000_0000];
+        bytes.extend_from_slice(commands);
+        I2cTransaction::write(address, bytes)
+    }
+
+    fn data_transaction(address: u8, data: &[u8]) -> I2cTransaction {
+        let mut bytes = std::vec![0b0100_0000];
+        bytes.extend_from_slice(data);
+        I2cTransaction::write(address, bytes)
+    }
+
+    #[test]
+    fn test_pcf2119_init() {
+        let i2c_address = 0x3a;
+        let expected_i2c_transactions = std::vec![
+            // 8 bit 2 line mode, then the voltage generator in the extended instruction set
+            command_transaction(i2c_address, &[0x34]),
+            command_transaction(i2c_address, &[0x35, 0x04, 0x10, 0x42, 0x9F, 0x34]),
+            // display on and entry mode
+            command_transaction(i2c_address, &[0x0C, 0x06]),
+            // clear display
+            command_transaction(i2c_address, &[0x01]),
+            // set cursor to (3, 1) and print
+            command_transaction(i2c_address, &[0x80 | 0x43]),
+            data_transaction(i2c_address, b"hi"),
+        ];
+
+        let i2c = I2cMock::new(&expected_i2c_transactions);
+        let mut driver = PCF211x::<_, PCF2119>::default();
+        let mut device = DeviceSetupConfig {
+            i2c,
+            address: i2c_address,
+            lcd_type: LcdDisplayType::Lcd16x2,
+            delay: NoopDelay,
+        };
+
+        assert!(driver.init(&mut device).is_ok());
+        assert!(driver.set_cursor(&mut device, 3, 1).is_ok());
+        assert!(driver.print(&mut device, "hi").is_ok());
+        device.i2c.done();
+
+        device.lcd_type = LcdDisplayType::Lcd20x2;
+        assert!(matches!(
+            driver.init(&mut device),
+            Err(CharacterDisplayError::UnsupportedDisplayType)
+        ));
+    }
+
+    #[test]
+    fn test_pcf2119r_character_set() {
+        let i2c_address = 0x3a;
+        let expected_i2c_transactions = std::vec![
+            // the display is cleared by writing spaces to both DDRAM lines
+            command_transaction(i2c_address, &[0x01]),
+            command_transaction(i2c_address, &[0x06]),
+            command_transaction(i2c_address, &[0x80]),
+            data_transaction(i2c_address, &[0xA0; 40]),
+            command_transaction(i2c_address, &[0x80 | 0x40]),
+            data_transaction(i2c_address, &[0xA0; 40]),
+            command_transaction(i2c_address, &[0x02, 0x07]),
+            // printable ASCII is moved to 0xA0 and up, custom characters are not
+            data_transaction(i2c_address, &[0xC8, 0xE9, 0xA1, 0x00]),
+            data_transaction(i2c_address, &[0xB0]),
+        ];
+
+        let i2c = I2cMock::new(&expected_i2c_transactions);
+        let mut driver = PCF211x::<_, PCF2119R> {
+            display_mode: LCD_FLAG_ENTRYLEFT | LCD_FLAG_ENTRYSHIFTINCREMENT,
+            ..Default::default()
+        };
+        let mut device = DeviceSetupConfig {
+            i2c,
+            address: i2c_address,
+            lcd_type: LcdDisplayType::Lcd16x2,
+            delay: NoopDelay,
+        };
+
+        assert!(driver.clear(&mut device).is_ok());
+        assert!(driver.print(&mut device, "Hi!\u{0}").is_ok());
+        assert!(driver.write_data(&mut device, b"0").is_ok());
+        device.i2c.done();
+    }
+
+    #[test]
+    fn test_pcf2116_line_mode() {
+        assert_eq!(PCF2116::line_mode(LcdDisplayType::Lcd12x4), 0x0C);
+        assert_eq!(PCF2116::line_mode(LcdDisplayType::Lcd24x2), 0x08);
+        assert!(PCF2116::is_supported(LcdDisplayType::Lcd24x2));
+        assert!(!PCF2116::is_supported(LcdDisplayType::Lcd16x4));
+        assert!(!PCF2119::is_supported(LcdDisplayType::Lcd24x2));
+        assert_eq!(
+            LcdDisplayType::Lcd12x4.row_offsets(),
+            [0x00, 0x20, 0x40, 0x60]
+        );
+    }
+}
@@ -0,0 +1,836 @@
+use core::marker::PhantomData;
+use embedded_hal::{delay::DelayNs, i2c};
+
+use crate::{
+    driver::{init_in_steps, DriverTrait},
+    CharacterDisplayError, DeviceSetupConfig, DoubleHeightMode, InitProgress, LcdDisplayType,
+    Timings,
+};
+
+// fundamental commands, shared with the HD44780
+const LCD_CMD_CLEARDISPLAY: u8 = 0x01; //  Clear display, set cursor position to zero
+const LCD_CMD_RETURNHOME: u8 = 0x02; //  Set cursor position to zero
+const LCD_CMD_ENTRYMODESET: u8 = 0x04; //  Sets the entry mode
+const LCD_CMD_DISPLAYCONTROL: u8 = 0x08; //  Controls the display; does stuff like turning it off and on
+const LCD_CMD_CURSORSHIFT: u8 = 0x10; //  Lets you move the cursor
+const LCD_CMD_FUNCTIONSET: u8 = 0x20; //  Used to send the function to set to the display
+const LCD_CMD_SETCGRAMADDR: u8 = 0x40; //  Used to set the CGRAM (character generator RAM) with characters
+const LCD_CMD_SETDDRAMADDR: u8 = 0x80; //  Used to set the DDRAM (Display Data RAM)
+
+// extended commands, available when the RE bit of the function set is 1
+const LCD_CMD_EXTENDEDFUNCTIONSET: u8 = 0x08; //  Sets the font width, cursor inversion and 3/4 line mode
+const LCD_CMD_FUNCTIONSELECTA: u8 = 0x71; //  Enables or disables the internal VDD regulator
+const LCD_CMD_FUNCTIONSELECTB: u8 = 0x72; //  Selects the character ROM and the number of CGRAM characters
+const LCD_CMD_OLEDCMDSETENABLE: u8 = 0x79; //  Enables the OLED command set (SD = 1)
+const LCD_CMD_OLEDCMDSETDISABLE: u8 = 0x78; //  Disables the OLED command set (SD = 0)
+const LCD_CMD_SEGMENTREMAP: u8 = 0x06; //  Sets the common and segment scan directions
+const LCD_CMD_DOUBLEHEIGHT: u8 = 0x10; //  Selects the double height rows in 4 line mode
+
+// OLED commands, available when the SD bit is 1
+const LCD_CMD_SETCLOCKDIVIDE: u8 = 0xD5; //  Sets the display clock divide ratio and oscillator frequency
+const LCD_CMD_SETSEGPINS: u8 = 0xDA; //  Sets the segment pins hardware configuration
+const LCD_CMD_FUNCTIONSELECTC: u8 = 0xDC; //  Selects the external VSL and GPIO
+const LCD_CMD_SETCONTRAST: u8 = 0x81; //  Sets the contrast
+const LCD_CMD_SETPHASELENGTH: u8 = 0xD9; //  Sets the pre-charge period
+const LCD_CMD_SETVCOMHDESELECT: u8 = 0xDB; //  Sets the VCOMH deselect level
+
+// flags for display entry mode
+const LCD_FLAG_ENTRYRIGHT: u8 = 0x00; //  Used to set text to flow from right to left
+const LCD_FLAG_ENTRYLEFT: u8 = 0x02; //  Uset to set text to flow from left to right
+const LCD_FLAG_ENTRYSHIFTINCREMENT: u8 = 0x01; //  Used to 'right justify' text from the cursor
+const LCD_FLAG_ENTRYSHIFTDECREMENT: u8 = 0x00; //  Used to 'left justify' text from the cursor
+
+// flags for display on/off control
+const LCD_FLAG_DISPLAYON: u8 = 0x04; //  Turns the display on
+const LCD_FLAG_DISPLAYOFF: u8 = 0x00; //  Turns the display off
+const LCD_FLAG_CURSORON: u8 = 0x02; //  Turns the cursor on
+const LCD_FLAG_CURSOROFF: u8 = 0x00; //  Turns the cursor off
+const LCD_FLAG_BLINKON: u8 = 0x01; //  Turns on the blinking cursor
+const LCD_FLAG_BLINKOFF: u8 = 0x00; //  Turns off the blinking cursor
+
+// flags for display/cursor shift
+const LCD_FLAG_DISPLAYMOVE: u8 = 0x08; //  Flag for moving the display
+const LCD_FLAG_CURSORMOVE: u8 = 0x00; //  Flag for moving the cursor
+const LCD_FLAG_MOVERIGHT: u8 = 0x04; //  Flag for moving right
+const LCD_FLAG_MOVELEFT: u8 = 0x00; //  Flag for moving left
+
+// flags for function set
+const LCD_FLAG_2LINE: u8 = 0x08; //  2 line mode, or 4 line mode with the extended 4 line flag
+const LCD_FLAG_1LINE: u8 = 0x00; //  1 line mode, or 3 line mode with the extended 4 line flag
+const LCD_FLAG_DOUBLEHEIGHT: u8 = 0x04; //  Double height font in 2 line mode (RE = 0)
+const LCD_FLAG_EXTENDED: u8 = 0x02; //  Selects the extended command set (RE = 1)
+
+// flags for extended function set
+const LCD_FLAG_4LINE: u8 = 0x01; //  3 or 4 line mode
+
+// flags for the double height rows in 4 line mode
+const LCD_FLAG_DOUBLEHEIGHT_TOP: u8 = 0x00; //  Rows 1-2 double, then rows 3 and 4
+const LCD_FLAG_DOUBLEHEIGHT_MIDDLE: u8 = 0x04; //  Row 1, then rows 2-3 double, then row 4
+const LCD_FLAG_DOUBLEHEIGHT_BOTH: u8 = 0x08; //  Rows 1-2 and rows 3-4 double
+const LCD_FLAG_DOUBLEHEIGHT_BOTTOM: u8 = 0x0C; //  Rows 1 and 2, then rows 3-4 double
+const LCD_FLAG_DOUBLEHEIGHT_ENABLE: u8 = 0x01; //  Double height rather than dot scroll (DH' = 1)
+
+// the settings of the Newhaven NHD-0216CW / NHD-0420CW modules
+const FUNCTION_SELECT_A_INTERNAL_VDD: u8 = 0x5C; //  Enable the internal VDD regulator for 5V I/O
+const FUNCTION_SELECT_B_ROM_A: u8 = 0x00; //  ROM A with 8 CGRAM characters
+const CLOCK_DIVIDE: u8 = 0x70;
+const SEG_PINS_ALTERNATIVE: u8 = 0x10;
+const FUNCTION_SELECT_C_INTERNAL_VSL: u8 = 0x00;
+const PHASE_LENGTH: u8 = 0xF1;
+const VCOMH_DESELECT: u8 = 0x40;
+const DEFAULT_CONTRAST: u8 = 0x7F;
+
+const MAX_BUFFER_SIZE: usize = 81; // 80 bytes of data + 1 control byte.
+
+/// The datasheet times of the US2066.
+const DEFAULT_TIMINGS: Timings = Timings {
+    power_on_us: 1000,
+    clear_home_us: 2000,
+    command_us: 39,
+    enable_pulse_us: 0,
+};
+
+pub struct US2066<I2C>
+where
+    I2C: i2c::I2c,
+{
+    display_function: u8,
+    display_control: u8,
+    display_mode: u8,
+    contrast: u8,
+    double_height: DoubleHeightMode,
+    buffer: [u8; MAX_BUFFER_SIZE], // buffer for I2C data
+    timings: Timings,
+    _marker: PhantomData<I2C>,
+}
+
+impl<I2C> Default for US2066<I2C>
+where
+    I2C: i2c::I2c,
+{
+    fn default() -> Self {
+        US2066 {
+            display_function: 0,
+            display_control: 0,
+            display_mode: 0,
+            contrast: DEFAULT_CONTRAST,
+            double_height: DoubleHeightMode::Off,
+            buffer: [0; MAX_BUFFER_SIZE],
+            timings: DEFAULT_TIMINGS,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<I2C, DELAY> DriverTrait<I2C, DELAY> for US2066<I2C>
+where
+    I2C: i2c::I2c,
+    DELAY: DelayNs,
+{
+    fn default_i2c_address() -> u8 {
+        0x3c
+    }
+
+    fn alternate_i2c_address(address: u8) -> Option<u8> {
+        // selected with the SA0 pin
+        match address {
+            0x3c => Some(0x3d),
+            0x3d => Some(0x3c),
+            _ => None,
+        }
+    }
+
+    fn supports_reads() -> bool {
+        false
+    }
+
+    fn timings(&self) -> Timings {
+        self.timings
+    }
+
+    fn set_timings(&mut self, timings: Timings) {
+        self.timings = timings;
+    }
+
+    fn control_flags(&self) -> (u8, u8) {
+        (self.display_control, self.display_mode)
+    }
+
+    fn set_control_flags(&mut self, display_control: u8, display_mode: u8) {
+        self.display_control = display_control;
+        self.display_mode = display_mode;
+    }
+
+    fn init(
+        &mut self,
+        device: &mut DeviceSetupConfig<I2C, DELAY>,
+    ) -> Result<(), CharacterDisplayError<I2C>> {
+        init_in_steps(self, device)
+    }
+
+    fn init_step(
+        &mut self,
+        device: &mut DeviceSetupConfig<I2C, DELAY>,
+        step: usize,
+    ) -> Result<InitProgress, CharacterDisplayError<I2C>> {
+        match step {
+            0 => {
+                if !Self::is_supported(device.lcd_type) {
+                    return Err(CharacterDisplayError::UnsupportedDisplayType);
+                }
+                #[cfg(feature = "defmt")]
+                defmt::debug!("Initializing US2066");
+                // wait for the controller to come out of reset
+                Ok(InitProgress::WaitUs(self.timings.power_on_us))
+            }
+            1 => self.init_settings(device),
+            2 => {
+                self.write_commands(
+                    device,
+                    &[
+                        LCD_CMD_SETDDRAMADDR,
+                        LCD_CMD_ENTRYMODESET | self.display_mode,
+                        LCD_CMD_DISPLAYCONTROL | self.display_control,
+                    ],
+                )?;
+                // wait for the panel to power up
+                Ok(InitProgress::WaitUs(100_000))
+            }
+            _ => Ok(InitProgress::Done),
+        }
+    }
+
+    fn soft_reset(
+        &mut self,
+        device: &mut DeviceSetupConfig<I2C, DELAY>,
+    ) -> Result<(), CharacterDisplayError<I2C>> {
+        #[cfg(feature = "defmt")]
+        defmt::debug!("Soft resetting US2066");
+        // restore the tracked state, which also returns to the fundamental command set
+        self.write_commands(
+            device,
+            &[
+                LCD_CMD_FUNCTIONSET | LCD_FLAG_2LINE | LCD_FLAG_EXTENDED,
+                LCD_CMD_OLEDCMDSETENABLE,
+                LCD_CMD_SETCONTRAST,
+                self.contrast,
+                LCD_CMD_OLEDCMDSETDISABLE,
+                LCD_CMD_FUNCTIONSET | self.display_function,
+                LCD_CMD_DISPLAYCONTROL | self.display_control,
+                LCD_CMD_ENTRYMODESET | self.display_mode,
+            ],
+        )?;
+        device.delay.delay_us(self.timings.command_us);
+        Ok(())
+    }
+
+    fn clear(
+        &mut self,
+        device: &mut DeviceSetupConfig<I2C, DELAY>,
+    ) -> Result<(), CharacterDisplayError<I2C>> {
+        self.write_commands(device, &[LCD_CMD_CLEARDISPLAY])?;
+        // wait for command to complete
+        device.delay.delay_us(self.timings.clear_home_us);
+        Ok(())
+    }
+
+    fn home(
+        &mut self,
+        device: &mut DeviceSetupConfig<I2C, DELAY>,
+    ) -> Result<(), CharacterDisplayError<I2C>> {
+        self.write_commands(device, &[LCD_CMD_RETURNHOME])?;
+        // wait for command to complete
+        device.delay.delay_us(self.timings.clear_home_us);
+        Ok(())
+    }
+
+    fn row_offsets(&self, display_type: LcdDisplayType) -> [u8; 4] {
+        let lines = Self::visible_lines(display_type, self.double_height);
+        core::array::from_fn(|row| {
+            let line = lines.get(row).copied().unwrap_or(row as u8);
+            Self::row_offset(display_type, line)
+        })
+    }
+
+    fn set_cursor(
+        &mut self,
+        device: &mut DeviceSetupConfig<I2C, DELAY>,
+        col: u8,
+        row: u8,
+    ) -> Result<(), CharacterDisplayError<I2C>> {
+        if row >= device.lcd_type.rows() {
+            return Err(CharacterDisplayError::RowOutOfRange { row });
+        }
+        if col >= device.lcd_type.cols() {
+            return Err(CharacterDisplayError::ColumnOutOfRange { col });
+        }
+        let Some(line) = Self::visible_lines(device.lcd_type, self.double_height)
+            .get(row as usize)
+            .copied()
+        else {
+            return Err(CharacterDisplayError::RowOutOfRange { row });
+        };
+        let row_offset = Self::row_offset(device.lcd_type, line);
+        self.write_commands(device, &[LCD_CMD_SETDDRAMADDR | (col + row_offset)])?;
+        // wait for command to complete
+        device.delay.delay_us(self.timings.command_us);
+        Ok(())
+    }
+
+    fn set_ddram_address(
+        &mut self,
+        device: &mut DeviceSetupConfig<I2C, DELAY>,
+        row: u8,
+        address: u8,
+    ) -> Result<(), CharacterDisplayError<I2C>> {
+        if row >= device.lcd_type.rows() {
+            return Err(CharacterDisplayError::RowOutOfRange { row });
+        }
+        self.write_commands(device, &[LCD_CMD_SETDDRAMADDR | address])?;
+        // wait for command to complete
+        device.delay.delay_us(self.timings.command_us);
+        Ok(())
+    }
+
+    fn show_cursor(
+        &mut self,
+        device: &mut DeviceSetupConfig<I2C, DELAY>,
+        show_cursor: bool,
+    ) -> Result<(), CharacterDisplayError<I2C>> {
+        if show_cursor {
+            self.display_control |= LCD_FLAG_CURSORON;
+        } else {
+            self.display_control &= !LCD_FLAG_CURSORON;
+        }
+        self.write_commands(device, &[LCD_CMD_DISPLAYCONTROL | self.display_control])?;
+        // wait for command to complete
+        device.delay.delay_us(self.timings.command_us);
+        Ok(())
+    }
+
+    fn blink_cursor(
+        &mut self,
+        device: &mut DeviceSetupConfig<I2C, DELAY>,
+        blink_cursor: bool,
+    ) -> Result<(), CharacterDisplayError<I2C>> {
+        if blink_cursor {
+            self.display_control |= LCD_FLAG_BLINKON;
+        } else {
+            self.display_control &= !LCD_FLAG_BLINKON;
+        }
+        self.write_commands(device, &[LCD_CMD_DISPLAYCONTROL | self.display_control])?;
+        // wait for command to complete
+        device.delay.delay_us(self.timings.command_us);
+        Ok(())
+    }
+
+    fn show_display(
+        &mut self,
+        device: &mut DeviceSetupConfig<I2C, DELAY>,
+        show_display: bool,
+    ) -> Result<(), CharacterDisplayError<I2C>> {
+        if show_display {
+            self.display_control |= LCD_FLAG_DISPLAYON;
+        } else {
+            self.display_control &= !LCD_FLAG_DISPLAYON;
+        }
+        self.write_commands(device, &[LCD_CMD_DISPLAYCONTROL | self.display_control])?;
+        // wait for command to complete
+        device.delay.delay_us(self.timings.command_us);
+        Ok(())
+    }
+
+    fn scroll_left(
+        &mut self,
+        device: &mut DeviceSetupConfig<I2C, DELAY>,
+    ) -> Result<(), CharacterDisplayError<I2C>> {
+        self.write_commands(
+            device,
+            &[LCD_CMD_CURSORSHIFT | LCD_FLAG_DISPLAYMOVE | LCD_FLAG_MOVELEFT],
+        )?;
+        // wait for command to complete
+        device.delay.delay_us(self.timings.command_us);
+        Ok(())
+    }
+
+    fn scroll_right(
+        &mut self,
+        device: &mut DeviceSetupConfig<I2C, DELAY>,
+    ) -> Result<(), CharacterDisplayError<I2C>> {
+        self.write_commands(
+            device,
+            &[LCD_CMD_CURSORSHIFT | LCD_FLAG_DISPLAYMOVE | LCD_FLAG_MOVERIGHT],
+        )?;
+        // wait for command to complete
+        device.delay.delay_us(self.timings.command_us);
+        Ok(())
+    }
+
+    fn left_to_right(
+        &mut self,
+        device: &mut DeviceSetupConfig<I2C, DELAY>,
+    ) -> Result<(), CharacterDisplayError<I2C>> {
+        self.display_mode |= LCD_FLAG_ENTRYLEFT;
+        self.write_commands(device, &[LCD_CMD_ENTRYMODESET | self.display_mode])?;
+        // wait for command to complete
+        device.delay.delay_us(self.timings.command_us);
+        Ok(())
+    }
+
+    fn right_to_left(
+        &mut self,
+        device: &mut DeviceSetupConfig<I2C, DELAY>,
+    ) -> Result<(), CharacterDisplayError<I2C>> {
+        self.display_mode &= !LCD_FLAG_ENTRYLEFT;
+        self.write_commands(device, &[LCD_CMD_ENTRYMODESET | self.display_mode])?;
+        // wait for command to complete
+        device.delay.delay_us(self.timings.command_us);
+        Ok(())
+    }
+
+    fn autoscroll(
+        &mut self,
+        device: &mut DeviceSetupConfig<I2C, DELAY>,
+        autoscroll: bool,
+    ) -> Result<(), CharacterDisplayError<I2C>> {
+        if autoscroll {
+            self.display_mode |= LCD_FLAG_ENTRYSHIFTINCREMENT;
+        } else {
+            self.display_mode &= !LCD_FLAG_ENTRYSHIFTINCREMENT;
+        }
+        self.write_commands(device, &[LCD_CMD_ENTRYMODESET | self.display_mode])?;
+        // wait for command to complete
+        device.delay.delay_us(self.timings.command_us);
+        Ok(())
+    }
+
+    fn print(
+        &mut self,
+        device: &mut DeviceSetupConfig<I2C, DELAY>,
+        text: &str,
+    ) -> Result<(), CharacterDisplayError<I2C>> {
+        self.write_data(device, text.as_bytes())
+    }
+
+    fn write_data(
+        &mut self,
+        device: &mut DeviceSetupConfig<I2C, DELAY>,
+        data: &[u8],
+    ) -> Result<(), CharacterDisplayError<I2C>> {
+        self.write_bytes(device, Self::CONTROL_RS_DATA, data)
+    }
+
+    fn backlight(
+        &mut self,
+        _device: &mut DeviceSetupConfig<I2C, DELAY>,
+        _on: bool,
+    ) -> Result<(), CharacterDisplayError<I2C>> {
+        // an OLED has no backlight
+        Err(CharacterDisplayError::UnsupportedOperation)
+    }
+
+    fn set_contrast(
+        &mut self,
+        device: &mut DeviceSetupConfig<I2C, DELAY>,
+        contrast: u8,
+    ) -> Result<(), CharacterDisplayError<I2C>> {
+        self.contrast = contrast;
+        self.write_commands(
+            device,
+            &[
+                LCD_CMD_FUNCTIONSET | LCD_FLAG_2LINE | LCD_FLAG_EXTENDED,
+                LCD_CMD_OLEDCMDSETENABLE,
+                LCD_CMD_SETCONTRAST,
+                contrast,
+                LCD_CMD_OLEDCMDSETDISABLE,
+                LCD_CMD_FUNCTIONSET | self.display_function,
+            ],
+        )?;
+        // wait for command to complete
+        device.delay.delay_us(self.timings.command_us);
+        Ok(())
+    }
+
+    fn set_double_height(
+        &mut self,
+        device: &mut DeviceSetupConfig<I2C, DELAY>,
+        mode: DoubleHeightMode,
+    ) -> Result<(), CharacterDisplayError<I2C>> {
+        let rows_4_line = match (device.lcd_type.rows(), mode) {
+            (_, DoubleHeightMode::Off) => None,
+            (2, DoubleHeightMode::Top) => None,
+            (4, DoubleHeightMode::Top) => Some(LCD_FLAG_DOUBLEHEIGHT_TOP),
+            (4, DoubleHeightMode::Middle) => Some(LCD_FLAG_DOUBLEHEIGHT_MIDDLE),
+            (4, DoubleHeightMode::Bottom) => Some(LCD_FLAG_DOUBLEHEIGHT_BOTTOM),
+            (4, DoubleHeightMode::TopAndBottom) => Some(LCD_FLAG_DOUBLEHEIGHT_BOTH),
+            _ => return Err(CharacterDisplayError::UnsupportedOperation),
+        };
+        if let Some(rows) = rows_4_line {
+            // select the double height rows in the extended command set
+            self.write_commands(
+                device,
+                &[
+                    LCD_CMD_FUNCTIONSET | LCD_FLAG_2LINE | LCD_FLAG_EXTENDED,
+                    LCD_CMD_DOUBLEHEIGHT | rows | LCD_FLAG_DOUBLEHEIGHT_ENABLE,
+                ],
+            )?;
+        }
+        if mode == DoubleHeightMode::Off {
+            self.display_function &= !LCD_FLAG_DOUBLEHEIGHT;
+        } else {
+            self.display_function |= LCD_FLAG_DOUBLEHEIGHT;
+        }
+        self.write_commands(device, &[LCD_CMD_FUNCTIONSET | self.display_function])?;
+        self.double_height = mode;
+        // wait for command to complete
+        device.delay.delay_us(self.timings.command_us);
+        Ok(())
+    }
+
+    fn create_char(
+        &mut self,
+        device: &mut DeviceSetupConfig<I2C, DELAY>,
+        location: u8,
+        charmap: [u8; 8],
+    ) -> Result<(), CharacterDisplayError<I2C>> {
+        self.write_commands(device, &[LCD_CMD_SETCGRAMADDR | ((location & 0x7) << 3)])?;
+        self.write_data(device, &charmap)?;
+        // wait for command to complete
+        device.delay.delay_us(self.timings.command_us);
+        Ok(())
+    }
+
+    /// Read the device data into the buffer.
+    /// This function is not supported by the US2066 driver.
+    fn read_device_data(
+        &self,
+        _device: &mut DeviceSetupConfig<I2C, DELAY>,
+        _buffer: &mut [u8],
+    ) -> Result<(), CharacterDisplayError<I2C>> {
+        Err(CharacterDisplayError::UnsupportedOperation)
+    }
+
+    /// Read the address counter.
+    /// This function is not supported by the US2066 driver.
+    fn read_address_counter(
+        &mut self,
+        _device: &mut DeviceSetupConfig<I2C, DELAY>,
+    ) -> Result<u8, CharacterDisplayError<I2C>> {
+        Err(CharacterDisplayError::UnsupportedOperation)
+    }
+}
+
+impl<I2C> US2066<I2C>
+where
+    I2C: i2c::I2c,
+{
+    const CONTROL_RS_DATA: u8 = 0b0100_0000;
+    const CONTROL_RS_COMMAND: u8 = 0b0000_0000;
+
+    /// Determines if the display type is supported by the US2066, which drives up to 4 rows of 20 characters.
+    fn is_supported(display_type: LcdDisplayType) -> bool {
+        display_type.cols() <= 20 && display_type.rows() <= 4
+    }
+
+    /// The extended function set flags for the number of rows of the display type.
+    fn line_mode(display_type: LcdDisplayType) -> u8 {
+        if display_type.rows() > 2 {
+            LCD_FLAG_4LINE
+        } else {
+            0
+        }
+    }
+
+    /// The lines of the display shown as each row, which are fewer than the rows of the display when some are
+    /// shown in double height.
+    fn visible_lines(display_type: LcdDisplayType, mode: DoubleHeightMode) -> &'static [u8] {
+        let lines: &'static [u8] = match (display_type.rows() > 2, mode) {
+            (_, DoubleHeightMode::Off) => &[0, 1, 2, 3],
+            (false, _) => &[0],
+            (true, DoubleHeightMode::Top) => &[0, 2, 3],
+            (true, DoubleHeightMode::Middle) => &[0, 1, 3],
+            (true, DoubleHeightMode::Bottom) => &[0, 1, 2],
+            (true, DoubleHeightMode::TopAndBottom) => &[0, 2],
+        };
+        let rows = (display_type.rows() as usize).min(lines.len());
+        &lines[..rows]
+    }
+
+    /// The DDRAM address of the first column of a row. In 4 line mode, each row has 32 addresses.
+    fn row_offset(display_type: LcdDisplayType, row: u8) -> u8 {
+        if display_type.rows() > 2 {
+            row * 0x20
+        } else {
+            row * 0x40
+        }
+    }
+
+    /// reset the tracked state and send the setup commands of the initialization, ending with a clear of the
+    /// display.
+    fn init_settings<DELAY: DelayNs>(
+        &mut self,
+        device: &mut DeviceSetupConfig<I2C, DELAY>,
+    ) -> Result<InitProgress, CharacterDisplayError<I2C>> {
+        self.display_function = LCD_FLAG_2LINE;
+        self.display_control = LCD_FLAG_DISPLAYON | LCD_FLAG_CURSOROFF | LCD_FLAG_BLINKOFF;
+        self.display_mode = LCD_FLAG_ENTRYLEFT | LCD_FLAG_ENTRYSHIFTDECREMENT;
+        self.contrast = DEFAULT_CONTRAST;
+        self.double_height = DoubleHeightMode::Off;
+
+        // select the extended command set and set up the power supply, with the display off
+        self.write_commands(
+            device,
+            &[
+                LCD_CMD_FUNCTIONSET | LCD_FLAG_2LINE | LCD_FLAG_EXTENDED,
+                LCD_CMD_FUNCTIONSELECTA,
+            ],
+        )?;
+        self.write_bytes(
+            device,
+            Self::CONTROL_RS_DATA,
+            &[FUNCTION_SELECT_A_INTERNAL_VDD],
+        )?;
+        self.write_commands(
+            device,
+            &[
+                LCD_CMD_FUNCTIONSET | self.display_function,
+                LCD_CMD_DISPLAYCONTROL | LCD_FLAG_DISPLAYOFF,
+            ],
+        )?;
+
+        // the OLED timing and line mode settings
+        self.write_commands(
+            device,
+            &[
+                LCD_CMD_FUNCTIONSET | LCD_FLAG_2LINE | LCD_FLAG_EXTENDED,
+                LCD_CMD_OLEDCMDSETENABLE,
+                LCD_CMD_SETCLOCKDIVIDE,
+                CLOCK_DIVIDE,
+                LCD_CMD_OLEDCMDSETDISABLE,
+                LCD_CMD_EXTENDEDFUNCTIONSET | Self::line_mode(device.lcd_type),
+                LCD_CMD_SEGMENTREMAP,
+                LCD_CMD_FUNCTIONSELECTB,
+            ],
+        )?;
+        self.write_bytes(device, Self::CONTROL_RS_DATA, &[FUNCTION_SELECT_B_ROM_A])?;
+
+        // the OLED panel drive settings
+        self.write_commands(
+            device,
+            &[
+                LCD_CMD_FUNCTIONSET | LCD_FLAG_2LINE | LCD_FLAG_EXTENDED,
+                LCD_CMD_OLEDCMDSETENABLE,
+                LCD_CMD_SETSEGPINS,
+                SEG_PINS_ALTERNATIVE,
+                LCD_CMD_FUNCTIONSELECTC,
+                FUNCTION_SELECT_C_INTERNAL_VSL,
+                LCD_CMD_SETCONTRAST,
+                self.contrast,
+                LCD_CMD_SETPHASELENGTH,
+                PHASE_LENGTH,
+                LCD_CMD_SETVCOMHDESELECT,
+                VCOMH_DESELECT,
+                LCD_CMD_OLEDCMDSETDISABLE,
+                LCD_CMD_FUNCTIONSET | self.display_function,
+            ],
+        )?;
+
+        // clear display
+        self.write_commands(device, &[LCD_CMD_CLEARDISPLAY])?;
+        Ok(InitProgress::WaitUs(self.timings.clear_home_us))
+    }
+
+    /// write a sequence of commands to the display.
+    fn write_commands<DELAY: DelayNs>(
+        &mut self,
+        device: &mut DeviceSetupConfig<I2C, DELAY>,
+        commands: &[u8],
+    ) -> Result<(), CharacterDisplayError<I2C>> {
+        self.write_bytes(device, Self::CONTROL_RS_COMMAND, commands)
+    }
+
+    /// write one or more bytes to the display, preceded by the control byte that selects whether they are commands
+    /// or data.
+    fn write_bytes<DELAY: DelayNs>(
+        &mut self,
+        device: &mut DeviceSetupConfig<I2C, DELAY>,
+        control_byte: u8,
+        data: &[u8],
+    ) -> Result<(), CharacterDisplayError<I2C>> {
+        if data.is_empty() {
+            return Ok(());
+        }
+        if data.len() >= MAX_BUFFER_SIZE {
+            return Err(CharacterDisplayError::BufferTooSmall);
+        }
+        self.buffer[0] = control_byte;
+        self.buffer[1..=data.len()].copy_from_slice(data);
+        device
+            .i2c
+            .write(device.address, &self.buffer[..=data.len()])
+            .map_err(CharacterDisplayError::I2cError)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+    use super::*;
+    use embedded_hal_mock::eh1::{
+        delay::NoopDelay,
+        i2c::{Mock as I2cMock, Transaction as I2cTransaction},
+    };
+
+    fn command_transaction(address: u8, commands: &[u8]) -> I2cTransaction {
+        let mut bytes = std::vec![0b0000_0000];
+        bytes.extend_from_slice(commands);
+        I2cTransaction::write(address, bytes)
+    }
+
+    fn data_transaction(address: u8, data: &[u8]) -> I2cTransaction {
+        let mut bytes = std::vec![0b0100_0000];
+        bytes.extend_from_slice(data);
+        I2cTransaction::write(address, bytes)
+    }
+
+    #[test]
+    fn test_us2066_init() {
+        let i2c_address = 0x3c;
+        let expected_i2c_transactions = std::vec![
+            // extended command set, internal VDD regulator on, display off
+            command_transaction(i2c_address, &[0x2A, 0x71]),
+            data_transaction(i2c_address, &[0x5C]),
+            command_transaction(i2c_address, &[0x28, 0x08]),
+            // clock, 2 line mode, scan direction and character ROM
+            command_transaction(
+                i2c_address,
+                &[0x2A, 0x79, 0xD5, 0x70, 0x78, 0x08, 0x06, 0x72]
+            ),
+            data_transaction(i2c_address, &[0x00]),
+            // panel drive settings, ending in the fundamental command set
+            command_transaction(
+                i2c_address,
+                &[
+                    0x2A, 0x79, 0xDA, 0x10, 0xDC, 0x00, 0x81, 0x7F, 0xD9, 0xF1, 0xDB, 0x40, 0x78,
+                    0x28
+                ],
+            ),
+            // clear display
+            command_transaction(i2c_address, &[0x01]),
+            // home, entry mode and display on
+            command_transaction(i2c_address, &[0x80, 0x06, 0x0C]),
+        ];
+
+        let i2c = I2cMock::new(&expected_i2c_transactions);
+        let mut driver = US2066::default();
+        let mut device = DeviceSetupConfig {
+            i2c,
+            address: i2c_address,
+            lcd_type: LcdDisplayType::Lcd16x2,
+            delay: NoopDelay,
+        };
+
+        assert!(driver.init(&mut device).is_ok());
+        device.i2c.done();
+    }
+
+    #[test]
+    fn test_us2066_contrast_and_double_height() {
+        let i2c_address = 0x3c;
+        let expected_i2c_transactions = std::vec![
+            // set contrast to 0x20, passing through the OLED command set
+            command_transaction(i2c_address, &[0x2A, 0x79, 0x81, 0x20, 0x78, 0x28]),
+            // double height on and off
+            command_transaction(i2c_address, &[0x2C]),
+            command_transaction(i2c_address, &[0x28]),
+            // set cursor to (3, 1) and print
+            command_transaction(i2c_address, &[0x80 | 0x43]),
+            data_transaction(i2c_address, b"hi"),
+        ];
+
+        let i2c = I2cMock::new(&expected_i2c_transactions);
+        let mut driver = US2066 {
+            display_function: LCD_FLAG_2LINE,
+            ..Default::default()
+        };
+        let mut device = DeviceSetupConfig {
+            i2c,
+            address: i2c_address,
+            lcd_type: LcdDisplayType::Lcd16x2,
+            delay: NoopDelay,
+        };
+
+        assert!(driver.set_contrast(&mut device, 0x20).is_ok());
+        assert!(driver
+            .set_double_height(&mut device, DoubleHeightMode::Top)
+            .is_ok());
+        assert!(driver
+            .set_double_height(&mut device, DoubleHeightMode::Off)
+            .is_ok());
+        assert!(driver.set_cursor(&mut device, 3, 1).is_ok());
+        assert!(driver.print(&mut device, "hi").is_ok());
+        device.i2c.done();
+    }
+
+    #[test]
+    fn test_us2066_4_line_double_height() {
+        let i2c_address = 0x3c;
+        let expected_i2c_transactions = std::vec![
+            // double height top row, passing through the extended command set
+            command_transaction(i2c_address, &[0x2A, 0x11]),
+            command_transaction(i2c_address, &[0x2C]),
+            // row 1 is now the third line of the display
+            command_transaction(i2c_address, &[0x80 | 0x42]),
+        ];
+
+        let i2c = I2cMock::new(&expected_i2c_transactions);
+        let mut driver = US2066 {
+            display_function: LCD_FLAG_2LINE,
+            ..Default::default()
+        };
+        let mut device = DeviceSetupConfig {
+            i2c,
+            address: i2c_address,
+            lcd_type: LcdDisplayType::Lcd20x4,
+            delay: NoopDelay,
+        };
+
+        assert!(driver
+            .set_double_height(&mut device, DoubleHeightMode::Top)
+            .is_ok());
+        assert!(driver.set_cursor(&mut device, 2, 1).is_ok());
+        assert!(matches!(
+            driver.set_cursor(&mut device, 0, 3),
+            Err(CharacterDisplayError::RowOutOfRange { .. })
+        ));
+        assert_eq!(
+            <US2066<_> as DriverTrait<_, NoopDelay>>::row_offsets(&driver, LcdDisplayType::Lcd20x4),
+            [0x00, 0x40, 0x60, 0x60]
+        );
+        device.i2c.done();
+
+        device.lcd_type = LcdDisplayType::Lcd16x2;
+        assert!(matches!(
+            driver.set_double_height(&mut device, DoubleHeightMode::Middle),
+            Err(CharacterDisplayError::UnsupportedOperation)
+        ));
+    }
+
+    #[test]
+    fn test_us2066_4_line_mode() {
+        assert_eq!(
+            US2066::<I2cMock>::line_mode(LcdDisplayType::Lcd20x4),
+            LCD_FLAG_4LINE
+        );
+        assert_eq!(US2066::<I2cMock>::line_mode(LcdDisplayType::Lcd20x2), 0);
+        assert_eq!(
+            US2066::<I2cMock>::row_offset(LcdDisplayType::Lcd20x4, 3),
+            0x60
+        );
+        assert_eq!(
+            US2066::<I2cMock>::row_offset(LcdDisplayType::Lcd20x2, 1),
+            0x40
+        );
+        assert!(!US2066::<I2cMock>::is_supported(LcdDisplayType::Lcd40x2));
+    }
+}
@@ -0,0 +1,310 @@
+use embedded_hal::{delay::DelayNs, i2c};
+
+use crate::{
+    detect::DetectedAdapter, AdafruitLCDBackpack, Align, CharacterDisplayAIP31068,
+    CharacterDisplayAIP31068RGB, CharacterDisplayDualHD44780, CharacterDisplayError,
+    CharacterDisplayMCP23017, CharacterDisplayPCF2116, CharacterDisplayPCF2119,
+    CharacterDisplayPCF2119R, CharacterDisplayPCF8574T, CharacterDisplayPCF8574TButtons,
+    CharacterDisplayPCF8574TContrast, CharacterDisplayUS2066, ContrastPreset, DisplayState,
+    ErrorContext, ErrorStats, I2cPacing, InitProgress, LcdDisplayType, Timings,
+};
+
+/// A character display whose adapter or controller is chosen at runtime, such as from a DIP switch or a setting in
+/// flash, wrapping one of the character display types. The methods forward to the wrapped display, so it is used
+/// like the display types themselves, without making the code using it generic over the driver. Methods not
+/// available here can be reached through the wrapped display by matching on the variants. Displays with more than
+/// two HD44780 controllers, `CharacterDisplayMultiHD44780`, are not included, since their type depends on the number
+/// of controllers.
+///
+/// ```rust
+/// let mut lcd: DynCharacterDisplay<_, _> = if config.uses_aip31068() {
+///     CharacterDisplayAIP31068::new(i2c, LcdDisplayType::Lcd16x2, delay).into()
+/// } else {
+///     CharacterDisplayPCF8574T::new(i2c, LcdDisplayType::Lcd16x2, delay).into()
+/// };
+/// lcd.init()?;
+/// lcd.print("Hello")?;
+/// ```
+pub enum DynCharacterDisplay<I2C, DELAY>
+where
+    I2C: i2c::I2c,
+    DELAY: DelayNs,
+{
+    /// HD44780 display with a generic PCF8574T adapter
+    PCF8574T(CharacterDisplayPCF8574T<I2C, DELAY>),
+    /// HD44780 display with a PCF8574T adapter that switches between two contrast presets
+    PCF8574TContrast(CharacterDisplayPCF8574TContrast<I2C, DELAY>),
+    /// HD44780 display with a PCF8574T adapter that breaks out a pushbutton
+    PCF8574TButtons(CharacterDisplayPCF8574TButtons<I2C, DELAY>),
+    /// HD44780 display with an MCP23017 adapter
+    MCP23017(CharacterDisplayMCP23017<I2C, DELAY>),
+    /// HD44780 display with an Adafruit I2C/SPI LCD backpack
+    AdafruitLCDBackpack(AdafruitLCDBackpack<I2C, DELAY>),
+    /// Display with two HD44780 controllers and a PCF8574T adapter
+    DualHD44780(CharacterDisplayDualHD44780<I2C, DELAY>),
+    /// AIP31068 display
+    AIP31068(CharacterDisplayAIP31068<I2C, DELAY>),
+    /// AIP31068 display with a PCA9633 RGB backlight controller
+    AIP31068RGB(CharacterDisplayAIP31068RGB<I2C, DELAY>),
+    /// US2066 or SSD1311 OLED display
+    US2066(CharacterDisplayUS2066<I2C, DELAY>),
+    /// PCF2116 display
+    PCF2116(CharacterDisplayPCF2116<I2C, DELAY>),
+    /// PCF2119 display with the ASCII character set
+    PCF2119(CharacterDisplayPCF2119<I2C, DELAY>),
+    /// PCF2119 display with character set R
+    PCF2119R(CharacterDisplayPCF2119R<I2C, DELAY>),
+}
+
+/// Evaluates `$body` with `$lcd` bound to the display wrapped by `$display`.
+macro_rules! dispatch {
+    ($display:expr, $lcd:ident => $body:expr) => {
+        match $display {
+            DynCharacterDisplay::PCF8574T($lcd) => $body,
+            DynCharacterDisplay::PCF8574TContrast($lcd) => $body,
+            DynCharacterDisplay::PCF8574TButtons($lcd) => $body,
+            DynCharacterDisplay::MCP23017($lcd) => $body,
+            DynCharacterDisplay::AdafruitLCDBackpack($lcd) => $body,
+            DynCharacterDisplay::DualHD44780($lcd) => $body,
+            DynCharacterDisplay::AIP31068($lcd) => $body,
+            DynCharacterDisplay::AIP31068RGB($lcd) => $body,
+            DynCharacterDisplay::US2066($lcd) => $body,
+            DynCharacterDisplay::PCF2116($lcd) => $body,
+            DynCharacterDisplay::PCF2119($lcd) => $body,
+            DynCharacterDisplay::PCF2119R($lcd) => $body,
+        }
+    };
+}
+
+/// Defines methods that forward to the method of the same name of the wrapped display and return `self` for
+/// chaining.
+macro_rules! forward_chained {
+    ($($name:ident($($arg:ident: $arg_type:ty),*);)*) => {
+        $(
+            #[doc = concat!("See `BaseCharacterDisplay::", stringify!($name), "()`.")]
+            pub fn $name(&mut self, $($arg: $arg_type),*) -> Result<&mut Self, CharacterDisplayError<I2C>> {
+                dispatch!(self, lcd => lcd.$name($($arg),*).map(|_| ()))?;
+                Ok(self)
+            }
+        )*
+    };
+}
+
+/// Defines methods that forward to the method of the same name of the wrapped display and return its result, taking
+/// `&self` or, after `mut`, `&mut self`.
+macro_rules! forward {
+    () => {};
+    (mut $name:ident($($arg:ident: $arg_type:ty),*) -> $result:ty; $($rest:tt)*) => {
+        #[doc = concat!("See `BaseCharacterDisplay::", stringify!($name), "()`.")]
+        pub fn $name(&mut self, $($arg: $arg_type),*) -> $result {
+            dispatch!(self, lcd => lcd.$name($($arg),*))
+        }
+        forward!($($rest)*);
+    };
+    ($name:ident($($arg:ident: $arg_type:ty),*) -> $result:ty; $($rest:tt)*) => {
+        #[doc = concat!("See `BaseCharacterDisplay::", stringify!($name), "()`.")]
+        pub fn $name(&self, $($arg: $arg_type),*) -> $result {
+            dispatch!(self, lcd => lcd.$name($($arg),*))
+        }
+        forward!($($rest)*);
+    };
+}
+
+/// Implements `From` for each display type wrapped by a variant.
+macro_rules! from_display {
+    ($($variant:ident($display:ident);)*) => {
+        $(
+            impl<I2C, DELAY> From<$display<I2C, DELAY>> for DynCharacterDisplay<I2C, DELAY>
+            where
+                I2C: i2c::I2c,
+                DELAY: DelayNs,
+            {
+                fn from(lcd: $display<I2C, DELAY>) -> Self {
+                    DynCharacterDisplay::$variant(lcd)
+                }
+            }
+        )*
+    };
+}
+
+from_display! {
+    PCF8574T(CharacterDisplayPCF8574T);
+    PCF8574TContrast(CharacterDisplayPCF8574TContrast);
+    PCF8574TButtons(CharacterDisplayPCF8574TButtons);
+    MCP23017(CharacterDisplayMCP23017);
+    AdafruitLCDBackpack(AdafruitLCDBackpack);
+    DualHD44780(CharacterDisplayDualHD44780);
+    AIP31068(CharacterDisplayAIP31068);
+    AIP31068RGB(CharacterDisplayAIP31068RGB);
+    US2066(CharacterDisplayUS2066);
+    PCF2116(CharacterDisplayPCF2116);
+    PCF2119(CharacterDisplayPCF2119);
+    PCF2119R(CharacterDisplayPCF2119R);
+}
+
+impl<I2C, DELAY> DynCharacterDisplay<I2C, DELAY>
+where
+    I2C: i2c::I2c,
+    DELAY: DelayNs,
+{
+    /// Create the display for an adapter found by `detect::detect()`, at the address it was found at. Returns `None`
+    /// for `DetectedAdapter::GpioExpander`, whose kind of adapter is not known.
+    pub fn from_detected(
+        adapter: DetectedAdapter,
+        i2c: I2C,
+        lcd_type: LcdDisplayType,
+        delay: DELAY,
+    ) -> Option<Self> {
+        match adapter {
+            DetectedAdapter::PCF8574 { address } => Some(
+                CharacterDisplayPCF8574T::new_with_address(i2c, address, lcd_type, delay).into(),
+            ),
+            DetectedAdapter::AIP31068 { address } => Some(
+                CharacterDisplayAIP31068::new_with_address(i2c, address, lcd_type, delay).into(),
+            ),
+            DetectedAdapter::GpioExpander { .. } => None,
+        }
+    }
+
+    forward! {
+        mut init() -> Result<(), CharacterDisplayError<I2C>>;
+        mut init_with_address_fallback() -> Result<u8, CharacterDisplayError<I2C>>;
+        mut init_step() -> Result<InitProgress, CharacterDisplayError<I2C>>;
+        i2c_address() -> u8;
+        display_type() -> LcdDisplayType;
+        rows() -> u8;
+        cols() -> u8;
+        controller_count() -> usize;
+        input_pins() -> u8;
+        cursor_position() -> Option<(u8, u8)>;
+        display_state() -> DisplayState;
+        error_stats() -> ErrorStats;
+        timings() -> Timings;
+        i2c_pacing() -> I2cPacing;
+        error_context() -> Option<ErrorContext>;
+        last_error() -> Option<&CharacterDisplayError<I2C>>;
+        mut take_last_error() -> Option<CharacterDisplayError<I2C>>;
+        has_pending_changes() -> bool;
+        mut read_address_counter() -> Result<u8, CharacterDisplayError<I2C>>;
+        mut read_input_pins() -> Result<u8, CharacterDisplayError<I2C>>;
+        mut pressed_buttons() -> Result<u8, CharacterDisplayError<I2C>>;
+        mut read_screen_row(row: u8, buffer: &mut [u8]) -> Result<usize, CharacterDisplayError<I2C>>;
+        mut screen_hash() -> Result<u32, CharacterDisplayError<I2C>>;
+    }
+
+    forward_chained! {
+        soft_reset();
+        recover();
+        restore_state(state: &DisplayState);
+        clear();
+        clear_row(row: u8);
+        clear_region(col: u8, row: u8, width: u8);
+        home();
+        set_cursor(col: u8, row: u8);
+        set_cursor_linear(index: u16);
+        show_cursor(show_cursor: bool);
+        blink_cursor(blink_cursor: bool);
+        show_display(show_display: bool);
+        scroll_display_left();
+        scroll_display_right();
+        left_to_right();
+        right_to_left();
+        autoscroll(autoscroll: bool);
+        backlight(on: bool);
+        set_backlight_rgb(red: u8, green: u8, blue: u8);
+        set_backlight_brightness(brightness: u8);
+        set_contrast(contrast: u8);
+        set_contrast_preset(preset: ContrastPreset);
+        create_char(location: u8, charmap: [u8; 8]);
+        read_device_data(buffer: &mut [u8]);
+        progress_bar(col: u8, row: u8, width: u8, percent: u8);
+        print(text: &str);
+        print_clipped(text: &str, max_len: usize);
+        print_padded(text: &str, width: u8, align: Align);
+        print_at(col: u8, row: u8, text: &str);
+        print_at_wrapping(col: u8, row: u8, text: &str);
+        print_at_index(index: u16, text: &str);
+        print_wrapped(text: &str);
+        print_fmt(args: core::fmt::Arguments<'_>);
+        flush();
+    }
+}
+
+impl<I2C, DELAY> core::fmt::Write for DynCharacterDisplay<I2C, DELAY>
+where
+    I2C: i2c::I2c,
+    DELAY: DelayNs,
+{
+    fn write_str(&mut self, s: &str) -> Result<(), core::fmt::Error> {
+        dispatch!(self, lcd => lcd.write_str(s))
+    }
+}
+
+#[cfg(feature = "ufmt")]
+impl<I2C, DELAY> ufmt::uWrite for DynCharacterDisplay<I2C, DELAY>
+where
+    I2C: i2c::I2c,
+    DELAY: DelayNs,
+{
+    fn write_str(&mut self, s: &str) -> Result<(), CharacterDisplayError<I2C>> {
+        dispatch!(self, lcd => ufmt::uWrite::write_str(lcd, s))
+    }
+
+    type Error = CharacterDisplayError<I2C>;
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+    use super::*;
+    use crate::testing::{aip31068_command_transaction, aip31068_data_transaction, pcf8574_gpio};
+    use core::fmt::Write;
+    use embedded_hal_mock::eh1::{
+        delay::NoopDelay,
+        i2c::{Mock as I2cMock, Transaction as I2cTransaction},
+    };
+
+    #[test]
+    fn test_dyn_character_display() {
+        let expected_i2c_transactions = std::vec![
+            aip31068_command_transaction(0x3e, 0x80 | 0x42),
+            aip31068_data_transaction(0x3e, b"Hi"),
+            aip31068_data_transaction(0x3e, b"42"),
+            I2cTransaction::write(0x3f, pcf8574_gpio(false, false)(0, false)),
+        ];
+
+        let mut i2c = I2cMock::new(&expected_i2c_transactions);
+        let mut lcd: DynCharacterDisplay<_, _> =
+            CharacterDisplayAIP31068::new(i2c.clone(), LcdDisplayType::Lcd16x2, NoopDelay::new())
+                .into();
+        assert_eq!((lcd.cols(), lcd.rows()), (16, 2));
+        assert!(lcd.print_at(2, 1, "Hi").is_ok());
+        let answer = 42;
+        assert!(write!(lcd, "{answer}").is_ok());
+        assert_eq!(lcd.cursor_position(), Some((6, 1)));
+        // the AIP31068 has no backlight control
+        assert!(matches!(
+            lcd.backlight(false),
+            Err(CharacterDisplayError::UnsupportedOperation)
+        ));
+
+        let mut lcd = DynCharacterDisplay::from_detected(
+            DetectedAdapter::PCF8574 { address: 0x3f },
+            i2c.clone(),
+            LcdDisplayType::Lcd20x4,
+            NoopDelay::new(),
+        )
+        .unwrap();
+        assert!(matches!(lcd, DynCharacterDisplay::PCF8574T(_)));
+        assert_eq!(lcd.i2c_address(), 0x3f);
+        assert!(lcd.backlight(false).is_ok());
+        assert!(DynCharacterDisplay::from_detected(
+            DetectedAdapter::GpioExpander { address: 0x20 },
+            i2c.clone(),
+            LcdDisplayType::Lcd20x4,
+            NoopDelay::new(),
+        )
+        .is_none());
+        i2c.done();
+    }
+}
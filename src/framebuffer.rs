@@ -0,0 +1,162 @@
+/// The largest number of rows held by the framebuffer.
+pub(crate) const FRAMEBUFFER_ROWS: usize = 4;
+/// The largest number of columns held by the framebuffer.
+pub(crate) const FRAMEBUFFER_COLS: usize = 40;
+/// The largest number of blinking regions.
+pub(crate) const MAX_BLINK_REGIONS: usize = 8;
+
+/// Cells of a row whose text blinks, alternately shown and hidden for half of `period_ms` each.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct BlinkRegion {
+    pub(crate) col: u8,
+    pub(crate) row: u8,
+    pub(crate) len: u8,
+    pub(crate) period_ms: u32,
+}
+
+/// In-RAM copy of the display contents used when framebuffer mode is enabled. Printing writes into `pending`, and
+/// flushing writes the cells of `pending` that differ from `shown` to the display.
+///
+/// The buffer is supplied by the application to `BaseCharacterDisplay::with_framebuffer()`, so displays that do not
+/// use framebuffer mode do not hold one. It is usually placed in a `static`, such as with `static_cell::StaticCell`.
+#[derive(Clone)]
+pub struct Framebuffer {
+    /// The display contents as printed, waiting to be flushed
+    pub(crate) pending: [[u8; FRAMEBUFFER_COLS]; FRAMEBUFFER_ROWS],
+    /// The display contents as last written to the display
+    pub(crate) shown: [[u8; FRAMEBUFFER_COLS]; FRAMEBUFFER_ROWS],
+    /// The position in `pending` that the next print writes to, as `( col, row )`
+    pub(crate) cursor: (u8, u8),
+    /// The cells of each row that are shown in inverse video, one bit per column
+    pub(crate) inverse: [u64; FRAMEBUFFER_ROWS],
+    /// The regions whose text blinks
+    pub(crate) blink_regions: [Option<BlinkRegion>; MAX_BLINK_REGIONS],
+    /// The cells of each row that are blanked in the hidden half of their blink period, one bit per column
+    pub(crate) hidden: [u64; FRAMEBUFFER_ROWS],
+}
+
+impl Default for Framebuffer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Framebuffer {
+    /// Create a framebuffer for a blank display.
+    pub const fn new() -> Self {
+        Self {
+            pending: [[b' '; FRAMEBUFFER_COLS]; FRAMEBUFFER_ROWS],
+            shown: [[b' '; FRAMEBUFFER_COLS]; FRAMEBUFFER_ROWS],
+            cursor: (0, 0),
+            inverse: [0; FRAMEBUFFER_ROWS],
+            blink_regions: [None; MAX_BLINK_REGIONS],
+            hidden: [0; FRAMEBUFFER_ROWS],
+        }
+    }
+
+    /// Reset the framebuffer to that of a blank display.
+    pub(crate) fn reset(&mut self) {
+        *self = Self::new();
+    }
+
+    /// Blank the pending contents, turn off inverse video and blinking and move the cursor home.
+    pub(crate) fn clear(&mut self) {
+        self.pending = [[b' '; FRAMEBUFFER_COLS]; FRAMEBUFFER_ROWS];
+        self.inverse = [0; FRAMEBUFFER_ROWS];
+        self.blink_regions = [None; MAX_BLINK_REGIONS];
+        self.hidden = [0; FRAMEBUFFER_ROWS];
+        self.cursor = (0, 0);
+    }
+
+    /// Make a region blink, replacing the region starting at the same cell, if any. A `period_ms` of 0 stops the
+    /// region from blinking instead. Returns `false` when all the blinking regions are in use.
+    pub(crate) fn set_blink(&mut self, region: BlinkRegion) -> bool {
+        let existing = self
+            .blink_regions
+            .iter()
+            .position(|r| r.is_some_and(|r| r.col == region.col && r.row == region.row));
+        let slot = existing.or_else(|| self.blink_regions.iter().position(Option::is_none));
+        if region.period_ms == 0 {
+            if let Some(slot) = existing {
+                self.blink_regions[slot] = None;
+            }
+        } else if let Some(slot) = slot {
+            self.blink_regions[slot] = Some(region);
+        } else {
+            return false;
+        }
+        self.update_hidden(None);
+        true
+    }
+
+    /// Work out which cells are hidden at `now_ms`, or show all the cells when `now_ms` is `None`. Each region is
+    /// shown in the first half of its period and hidden in the second half.
+    pub(crate) fn update_hidden(&mut self, now_ms: Option<u64>) {
+        self.hidden = [0; FRAMEBUFFER_ROWS];
+        let Some(now_ms) = now_ms else {
+            return;
+        };
+        for region in self.blink_regions.iter().flatten() {
+            let period_ms = region.period_ms as u64;
+            if now_ms % period_ms < period_ms.div_ceil(2) {
+                continue;
+            }
+            let end = (region.col as usize + region.len as usize).min(FRAMEBUFFER_COLS);
+            for col in region.col as usize..end {
+                self.hidden[region.row as usize] |= 1 << col;
+            }
+        }
+    }
+
+    /// Mark `len` cells of a row starting at `col` as shown in inverse video or not.
+    pub(crate) fn set_inverse(&mut self, col: u8, row: u8, len: u8, on: bool) {
+        let end = (col as usize + len as usize).min(FRAMEBUFFER_COLS);
+        for col in col as usize..end {
+            if on {
+                self.inverse[row as usize] |= 1 << col;
+            } else {
+                self.inverse[row as usize] &= !(1 << col);
+            }
+        }
+    }
+
+    /// The pending contents of a row as they are written to the display, with the cells shown in inverse video
+    /// replaced by the custom character holding the inverse glyph of their character, if there is one, and the
+    /// blinking cells in the hidden half of their period blanked.
+    /// `inverse_glyphs` holds the character whose inverse glyph is at each custom character location.
+    pub(crate) fn rendered_row(
+        &self,
+        row: usize,
+        inverse_glyphs: &[Option<u8>; 8],
+    ) -> [u8; FRAMEBUFFER_COLS] {
+        let mut rendered = self.pending[row];
+        for (col, cell) in rendered.iter_mut().enumerate() {
+            if self.hidden[row] & (1 << col) != 0 {
+                *cell = b' ';
+                continue;
+            }
+            if self.inverse[row] & (1 << col) == 0 {
+                continue;
+            }
+            if let Some(location) = inverse_glyphs.iter().position(|c| *c == Some(*cell)) {
+                *cell = location as u8;
+            }
+        }
+        rendered
+    }
+
+    /// Write text to the pending contents at the cursor, advancing the cursor. Characters that do not fit within
+    /// the first `cols` columns of the row are dropped. Characters are converted to bytes the same way `print()`
+    /// does, so custom characters and the controller's character ROM can be used.
+    pub(crate) fn write(&mut self, text: &str, cols: usize) {
+        let (col, row) = self.cursor;
+        let mut col = col as usize;
+        for c in text.chars() {
+            if col < cols.min(FRAMEBUFFER_COLS) {
+                self.pending[row as usize][col] = c as u8;
+            }
+            col = col.saturating_add(1);
+        }
+        self.cursor = (col.min(u8::MAX as usize) as u8, row);
+    }
+}
@@ -0,0 +1,172 @@
+//! Utilities for converting the character maps of custom characters to and from ASCII art, so tests and debug logs
+//! can show what a custom character looks like. Each of the 8 rows of a character map is a line of 5 pixels, with
+//! `#` for a pixel that is on and `.` for a pixel that is off.
+//!
+//! ```rust
+//! use i2c_character_display::glyph::{glyph_art, parse_glyph};
+//!
+//! let heart = parse_glyph(
+//!     "
+//!     .....
+//!     .#.#.
+//!     #####
+//!     #####
+//!     .###.
+//!     ..#..
+//!     .....
+//!     .....
+//!     ",
+//! )?;
+//! lcd.create_char(0, heart)?;
+//! log::debug!("heart:\n{}", glyph_art(&heart));
+//! ```
+
+use core::fmt;
+
+/// The number of pixel columns of a character
+const GLYPH_COLS: usize = 5;
+/// The number of pixel rows of a character map
+const GLYPH_ROWS: usize = 8;
+
+/// A character map rendered as ASCII art by its `Display` implementation, returned by [`glyph_art`]. The rows are
+/// separated by new lines, without one after the last row.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct GlyphArt([u8; GLYPH_ROWS]);
+
+impl fmt::Display for GlyphArt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (row, bits) in self.0.iter().enumerate() {
+            if row > 0 {
+                f.write_str("\n")?;
+            }
+            for col in 0..GLYPH_COLS {
+                let pixel = bits & (1 << (GLYPH_COLS - 1 - col)) != 0;
+                f.write_str(if pixel { "#" } else { "." })?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Errors from parsing the ASCII art of a character map with [`parse_glyph`].
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum GlyphParseError {
+    /// A character other than `#` or `.` at the zero-indexed row and column.
+    InvalidCharacter { row: usize, col: usize },
+    /// The zero-indexed row does not have 5 pixels.
+    RowLength { row: usize },
+    /// The art does not have 8 rows.
+    RowCount,
+}
+
+impl fmt::Display for GlyphParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GlyphParseError::InvalidCharacter { row, col } => {
+                write!(f, "Invalid character at row {row}, column {col}")
+            }
+            GlyphParseError::RowLength { row } => write!(f, "Row {row} is not 5 pixels long"),
+            GlyphParseError::RowCount => write!(f, "Glyph does not have 8 rows"),
+        }
+    }
+}
+
+/// Render a character map as ASCII art.
+pub const fn glyph_art(charmap: &[u8; 8]) -> GlyphArt {
+    GlyphArt(*charmap)
+}
+
+/// Invert a character map, turning on the pixels that are off and off the pixels that are on, for showing the
+/// character in inverse video.
+pub const fn inverted(charmap: &[u8; 8]) -> [u8; 8] {
+    let mut inverted = [0; GLYPH_ROWS];
+    let mut row = 0;
+    while row < GLYPH_ROWS {
+        inverted[row] = !charmap[row] & 0x1F;
+        row += 1;
+    }
+    inverted
+}
+
+/// Parse ASCII art of 8 rows of 5 pixels into a character map. Leading and trailing whitespace on each line is
+/// ignored, as are blank lines, so the art can be indented in a multi-line string literal.
+pub fn parse_glyph(art: &str) -> Result<[u8; 8], GlyphParseError> {
+    let mut charmap = [0; GLYPH_ROWS];
+    let mut rows = art.lines().map(str::trim).filter(|line| !line.is_empty());
+    for (row, bits) in charmap.iter_mut().enumerate() {
+        let line = rows.next().ok_or(GlyphParseError::RowCount)?;
+        if line.chars().count() != GLYPH_COLS {
+            return Err(GlyphParseError::RowLength { row });
+        }
+        for (col, pixel) in line.chars().enumerate() {
+            *bits <<= 1;
+            match pixel {
+                '#' => *bits |= 1,
+                '.' => {}
+                _ => return Err(GlyphParseError::InvalidCharacter { row, col }),
+            }
+        }
+    }
+    if rows.next().is_some() {
+        return Err(GlyphParseError::RowCount);
+    }
+    Ok(charmap)
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+    use super::*;
+    use std::string::ToString;
+
+    const HEART: [u8; 8] = [0x00, 0x0A, 0x1F, 0x1F, 0x0E, 0x04, 0x00, 0x00];
+    const HEART_ART: &str = ".....\n.#.#.\n#####\n#####\n.###.\n..#..\n.....\n.....";
+
+    #[test]
+    fn test_glyph_art() {
+        assert_eq!(glyph_art(&HEART).to_string(), HEART_ART);
+        // the unused upper bits of each row are ignored
+        assert_eq!(
+            glyph_art(&[0xE0; 8]).to_string(),
+            glyph_art(&[0; 8]).to_string()
+        );
+    }
+
+    #[test]
+    fn test_parse_glyph() {
+        assert_eq!(parse_glyph(HEART_ART), Ok(HEART));
+        assert_eq!(
+            inverted(&HEART),
+            [0x1F, 0x15, 0x00, 0x00, 0x11, 0x1B, 0x1F, 0x1F]
+        );
+        assert_eq!(
+            parse_glyph(&glyph_art(&[0x15; 8]).to_string()),
+            Ok([0x15; 8])
+        );
+        // indentation and blank lines are ignored
+        let indented = "
+            .....
+            .#.#.
+            #####
+            #####
+            .###.
+            ..#..
+            .....
+            .....
+        ";
+        assert_eq!(parse_glyph(indented), Ok(HEART));
+        assert_eq!(
+            parse_glyph(&HEART_ART.replace("#.#", "#x#")),
+            Err(GlyphParseError::InvalidCharacter { row: 1, col: 2 })
+        );
+        assert_eq!(
+            parse_glyph(&HEART_ART.replace("#####", "####")),
+            Err(GlyphParseError::RowLength { row: 2 })
+        );
+        assert_eq!(parse_glyph(".....\n....."), Err(GlyphParseError::RowCount));
+        assert_eq!(
+            parse_glyph(&(HEART_ART.to_string() + "\n.....")),
+            Err(GlyphParseError::RowCount)
+        );
+    }
+}
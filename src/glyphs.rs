@@ -0,0 +1,139 @@
+//! Ready-made 5x8 icons for custom characters, such as arrows, battery levels and Wi-Fi signal bars. A [`Glyph`] is
+//! uploaded with `load_glyph()`, which returns the character to print it with:
+//!
+//! ```rust
+//! use i2c_character_display::glyphs::Glyph;
+//!
+//! let battery = lcd.load_glyph(0, Glyph::Battery(3))?;
+//! let wifi = lcd.load_glyph(1, Glyph::WifiBars(2))?;
+//! lcd_print!(lcd, 14, 0, "{}{}", wifi, battery)?;
+//! ```
+
+/// The number of levels of a battery icon above empty.
+pub const BATTERY_LEVELS: u8 = 5;
+/// The number of bars of a Wi-Fi signal icon.
+pub const WIFI_BARS: u8 = 3;
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+/// A predefined icon for a custom character.
+pub enum Glyph {
+    /// An arrow pointing up
+    ArrowUp,
+    /// An arrow pointing down
+    ArrowDown,
+    /// An arrow pointing left
+    ArrowLeft,
+    /// An arrow pointing right
+    ArrowRight,
+    /// A battery filled to a level from 0 (empty) to `BATTERY_LEVELS` (full). Higher levels show a full battery.
+    Battery(u8),
+    /// A Wi-Fi signal with from 0 to `WIFI_BARS` bars lit. More bars show all the bars lit.
+    WifiBars(u8),
+    /// A thermometer
+    Thermometer,
+    /// A bell, such as for an alarm
+    Bell,
+    /// A padlock
+    Lock,
+    /// The degree sign, which the standard A00 character ROM lacks
+    Degree,
+    /// The ohm sign
+    Ohm,
+}
+
+impl Glyph {
+    /// Returns the character map of the icon.
+    pub const fn charmap(self) -> [u8; 8] {
+        match self {
+            Glyph::ArrowUp => [0x04, 0x0E, 0x15, 0x04, 0x04, 0x04, 0x00, 0x00],
+            Glyph::ArrowDown => [0x04, 0x04, 0x04, 0x15, 0x0E, 0x04, 0x00, 0x00],
+            Glyph::ArrowLeft => [0x00, 0x04, 0x08, 0x1F, 0x08, 0x04, 0x00, 0x00],
+            Glyph::ArrowRight => [0x00, 0x04, 0x02, 0x1F, 0x02, 0x04, 0x00, 0x00],
+            Glyph::Battery(level) => battery(level),
+            Glyph::WifiBars(bars) => wifi_bars(bars),
+            Glyph::Thermometer => [0x04, 0x0A, 0x0A, 0x0A, 0x0E, 0x1F, 0x1F, 0x0E],
+            Glyph::Bell => [0x04, 0x0E, 0x0E, 0x0E, 0x1F, 0x00, 0x04, 0x00],
+            Glyph::Lock => [0x0E, 0x11, 0x11, 0x1F, 0x1B, 0x1B, 0x1F, 0x00],
+            Glyph::Degree => [0x0C, 0x12, 0x12, 0x0C, 0x00, 0x00, 0x00, 0x00],
+            Glyph::Ohm => [0x00, 0x0E, 0x11, 0x11, 0x11, 0x0A, 0x1B, 0x00],
+        }
+    }
+}
+
+/// A battery outline with a terminal on top, filled from the bottom by one of its 5 inner rows per level.
+const fn battery(level: u8) -> [u8; 8] {
+    let mut charmap = [0x0E, 0x11, 0x11, 0x11, 0x11, 0x11, 0x1F, 0x00];
+    let level = if level > BATTERY_LEVELS {
+        BATTERY_LEVELS
+    } else {
+        level
+    };
+    let mut row = 6 - level as usize;
+    while row < 6 {
+        charmap[row] = 0x1F;
+        row += 1;
+    }
+    charmap
+}
+
+/// Bars of rising height in columns 0, 2 and 4, with the bars that are not lit shown as a dot on the bottom row.
+const fn wifi_bars(bars: u8) -> [u8; 8] {
+    let mut charmap = [0; 8];
+    let mut bar = 0;
+    while bar < WIFI_BARS {
+        let column = 0x10 >> (2 * bar);
+        let height = if bar < bars { 2 * (bar + 1) } else { 1 };
+        let mut row = 7 - height as usize;
+        while row < 7 {
+            charmap[row] |= column;
+            row += 1;
+        }
+        bar += 1;
+    }
+    charmap
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::glyph::parse_glyph;
+
+    #[test]
+    fn test_glyph_charmaps() {
+        let half_full = parse_glyph(
+            "
+            .###.
+            #...#
+            #...#
+            #####
+            #####
+            #####
+            #####
+            .....
+            ",
+        );
+        assert_eq!(Ok(Glyph::Battery(3).charmap()), half_full);
+        assert_eq!(Glyph::Battery(0).charmap()[1..6], [0x11; 5]);
+        assert_eq!(Glyph::Battery(9).charmap(), Glyph::Battery(5).charmap());
+
+        let two_bars = parse_glyph(
+            "
+            .....
+            .....
+            .....
+            ..#..
+            ..#..
+            #.#..
+            #.#.#
+            .....
+            ",
+        );
+        assert_eq!(Ok(Glyph::WifiBars(2).charmap()), two_bars);
+        assert_eq!(Glyph::WifiBars(0).charmap()[6], 0x15);
+        assert_eq!(Glyph::WifiBars(3).charmap()[..2], [0x00, 0x01]);
+        assert_eq!(
+            Some(Glyph::Degree.charmap()),
+            crate::charset::fallback_glyph('°')
+        );
+    }
+}
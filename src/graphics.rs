@@ -0,0 +1,220 @@
+//! An [`embedded-graphics`](https://crates.io/crates/embedded-graphics) draw target for simple monochrome graphics on
+//! a character display. Enabled by the `embedded-graphics` feature. A [`CellCanvas`] is a block of character cells,
+//! each of which is 5x8 pixels, that is drawn into with embedded-graphics and then shown on the display with
+//! [`CellCanvas::flush`], which turns the cells into custom characters. A display only has 8 custom characters, so
+//! at most 8 cells of the canvas can have distinct pixels at a time. Blank cells and cells with identical pixels do
+//! not count towards this limit.
+//!
+//! ```rust
+//! use embedded_graphics::{pixelcolor::BinaryColor, prelude::*, primitives::{Circle, PrimitiveStyle}};
+//! use i2c_character_display::graphics::CellCanvas;
+//!
+//! let mut canvas = CellCanvas::<4, 2>::new();
+//! Circle::new(Point::new(2, 0), 16)
+//!     .into_styled(PrimitiveStyle::with_stroke(BinaryColor::On, 1))
+//!     .draw(&mut canvas)?;
+//! canvas.flush(&mut lcd, 6, 0)?;
+//! ```
+
+use embedded_graphics_core::{
+    draw_target::DrawTarget,
+    geometry::{OriginDimensions, Size},
+    pixelcolor::BinaryColor,
+    Pixel,
+};
+use embedded_hal::{delay::DelayNs, i2c};
+
+use crate::{driver::DriverTrait, BaseCharacterDisplay, CharacterDisplayError};
+
+/// The number of pixel columns of a character cell
+const CELL_WIDTH: usize = 5;
+/// The number of pixel rows of a character cell
+const CELL_HEIGHT: usize = 8;
+/// The number of custom characters the cells are shown with
+const CUSTOM_CHARS: usize = 8;
+
+/// A block of `COLS` by `ROWS` character cells to draw into with embedded-graphics, which is `COLS * 5` by
+/// `ROWS * 8` pixels. The cells are shown with custom character locations 0 to 7, so no other custom characters can
+/// be used while a canvas is shown.
+#[derive(Debug, Clone)]
+pub struct CellCanvas<const COLS: usize, const ROWS: usize> {
+    cells: [[[u8; CELL_HEIGHT]; COLS]; ROWS],
+    /// The glyphs uploaded to each custom character location by the last `flush()`
+    uploaded: [Option<[u8; CELL_HEIGHT]>; CUSTOM_CHARS],
+}
+
+impl<const COLS: usize, const ROWS: usize> Default for CellCanvas<COLS, ROWS> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const COLS: usize, const ROWS: usize> CellCanvas<COLS, ROWS> {
+    /// Create a canvas with all pixels off.
+    pub const fn new() -> Self {
+        Self {
+            cells: [[[0; CELL_HEIGHT]; COLS]; ROWS],
+            uploaded: [None; CUSTOM_CHARS],
+        }
+    }
+
+    /// Forget which glyphs are in the custom character locations, so the next `flush()` uploads all of them again.
+    /// Call this when the custom characters were overwritten, such as by `init()` or `create_char()`.
+    pub fn invalidate(&mut self) -> &mut Self {
+        self.uploaded = [None; CUSTOM_CHARS];
+        self
+    }
+
+    /// Show the canvas on the display with its top left cell at the specified column and row. The glyphs of the
+    /// cells are uploaded to the custom character locations that do not hold them already, then the cells are
+    /// printed. Fails with `CharacterDisplayError::UnsupportedOperation` before anything is written when more than 8
+    /// cells have distinct pixels, and with `CharacterDisplayError::ColumnOutOfRange` or
+    /// `CharacterDisplayError::RowOutOfRange` when the canvas does not fit on the display.
+    pub fn flush<I2C, DELAY, DEVICE>(
+        &mut self,
+        lcd: &mut BaseCharacterDisplay<I2C, DELAY, DEVICE>,
+        col: u8,
+        row: u8,
+    ) -> Result<(), CharacterDisplayError<I2C>>
+    where
+        I2C: i2c::I2c,
+        DELAY: DelayNs,
+        DEVICE: DriverTrait<I2C, DELAY>,
+    {
+        if col as usize + COLS > lcd.cols() as usize {
+            return Err(CharacterDisplayError::ColumnOutOfRange { col });
+        }
+        if row as usize + ROWS > lcd.rows() as usize {
+            return Err(CharacterDisplayError::RowOutOfRange { row });
+        }
+
+        let mut glyphs = [[0; CELL_HEIGHT]; CUSTOM_CHARS];
+        let mut glyph_count = 0;
+        let mut codes = [[b' '; COLS]; ROWS];
+        for (cell_row, code_row) in self.cells.iter().zip(codes.iter_mut()) {
+            for (cell, code) in cell_row.iter().zip(code_row.iter_mut()) {
+                if cell.iter().all(|bits| *bits == 0) {
+                    continue;
+                }
+                let location = match glyphs[..glyph_count].iter().position(|g| g == cell) {
+                    Some(location) => location,
+                    None if glyph_count < CUSTOM_CHARS => {
+                        glyphs[glyph_count] = *cell;
+                        glyph_count += 1;
+                        glyph_count - 1
+                    }
+                    None => return Err(CharacterDisplayError::UnsupportedOperation),
+                };
+                *code = location as u8;
+            }
+        }
+
+        for (location, glyph) in glyphs[..glyph_count].iter().enumerate() {
+            if self.uploaded[location] != Some(*glyph) {
+                self.uploaded[location] = None;
+                lcd.create_char(location as u8, *glyph)?;
+                self.uploaded[location] = Some(*glyph);
+            }
+        }
+        for (offset, code_row) in codes.iter().enumerate() {
+            // the cells are spaces and custom character codes, all of which are valid single byte UTF-8
+            let text = core::str::from_utf8(code_row).map_err(|_| core::fmt::Error)?;
+            lcd.print_at(col, row + offset as u8, text)?;
+        }
+        Ok(())
+    }
+}
+
+impl<const COLS: usize, const ROWS: usize> OriginDimensions for CellCanvas<COLS, ROWS> {
+    fn size(&self) -> Size {
+        Size::new((COLS * CELL_WIDTH) as u32, (ROWS * CELL_HEIGHT) as u32)
+    }
+}
+
+impl<const COLS: usize, const ROWS: usize> DrawTarget for CellCanvas<COLS, ROWS> {
+    type Color = BinaryColor;
+    type Error = core::convert::Infallible;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        for Pixel(point, color) in pixels {
+            let (Ok(x), Ok(y)) = (usize::try_from(point.x), usize::try_from(point.y)) else {
+                continue;
+            };
+            if x >= COLS * CELL_WIDTH || y >= ROWS * CELL_HEIGHT {
+                continue;
+            }
+            let bits = &mut self.cells[y / CELL_HEIGHT][x / CELL_WIDTH][y % CELL_HEIGHT];
+            let mask = 1 << (CELL_WIDTH - 1 - x % CELL_WIDTH);
+            match color {
+                BinaryColor::On => *bits |= mask,
+                BinaryColor::Off => *bits &= !mask,
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+    use super::*;
+    use crate::{
+        testing::{aip31068_command_transaction, aip31068_data_transaction},
+        CharacterDisplayAIP31068, LcdDisplayType,
+    };
+    use embedded_graphics_core::geometry::Point;
+    use embedded_hal_mock::eh1::{delay::NoopDelay, i2c::Mock as I2cMock};
+
+    #[test]
+    fn test_cell_canvas() {
+        let i2c_address = 0x3e;
+        let mut glyph = [0; 8];
+        glyph[0] = 0x10;
+        glyph[7] = 0x01;
+        let expected_i2c_transactions = std::vec![
+            // the two cells with the same pixels share a custom character
+            aip31068_command_transaction(i2c_address, 0x40),
+            aip31068_data_transaction(i2c_address, &glyph),
+            aip31068_command_transaction(i2c_address, 0x80 | 0x04),
+            aip31068_data_transaction(i2c_address, &[0, b' ', 0]),
+            // the glyph is already uploaded, so it is only printed again
+            aip31068_command_transaction(i2c_address, 0x80 | 0x04),
+            aip31068_data_transaction(i2c_address, &[0, b' ', 0]),
+        ];
+
+        let i2c = I2cMock::new(&expected_i2c_transactions);
+        let mut lcd = CharacterDisplayAIP31068::new(i2c, LcdDisplayType::Lcd16x2, NoopDelay::new());
+        let mut canvas = CellCanvas::<3, 1>::new();
+        assert_eq!(canvas.size(), Size::new(15, 8));
+        let pixels = [(0, 0), (4, 7), (10, 0), (14, 7), (15, 0), (-1, 3), (7, 3)];
+        assert!(canvas
+            .draw_iter(pixels.map(|(x, y)| Pixel(Point::new(x, y), BinaryColor::On)))
+            .is_ok());
+        assert!(canvas
+            .draw_iter([Pixel(Point::new(7, 3), BinaryColor::Off)])
+            .is_ok());
+        assert!(canvas.flush(&mut lcd, 4, 0).is_ok());
+        assert!(canvas.flush(&mut lcd, 4, 0).is_ok());
+        assert!(matches!(
+            canvas.flush(&mut lcd, 14, 0),
+            Err(CharacterDisplayError::ColumnOutOfRange { .. })
+        ));
+
+        // a canvas with more than 8 distinct cells cannot be shown
+        let mut canvas = CellCanvas::<9, 1>::new();
+        assert!(canvas
+            .draw_iter(
+                (0..9)
+                    .map(|cell| Pixel(Point::new(cell * 5 + cell / 8, cell % 8), BinaryColor::On))
+            )
+            .is_ok());
+        assert!(matches!(
+            canvas.flush(&mut lcd, 0, 0),
+            Err(CharacterDisplayError::UnsupportedOperation)
+        ));
+        lcd.i2c().done();
+    }
+}
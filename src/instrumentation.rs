@@ -0,0 +1,81 @@
+//! Optional hooks for measuring how long display operations take, to find display update hotspots in tight
+//! control loops. Implement [`Instrumentation`] with a microsecond clock and attach it to the display with
+//! `with_instrumentation()`. Displays without instrumentation attached have no timing overhead.
+//!
+//! ```rust
+//! use core::sync::atomic::{AtomicU32, Ordering};
+//! use i2c_character_display::instrumentation::{DisplayOperation, Instrumentation};
+//!
+//! struct PrintTimer {
+//!     total_us: AtomicU32,
+//! }
+//!
+//! impl Instrumentation for PrintTimer {
+//!     fn now_us(&self) -> u64 {
+//!         // read a free running microsecond timer
+//!         timer_now_us()
+//!     }
+//!
+//!     fn operation_finished(&self, operation: DisplayOperation, started_us: u64, finished_us: u64) {
+//!         if operation == DisplayOperation::Print {
+//!             let elapsed_us = (finished_us - started_us) as u32;
+//!             self.total_us.fetch_add(elapsed_us, Ordering::Relaxed);
+//!         }
+//!     }
+//! }
+//!
+//! static TIMER: PrintTimer = PrintTimer { total_us: AtomicU32::new(0) };
+//! let mut lcd = CharacterDisplayPCF8574T::new(i2c, LcdDisplayType::Lcd16x2, delay).with_instrumentation(&TIMER);
+//! ```
+
+/// The display operations reported to [`Instrumentation`].
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum DisplayOperation {
+    Init,
+    SoftReset,
+    RestoreState,
+    Clear,
+    Home,
+    SetCursor,
+    ShowCursor,
+    BlinkCursor,
+    ShowDisplay,
+    ScrollLeft,
+    ScrollRight,
+    LeftToRight,
+    RightToLeft,
+    Autoscroll,
+    Print,
+    CreateChar,
+    Backlight,
+    BacklightRgb,
+    BacklightBrightness,
+    SetContrastPreset,
+    SetContrast,
+    ReadInputPins,
+    DoubleHeight,
+    ReadDeviceData,
+    ReadAddressCounter,
+    Flush,
+}
+
+/// Receives timing callbacks for display operations. The timestamps are in microseconds from the clock provided
+/// by `now_us()`. The callbacks take `&self` so the implementation can be shared as a `&'static` reference, so
+/// use interior mutability such as atomics or a critical section mutex to record measurements. The implementation
+/// must be `Sync` so that a display with instrumentation attached can still be moved to another task.
+pub trait Instrumentation: Sync {
+    /// Returns the current time in microseconds.
+    fn now_us(&self) -> u64;
+
+    /// Called when a display operation starts.
+    fn operation_started(&self, _operation: DisplayOperation, _timestamp_us: u64) {}
+
+    /// Called when a display operation finishes, whether or not it succeeded.
+    fn operation_finished(
+        &self,
+        _operation: DisplayOperation,
+        _started_us: u64,
+        _finished_us: u64,
+    ) {
+    }
+}
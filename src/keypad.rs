@@ -0,0 +1,185 @@
+//! Input from a 4x4 matrix keypad wired to a PCF8574 I2C GPIO expander, as on the classic front panels that pair a
+//! character display with a keypad on the same I2C bus. [`PCF8574Keypad`] scans the keypad one row at a time and
+//! debounces the key presses. The bus is shared with the display the same way as with any other device, such as
+//! through the bus sharing devices of `embedded-hal-bus`.
+//!
+//! ```rust
+//! use core::cell::RefCell;
+//! use embedded_hal_bus::i2c::RefCellDevice;
+//! use i2c_character_display::{keypad::PCF8574Keypad, CharacterDisplayPCF8574T, LcdDisplayType};
+//!
+//! let bus = RefCell::new(i2c);
+//! let mut lcd = CharacterDisplayPCF8574T::new_ref_cell(&bus, LcdDisplayType::Lcd16x2, delay);
+//! let mut keypad = PCF8574Keypad::new(RefCellDevice::new(&bus), 0x20);
+//! lcd.init()?;
+//! loop {
+//!     if let Some(key) = keypad.poll()? {
+//!         write!(lcd, "{}", key)?;
+//!     }
+//!     timer.delay_ms(5);
+//! }
+//! ```
+
+use embedded_hal::i2c;
+
+use crate::CharacterDisplayError;
+
+/// The default I2C address of the PCF8574 with all address pins low.
+pub const DEFAULT_KEYPAD_ADDRESS: u8 = 0x20;
+
+/// The keys of a standard 4x4 keypad, by row and column.
+pub const STANDARD_KEYMAP: [[char; 4]; 4] = [
+    ['1', '2', '3', 'A'],
+    ['4', '5', '6', 'B'],
+    ['7', '8', '9', 'C'],
+    ['*', '0', '#', 'D'],
+];
+
+/// The default number of consecutive scans a key has to be read in before its press is reported.
+const DEFAULT_DEBOUNCE_SCANS: u8 = 3;
+
+/// A 4x4 keypad on a PCF8574, with its rows wired to pins P0 to P3 and its columns to pins P4 to P7. Each row is
+/// scanned by driving its pin low, which pulls the column pins of its pressed keys low. Only one key is read at a
+/// time; when several are held, the first one in the keymap is read.
+pub struct PCF8574Keypad<I2C>
+where
+    I2C: i2c::I2c,
+{
+    i2c: I2C,
+    address: u8,
+    keymap: [[char; 4]; 4],
+    debounce_scans: u8,
+    /// The key read by the last scans, as `( row, col )`, and the number of consecutive scans it was read in
+    candidate: (Option<(usize, usize)>, u8),
+    /// The key whose press was last reported, until it is released
+    reported: Option<(usize, usize)>,
+}
+
+impl<I2C> PCF8574Keypad<I2C>
+where
+    I2C: i2c::I2c,
+{
+    /// Create a keypad scanner for the PCF8574 at `address`, with the `STANDARD_KEYMAP` keys.
+    pub fn new(i2c: I2C, address: u8) -> Self {
+        Self {
+            i2c,
+            address,
+            keymap: STANDARD_KEYMAP,
+            debounce_scans: DEFAULT_DEBOUNCE_SCANS,
+            candidate: (None, 0),
+            reported: None,
+        }
+    }
+
+    /// Set the key at each row and column of the keypad.
+    pub fn with_keymap(mut self, keymap: [[char; 4]; 4]) -> Self {
+        self.keymap = keymap;
+        self
+    }
+
+    /// Set the number of consecutive scans by `poll()` a key has to be read in before its press is reported. The
+    /// default is 3, and 1 turns off debouncing.
+    pub fn with_debounce(mut self, scans: u8) -> Self {
+        self.debounce_scans = scans.max(1);
+        self
+    }
+
+    /// Returns the I2C bus, to use it for something else.
+    pub fn release(self) -> I2C {
+        self.i2c
+    }
+
+    /// Scan the keypad and return the key that is held, if any, without debouncing.
+    pub fn scan(&mut self) -> Result<Option<char>, CharacterDisplayError<I2C>> {
+        Ok(self
+            .scan_position()?
+            .map(|(row, col)| self.keymap[row][col]))
+    }
+
+    /// Scan the keypad and return a key when it has just been pressed, once it has been read in enough
+    /// consecutive scans. A key held down is reported once, and is reported again only after it was released.
+    /// Call this regularly, such as every few milliseconds.
+    pub fn poll(&mut self) -> Result<Option<char>, CharacterDisplayError<I2C>> {
+        let key = self.scan_position()?;
+        let (candidate, scans) = self.candidate;
+        self.candidate = if candidate == key {
+            (key, scans.saturating_add(1))
+        } else {
+            (key, 1)
+        };
+        if self.candidate.1 < self.debounce_scans || key == self.reported {
+            return Ok(None);
+        }
+        self.reported = key;
+        Ok(key.map(|(row, col)| self.keymap[row][col]))
+    }
+
+    /// Scan the rows in turn and return the first key found held, as `( row, col )`.
+    fn scan_position(&mut self) -> Result<Option<(usize, usize)>, CharacterDisplayError<I2C>> {
+        for row in 0..4 {
+            // the column pins are written high so they can be read as inputs
+            self.i2c
+                .write(self.address, &[!(1 << row)])
+                .map_err(CharacterDisplayError::I2cError)?;
+            let mut pins = [0u8];
+            self.i2c
+                .read(self.address, &mut pins)
+                .map_err(CharacterDisplayError::I2cError)?;
+            if let Some(col) = (0..4).find(|col| pins[0] & (0x10 << col) == 0) {
+                return Ok(Some((row, col)));
+            }
+        }
+        Ok(None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+    use super::*;
+    use embedded_hal_mock::eh1::i2c::{Mock as I2cMock, Transaction as I2cTransaction};
+
+    /// The transactions of a scan of the keypad while the key at `pressed` is held, if any.
+    fn scan_transactions(pressed: Option<(u8, u8)>) -> std::vec::Vec<I2cTransaction> {
+        let mut transactions = std::vec::Vec::new();
+        for row in 0..4 {
+            let driven = !(1u8 << row);
+            let read = match pressed {
+                Some((pressed_row, col)) if pressed_row == row => driven & !(0x10 << col),
+                _ => driven,
+            };
+            transactions.push(I2cTransaction::write(0x20, std::vec![driven]));
+            transactions.push(I2cTransaction::read(0x20, std::vec![read]));
+            if read != driven {
+                break;
+            }
+        }
+        transactions
+    }
+
+    #[test]
+    fn test_keypad_poll() {
+        let mut expected_i2c_transactions = scan_transactions(Some((3, 2)));
+        let held = Some((1, 1));
+        for pressed in [held, held, held, None, held, None, None, held, held] {
+            expected_i2c_transactions.extend(scan_transactions(pressed));
+        }
+
+        let i2c = I2cMock::new(&expected_i2c_transactions);
+        let mut keypad = PCF8574Keypad::new(i2c, DEFAULT_KEYPAD_ADDRESS).with_debounce(2);
+        assert_eq!(keypad.scan().ok(), Some(Some('#')));
+        // the press is reported once the key is read in two scans in a row, and only once while it is held
+        assert_eq!(keypad.poll().ok(), Some(None));
+        assert_eq!(keypad.poll().ok(), Some(Some('5')));
+        assert_eq!(keypad.poll().ok(), Some(None));
+        // a bounce shorter than the debounce does not count as a release
+        assert_eq!(keypad.poll().ok(), Some(None));
+        assert_eq!(keypad.poll().ok(), Some(None));
+        // after a release, the next press is reported again
+        assert_eq!(keypad.poll().ok(), Some(None));
+        assert_eq!(keypad.poll().ok(), Some(None));
+        assert_eq!(keypad.poll().ok(), Some(None));
+        assert_eq!(keypad.poll().ok(), Some(Some('5')));
+        keypad.release().done();
+    }
+}
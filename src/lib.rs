@@ -7,9 +7,24 @@
 //! - **PCF8574-based I2C adapter** - These adapters are ubiquitous on eBay and AliExpress and have no clear branding. Furthermore, some HD44780-based character
 //!   display makers, such as [Surenoo](https://www.surenoo.com), integrate a PCF8574T directly on the display board enabling I2C connections without a seperate adapter.
 //!   The most common pin wiring uses 4 data pins and 3 control pins. Most models have the display's 4-bit mode data pins connected to P4-P7 of the PCF8574.
-//!   This library supports that configuration, though it would be straightforward to add support for other pin configurations.
+//!   This library supports that configuration, though it would be straightforward to add support for other pin configurations. Some HW-061 clone
+//!   backpacks tie the display's RW pin to ground and use P1 to switch the contrast between two presets, which is supported by `CharacterDisplayPCF8574TContrast`.
+//!   Backpacks that instead break out P1 to a pushbutton are supported by `CharacterDisplayPCF8574TButtons`, whose button is read with `pressed_buttons()`.
+//! - **MCP23017-based I2C adapter** - Backpacks built around the MCP23017 16-bit GPIO expander can drive the display through its full 8-bit interface,
+//!   which takes half the I2C transactions per byte of the 4-bit PCF8574 adapters. Port A drives the data pins D0-D7, and port B drives RS on GPB0,
+//!   RW on GPB1, enable on GPB2 and the backlight on GPB3. These are supported by `CharacterDisplayMCP23017`.
+//! - **PCF8575-based multi-controller builds** - Large custom displays, such as a 40x6 or 40x8 display made from three or four 40x2 panels, can be driven by up
+//!   to 4 HD44780 controllers through a PCF8575 16-bit GPIO expander. P0-P7 are wired like the common PCF8574 adapters (without the enable pin on P2), and each
+//!   controller's enable pin is connected to the second port starting at P10. Each controller drives two rows of the display, and panels wired to the enable pins
+//!   in another order can be mapped to their rows with `with_controller_rows()`.
 //! - **AiP31068** - This is a character display controller with a built-in I2C support. The command set is similar to the HD44780, but the controller
 //!   operates in 8-bit mode and is initialized differently.  Examples of displays that use this controller include the [Surenoo SLC1602O](https://www.surenoo.com/products/8109143).
+//! - **US2066 / SSD1311** - These OLED character display controllers have built-in I2C support and share the HD44780 command set, but need an
+//!   extended initialization sequence. They also support software contrast control with `set_contrast` and double height characters with
+//!   `double_height` and `set_double_height`. Examples of displays that use this controller include the Newhaven NHD-0216CW and NHD-0420CW families.
+//! - **PCF2116 / PCF2119** - These NXP character display controllers have built-in I2C support and share the HD44780 command set. The PCF2119
+//!   generates the LCD voltage itself, which is set up when the display is initialized. Some PCF2119 models, such as those with character set R,
+//!   have ASCII at 0xA0 to 0xFF, which `CharacterDisplayPCF2119R` translates printed text to. The PCF2116 can also drive 4 rows of 12 characters.
 //!
 //! Key features include:
 //! - Convenient high-level API for controlling many types of character display
@@ -29,6 +44,15 @@
 //! ```
 //! The `features = ["defmt"]` line is optional and enables the `defmt` feature, which allows the library's errors to be used with the `defmt` logging
 //! framework. Another optional feature is `features = ["ufmt"]`, which enables the `ufmt` feature, allowing the `uwriteln!` and `uwrite!` macros to be used.
+//! For testing code that uses this library, the `testing` feature (which requires `std`) exposes the `testing` module with helpers for building the
+//! I2C transactions expected by an `embedded-hal-mock` I2C mock. The `advanced` feature exposes low-level writes to the HD44780 controllers for
+//! hardware bring-up, such as stepping through the 4-bit interface sync sequence by hand to probe marginal wiring.
+//! The `embedded-graphics` feature exposes the `graphics` module, with a draw target for drawing simple monochrome graphics and
+//! embedded-graphics text into a block of character cells, which are shown with custom characters.
+//! The `embedded-hal-bus` feature adds constructors for displays on an I2C bus shared with other devices through the
+//! `embedded-hal-bus` crate's `CriticalSectionDevice`, `AtomicDevice` and `RefCellDevice`, such as `new_critical_section()`.
+//! The `embassy` feature adds `new_embassy_shared()` for displays in Embassy firmware, on an I2C bus shared through
+//! `embassy_embedded_hal::shared_bus`.
 //!
 //! Then select the appropriate adapter for your display:
 //! ```rust
@@ -47,14 +71,69 @@
 //! let mut lcd = AdafruitLCDBackpack::new(i2c, LcdDisplayType::Lcd16x2, delay);
 //! // PCF8574T adapter for a single HD44780 controller
 //! let mut lcd = CharacterDisplayPCF8574T::new(i2c, LcdDisplayType::Lcd16x2, delay);
+//! // HW-061 clone PCF8574T adapter with a contrast select pin
+//! let mut lcd = CharacterDisplayPCF8574TContrast::new(i2c, LcdDisplayType::Lcd16x2, delay);
+//! lcd.set_contrast_preset(ContrastPreset::Secondary)?;
+//! // MCP23017 adapter driving a single HD44780 controller in 8-bit mode
+//! let mut lcd = CharacterDisplayMCP23017::new(i2c, LcdDisplayType::Lcd16x2, delay);
 //! // Character display with dual HD44780 controllers using a single PCF8574T I2C adapter
 //! let mut lcd = CharacterDisplayDualHD44780::new(i2c, LcdDisplayType::Lcd40x4, delay);
+//! // Character display built from four HD44780 controllers using a PCF8575 I2C GPIO expander
+//! let mut lcd = CharacterDisplayMultiHD44780::<_, _, 4>::new(i2c, LcdDisplayType::Lcd40x8, delay);
 //! // Character display with the AiP31068 controller
 //! let mut lcd = CharacterDisplayAIP31068::new(i2c, LcdDisplayType::Lcd16x2, delay);
+//! // Character OLED display with the US2066 controller
+//! let mut lcd = CharacterDisplayUS2066::new(i2c, LcdDisplayType::Lcd16x2, delay);
+//! // Character display with the PCF2119 controller and character set R
+//! let mut lcd = CharacterDisplayPCF2119R::new(i2c, LcdDisplayType::Lcd16x2, delay);
+//! ```
+//! The I2C bus and the delay can also be borrowed as `&mut i2c` and `&mut delay`, which the `embedded-hal` traits are
+//! implemented for, so one bus and delay provider can be used by several drivers in turn without wrapping them:
+//! ```rust
+//! let mut lcd = CharacterDisplayPCF8574T::new(&mut i2c, LcdDisplayType::Lcd16x2, &mut delay);
+//! lcd.init()?;
+//! lcd.print("Hello")?;
+//! drop(lcd);
+//! let mut sensor = Bme280::new(&mut i2c, &mut delay);
 //! ```
 //! When creating the display object, you can choose the display type from the `LcdDisplayType` enum. The display type should match the physical
 //! display you are using. This display type configures the number of rows and columns, and the internal row offsets for the display.
 //!
+//! On displays with 4 rows, the controller stores the rows interleaved, so by default text printed past the end of row 0 continues on row 2.
+//! `with_addressing(Addressing::RowMajor)` makes printed text continue on the next row instead, as on a terminal.
+//!
+//! The options that are fixed when the display is initialized, such as the I2C address, font size, initial backlight state, entry mode and
+//! busy flag polling strategy, can be set with a `CharacterDisplayBuilder`, which can also initialize the display:
+//! ```rust
+//! let mut lcd = CharacterDisplayPCF8574T::builder(i2c, LcdDisplayType::Lcd16x2, delay)
+//!     .with_address(0x3F)
+//!     .with_backlight(false)
+//!     .init()?;
+//! ```
+//!
+//! The character display types are aliases of `BaseCharacterDisplay` with a device driver from the `drivers` module.
+//! The full type can be named when needed, such as in the signature of a function that creates the display:
+//! ```rust
+//! use i2c_character_display::{drivers::GenericHD44780PCF8574T, BaseCharacterDisplay};
+//!
+//! fn create_display(i2c: I2C, delay: Delay) -> BaseCharacterDisplay<I2C, Delay, GenericHD44780PCF8574T<I2C>> {
+//!     CharacterDisplayPCF8574T::new(i2c, LcdDisplayType::Lcd16x2, delay)
+//! }
+//! ```
+//!
+//! Displays of different types can be used through the object safe `CharacterDisplay` trait, such as to keep them in
+//! one array or to pass them to code that should not name the display type. Its methods return a
+//! `CharacterDisplayErrorKind`, which does not depend on the type of the I2C bus:
+//! ```rust
+//! use i2c_character_display::CharacterDisplay;
+//!
+//! let displays: [&mut dyn CharacterDisplay; 2] = [&mut status_lcd, &mut oled];
+//! for display in displays {
+//!     display.clear()?;
+//!     display.print("ready")?;
+//! }
+//! ```
+//!
 //! Initialize the display:
 //! ```rust
 //! if let Err(e) = lcd.init() {
@@ -72,6 +151,14 @@
 //!
 //! write!(lcd, "Hello, world!")?;
 //! ```
+//! The `lcd_print!` macro combines setting the cursor position and a formatted print into one call with a single
+//! `CharacterDisplayError` result. The `lcd_write!` macro does the same at the current cursor position:
+//! ```rust
+//! use i2c_character_display::{lcd_print, lcd_write};
+//!
+//! lcd_print!(lcd, 0, 1, "T={}C", temperature)?;
+//! lcd_write!(lcd, " H={}%", humidity)?;
+//! ```
 //! The optional `ufmt` feature enables the `ufmt` crate, which allows the `uwriteln!` and `uwrite!` macros to be used with the display:
 //! ```rust
 //! use ufmt::uwriteln;
@@ -89,21 +176,88 @@
 //! from either the CGRAM or DDRAM at the current cursor position. The `read_address_counter` method can be used to read the address counter from the HD44780 controller.
 //! In both cases, the specific meaning of the data depends on the prior commands sent to the display. See the HD44780 datasheet for more information.
 //!
+//! Before each read, the busy flag is polled until the controller is ready. On boards that wire the I2C adapter's interrupt line to the MCU, `with_ready_signal()`
+//! sets a function reading that line, and the busy flag is not read over I2C while the function reports the controller is ready.
+//!
 //! ### Backlight control
 //! All HD44780 controllers support backlight control. The `backlight` method can be used to turn the backlight on or off. The AiP31068 controller does not support
 //! backlight control, and calling the `backlight` method with a AiP31068 controller will return an error.
 //!
+//! AiP31068 displays with a PCA9633 RGB backlight controller, such as the Grove 16x2 RGB LCD (up to v4), can use `CharacterDisplayAIP31068RGB`.
+//! The `set_backlight_rgb` method sets the backlight color, and the `backlight` method turns the backlight off or back on with that color:
+//! ```rust
+//! let mut lcd = CharacterDisplayAIP31068RGB::new(i2c, LcdDisplayType::Lcd16x2, delay);
+//! lcd.init()?;
+//! lcd.set_backlight_rgb(0, 128, 255)?;
+//! ```
+//!
 //! ### Multiple HD44780 controller character displays
 //! Some character displays, such as the 40x4 display, use two HD44780 controllers to drive the display. This library supports these displays by
 //! treating them as one logical display with multiple HD44780 controllers. The `CharacterDisplayDualHD44780` type is used to control these displays.
 //! Use the various methods to control the display as you would with a single HD44780 controller display. The `set_cursor` method sets the active HD44780
 //! controller device based on the row number you select.
 //!
+//! ### Framebuffer mode
+//! Applications that redraw the whole screen on every loop can enable framebuffer mode with `with_framebuffer()`. In this mode `clear`,
+//! `set_cursor` and `print` (and everything built on them, such as `write!`) only update an in-RAM copy of the display, and the `flush`
+//! method writes just the characters that changed since the last flush to the display. This keeps the I2C traffic proportional to what
+//! actually changed rather than to the size of the display. The `Framebuffer` is supplied by the application, so displays that don't
+//! use framebuffer mode don't pay for its RAM.
+//! ```rust
+//! static FRAMEBUFFER: StaticCell<Framebuffer> = StaticCell::new();
+//! let mut lcd = CharacterDisplayPCF8574T::new(i2c, LcdDisplayType::Lcd16x2, delay)
+//!     .with_framebuffer(FRAMEBUFFER.init(Framebuffer::new()));
+//! lcd.init()?;
+//! loop {
+//!     lcd.clear()?;
+//!     write!(lcd, "T={:5.1}C", read_temperature())?;
+//!     lcd.flush()?;
+//! }
+//! ```
+//!
+//! In framebuffer mode, cells can be highlighted in inverse video with `set_inverse()`, for characters whose inverse glyph is preloaded
+//! as a custom character with `with_inverse_glyph()`.
+//!
+//! The controllers can only blink the cursor, but in framebuffer mode any text can blink. `blink_region()` marks the cells
+//! that blink, and `tick()`, called regularly with a `Clock` from the `clock` module, alternately shows and blanks them:
+//! ```rust
+//! let clock = || timer.now().duration_since_epoch().to_millis();
+//! lcd.print_at(0, 0, "ALARM")?;
+//! lcd.blink_region(0, 0, 5, 1000)?;
+//! loop {
+//!     lcd.tick(&clock)?;
+//! }
+//! ```
+//!
+//! ### Terminal mode
+//! Applications that log messages to the display can enable terminal mode with `with_terminal()`. In this mode a `'\n'` printed with
+//! `print` or `write!` moves to the start of the next row, text wraps at the end of each row, and printing past the last row scrolls
+//! the earlier lines up by one row, like a console. The `TerminalBuffer` holding the lines is supplied by the application:
+//! ```rust
+//! static TERMINAL: StaticCell<TerminalBuffer> = StaticCell::new();
+//! let mut lcd = CharacterDisplayPCF8574T::new(i2c, LcdDisplayType::Lcd20x4, delay)
+//!     .with_terminal(TERMINAL.init(TerminalBuffer::new()));
+//! lcd.init()?;
+//! writeln!(lcd, "boot ok")?;
+//! writeln!(lcd, "sensors: {}", sensor_count)?;
+//! ```
+//!
 #![no_std]
 #![allow(dead_code, non_camel_case_types, non_upper_case_globals)]
 use core::fmt::Display;
 
+pub use builder::CharacterDisplayBuilder;
+pub use character_display::CharacterDisplay;
+use charset::CharacterRom;
+pub use dyn_display::DynCharacterDisplay;
 use embedded_hal::{delay::DelayNs, i2c};
+pub use framebuffer::Framebuffer;
+use framebuffer::{BlinkRegion, FRAMEBUFFER_ROWS};
+use instrumentation::{DisplayOperation, Instrumentation};
+pub use queue::CommandQueue;
+use queue::{QueuedCommand, QUEUE_DATA};
+pub use terminal::TerminalBuffer;
+use terminal::{TERMINAL_COLS, TERMINAL_ROWS};
 
 /// HD44780 based character display using a generic PCF8574T I2C adapter.
 pub type CharacterDisplayPCF8574T<I2C, DELAY> =
@@ -113,15 +267,92 @@ pub type CharacterDisplayPCF8574T<I2C, DELAY> =
 pub type AdafruitLCDBackpack<I2C, DELAY> =
     BaseCharacterDisplay<I2C, DELAY, crate::driver::hd44780::AdafruitLCDBackpack<I2C>>;
 
+/// HD44780 based character display using a HW-061 clone PCF8574T I2C adapter that ties the display's RW pin to ground
+/// and uses the freed up P1 pin to switch between two contrast presets. See `set_contrast_preset()`.
+pub type CharacterDisplayPCF8574TContrast<I2C, DELAY> =
+    BaseCharacterDisplay<I2C, DELAY, crate::driver::hd44780::ContrastHD44780PCF8574T<I2C>>;
+
+/// HD44780 based character display using a PCF8574T I2C adapter that ties the display's RW pin to ground and breaks
+/// out the freed up P1 pin to a pushbutton. See `pressed_buttons()`.
+pub type CharacterDisplayPCF8574TButtons<I2C, DELAY> =
+    BaseCharacterDisplay<I2C, DELAY, crate::driver::hd44780::ButtonsHD44780PCF8574T<I2C>>;
+
+/// HD44780 based character display using an MCP23017 I2C adapter that drives the display through the 8-bit interface.
+pub type CharacterDisplayMCP23017<I2C, DELAY> =
+    BaseCharacterDisplay<I2C, DELAY, crate::driver::hd44780::MCP23017HD44780<I2C>>;
+
 /// Character display using dual HD44780 I2C drivers connected using a generic PCF8574T I2C adapter with a pinout that
 /// has two enable pins, one for each HD44780 driver. Typically used for 40x4 character displays.
 pub type CharacterDisplayDualHD44780<I2C, DELAY> =
     BaseCharacterDisplay<I2C, DELAY, crate::driver::hd44780::DualHD44780PCF8574T<I2C>>;
 
+/// Character display using `N` HD44780 controllers connected using a PCF8575 16-bit I2C GPIO expander, with one
+/// enable pin per controller and each controller driving two rows. Typically used for large multi-panel builds,
+/// such as a 40x8 display made from four 40x2 panels. See `with_controller_rows()` for panels wired in another
+/// order.
+pub type CharacterDisplayMultiHD44780<I2C, DELAY, const N: usize> =
+    BaseCharacterDisplay<I2C, DELAY, crate::driver::hd44780::MultiHD44780PCF8575<I2C, N>>;
+
 /// Character display using the AIP31068 controller with built-in I2C adapter.
 pub type CharacterDisplayAIP31068<I2C, DELAY> =
     BaseCharacterDisplay<I2C, DELAY, crate::driver::aip31068::AIP31068<I2C>>;
 
+/// Character display using the AIP31068 controller with a PCA9633 RGB backlight controller, such as the Grove 16x2
+/// RGB LCD. See `set_backlight_rgb()`.
+pub type CharacterDisplayAIP31068RGB<I2C, DELAY> =
+    BaseCharacterDisplay<I2C, DELAY, crate::driver::aip31068_rgb::AIP31068RGB<I2C>>;
+
+/// Character OLED display using the US2066 or SSD1311 controller with built-in I2C adapter, such as the Newhaven
+/// NHD-0216CW family.
+pub type CharacterDisplayUS2066<I2C, DELAY> =
+    BaseCharacterDisplay<I2C, DELAY, crate::driver::us2066::US2066<I2C>>;
+
+/// Character display using the NXP PCF2116 controller with built-in I2C interface.
+pub type CharacterDisplayPCF2116<I2C, DELAY> = BaseCharacterDisplay<
+    I2C,
+    DELAY,
+    crate::driver::pcf211x::PCF211x<I2C, crate::driver::pcf211x::PCF2116>,
+>;
+
+/// Character display using the NXP PCF2119 controller with built-in I2C interface and a character set that has
+/// ASCII at the usual codes.
+pub type CharacterDisplayPCF2119<I2C, DELAY> = BaseCharacterDisplay<
+    I2C,
+    DELAY,
+    crate::driver::pcf211x::PCF211x<I2C, crate::driver::pcf211x::PCF2119>,
+>;
+
+/// Character display using the NXP PCF2119 controller with built-in I2C interface and character set R, which has
+/// ASCII at 0xA0 to 0xFF. Printed text is translated to this character set.
+pub type CharacterDisplayPCF2119R<I2C, DELAY> = BaseCharacterDisplay<
+    I2C,
+    DELAY,
+    crate::driver::pcf211x::PCF211x<I2C, crate::driver::pcf211x::PCF2119R>,
+>;
+
+/// The size of the buffer holding text translated by the active glyph set, which is printed in chunks of this size.
+const TRANSLATION_BUFFER_SIZE: usize = 32;
+
+/// The number of pixel columns of a character cell, which is the resolution of each cell of `progress_bar()`.
+const PROGRESS_BAR_CELL_PIXELS: u8 = 5;
+
+/// The initial value of the FNV-1a hash computed by `screen_hash()`.
+const FNV_OFFSET_BASIS: u32 = 0x811C_9DC5;
+/// The multiplier of the FNV-1a hash computed by `screen_hash()`.
+const FNV_PRIME: u32 = 0x0100_0193;
+
+/// The number of pixel rows of a character cell, which is the resolution of each cell of `vbar()`.
+const VBAR_CELL_PIXELS: u8 = 8;
+
+/// The partial block glyphs of the bar graphs, which share custom character locations.
+#[derive(Debug, PartialEq, Clone, Copy)]
+enum BarGlyphs {
+    /// The glyphs of `progress_bar()`, filled from the left.
+    Horizontal,
+    /// The glyphs of `vbar()`, filled from the bottom.
+    Vertical,
+}
+
 // commands
 const LCD_CMD_CLEARDISPLAY: u8 = 0x01; //  Clear display, set cursor position to zero
 const LCD_CMD_RETURNHOME: u8 = 0x02; //  Set cursor position to zero
@@ -160,9 +391,66 @@ const LCD_FLAG_1LINE: u8 = 0x00; //  LCD 1 line mode
 const LCD_FLAG_5x10_DOTS: u8 = 0x04; //  10 pixel high font mode
 const LCD_FLAG_5x8_DOTS: u8 = 0x00; //  8 pixel high font mode
 
+pub mod animation;
+pub mod backlight;
+pub mod big_numbers;
+mod builder;
+mod character_display;
+pub mod charmap;
+pub mod charset;
+pub mod clock;
+pub mod dashboard;
+pub mod detect;
+pub mod diff;
 mod driver;
+mod dyn_display;
+mod framebuffer;
+pub mod glyph;
+pub mod glyphs;
+#[cfg(feature = "embedded-graphics")]
+pub mod graphics;
+pub mod instrumentation;
+pub mod keypad;
+mod macros;
+pub mod marquee;
+pub mod menu;
+pub mod pages;
+mod queue;
+pub mod rate_limit;
+#[cfg(any(feature = "embedded-hal-bus", feature = "embassy"))]
+pub mod shared_bus;
+pub mod spinner;
+pub mod splash;
+mod terminal;
+#[cfg(any(test, feature = "testing"))]
+pub mod testing;
+pub mod tiled;
+pub mod window;
+
+/// The device driver and I2C adapter types behind the character display type aliases, so the full concrete type
+/// of a display can be named in user code, such as the return type of a function creating the display.
+pub mod drivers {
+    pub use crate::driver::aip31068::AIP31068;
+    pub use crate::driver::aip31068_rgb::AIP31068RGB;
+    pub use crate::driver::hd44780::adapter::{
+        adafruit_lcd_backpack::AdafruitLCDBackpackAdapter,
+        buttons_pcf8574t::ButtonsPCF8574TAdapter, contrast_pcf8574t::ContrastPCF8574TAdapter,
+        dual_controller_pcf8574t::DualHD44780_PCF8574TAdapter,
+        generic_pcf8574t::GenericPCF8574TAdapter, mcp23017::MCP23017Adapter,
+        multi_controller_pcf8575::MultiHD44780_PCF8575Adapter, HD44780AdapterTrait,
+    };
+    pub use crate::driver::hd44780::{
+        AdafruitLCDBackpack, ButtonsHD44780PCF8574T, ContrastHD44780PCF8574T, DualHD44780PCF8574T,
+        GenericHD44780PCF8574T, MultiHD44780PCF8575, HD44780, MAX_CONTROLLER_COUNT,
+        MCP23017HD44780,
+    };
+    pub use crate::driver::pcf211x::{PCF211x, PCF211xModel, PCF2116, PCF2119, PCF2119R};
+    pub use crate::driver::us2066::US2066;
+    pub use crate::driver::DriverTrait;
+}
 
-const MAX_DEVICE_COUNT: usize = 2;
+/// The length of each DDRAM line, which is the range the display can be shifted through by scrolling.
+const DDRAM_LINE_LENGTH: u8 = 40;
 
 #[derive(Debug, PartialEq, Copy, Clone)]
 /// Errors that can occur when using the LCD backpack
@@ -173,9 +461,15 @@ where
     /// I2C error returned from the underlying I2C implementation
     I2cError(I2C::Error),
     /// Row is out of range
-    RowOutOfRange,
+    RowOutOfRange {
+        /// The row, or the first row of the area, that does not fit on the display
+        row: u8,
+    },
     /// Column is out of range
-    ColumnOutOfRange,
+    ColumnOutOfRange {
+        /// The column, or the first column of the area, that does not fit on the display
+        col: u8,
+    },
     /// Formatting error
     FormattingError(core::fmt::Error),
     /// The discplay type is not compatible with specific adapter.
@@ -188,6 +482,21 @@ where
     BadDeviceId,
     /// Internal error - buffer too small
     BufferTooSmall,
+    /// The controller was still busy after the allowed number of busy flag polls
+    Timeout,
+    /// There is no item at `index`, such as a dashboard field
+    IndexOutOfRange {
+        /// The index that is out of range
+        index: usize,
+    },
+    /// A character read back from the display in write verification mode differs from the one written at
+    /// `( col, row )`
+    VerificationFailed {
+        /// The column of the character
+        col: u8,
+        /// The row of the character
+        row: u8,
+    },
 }
 
 impl<I2C> From<core::fmt::Error> for CharacterDisplayError<I2C>
@@ -199,22 +508,54 @@ where
     }
 }
 
+impl<I2C> CharacterDisplayError<I2C>
+where
+    I2C: i2c::I2c,
+{
+    /// Returns the kind of the error, which does not depend on the type of the I2C bus.
+    pub fn kind(&self) -> CharacterDisplayErrorKind {
+        match self {
+            CharacterDisplayError::I2cError(e) => {
+                CharacterDisplayErrorKind::I2cError(i2c::Error::kind(e))
+            }
+            CharacterDisplayError::RowOutOfRange { row } => {
+                CharacterDisplayErrorKind::RowOutOfRange { row: *row }
+            }
+            CharacterDisplayError::ColumnOutOfRange { col } => {
+                CharacterDisplayErrorKind::ColumnOutOfRange { col: *col }
+            }
+            CharacterDisplayError::FormattingError(e) => {
+                CharacterDisplayErrorKind::FormattingError(*e)
+            }
+            CharacterDisplayError::UnsupportedDisplayType => {
+                CharacterDisplayErrorKind::UnsupportedDisplayType
+            }
+            CharacterDisplayError::UnsupportedOperation => {
+                CharacterDisplayErrorKind::UnsupportedOperation
+            }
+            CharacterDisplayError::ReadNotSupported => CharacterDisplayErrorKind::ReadNotSupported,
+            CharacterDisplayError::BadDeviceId => CharacterDisplayErrorKind::BadDeviceId,
+            CharacterDisplayError::BufferTooSmall => CharacterDisplayErrorKind::BufferTooSmall,
+            CharacterDisplayError::Timeout => CharacterDisplayErrorKind::Timeout,
+            CharacterDisplayError::IndexOutOfRange { index } => {
+                CharacterDisplayErrorKind::IndexOutOfRange { index: *index }
+            }
+            CharacterDisplayError::VerificationFailed { col, row } => {
+                CharacterDisplayErrorKind::VerificationFailed {
+                    col: *col,
+                    row: *row,
+                }
+            }
+        }
+    }
+}
+
 impl<I2C> From<&CharacterDisplayError<I2C>> for &'static str
 where
     I2C: i2c::I2c,
 {
     fn from(err: &CharacterDisplayError<I2C>) -> Self {
-        match err {
-            CharacterDisplayError::I2cError(_) => "I2C error",
-            CharacterDisplayError::RowOutOfRange => "Row out of range",
-            CharacterDisplayError::ColumnOutOfRange => "Column out of range",
-            CharacterDisplayError::FormattingError(_) => "Formatting error",
-            CharacterDisplayError::UnsupportedDisplayType => "Unsupported display type",
-            CharacterDisplayError::UnsupportedOperation => "Unsupported operation",
-            CharacterDisplayError::ReadNotSupported => "Read operation not supported",
-            CharacterDisplayError::BadDeviceId => "Bad device ID",
-            CharacterDisplayError::BufferTooSmall => "Buffer too small",
-        }
+        From::from(&err.kind())
     }
 }
 
@@ -224,8 +565,7 @@ where
     I2C: i2c::I2c,
 {
     fn format(&self, fmt: defmt::Formatter) {
-        let msg: &'static str = From::from(self);
-        defmt::write!(fmt, "{}", msg);
+        self.kind().format(fmt);
     }
 }
 
@@ -238,8 +578,7 @@ where
     where
         W: ufmt::uWrite + ?Sized,
     {
-        let msg: &'static str = From::from(self);
-        ufmt::uwrite!(w, "{}", msg)
+        ufmt::uDisplay::fmt(&self.kind(), w)
     }
 }
 
@@ -248,8 +587,261 @@ where
     I2C: i2c::I2c,
 {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
-        let msg: &'static str = From::from(self);
-        write!(f, "{}", msg)
+        Display::fmt(&self.kind(), f)
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+/// The kind of a `CharacterDisplayError`, which does not depend on the type of the I2C bus. It is the error type of
+/// the `CharacterDisplay` trait, where I2C errors are reduced to their `embedded_hal::i2c::ErrorKind`.
+pub enum CharacterDisplayErrorKind {
+    /// I2C error returned from the underlying I2C implementation
+    I2cError(i2c::ErrorKind),
+    /// Row is out of range
+    RowOutOfRange {
+        /// The row, or the first row of the area, that does not fit on the display
+        row: u8,
+    },
+    /// Column is out of range
+    ColumnOutOfRange {
+        /// The column, or the first column of the area, that does not fit on the display
+        col: u8,
+    },
+    /// Formatting error
+    FormattingError(core::fmt::Error),
+    /// The display type is not compatible with specific adapter.
+    UnsupportedDisplayType,
+    /// The requested operation is not supported by the adapter or controller
+    UnsupportedOperation,
+    /// Read operation is not supported by the adapter
+    ReadNotSupported,
+    /// Internal error - bad device ID
+    BadDeviceId,
+    /// Internal error - buffer too small
+    BufferTooSmall,
+    /// The controller was still busy after the allowed number of busy flag polls
+    Timeout,
+    /// There is no item at `index`, such as a dashboard field
+    IndexOutOfRange {
+        /// The index that is out of range
+        index: usize,
+    },
+    /// A character read back from the display in write verification mode differs from the one written at
+    /// `( col, row )`
+    VerificationFailed {
+        /// The column of the character
+        col: u8,
+        /// The row of the character
+        row: u8,
+    },
+}
+
+impl<I2C> From<CharacterDisplayError<I2C>> for CharacterDisplayErrorKind
+where
+    I2C: i2c::I2c,
+{
+    fn from(err: CharacterDisplayError<I2C>) -> Self {
+        err.kind()
+    }
+}
+
+impl From<&CharacterDisplayErrorKind> for &'static str {
+    fn from(kind: &CharacterDisplayErrorKind) -> Self {
+        match kind {
+            CharacterDisplayErrorKind::I2cError(_) => "I2C error",
+            CharacterDisplayErrorKind::RowOutOfRange { .. } => "Row out of range",
+            CharacterDisplayErrorKind::ColumnOutOfRange { .. } => "Column out of range",
+            CharacterDisplayErrorKind::FormattingError(_) => "Formatting error",
+            CharacterDisplayErrorKind::UnsupportedDisplayType => "Unsupported display type",
+            CharacterDisplayErrorKind::UnsupportedOperation => "Unsupported operation",
+            CharacterDisplayErrorKind::ReadNotSupported => "Read operation not supported",
+            CharacterDisplayErrorKind::BadDeviceId => "Bad device ID",
+            CharacterDisplayErrorKind::BufferTooSmall => "Buffer too small",
+            CharacterDisplayErrorKind::Timeout => "Timeout",
+            CharacterDisplayErrorKind::IndexOutOfRange { .. } => "Index out of range",
+            CharacterDisplayErrorKind::VerificationFailed { .. } => "Verification failed",
+        }
+    }
+}
+
+#[cfg(feature = "defmt")]
+impl defmt::Format for CharacterDisplayErrorKind {
+    fn format(&self, fmt: defmt::Formatter) {
+        match self {
+            CharacterDisplayErrorKind::I2cError(kind) => defmt::write!(fmt, "I2C error: {}", kind),
+            CharacterDisplayErrorKind::RowOutOfRange { row } => {
+                defmt::write!(fmt, "Row {} out of range", row)
+            }
+            CharacterDisplayErrorKind::ColumnOutOfRange { col } => {
+                defmt::write!(fmt, "Column {} out of range", col)
+            }
+            CharacterDisplayErrorKind::IndexOutOfRange { index } => {
+                defmt::write!(fmt, "Index {} out of range", index)
+            }
+            CharacterDisplayErrorKind::VerificationFailed { col, row } => {
+                defmt::write!(fmt, "Verification failed at column {}, row {}", col, row)
+            }
+            _ => {
+                let msg: &'static str = From::from(self);
+                defmt::write!(fmt, "{}", msg)
+            }
+        }
+    }
+}
+
+#[cfg(feature = "ufmt")]
+impl ufmt::uDisplay for CharacterDisplayErrorKind {
+    fn fmt<W>(&self, w: &mut ufmt::Formatter<'_, W>) -> Result<(), W::Error>
+    where
+        W: ufmt::uWrite + ?Sized,
+    {
+        match self {
+            CharacterDisplayErrorKind::I2cError(kind) => {
+                ufmt::uwrite!(w, "I2C error: {}", i2c_error_kind_str(kind))
+            }
+            CharacterDisplayErrorKind::RowOutOfRange { row } => {
+                ufmt::uwrite!(w, "Row {} out of range", row)
+            }
+            CharacterDisplayErrorKind::ColumnOutOfRange { col } => {
+                ufmt::uwrite!(w, "Column {} out of range", col)
+            }
+            CharacterDisplayErrorKind::IndexOutOfRange { index } => {
+                ufmt::uwrite!(w, "Index {} out of range", index)
+            }
+            CharacterDisplayErrorKind::VerificationFailed { col, row } => {
+                ufmt::uwrite!(w, "Verification failed at column {}, row {}", col, row)
+            }
+            _ => {
+                let msg: &'static str = From::from(self);
+                ufmt::uwrite!(w, "{}", msg)
+            }
+        }
+    }
+}
+
+/// A short description of an I2C error kind, for `ufmt`, which `embedded_hal::i2c::ErrorKind` does not support.
+#[cfg(feature = "ufmt")]
+fn i2c_error_kind_str(kind: &i2c::ErrorKind) -> &'static str {
+    match kind {
+        i2c::ErrorKind::Bus => "bus error",
+        i2c::ErrorKind::ArbitrationLoss => "arbitration lost",
+        i2c::ErrorKind::NoAcknowledge(i2c::NoAcknowledgeSource::Address) => {
+            "address not acknowledged"
+        }
+        i2c::ErrorKind::NoAcknowledge(i2c::NoAcknowledgeSource::Data) => "data not acknowledged",
+        i2c::ErrorKind::NoAcknowledge(_) => "not acknowledged",
+        i2c::ErrorKind::Overrun => "overrun",
+        _ => "other error",
+    }
+}
+
+impl Display for CharacterDisplayErrorKind {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            CharacterDisplayErrorKind::I2cError(kind) => write!(f, "I2C error: {}", kind),
+            CharacterDisplayErrorKind::RowOutOfRange { row } => {
+                write!(f, "Row {} out of range", row)
+            }
+            CharacterDisplayErrorKind::ColumnOutOfRange { col } => {
+                write!(f, "Column {} out of range", col)
+            }
+            CharacterDisplayErrorKind::IndexOutOfRange { index } => {
+                write!(f, "Index {} out of range", index)
+            }
+            CharacterDisplayErrorKind::VerificationFailed { col, row } => {
+                write!(f, "Verification failed at column {}, row {}", col, row)
+            }
+            _ => {
+                let msg: &'static str = From::from(self);
+                write!(f, "{}", msg)
+            }
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+/// A label identifying a display in logs and error messages, for systems with several identical panels. See
+/// `with_label()`.
+pub enum DisplayLabel {
+    /// A name, such as `"front panel"`
+    Name(&'static str),
+    /// A numeric ID, such as the index of the panel
+    Id(u32),
+}
+
+impl From<&'static str> for DisplayLabel {
+    fn from(name: &'static str) -> Self {
+        DisplayLabel::Name(name)
+    }
+}
+
+impl From<u32> for DisplayLabel {
+    fn from(id: u32) -> Self {
+        DisplayLabel::Id(id)
+    }
+}
+
+impl Display for DisplayLabel {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            DisplayLabel::Name(name) => write!(f, "{}", name),
+            DisplayLabel::Id(id) => write!(f, "#{}", id),
+        }
+    }
+}
+
+#[cfg(feature = "defmt")]
+impl defmt::Format for DisplayLabel {
+    fn format(&self, fmt: defmt::Formatter) {
+        match self {
+            DisplayLabel::Name(name) => defmt::write!(fmt, "{=str}", name),
+            DisplayLabel::Id(id) => defmt::write!(fmt, "#{}", id),
+        }
+    }
+}
+
+/// An error together with the label of the display it came from and the context it happened in, returned by
+/// `labeled_error()`. It formats as the label and the context followed by the error, leaving out what is not known.
+pub struct LabeledError<'a, I2C>
+where
+    I2C: i2c::I2c,
+{
+    /// The label of the display, if it has one
+    pub label: Option<DisplayLabel>,
+    /// The operation and controller the error came from, if known
+    pub context: Option<ErrorContext>,
+    /// The error
+    pub error: &'a CharacterDisplayError<I2C>,
+}
+
+impl<I2C> Display for LabeledError<'_, I2C>
+where
+    I2C: i2c::I2c,
+{
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        if let Some(label) = self.label {
+            write!(f, "{}: ", label)?;
+        }
+        if let Some(context) = self.context {
+            write!(f, "{}: ", context)?;
+        }
+        write!(f, "{}", self.error)
+    }
+}
+
+#[cfg(feature = "defmt")]
+impl<I2C> defmt::Format for LabeledError<'_, I2C>
+where
+    I2C: i2c::I2c,
+{
+    fn format(&self, fmt: defmt::Formatter) {
+        if let Some(label) = self.label {
+            defmt::write!(fmt, "{}: ", label);
+        }
+        if let Some(context) = self.context {
+            defmt::write!(fmt, "{}: ", context);
+        }
+        defmt::write!(fmt, "{}", self.error);
     }
 }
 
@@ -270,6 +862,16 @@ pub enum LcdDisplayType {
     Lcd40x2,
     /// 40x4 display. Should be used with a DualHD44780 adapter.
     Lcd40x4,
+    /// 40x6 display, such as three 40x2 panels driven as one display. Should be used with a 3 controller
+    /// MultiHD44780 adapter.
+    Lcd40x6,
+    /// 40x8 display, such as four 40x2 panels driven as one display. Should be used with a 4 controller
+    /// MultiHD44780 adapter.
+    Lcd40x8,
+    /// 24x2 display, such as those driven by the PCF2116
+    Lcd24x2,
+    /// 12x4 display, such as those driven by the PCF2116 in 4 line mode
+    Lcd12x4,
 }
 
 impl From<&LcdDisplayType> for &'static str {
@@ -282,6 +884,10 @@ impl From<&LcdDisplayType> for &'static str {
             LcdDisplayType::Lcd8x2 => "8x2",
             LcdDisplayType::Lcd40x2 => "40x2",
             LcdDisplayType::Lcd40x4 => "40x4",
+            LcdDisplayType::Lcd40x6 => "40x6",
+            LcdDisplayType::Lcd40x8 => "40x8",
+            LcdDisplayType::Lcd24x2 => "24x2",
+            LcdDisplayType::Lcd12x4 => "12x4",
         }
     }
 }
@@ -323,6 +929,10 @@ impl LcdDisplayType {
             LcdDisplayType::Lcd8x2 => 2,
             LcdDisplayType::Lcd40x2 => 2,
             LcdDisplayType::Lcd40x4 => 4,
+            LcdDisplayType::Lcd40x6 => 6,
+            LcdDisplayType::Lcd40x8 => 8,
+            LcdDisplayType::Lcd24x2 => 2,
+            LcdDisplayType::Lcd12x4 => 4,
         }
     }
 
@@ -336,11 +946,16 @@ impl LcdDisplayType {
             LcdDisplayType::Lcd8x2 => 8,
             LcdDisplayType::Lcd40x2 => 40,
             LcdDisplayType::Lcd40x4 => 40,
+            LcdDisplayType::Lcd40x6 => 40,
+            LcdDisplayType::Lcd40x8 => 40,
+            LcdDisplayType::Lcd24x2 => 24,
+            LcdDisplayType::Lcd12x4 => 12,
         }
     }
 
     /// Get the row offsets for the display type. This always returns an array of length 4.
-    /// For displays with less than 4 rows, the unused rows will be set to offsets offscreen.
+    /// For displays with less than 4 rows, the unused rows will be set to offsets offscreen. For displays
+    /// driven by multiple controllers, the offsets are for the rows of each controller.
     const fn row_offsets(&self) -> [u8; 4] {
         match self {
             LcdDisplayType::Lcd20x4 => [0x00, 0x40, 0x14, 0x54],
@@ -350,10 +965,359 @@ impl LcdDisplayType {
             LcdDisplayType::Lcd8x2 => [0x00, 0x40, 0x00, 0x40],
             LcdDisplayType::Lcd40x2 => [0x00, 0x40, 0x00, 0x40],
             LcdDisplayType::Lcd40x4 => [0x00, 0x40, 0x00, 0x40],
+            LcdDisplayType::Lcd40x6 => [0x00, 0x40, 0x00, 0x40],
+            LcdDisplayType::Lcd40x8 => [0x00, 0x40, 0x00, 0x40],
+            LcdDisplayType::Lcd24x2 => [0x00, 0x40, 0x00, 0x40],
+            LcdDisplayType::Lcd12x4 => [0x00, 0x20, 0x40, 0x60],
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+/// The contrast presets of adapters that can switch the display contrast with a GPIO pin.
+pub enum ContrastPreset {
+    /// The contrast set when the contrast pin is low, which is the power on state.
+    Primary,
+    /// The contrast set when the contrast pin is high.
+    Secondary,
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+/// How to poll the busy flag of the controller while waiting for it to be ready, on adapters that support reads,
+/// both before reading from the controller and for the clear and home commands with `CommandCompletion::BusyFlag`.
+/// Each poll is several I2C transactions, so polling trades bus load against how quickly a ready controller is
+/// noticed. The default is `Timeout { max_polls: 100, interval_us: 50 }`, which gives the controller over 5 ms.
+pub enum BusyPollStrategy {
+    /// Poll the busy flag back to back until the controller is ready, waiting forever if the display is absent or
+    /// wedged.
+    Continuous,
+    /// Wait `interval_us` microseconds between polls of the busy flag.
+    Interval { interval_us: u32 },
+    /// Poll the busy flag at most `max_polls` times, and if the controller is still busy, wait `fallback_us`
+    /// microseconds and then carry on as if it is ready.
+    Bounded { max_polls: u32, fallback_us: u32 },
+    /// Poll the busy flag at most `max_polls` times, waiting `interval_us` microseconds between polls, and if the
+    /// controller is still busy, fail with `CharacterDisplayError::Timeout`, such as when the display is absent or
+    /// wedged. The controller gets about `max_polls * interval_us` microseconds to become ready.
+    Timeout { max_polls: u32, interval_us: u32 },
+}
+
+impl Default for BusyPollStrategy {
+    fn default() -> Self {
+        BusyPollStrategy::Timeout {
+            max_polls: 100,
+            interval_us: 50,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Clone, Copy, Default)]
+/// How the driver waits for the slow clear and home commands to complete.
+pub enum CommandCompletion {
+    /// Wait a fixed time long enough for the slowest controllers.
+    #[default]
+    FixedDelay,
+    /// Poll the busy flag until the controller is ready, which is usually much sooner than the fixed delay. Only
+    /// used on adapters that support reads, and others keep using the fixed delay. The busy flag is polled as set
+    /// with `with_busy_poll_strategy()`, so with the default `BusyPollStrategy::Timeout` the command fails with
+    /// `CharacterDisplayError::Timeout` if the controller stays busy.
+    BusyFlag,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+/// The waits the driver makes for the controller, in microseconds. Each controller starts out with the times from its
+/// datasheet, which `timings()` returns, and `with_timings()` overrides them.
+pub struct Timings {
+    /// The wait before the first command of the initialization, for the controller to come out of its power on reset.
+    pub power_on_us: u32,
+    /// The execution time of the clear and return home commands.
+    pub clear_home_us: u32,
+    /// The execution time of the other commands, and of data writes on controllers that the driver waits for.
+    pub command_us: u32,
+    /// The wait after each I2C write that raises or lowers the enable pin of an HD44780 controller through its GPIO
+    /// expander, which lengthens the enable pulse and the time between pulses. When this is not zero, every GPIO
+    /// update is written in its own I2C transaction rather than packed with others. Only used by HD44780 controllers.
+    pub enable_pulse_us: u32,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+/// How the writes to an HD44780 controller through a GPIO expander are paced for the I2C bus speed declared with
+/// `with_i2c_frequency()`, so that the bytes do not reach the controller faster than it executes them.
+pub enum I2cPacing {
+    /// The writes are made as fast as the bus allows, which meets the controller timing.
+    FullSpeed,
+    /// Each GPIO update is written in its own I2C transaction rather than packed with others, as packed writes would
+    /// send the bytes faster than the controller executes them.
+    Unpacked,
+    /// Each GPIO update is written in its own I2C transaction, and each nibble is followed by a wait of
+    /// `nibble_delay_us` microseconds.
+    Delayed { nibble_delay_us: u32 },
+}
+
+#[derive(Debug, PartialEq, Clone, Copy, Default)]
+/// The character font size of the controller.
+pub enum FontSize {
+    /// 5x8 dot characters, the font used by almost all character displays.
+    #[default]
+    Dots5x8,
+    /// 5x10 dot characters. HD44780 compatible controllers only support this font in one line mode, so only the
+    /// first row of the display is used.
+    Dots5x10,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+/// The progress of a display initialization run one step at a time with `init_step()`.
+pub enum InitProgress {
+    /// The initialization is not done yet. Call `init_step()` again once at least this many microseconds have
+    /// passed.
+    WaitUs(u32),
+    /// The display is initialized.
+    Done,
+}
+
+#[derive(Debug, PartialEq, Clone, Copy, Default)]
+/// The power supply specific setup of the oscillator, voltage booster and voltage follower done when an AiP31068
+/// display is initialized. Boards such as the Surenoo SLC1602O, with the AiP31068 or the SPLC780D1, only reach a
+/// readable contrast when these match the supply voltage of the board.
+pub enum InitProfile {
+    /// The HD44780 compatible initialization, which leaves the oscillator and the LCD voltage at their power on
+    /// settings.
+    #[default]
+    Standard,
+    /// Boards powered from 5V, which run with the voltage booster off.
+    Supply5V,
+    /// Boards powered from 3.3V, which need the voltage booster on to drive the LCD.
+    Supply3V3,
+    /// The oscillator and LCD voltage settings of the board, for panels that the other profiles do not suit.
+    Custom(VoltageConfig),
+}
+
+impl InitProfile {
+    /// The oscillator and LCD voltage settings of the profile, or `None` for the standard initialization.
+    pub(crate) const fn voltage_config(&self) -> Option<VoltageConfig> {
+        match self {
+            InitProfile::Standard => None,
+            InitProfile::Supply5V => Some(VoltageConfig::SUPPLY_5V),
+            InitProfile::Supply3V3 => Some(VoltageConfig::SUPPLY_3V3),
+            InitProfile::Custom(config) => Some(*config),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Clone, Copy, Default)]
+/// The bias of the LCD driving voltages, which has to match the panel.
+pub enum LcdBias {
+    /// 1/5 bias, used by most 2 line panels.
+    #[default]
+    OneFifth,
+    /// 1/4 bias.
+    OneFourth,
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+/// The oscillator and LCD voltage settings of controllers with the ST7032i extended instruction set, such as the
+/// AiP31068. See `InitProfile::Custom`.
+pub struct VoltageConfig {
+    /// The bias of the LCD driving voltages.
+    pub bias: LcdBias,
+    /// The internal oscillator frequency adjustment, from 0 to 7.
+    pub osc_frequency: u8,
+    /// Whether the voltage booster is on, which boards powered from 3.3V need.
+    pub booster: bool,
+    /// Whether the voltage follower is on.
+    pub follower: bool,
+    /// The amplification ratio of the voltage follower, from 0 to 7.
+    pub follower_ratio: u8,
+    /// The contrast, from 0 to 63.
+    pub contrast: u8,
+}
+
+impl VoltageConfig {
+    /// The settings of `InitProfile::Supply5V`.
+    pub const SUPPLY_5V: VoltageConfig = VoltageConfig {
+        bias: LcdBias::OneFifth,
+        osc_frequency: 4,
+        booster: false,
+        follower: true,
+        follower_ratio: 2,
+        contrast: 0x18,
+    };
+
+    /// The settings of `InitProfile::Supply3V3`.
+    pub const SUPPLY_3V3: VoltageConfig = VoltageConfig {
+        bias: LcdBias::OneFifth,
+        osc_frequency: 4,
+        booster: true,
+        follower: true,
+        follower_ratio: 5,
+        contrast: 0x28,
+    };
+}
+
+#[derive(Debug, PartialEq, Clone, Copy, Default)]
+/// Which rows of the display are shown in double height characters, each taking the space of two rows. See
+/// `set_double_height()`. A 2 row display only supports `Off` and `Top`.
+pub enum DoubleHeightMode {
+    /// All rows in normal height.
+    #[default]
+    Off,
+    /// The first row in double height, followed by the last two rows of a 4 row display.
+    Top,
+    /// The first row, then the second row in double height, then the last row of a 4 row display.
+    Middle,
+    /// The first two rows, then the third row in double height on a 4 row display.
+    Bottom,
+    /// The first and the third row in double height on a 4 row display.
+    TopAndBottom,
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+/// The entry mode set up when the display is initialized: the text flow direction and whether the display is
+/// shifted as text is printed. See `left_to_right()`, `right_to_left()` and `autoscroll()`.
+pub struct EntryMode {
+    /// Whether text flows from left to right.
+    pub left_to_right: bool,
+    /// Whether the display is shifted as text is printed.
+    pub autoscroll: bool,
+}
+
+impl Default for EntryMode {
+    fn default() -> Self {
+        Self {
+            left_to_right: true,
+            autoscroll: false,
         }
     }
 }
 
+#[derive(Debug, PartialEq, Clone, Copy, Default)]
+/// Where printed text continues once it runs past the end of a row. `set_cursor()` always addresses the rows in
+/// order from top to bottom.
+pub enum Addressing {
+    /// Text continues at the next DDRAM address of the controller. On displays with 4 rows the rows are interleaved
+    /// in DDRAM, so text printed past the end of row 0 continues on row 2, and past the end of row 1 on row 3.
+    #[default]
+    Raw,
+    /// Text continues at the start of the next row, and past the end of the last row at the start of the first row,
+    /// as on a terminal. This needs the cursor position to be known, so it applies to left to right text flow after
+    /// the cursor is set with `set_cursor()`, `home()` or `clear()`, and text otherwise continues as with `Raw`.
+    RowMajor,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+/// Where text is placed within the field printed by `print_padded()`.
+pub enum Align {
+    /// Text starts at the left of the field, followed by spaces.
+    #[default]
+    Left,
+    /// Text is centered in the field, with the extra space on the right when the padding is uneven.
+    Center,
+    /// Text ends at the right of the field, after spaces.
+    Right,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+/// How `'\n'` and `'\r'` are handled when written with `write!` or `writeln!`, or with `uwrite!` and `uwriteln!`
+/// with the `ufmt` feature. See `with_line_breaks()`. `print()` always writes them as they are, except in terminal
+/// mode.
+pub enum LineBreaks {
+    /// `'\n'` moves the cursor to column 0 of the next row, and past the last row to column 0 of the first row.
+    /// `'\r'` moves the cursor to column 0 of the current row. Both need the cursor position to be known, see
+    /// `cursor_position()`, and are dropped when it is not.
+    #[default]
+    MoveCursor,
+    /// `'\n'` and `'\r'` are dropped.
+    Ignore,
+    /// `'\n'` and `'\r'` are written to the display as character codes, which most character ROMs show as
+    /// garbage glyphs.
+    Print,
+}
+
+#[derive(Debug, PartialEq, Clone, Copy, Default)]
+/// Counts of the errors a display has run into since it was created or `reset_error_stats()` was called, for
+/// reporting flaky wiring. See `error_stats()`. The counts saturate rather than wrap around.
+pub struct ErrorStats {
+    /// The number of operations that failed with an error from the I2C bus.
+    pub i2c_errors: u32,
+    /// The number of operations that were tried again after failing, such as by `init_with_address_fallback()`, and
+    /// of I2C writes that were tried again after failing, see `with_i2c_retries()`.
+    pub retries: u32,
+    /// The number of operations that failed because the controller was still busy after the allowed number of busy
+    /// flag polls.
+    pub busy_timeouts: u32,
+    /// The operation that failed most recently with an I2C error or busy timeout.
+    pub last_failed_operation: Option<DisplayOperation>,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+/// Where the most recent error of a display came from, such as to tell which controller of a display with several
+/// HD44780 controllers does not acknowledge its I2C writes. See `error_context()`.
+pub struct ErrorContext {
+    /// The display operation that failed.
+    pub operation: DisplayOperation,
+    /// The index of the controller the operation last sent a command or data to, or read from. Always `0` for
+    /// displays with a single controller.
+    pub controller: usize,
+}
+
+impl Display for ErrorContext {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{:?} on controller {}", self.operation, self.controller)
+    }
+}
+
+#[cfg(feature = "defmt")]
+impl defmt::Format for ErrorContext {
+    fn format(&self, fmt: defmt::Formatter) {
+        defmt::write!(
+            fmt,
+            "{} on controller {}",
+            defmt::Debug2Format(&self.operation),
+            self.controller
+        );
+    }
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+/// A snapshot of the display settings, taken with `display_state()` and put back with `restore_state()`, such as
+/// after a deep sleep that powered the display down. The fields are plain data, so the snapshot can be kept in
+/// retained RAM or serialized by the application. The text shown on the display is not part of the snapshot.
+pub struct DisplayState {
+    /// The cursor position as `( col, row )`, or `None` when it is not known.
+    pub cursor: Option<(u8, u8)>,
+    /// The display control flags of the controller: display on, cursor shown and cursor blinking.
+    pub display_control: u8,
+    /// The entry mode flags of the controller: the text flow direction and autoscroll.
+    pub display_mode: u8,
+    /// Whether the backlight is on, or `None` when it has not been changed since `init()`.
+    pub backlight: Option<bool>,
+    /// The contrast, or `None` when it has not been changed since `init()`.
+    pub contrast: Option<u8>,
+    /// How many columns the display is scrolled to the left.
+    pub scroll_offset: u8,
+    /// The custom character known to be at each location, or `None` when it is not known.
+    pub custom_chars: [Option<[u8; 8]>; 8],
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+/// A display command serialized by `serialize_batch()`.
+pub enum BatchCommand<'a> {
+    /// Move the cursor to `( col, row )`.
+    SetCursor(u8, u8),
+    /// Print the text at the cursor.
+    Print(&'a str),
+}
+
+#[derive(Debug, PartialEq, Clone, Copy, Default)]
+/// What happens to the display when the display object is dropped.
+pub enum DropBehavior {
+    /// The display is left as it is.
+    #[default]
+    LeaveUntouched,
+    /// The display and the backlight are turned off, so the panel goes dark when the task using it ends.
+    TurnOff,
+}
+
 pub struct DeviceSetupConfig<I2C, DELAY>
 where
     I2C: i2c::I2c,
@@ -373,6 +1337,68 @@ where
 {
     config: DeviceSetupConfig<I2C, DELAY>,
     device: DEVICE,
+    /// The cursor position as tracked in software, or `None` when it is not known.
+    cursor: Option<(u8, u8)>,
+    /// Whether printing moves the cursor to the right (left to right text flow), which is required to track
+    /// the cursor while printing.
+    cursor_advances: bool,
+    /// The error from the last failed `core::fmt::Write::write_str` call, which can only return `core::fmt::Error`.
+    last_error: Option<CharacterDisplayError<I2C>>,
+    /// The counts of the errors the display has run into.
+    error_stats: ErrorStats,
+    /// Where the most recent error came from.
+    error_context: Option<ErrorContext>,
+    /// The label identifying the display in logs and error messages.
+    label: Option<DisplayLabel>,
+    /// Custom characters that are uploaded by `init()` and `soft_reset()`, indexed by location.
+    preloaded_chars: [Option<[u8; 8]>; 8],
+    /// Receives timing callbacks for display operations, if attached.
+    instrumentation: Option<&'static dyn Instrumentation>,
+    /// What to do with the display when this object is dropped.
+    drop_behavior: DropBehavior,
+    /// How many columns the display is shifted to the left by scrolling, from 0 to `DDRAM_LINE_LENGTH - 1`.
+    scroll_offset: u8,
+    /// The in-RAM copy of the display contents when framebuffer mode is enabled, supplied by the application.
+    framebuffer: Option<&'static mut Framebuffer>,
+    /// Whether `init()` leaves the backlight on.
+    backlight_on_init: bool,
+    /// The entry mode set up by `init()`.
+    entry_mode: EntryMode,
+    /// The character whose inverse glyph is preloaded at each custom character location, for inverse video in
+    /// framebuffer mode.
+    inverse_glyphs: [Option<u8>; 8],
+    /// Where printed text continues past the end of a row.
+    addressing: Addressing,
+    /// The character ROM that printed text is encoded for, or `None` to print text as it is.
+    character_rom: Option<CharacterRom>,
+    /// The partial block glyphs of the bar graphs last uploaded since `init()`.
+    bar_glyphs: Option<BarGlyphs>,
+    /// The character printed as each custom character location, from the active glyph set.
+    glyph_translation: [Option<char>; 8],
+    /// Whether characters missing from the character ROM are shown with built-in glyphs.
+    glyph_fallback: bool,
+    /// The character whose built-in glyph has been uploaded to each custom character location by `print()`.
+    fallback_chars: [Option<char>; 8],
+    /// The backlight state set since `init()`, or `None` when it is still the state set by `init()`.
+    backlight: Option<bool>,
+    /// The contrast set since `init()`, or `None` when it is still the contrast set by `init()`.
+    contrast: Option<u8>,
+    /// The custom character uploaded to each location of every controller, for `display_state()`.
+    custom_chars: [Option<[u8; 8]>; 8],
+    /// The display writes deferred until the next flush, when deferred mode is enabled, supplied by the application.
+    queue: Option<&'static mut CommandQueue>,
+    /// The printed lines, when terminal mode is enabled, supplied by the application.
+    terminal: Option<&'static mut TerminalBuffer>,
+    /// How `'\n'` and `'\r'` written with `write!` are handled.
+    line_breaks: LineBreaks,
+    /// The number of columns between tab stops, or 0 to print `'\t'` as it is.
+    tab_width: u8,
+    /// The character that ends text truncated by `print_padded()`, if any.
+    ellipsis: Option<char>,
+    /// Whether printed text is read back from the display and compared with what was written.
+    verify_writes: bool,
+    /// The next step of an initialization run with `init_step()`, or 0 when none is under way.
+    next_init_step: usize,
 }
 
 impl<I2C, DELAY, DEVICE> BaseCharacterDisplay<I2C, DELAY, DEVICE>
@@ -396,17 +1422,858 @@ where
                 delay,
             },
             device: DEVICE::default(),
+            cursor: None,
+            cursor_advances: true,
+            last_error: None,
+            error_stats: ErrorStats::default(),
+            error_context: None,
+            label: None,
+            preloaded_chars: [None; 8],
+            instrumentation: None,
+            drop_behavior: DropBehavior::LeaveUntouched,
+            scroll_offset: 0,
+            framebuffer: None,
+            backlight_on_init: true,
+            entry_mode: EntryMode::default(),
+            inverse_glyphs: [None; 8],
+            addressing: Addressing::default(),
+            character_rom: None,
+            bar_glyphs: None,
+            glyph_translation: [None; 8],
+            glyph_fallback: false,
+            fallback_chars: [None; 8],
+            backlight: None,
+            contrast: None,
+            custom_chars: [None; 8],
+            queue: None,
+            terminal: None,
+            line_breaks: LineBreaks::default(),
+            tab_width: 4,
+            ellipsis: None,
+            verify_writes: false,
+            next_init_step: 0,
         }
     }
 
-    /// Initialize the display. This must be called before using the display.
-    pub fn init(&mut self) -> Result<(), CharacterDisplayError<I2C>> {
-        self.device.init(&mut self.config)
+    /// Start building a character display with configurable options. See `CharacterDisplayBuilder`.
+    pub fn builder(
+        i2c: I2C,
+        lcd_type: LcdDisplayType,
+        delay: DELAY,
+    ) -> CharacterDisplayBuilder<I2C, DELAY, DEVICE> {
+        CharacterDisplayBuilder::new(i2c, lcd_type, delay)
     }
 
-    /// returns a reference to the I2C peripheral. mostly needed for testing
-    fn i2c(&mut self) -> &mut I2C {
-        &mut self.config.i2c
+    /// Set what happens to the display when this object is dropped. The default is
+    /// `DropBehavior::LeaveUntouched`. A display that turns off when dropped keeps a borrowed I2C bus or delay
+    /// borrowed until it goes out of scope, so drop it explicitly before using the bus for something else.
+    pub fn with_drop_behavior(mut self, drop_behavior: DropBehavior) -> Self {
+        self.drop_behavior = drop_behavior;
+        self
+    }
+
+    /// Enable framebuffer mode. `clear()`, `set_cursor()` and `print()` then only update an in-RAM copy of the display,
+    /// and nothing is written to the display until `flush()` is called, which writes only the characters that changed.
+    /// Characters printed past the end of a row are dropped. The framebuffer assumes left to right text flow without
+    /// autoscroll or scrolling of the display, and holds up to 4 rows, so displays with more rows are not supported.
+    /// The framebuffer is supplied by the application, so displays that do not use framebuffer mode do not hold one,
+    /// and is reset to a blank display:
+    ///
+    /// ```rust
+    /// static FRAMEBUFFER: StaticCell<Framebuffer> = StaticCell::new();
+    /// let mut lcd = CharacterDisplayPCF8574T::new(i2c, LcdDisplayType::Lcd16x2, delay)
+    ///     .with_framebuffer(FRAMEBUFFER.init(Framebuffer::new()));
+    /// ```
+    pub fn with_framebuffer(mut self, framebuffer: &'static mut Framebuffer) -> Self {
+        framebuffer.reset();
+        self.framebuffer = Some(framebuffer);
+        self
+    }
+
+    /// Enable deferred mode. `clear()`, `home()`, `set_cursor()` and `print()` are then queued rather than written
+    /// to the display, and `flush()` writes the queue in as few I2C transactions as possible, so a whole screen
+    /// update can be made while holding the bus once. Consecutive prints are written together and a cursor move
+    /// replaces the cursor move queued right before it. The queue holds up to 16 commands and 80 characters, and is
+    /// flushed early when it is full. Any other operation, such as `show_cursor()` or `create_char()`, flushes the
+    /// queue before it runs, so the display is always updated in order. Has no effect in framebuffer mode. The queue
+    /// is supplied by the application, so displays that do not use deferred mode do not hold one, and is emptied:
+    ///
+    /// ```rust
+    /// static QUEUE: StaticCell<CommandQueue> = StaticCell::new();
+    /// let mut lcd = CharacterDisplayPCF8574T::new(i2c, LcdDisplayType::Lcd20x4, delay)
+    ///     .with_deferred_writes(QUEUE.init(CommandQueue::new()));
+    /// ```
+    pub fn with_deferred_writes(mut self, queue: &'static mut CommandQueue) -> Self {
+        queue.clear();
+        self.queue = Some(queue);
+        self
+    }
+
+    /// Enable terminal mode. `print()`, and everything built on it such as `write!`, then treats `'\n'` as a move to
+    /// the start of the next row and `'\r'` as a move to the start of the current row, and wraps text at the end of
+    /// each row. Moving past the last row redraws the earlier lines shifted up by one row and continues on a blank
+    /// last row. The lines are kept in RAM for the redraw, for displays with up to 4 rows; printing to displays with
+    /// more rows returns `CharacterDisplayError::UnsupportedDisplayType`. Assumes left to right text flow. The line
+    /// buffer is supplied by the application, so displays that do not use terminal mode do not hold one, and is
+    /// blanked:
+    ///
+    /// ```rust
+    /// static TERMINAL: StaticCell<TerminalBuffer> = StaticCell::new();
+    /// let mut lcd = CharacterDisplayPCF8574T::new(i2c, LcdDisplayType::Lcd20x4, delay)
+    ///     .with_terminal(TERMINAL.init(TerminalBuffer::new()));
+    /// ```
+    pub fn with_terminal(mut self, terminal: &'static mut TerminalBuffer) -> Self {
+        terminal.clear();
+        self.terminal = Some(terminal);
+        self
+    }
+
+    /// Set how the busy flag is polled while waiting for the controller to be ready, on adapters that support reads.
+    /// The default is a `BusyPollStrategy::Timeout`, which fails rather than hangs when the display is absent or
+    /// wedged. Has no effect on controllers and adapters that cannot be read.
+    pub fn with_busy_poll_strategy(mut self, strategy: BusyPollStrategy) -> Self {
+        self.device.set_busy_poll_strategy(strategy);
+        self
+    }
+
+    /// Try a failed I2C write to the adapter again up to `retries` times, waiting `interval_us` microseconds before
+    /// each retry, so that a momentary bus glitch, such as from long cables or a relay switching nearby, does not fail
+    /// the operation. Only a write whose address the adapter did not acknowledge is repeated, as the adapter then has
+    /// not applied any of it, so the controller does not see any GPIO update twice. Any other I2C error fails the
+    /// operation at once. The retries are counted in `error_stats()`. The default is no retries. Has no effect on
+    /// displays other than those with HD44780 controllers.
+    pub fn with_i2c_retries(mut self, retries: u8, interval_us: u32) -> Self {
+        self.device.set_i2c_retries(retries, interval_us);
+        self
+    }
+
+    /// Declare the speed of the I2C bus in Hz, so that the writes to HD44780 controllers are paced when the bus is fast
+    /// enough to break the controller timing. Packed writes through a PCF8574 send the bytes of a string 18 bit times
+    /// apart, counted between the enable pulse that completes a byte and the one that starts the next, which on buses
+    /// faster than about 490 kHz is less than the 37 us the controller takes to execute each byte. The writes are then
+    /// unpacked, 40 bit times apart, and on buses faster than about 1.08 MHz also followed by a delay after each
+    /// nibble. The pacing chosen is returned by `i2c_pacing()`. The default is an undeclared speed, which assumes a
+    /// bus of up to 400 kHz. Has no effect on displays other than those with HD44780 controllers driven through the 4
+    /// bit interface.
+    pub fn with_i2c_frequency(mut self, frequency_hz: u32) -> Self {
+        self.device.set_i2c_frequency(frequency_hz);
+        self
+    }
+
+    /// Wait `delay_us` microseconds after each nibble written to an HD44780 controller through the 4 bit interface,
+    /// for clones that miss enable pulses arriving back to back from fast MCUs on 400 kHz or faster I2C buses. Each
+    /// nibble is then written in its own I2C writes rather than packed with others. Unlike
+    /// `Timings::enable_pulse_us`, which waits after every write to the adapter, this waits once per nibble. The
+    /// default is no delay. Has no effect on displays other than those with HD44780 controllers driven through the 4
+    /// bit interface.
+    pub fn with_nibble_delay(mut self, delay_us: u32) -> Self {
+        self.device.set_nibble_delay(delay_us);
+        self
+    }
+
+    /// Set the waits the driver makes for the controller, for displays such as clones and OLED character modules that
+    /// need longer or shorter waits than the datasheet times of their controller. Start from the defaults returned by
+    /// `timings()` to change only some of them. By default, the HD44780 driver only waits for the clear and home
+    /// commands, as the I2C writes to the adapter otherwise take longer than the controller needs.
+    ///
+    /// ```rust
+    /// let lcd = CharacterDisplayPCF8574T::new(i2c, LcdDisplayType::Lcd16x2, delay);
+    /// let timings = Timings { clear_home_us: 5000, ..lcd.timings() };
+    /// let mut lcd = lcd.with_timings(timings);
+    /// ```
+    pub fn with_timings(mut self, timings: Timings) -> Self {
+        self.device.set_timings(timings);
+        self
+    }
+
+    /// Set how the driver waits for the clear and home commands to complete. The default is
+    /// `CommandCompletion::FixedDelay`. Has no effect on controllers and adapters that cannot be read.
+    pub fn with_command_completion(mut self, completion: CommandCompletion) -> Self {
+        self.device.set_command_completion(completion);
+        self
+    }
+
+    /// Set a signal that indicates whether the controller is ready, such as a function reading the MCU pin wired to
+    /// the I2C adapter's interrupt line on boards that route the controller's busy flag (D7) through the expander.
+    /// Before each poll of the busy flag, `is_ready` is called, and if it returns `true` the controller is taken to be
+    /// ready without reading the busy flag over I2C. If it returns `false`, the busy flag is polled as usual. Because
+    /// `is_ready` is a plain function, a pin it reads must be reachable from a static, for example in a
+    /// `critical_section::Mutex`. Has no effect on controllers and adapters that cannot be read.
+    pub fn with_ready_signal(mut self, is_ready: fn() -> bool) -> Self {
+        self.device.set_ready_signal(Some(is_ready));
+        self
+    }
+
+    /// Set which controller drives each row of the display, and which row of that controller it is, as a table of
+    /// `( controller, row )` indexed by the display row. Useful for multi-panel builds whose panels are not wired
+    /// to the enable pins in display order. Rows past the end of the table, and entries naming a controller or row
+    /// that does not exist, keep the adapter's default mapping. Has no effect on adapters with a fixed row layout,
+    /// such as the dual controller PCF8574T adapter.
+    ///
+    /// ```rust
+    /// // the top panel is wired to the enable pin of controller 2
+    /// const ROWS: [(usize, u8); 6] = [(2, 0), (2, 1), (0, 0), (0, 1), (1, 0), (1, 1)];
+    /// let mut lcd = CharacterDisplayMultiHD44780::<_, _, 3>::new(i2c, LcdDisplayType::Lcd40x6, delay)
+    ///     .with_controller_rows(&ROWS);
+    /// ```
+    pub fn with_controller_rows(mut self, table: &'static [(usize, u8)]) -> Self {
+        self.device.set_controller_rows(table);
+        self
+    }
+
+    /// Set the character font size selected by `init()`. The default is `FontSize::Dots5x8`. Has no effect on
+    /// controllers without a font size setting.
+    pub fn with_font_size(mut self, font_size: FontSize) -> Self {
+        self.device.set_font_size(font_size);
+        self
+    }
+
+    /// Set the power supply specific setup done by `init()`. The default is `InitProfile::Standard`. Has no effect on
+    /// controllers other than the AiP31068.
+    pub fn with_init_profile(mut self, profile: InitProfile) -> Self {
+        self.device.set_init_profile(profile);
+        self
+    }
+
+    /// Set whether `init()` leaves the backlight on. The default is on. Controllers without backlight control
+    /// return `CharacterDisplayError::UnsupportedOperation` from `init()` when this is off.
+    pub fn with_backlight(mut self, on: bool) -> Self {
+        self.backlight_on_init = on;
+        self
+    }
+
+    /// Set the entry mode set up by `init()`. The default is left to right text flow without autoscroll.
+    pub fn with_entry_mode(mut self, entry_mode: EntryMode) -> Self {
+        self.entry_mode = entry_mode;
+        self
+    }
+
+    /// Set where printed text continues once it runs past the end of a row. The default is `Addressing::Raw`, which
+    /// follows the DDRAM layout of the controller.
+    pub fn with_addressing(mut self, addressing: Addressing) -> Self {
+        self.addressing = addressing;
+        self
+    }
+
+    /// Set how `'\n'` and `'\r'` written with `write!` and `writeln!` are handled. The default is
+    /// `LineBreaks::MoveCursor`, so `writeln!` continues on the next row. Has no effect in terminal mode, which
+    /// always moves the cursor.
+    pub fn with_line_breaks(mut self, line_breaks: LineBreaks) -> Self {
+        self.line_breaks = line_breaks;
+        self
+    }
+
+    /// Set the number of columns between the tab stops that a `'\t'` printed with `print()` or `write!` moves to.
+    /// The default is 4. A width of 0 turns off tab handling, so `'\t'` is printed as it is.
+    pub fn with_tab_width(mut self, tab_width: u8) -> Self {
+        self.tab_width = tab_width;
+        self
+    }
+
+    /// Set the character that `print_padded()` ends truncated text with, such as `'~'` or `'…'` on controllers
+    /// whose character ROM has it. By default truncated text is cut off without one.
+    pub fn with_ellipsis(mut self, ellipsis: char) -> Self {
+        self.ellipsis = Some(ellipsis);
+        self
+    }
+
+    /// Enable write verification mode, for adapters that support reads, such as to detect marginal wiring in
+    /// production test fixtures. Text printed while the cursor position is known is then read back from the display
+    /// and compared with what was written, and the print fails with `CharacterDisplayError::VerificationFailed` at
+    /// the first character that differs. Printing returns `CharacterDisplayError::ReadNotSupported` on adapters that
+    /// cannot read. Text written in framebuffer or deferred mode, and text with characters that are not encoded to
+    /// single bytes by a character ROM, is not verified.
+    pub fn with_write_verification(mut self) -> Self {
+        self.verify_writes = true;
+        self
+    }
+
+    /// Set the character ROM of the controller, so that `print()` encodes text to the codes of the ROM, with `?` for
+    /// the characters the ROM has no glyph for. See the `charset` module. By default text is printed as it is.
+    pub fn with_character_rom(mut self, character_rom: CharacterRom) -> Self {
+        self.character_rom = Some(character_rom);
+        self
+    }
+
+    /// Returns the character ROM set with `with_character_rom()`, for decoding text read back from the display.
+    pub fn character_rom(&self) -> Option<CharacterRom> {
+        self.character_rom
+    }
+
+    /// Attach instrumentation that receives timing callbacks for each display operation. See the
+    /// `instrumentation` module.
+    pub fn with_instrumentation(mut self, instrumentation: &'static dyn Instrumentation) -> Self {
+        self.instrumentation = Some(instrumentation);
+        self
+    }
+
+    /// Run a display operation, reporting its start and finish to the attached instrumentation and counting its
+    /// failure in the error statistics.
+    fn instrumented<T>(
+        &mut self,
+        operation: DisplayOperation,
+        run: impl FnOnce(&mut Self) -> Result<T, CharacterDisplayError<I2C>>,
+    ) -> Result<T, CharacterDisplayError<I2C>> {
+        if !matches!(operation, DisplayOperation::Print | DisplayOperation::Flush) {
+            // queued writes go to the display before anything that is not queued
+            self.flush_queue()?;
+        }
+        let result = match self.instrumentation {
+            Some(instrumentation) => {
+                let started_us = instrumentation.now_us();
+                instrumentation.operation_started(operation, started_us);
+                let result = run(self);
+                instrumentation.operation_finished(operation, started_us, instrumentation.now_us());
+                result
+            }
+            None => run(self),
+        };
+        self.error_stats.retries = self
+            .error_stats
+            .retries
+            .saturating_add(self.device.take_retried_writes());
+        if result.is_err() {
+            self.error_context = Some(ErrorContext {
+                operation,
+                controller: self.device.last_controller(),
+            });
+        }
+        match result {
+            Err(CharacterDisplayError::I2cError(_)) => {
+                self.error_stats.i2c_errors = self.error_stats.i2c_errors.saturating_add(1);
+                self.error_stats.last_failed_operation = Some(operation);
+            }
+            Err(CharacterDisplayError::Timeout) => {
+                self.error_stats.busy_timeouts = self.error_stats.busy_timeouts.saturating_add(1);
+                self.error_stats.last_failed_operation = Some(operation);
+            }
+            _ => {}
+        }
+        result
+    }
+
+    /// Returns the counts of the errors the display has run into, for reporting flaky display wiring in telemetry.
+    /// Operations that fail with an I2C error or a busy timeout are counted, as are operations that are retried.
+    ///
+    /// ```rust
+    /// let stats = lcd.error_stats();
+    /// if stats.i2c_errors > 0 {
+    ///     telemetry.report_display_errors(stats.i2c_errors, stats.busy_timeouts);
+    ///     lcd.reset_error_stats();
+    /// }
+    /// ```
+    pub fn error_stats(&self) -> ErrorStats {
+        self.error_stats
+    }
+
+    /// Returns the operation that failed most recently and the controller it was addressing, to tell where the error
+    /// it returned came from. The context is kept until the next failed operation.
+    ///
+    /// ```rust
+    /// if let Err(e) = lcd.print("Hello") {
+    ///     if let Some(context) = lcd.error_context() {
+    ///         log::error!("{} failed: {}", context, e);
+    ///     }
+    /// }
+    /// ```
+    pub fn error_context(&self) -> Option<ErrorContext> {
+        self.error_context
+    }
+
+    /// Set a label identifying the display in logs and error messages, such as a name or a numeric ID, so logs from
+    /// systems with several identical panels show which panel they are about. See `labeled_error()`.
+    ///
+    /// ```rust
+    /// let mut left = CharacterDisplayPCF8574T::new(i2c_left, LcdDisplayType::Lcd16x2, delay).with_label("left");
+    /// let mut right = CharacterDisplayPCF8574T::new(i2c_right, LcdDisplayType::Lcd16x2, delay).with_label(2);
+    /// if let Err(e) = left.print("Hello") {
+    ///     log::error!("{}", left.labeled_error(&e));
+    /// }
+    /// ```
+    pub fn with_label(mut self, label: impl Into<DisplayLabel>) -> Self {
+        self.label = Some(label.into());
+        self
+    }
+
+    /// Returns the label set with `with_label()`.
+    pub fn label(&self) -> Option<DisplayLabel> {
+        self.label
+    }
+
+    /// Returns `error` together with the label of this display and the `error_context()` of the operation that
+    /// failed most recently, which formats as the label, the operation and controller, and then the error. Call it
+    /// with the error of the operation that failed most recently, for the context to be that of the error.
+    pub fn labeled_error<'a>(
+        &self,
+        error: &'a CharacterDisplayError<I2C>,
+    ) -> LabeledError<'a, I2C> {
+        LabeledError {
+            label: self.label,
+            context: self.error_context,
+            error,
+        }
+    }
+
+    /// Reset the counts of the errors the display has run into to zero.
+    pub fn reset_error_stats(&mut self) -> &mut Self {
+        self.error_stats = ErrorStats::default();
+        self
+    }
+
+    /// Add a custom character that is uploaded to the display at the given location (0-7) every time the display is
+    /// initialized with `init()` or reset with `soft_reset()`, so the custom characters never need to be restored
+    /// after recovering the display. Up to 8 characters can be preloaded, one per location.
+    ///
+    /// ```rust
+    /// let mut lcd = CharacterDisplayPCF8574T::new(i2c, LcdDisplayType::Lcd16x2, delay)
+    ///     .with_preloaded_char(0, HEART)
+    ///     .with_preloaded_char(1, ARROW_UP);
+    /// lcd.init()?;
+    /// ```
+    pub fn with_preloaded_char(mut self, location: u8, charmap: [u8; 8]) -> Self {
+        self.preloaded_chars[(location & 0x7) as usize] = Some(charmap);
+        self
+    }
+
+    /// Preload the inverse video glyph of `character` at a custom character location, so that cells marked with
+    /// `set_inverse()` in framebuffer mode show `charmap` in place of `character`. This uses up one of the 8 custom
+    /// character locations for each character that can be shown in inverse video. `glyph::inverted()` turns the
+    /// character map of a character into its inverse.
+    ///
+    /// ```rust
+    /// let mut lcd = CharacterDisplayPCF8574T::new(i2c, LcdDisplayType::Lcd16x2, delay)
+    ///     .with_framebuffer(FRAMEBUFFER.init(Framebuffer::new()))
+    ///     .with_inverse_glyph('>', 0, glyph::inverted(&ARROW_RIGHT));
+    /// ```
+    pub fn with_inverse_glyph(mut self, character: char, location: u8, charmap: [u8; 8]) -> Self {
+        self.inverse_glyphs[(location & 0x7) as usize] = Some(character as u8);
+        self.with_preloaded_char(location, charmap)
+    }
+
+    /// Set the glyph set that `init()` uploads. See `set_glyph_set()`.
+    pub fn with_glyph_set(mut self, glyphs: &[(char, [u8; 8])]) -> Self {
+        self.glyph_translation = [None; 8];
+        for (location, (character, charmap)) in glyphs.iter().take(8).enumerate() {
+            self.preloaded_chars[location] = Some(*charmap);
+            self.glyph_translation[location] = Some(*character);
+        }
+        self
+    }
+
+    /// Switch to a glyph set: a bank of up to 8 custom characters, each standing in for a character that the
+    /// controller's character ROM does not have. The glyphs are uploaded to custom character locations 0 and up, and
+    /// from then on `print()` shows each of the characters as its glyph, so localized text can be printed as is.
+    /// This replaces the previous glyph set as a unit, and the characters of the previous set are no longer
+    /// translated, even if uploading the new glyphs fails. The glyphs are preloaded, so `soft_reset()` uploads them
+    /// again. Only the first 8 glyphs are used.
+    ///
+    /// ```rust
+    /// const ACCENTED: [(char, [u8; 8]); 2] = [('é', E_ACUTE), ('è', E_GRAVE)];
+    /// const SYMBOLS: [(char, [u8; 8]); 2] = [('♥', HEART), ('♪', NOTE)];
+    ///
+    /// lcd.set_glyph_set(&ACCENTED)?.print("Café")?;
+    /// lcd.set_glyph_set(&SYMBOLS)?.print("I ♥ ♪")?;
+    /// ```
+    pub fn set_glyph_set(
+        &mut self,
+        glyphs: &[(char, [u8; 8])],
+    ) -> Result<&mut Self, CharacterDisplayError<I2C>> {
+        self.glyph_translation = [None; 8];
+        for (location, (_, charmap)) in glyphs.iter().take(8).enumerate() {
+            self.preloaded_chars[location] = Some(*charmap);
+            self.create_char(location as u8, *charmap)?;
+        }
+        for (location, (character, _)) in glyphs.iter().take(8).enumerate() {
+            self.glyph_translation[location] = Some(*character);
+        }
+        Ok(self)
+    }
+
+    /// Show the characters that are missing from the character ROM with built-in glyphs, such as `°`, `µ`, arrows
+    /// and common accented letters. The first time `print()` prints such a character, its glyph is uploaded to a free
+    /// custom character location, starting from location 7 and going down, so widgets that use the low locations,
+    /// such as `progress_bar()`, can still be used alongside a few fallback glyphs. Locations holding preloaded
+    /// characters or a glyph set are not used. Characters without a built-in glyph, or printed once all free
+    /// locations are taken, are shown as `?`. Without a character ROM set with `with_character_rom()`, all
+    /// characters outside of ASCII are treated as missing.
+    ///
+    /// Uploading a glyph moves the controller's address counter, so glyphs are only uploaded when the cursor
+    /// position is known, such as after `set_cursor()` or `clear()`, or in framebuffer mode. `init()` frees all the
+    /// locations used for fallback glyphs.
+    pub fn with_glyph_fallback(mut self) -> Self {
+        self.glyph_fallback = true;
+        self
+    }
+
+    /// Whether `c` is missing from the character ROM.
+    fn missing_from_rom(&self, c: char) -> bool {
+        match self.character_rom {
+            Some(character_rom) => !character_rom.contains(c),
+            None => !c.is_ascii(),
+        }
+    }
+
+    /// Upload the built-in glyphs of the characters in `text` that are missing from the character ROM and not
+    /// uploaded yet, as set up by `with_glyph_fallback()`, then put the cursor back where it was.
+    fn upload_fallback_glyphs(&mut self, text: &str) -> Result<(), CharacterDisplayError<I2C>> {
+        let cursor = self.cursor;
+        if cursor.is_none() && self.framebuffer.is_none() {
+            return Ok(());
+        }
+        let mut uploaded = false;
+        for c in text.chars() {
+            if !self.missing_from_rom(c)
+                || self.glyph_translation.contains(&Some(c))
+                || self.fallback_chars.contains(&Some(c))
+            {
+                continue;
+            }
+            let Some(charmap) = charset::fallback_glyph(c) else {
+                continue;
+            };
+            let Some(location) = (0..8).rev().find(|location| {
+                self.preloaded_chars[*location].is_none()
+                    && self.glyph_translation[*location].is_none()
+                    && self.fallback_chars[*location].is_none()
+            }) else {
+                break;
+            };
+            self.create_char(location as u8, charmap)?;
+            self.fallback_chars[location] = Some(c);
+            uploaded = true;
+        }
+        if let (true, None, Some((col, row))) = (uploaded, &self.framebuffer, cursor) {
+            self.set_cursor(col, row)?;
+        }
+        Ok(())
+    }
+
+    /// The character printed for `c`: the custom character code of `c` in the active glyph set or of its fallback
+    /// glyph, or else the code of `c` in the character ROM, or `c` itself when no character ROM is set.
+    fn translate(&self, c: char) -> char {
+        if let Some(location) = self.glyph_translation.iter().position(|g| *g == Some(c)) {
+            return char::from(location as u8);
+        }
+        if self.glyph_fallback && c >= ' ' {
+            if let Some(location) = self.fallback_chars.iter().position(|g| *g == Some(c)) {
+                return char::from(location as u8);
+            }
+            if self.missing_from_rom(c) {
+                return '?';
+            }
+        }
+        match self.character_rom {
+            Some(character_rom) => char::from(character_rom.encode(c)),
+            None => c,
+        }
+    }
+
+    /// Upload the preloaded custom characters. Returns whether any were uploaded, in which case the address counter
+    /// is left pointing into CGRAM.
+    fn upload_preloaded_chars(&mut self) -> Result<bool, CharacterDisplayError<I2C>> {
+        let mut uploaded = false;
+        for (location, charmap) in self.preloaded_chars.iter().enumerate() {
+            if let Some(charmap) = charmap {
+                self.device
+                    .create_char(&mut self.config, location as u8, *charmap)?;
+                uploaded = true;
+            }
+        }
+        Ok(uploaded)
+    }
+
+    /// Initialize the display. This must be called before using the display.
+    pub fn init(&mut self) -> Result<(), CharacterDisplayError<I2C>> {
+        self.cursor = None;
+        if let Some(queue) = self.queue.as_mut() {
+            queue.clear();
+        }
+        self.next_init_step = 0;
+        self.instrumented(DisplayOperation::Init, |lcd| {
+            lcd.device.init(&mut lcd.config)?;
+            lcd.apply_init_settings()
+        })?;
+        self.reset_state_after_init();
+        Ok(())
+    }
+
+    /// Initialize the display one step at a time, for firmware that cannot block for the power on waits of `init()`,
+    /// such as the 200 ms an ST7032 compatible AIP31068 takes for its LCD voltage to settle. Each call runs the next
+    /// step and returns `InitProgress::WaitUs` with the time to wait before the next call, during which other work
+    /// can be done, until it returns `InitProgress::Done` with the display set up as by `init()`. The display must
+    /// not be used otherwise until then. Waits shorter than a couple of milliseconds are still done within a step,
+    /// and the PCF2116 and PCF2119, which only wait a few milliseconds, are initialized in a single step. After an
+    /// error, the next call starts the initialization over.
+    ///
+    /// ```rust
+    /// let mut ready_at = None;
+    /// loop {
+    ///     if ready_at.map_or(true, |at| timer.now() >= at) {
+    ///         ready_at = match lcd.init_step()? {
+    ///             InitProgress::WaitUs(us) => Some(timer.now() + us.micros()),
+    ///             InitProgress::Done => break,
+    ///         };
+    ///     }
+    ///     service_other_tasks();
+    /// }
+    /// ```
+    pub fn init_step(&mut self) -> Result<InitProgress, CharacterDisplayError<I2C>> {
+        let step = self.next_init_step;
+        if step == 0 {
+            self.cursor = None;
+            if let Some(queue) = self.queue.as_mut() {
+                queue.clear();
+            }
+        }
+        // the step is started over after an error
+        self.next_init_step = 0;
+        let progress = self.instrumented(DisplayOperation::Init, |lcd| {
+            let progress = lcd.device.init_step(&mut lcd.config, step)?;
+            if progress == InitProgress::Done {
+                lcd.apply_init_settings()?;
+            }
+            Ok(progress)
+        })?;
+        match progress {
+            InitProgress::WaitUs(_) => self.next_init_step = step + 1,
+            InitProgress::Done => self.reset_state_after_init(),
+        }
+        Ok(progress)
+    }
+
+    /// Set up the options applied at initialization, once the controller has been initialized.
+    fn apply_init_settings(&mut self) -> Result<(), CharacterDisplayError<I2C>> {
+        if !self.backlight_on_init {
+            self.device.backlight(&mut self.config, false)?;
+        }
+        if !self.entry_mode.left_to_right {
+            self.device.right_to_left(&mut self.config)?;
+        }
+        if self.entry_mode.autoscroll {
+            self.device.autoscroll(&mut self.config, true)?;
+        }
+        if self.upload_preloaded_chars()? {
+            self.device.set_cursor(&mut self.config, 0, 0)?;
+        }
+        Ok(())
+    }
+
+    /// Reset the tracked display state to that of a freshly initialized display.
+    fn reset_state_after_init(&mut self) {
+        self.cursor = Some((0, 0));
+        self.cursor_advances = self.entry_mode.left_to_right;
+        self.scroll_offset = 0;
+        self.bar_glyphs = None;
+        self.fallback_chars = [None; 8];
+        self.backlight = (!self.backlight_on_init).then_some(false);
+        self.contrast = None;
+        self.custom_chars = self.preloaded_chars;
+        if let Some(framebuffer) = self.framebuffer.as_mut() {
+            // the display was cleared
+            framebuffer.reset();
+        }
+        if let Some(terminal) = self.terminal.as_mut() {
+            terminal.clear();
+        }
+    }
+
+    /// Initialize the display, and if the adapter does not acknowledge its I2C address, try again at the other address
+    /// commonly used by the adapter family before failing. For example, PCF8574T backpacks are usually at `0x27`
+    /// but the PCF8574AT version found on many clone backpacks is at `0x3F`. Returns the I2C address the display
+    /// responded at, which is then used for all further communication.
+    ///
+    /// ```rust
+    /// let mut lcd = CharacterDisplayPCF8574T::new(i2c, LcdDisplayType::Lcd16x2, delay);
+    /// let address = lcd.init_with_address_fallback()?;
+    /// ```
+    pub fn init_with_address_fallback(&mut self) -> Result<u8, CharacterDisplayError<I2C>> {
+        let address = self.config.address;
+        match self.init() {
+            Err(CharacterDisplayError::I2cError(e))
+                if matches!(i2c::Error::kind(&e), i2c::ErrorKind::NoAcknowledge(_)) =>
+            {
+                let Some(alternate) = DEVICE::alternate_i2c_address(address) else {
+                    return Err(CharacterDisplayError::I2cError(e));
+                };
+                self.config.address = alternate;
+                self.error_stats.retries = self.error_stats.retries.saturating_add(1);
+                if let Err(e) = self.init() {
+                    self.config.address = address;
+                    return Err(e);
+                }
+                Ok(alternate)
+            }
+            result => result.map(|_| address),
+        }
+    }
+
+    /// Returns the I2C address used to communicate with the display adapter.
+    pub fn i2c_address(&self) -> u8 {
+        self.config.address
+    }
+
+    /// Reset the display controller without the power on delays of `init()`. The controller is put back into
+    /// its interface mode and the display, cursor and text flow settings are restored. The display contents
+    /// are not cleared. This is useful to recover a display that is showing garbage after electrical noise.
+    /// Preloaded custom characters are uploaded again, after which the cursor is put back where it was, or at the
+    /// home position if that is not known.
+    pub fn soft_reset(&mut self) -> Result<&mut Self, CharacterDisplayError<I2C>> {
+        let cursor = self.cursor.take();
+        self.instrumented(DisplayOperation::SoftReset, |lcd| {
+            lcd.device.soft_reset(&mut lcd.config)?;
+            if lcd.upload_preloaded_chars()? {
+                let (col, row) = cursor.unwrap_or((0, 0));
+                lcd.device.set_cursor(&mut lcd.config, col, row)?;
+                lcd.cursor = Some((col, row));
+            }
+            Ok(())
+        })?;
+        Ok(self)
+    }
+
+    /// Recover a display whose controller lost its state, such as after a brownout reset it or a transient bus
+    /// error knocked the 4-bit interface out of sync, without recreating the display object. Like `soft_reset()`,
+    /// the interface is resynchronized and the display, cursor, text flow and backlight settings are restored along
+    /// with the preloaded custom characters. The display is then cleared, since its contents can no longer be
+    /// trusted. In framebuffer mode, the next `flush()` redraws the whole framebuffer; otherwise the application
+    /// has to print the contents again. Custom characters that are not preloaded have to be created again.
+    pub fn recover(&mut self) -> Result<&mut Self, CharacterDisplayError<I2C>> {
+        self.soft_reset()?;
+        self.cursor = None;
+        self.instrumented(DisplayOperation::Clear, |lcd| {
+            lcd.device.clear(&mut lcd.config)
+        })?;
+        self.cursor = Some((0, 0));
+        self.scroll_offset = 0;
+        self.bar_glyphs = None;
+        self.fallback_chars = [None; 8];
+        self.custom_chars = self.preloaded_chars;
+        if let Some(framebuffer) = self.framebuffer.as_mut() {
+            framebuffer.shown = Framebuffer::new().shown;
+        }
+        Ok(self)
+    }
+
+    /// Returns a snapshot of the display settings, which `restore_state()` puts back. In framebuffer mode, the
+    /// cursor is the position the next print writes to.
+    ///
+    /// ```rust
+    /// let state = lcd.display_state();
+    /// deep_sleep();
+    /// let mut lcd = CharacterDisplayPCF8574T::new(i2c, LcdDisplayType::Lcd16x2, delay);
+    /// lcd.init()?;
+    /// lcd.restore_state(&state)?;
+    /// ```
+    pub fn display_state(&self) -> DisplayState {
+        let (display_control, display_mode) = self.device.control_flags();
+        DisplayState {
+            cursor: self.cursor_position(),
+            display_control,
+            display_mode,
+            backlight: self.backlight,
+            contrast: self.contrast,
+            scroll_offset: self.scroll_offset,
+            custom_chars: self.custom_chars,
+        }
+    }
+
+    /// Put back the display settings of a snapshot taken with `display_state()`, after `init()` set up the display
+    /// again. The display and text flow settings, backlight, contrast, scrolling and custom characters are
+    /// restored and the cursor is put back where it was. The text shown on the display is not, so print it again
+    /// or, in framebuffer mode, `flush()` the framebuffer.
+    pub fn restore_state(
+        &mut self,
+        state: &DisplayState,
+    ) -> Result<&mut Self, CharacterDisplayError<I2C>> {
+        let (col, row) = state.cursor.unwrap_or((0, 0));
+        if row >= self.config.lcd_type.rows() {
+            return Err(CharacterDisplayError::RowOutOfRange { row });
+        }
+        if col >= self.config.lcd_type.cols() {
+            return Err(CharacterDisplayError::ColumnOutOfRange { col });
+        }
+        self.cursor = None;
+        self.custom_chars = [None; 8];
+        self.instrumented(DisplayOperation::RestoreState, |lcd| {
+            lcd.device
+                .set_control_flags(state.display_control, state.display_mode);
+            lcd.device.soft_reset(&mut lcd.config)?;
+            if let Some(on) = state.backlight {
+                lcd.device.backlight(&mut lcd.config, on)?;
+                lcd.backlight = Some(on);
+            }
+            if let Some(contrast) = state.contrast {
+                lcd.device.set_contrast(&mut lcd.config, contrast)?;
+                lcd.contrast = Some(contrast);
+            }
+            for (location, charmap) in state.custom_chars.iter().enumerate() {
+                if let Some(charmap) = charmap {
+                    lcd.device
+                        .create_char(&mut lcd.config, location as u8, *charmap)?;
+                    lcd.custom_chars[location] = Some(*charmap);
+                }
+            }
+            while lcd.scroll_offset != state.scroll_offset % DDRAM_LINE_LENGTH {
+                lcd.device.scroll_left(&mut lcd.config)?;
+                lcd.scroll_offset = (lcd.scroll_offset + 1) % DDRAM_LINE_LENGTH;
+            }
+            lcd.device.set_cursor(&mut lcd.config, col, row)?;
+            if lcd.device.controller_count() > 1 {
+                // the cursor is shown by the controller driving its row, which is only known now
+                lcd.device.show_cursor(
+                    &mut lcd.config,
+                    state.display_control & LCD_FLAG_CURSORON != 0,
+                )?;
+                lcd.device.blink_cursor(
+                    &mut lcd.config,
+                    state.display_control & LCD_FLAG_BLINKON != 0,
+                )?;
+            }
+            Ok(())
+        })?;
+        self.cursor_advances = state.display_mode & LCD_FLAG_ENTRYLEFT != 0;
+        self.bar_glyphs = None;
+        self.fallback_chars = [None; 8];
+        self.cursor = Some((col, row));
+        if let Some(framebuffer) = self.framebuffer.as_mut() {
+            framebuffer.cursor = (col, row);
+            framebuffer.shown = Framebuffer::new().shown;
+        }
+        Ok(self)
+    }
+
+    /// Release the I2C peripheral and the delay, such as to reconfigure the bus pins or to hand the bus to another
+    /// driver, once the display is no longer used. The drop behavior set with `with_drop_behavior()` is carried out
+    /// first, as when the display is dropped.
+    pub fn release(self) -> (I2C, DELAY) {
+        let mut lcd = core::mem::ManuallyDrop::new(self);
+        lcd.apply_drop_behavior();
+        // SAFETY: `lcd` is never used or dropped again, so each field read here is moved out exactly once. The
+        // fields that are not read hold plain data or references that need no dropping.
+        let (i2c, delay, device, last_error) = unsafe {
+            (
+                core::ptr::read(&lcd.config.i2c),
+                core::ptr::read(&lcd.config.delay),
+                core::ptr::read(&lcd.device),
+                core::ptr::read(&lcd.last_error),
+            )
+        };
+        drop((device, last_error));
+        (i2c, delay)
+    }
+
+    /// Carry out the drop behavior set with `with_drop_behavior()`.
+    fn apply_drop_behavior(&mut self) {
+        if self.drop_behavior == DropBehavior::TurnOff {
+            // errors cannot be reported from drop, and not every device has backlight control
+            let _ = self.show_display(false);
+            let _ = self.backlight(false);
+        }
+    }
+
+    /// returns a reference to the I2C peripheral. mostly needed for testing
+    fn i2c(&mut self) -> &mut I2C {
+        &mut self.config.i2c
     }
 
     /// returns the `LcdDisplayType` used to create the display
@@ -414,6 +2281,38 @@ where
         self.config.lcd_type
     }
 
+    /// Returns the number of rows of the display.
+    pub fn rows(&self) -> u8 {
+        self.config.lcd_type.rows()
+    }
+
+    /// Returns the number of columns of the display.
+    pub fn cols(&self) -> u8 {
+        self.config.lcd_type.cols()
+    }
+
+    /// Returns the number of controllers driving the display.
+    pub fn controller_count(&self) -> usize {
+        self.device.controller_count()
+    }
+
+    /// Returns the DDRAM address of the first column of each row. For displays driven by multiple controllers, the
+    /// offsets are for the rows of each controller. The array always has 4 entries, and the entries past the rows of
+    /// a controller are offscreen addresses.
+    pub fn row_offsets(&self) -> [u8; 4] {
+        self.device.row_offsets(self.config.lcd_type)
+    }
+
+    /// Returns how the writes are paced for the I2C bus speed declared with `with_i2c_frequency()`.
+    pub fn i2c_pacing(&self) -> I2cPacing {
+        self.device.i2c_pacing()
+    }
+
+    /// Returns the waits the driver makes for the controller. See `with_timings()`.
+    pub fn timings(&self) -> Timings {
+        self.device.timings()
+    }
+
     /// Supports the ability to read from the display.
     pub fn supports_reads() -> bool {
         DEVICE::supports_reads()
@@ -435,7 +2334,11 @@ where
         &mut self,
         buffer: &mut [u8],
     ) -> Result<&mut Self, CharacterDisplayError<I2C>> {
-        self.device.read_device_data(&mut self.config, buffer)?;
+        // reading moves the address counter, and it could be reading CGRAM
+        self.cursor = None;
+        self.instrumented(DisplayOperation::ReadDeviceData, |lcd| {
+            lcd.device.read_device_data(&mut lcd.config, buffer)
+        })?;
 
         Ok(self)
     }
@@ -444,41 +2347,202 @@ where
     /// Not all adapters support reads from the device. This will return an error if the adapter
     /// does not support reads.
     pub fn read_address_counter(&mut self) -> Result<u8, CharacterDisplayError<I2C>> {
-        self.device.read_address_counter(&mut self.config)
+        self.instrumented(DisplayOperation::ReadAddressCounter, |lcd| {
+            lcd.device.read_address_counter(&mut lcd.config)
+        })
     }
 
     //--------------------------------------------------------------------------------------------------
     // high level commands, for the user!
     //--------------------------------------------------------------------------------------------------
 
-    /// Clear the display
+    /// Clear the display. In framebuffer mode, this clears the framebuffer.
     pub fn clear(&mut self) -> Result<&mut Self, CharacterDisplayError<I2C>> {
-        self.device.clear(&mut self.config)?;
+        if let Some(terminal) = self.terminal.as_mut() {
+            terminal.clear();
+        }
+        if let Some(framebuffer) = self.framebuffer.as_mut() {
+            framebuffer.clear();
+            return Ok(self);
+        }
+        if self.queue_write(QueuedCommand::Clear, &[])? {
+            self.scroll_offset = 0;
+            self.cursor = Some((0, 0));
+            return Ok(self);
+        }
+        self.cursor = None;
+        self.instrumented(DisplayOperation::Clear, |lcd| {
+            lcd.device.clear(&mut lcd.config)
+        })?;
+        // clearing also returns the display to its unshifted position
+        self.scroll_offset = 0;
+        // every controller's cursor is reset and the first controller is made the active one
+        self.cursor = Some((0, 0));
+        Ok(self)
+    }
+
+    /// Clear one row by writing spaces over it, then move the cursor back to where it was. This is quicker than
+    /// `clear()` when only one row is redrawn, since `clear()` waits for the controller to clear the whole display.
+    pub fn clear_row(&mut self, row: u8) -> Result<&mut Self, CharacterDisplayError<I2C>> {
+        self.clear_region(0, row, self.config.lcd_type.cols())
+    }
+
+    /// Clear `width` characters of a row starting at `col` by writing spaces over them, then move the cursor back to
+    /// where it was, if its position is known. The region is cut off at the end of the row.
+    pub fn clear_region(
+        &mut self,
+        col: u8,
+        row: u8,
+        width: u8,
+    ) -> Result<&mut Self, CharacterDisplayError<I2C>> {
+        let cols = self.config.lcd_type.cols();
+        let cursor = self.cursor_position();
+        let spaces = [b' '; DDRAM_LINE_LENGTH as usize];
+        let width = width.min(cols.saturating_sub(col)) as usize;
+        let spaces = core::str::from_utf8(&spaces[..width]).map_err(|_| core::fmt::Error)?;
+        self.set_cursor(col, row)?;
+        self.print(spaces)?;
+        if let Some((col, row)) = cursor {
+            if col < cols && row < self.config.lcd_type.rows() {
+                self.set_cursor(col, row)?;
+            }
+        }
         Ok(self)
     }
 
-    /// Set the cursor to the home position.
+    /// Set the cursor to the home position. This also undoes any scrolling of the display.
     pub fn home(&mut self) -> Result<&mut Self, CharacterDisplayError<I2C>> {
-        self.device.home(&mut self.config)?;
+        if let Some(terminal) = self.terminal.as_mut() {
+            terminal.cursor = (0, 0);
+        }
+        if self.queue_write(QueuedCommand::Home, &[])? {
+            self.cursor = Some((0, 0));
+            self.scroll_offset = 0;
+            return Ok(self);
+        }
+        self.cursor = None;
+        self.instrumented(DisplayOperation::Home, |lcd| {
+            lcd.device.home(&mut lcd.config)
+        })?;
+        self.cursor = Some((0, 0));
+        self.scroll_offset = 0;
         Ok(self)
     }
 
-    /// Set the cursor position at specified column and row. Columns and rows are zero-indexed.
+    /// Set the cursor position at specified column and row. Columns and rows are zero-indexed. In framebuffer mode,
+    /// this sets the position in the framebuffer that the next print writes to.
     pub fn set_cursor(
         &mut self,
         col: u8,
         row: u8,
     ) -> Result<&mut Self, CharacterDisplayError<I2C>> {
-        self.device.set_cursor(&mut self.config, col, row)?;
+        if let Some(terminal) = self.terminal.as_mut() {
+            if row < self.config.lcd_type.rows() && col < self.config.lcd_type.cols() {
+                terminal.cursor = (col, row);
+            }
+        }
+        if let Some(framebuffer) = self.framebuffer.as_mut() {
+            if row >= self.config.lcd_type.rows() {
+                return Err(CharacterDisplayError::RowOutOfRange { row });
+            }
+            if col >= self.config.lcd_type.cols() {
+                return Err(CharacterDisplayError::ColumnOutOfRange { col });
+            }
+            if row as usize >= FRAMEBUFFER_ROWS {
+                return Err(CharacterDisplayError::UnsupportedDisplayType);
+            }
+            framebuffer.cursor = (col, row);
+            return Ok(self);
+        }
+        if self.queue.is_some() {
+            if row >= self.config.lcd_type.rows() {
+                return Err(CharacterDisplayError::RowOutOfRange { row });
+            }
+            if col >= self.config.lcd_type.cols() {
+                return Err(CharacterDisplayError::ColumnOutOfRange { col });
+            }
+            self.queue_write(QueuedCommand::SetCursor(col, row), &[])?;
+            self.cursor = Some((col, row));
+            return Ok(self);
+        }
+        self.cursor = None;
+        self.instrumented(DisplayOperation::SetCursor, |lcd| {
+            lcd.device.set_cursor(&mut lcd.config, col, row)
+        })?;
+        self.cursor = Some((col, row));
+        Ok(self)
+    }
+
+    /// Set the cursor position at the cell `index` of the display, counting the cells in row-major order from 0 at the
+    /// top left, as with the linear addressing used by many Arduino sketches. On a 20x4 display, index 25 is column 5
+    /// of row 1. The column and row are set as by `set_cursor()`, which also selects the controller driving the row.
+    /// An index past the last cell returns `CharacterDisplayError::RowOutOfRange`.
+    pub fn set_cursor_linear(
+        &mut self,
+        index: u16,
+    ) -> Result<&mut Self, CharacterDisplayError<I2C>> {
+        let (col, row) = self.linear_position(index);
+        self.set_cursor(col, row)
+    }
+
+    /// Returns the column and row of the cell `index` of the display, counting the cells in row-major order. The row
+    /// is out of range for an index past the last cell.
+    fn linear_position(&self, index: u16) -> (u8, u8) {
+        let cols = self.config.lcd_type.cols() as u16;
+        let row = (index / cols).min(u8::MAX as u16) as u8;
+        ((index % cols) as u8, row)
+    }
+
+    /// Set the cursor position at the specified column and row of what is currently visible on the display,
+    /// compensating for any scrolling done with `scroll_display_left()` and `scroll_display_right()`. Columns and
+    /// rows are zero-indexed. When the display has not been scrolled, this is the same as `set_cursor()`.
+    ///
+    /// ```rust
+    /// lcd.scroll_display_left()?.scroll_display_left()?;
+    /// // prints at the left edge of the display, which is DDRAM column 2
+    /// lcd.set_visible_cursor(0, 1)?.print("Hi")?;
+    /// ```
+    pub fn set_visible_cursor(
+        &mut self,
+        col: u8,
+        row: u8,
+    ) -> Result<&mut Self, CharacterDisplayError<I2C>> {
+        if self.scroll_offset == 0 {
+            return self.set_cursor(col, row);
+        }
+        if row >= self.config.lcd_type.rows() {
+            return Err(CharacterDisplayError::RowOutOfRange { row });
+        }
+        if col >= self.config.lcd_type.cols() {
+            return Err(CharacterDisplayError::ColumnOutOfRange { col });
+        }
+        let (_, controller_row) = self.device.controller_row(row);
+        let row_offset = self.config.lcd_type.row_offsets()[controller_row as usize];
+        // the display shift wraps around within each DDRAM line, which starts at either 0x00 or 0x40
+        let line_col = ((row_offset & 0x3F) + col + self.scroll_offset) % DDRAM_LINE_LENGTH;
+        let address = (row_offset & 0x40) | line_col;
+        // the tracked cursor is in DDRAM columns, which no longer match the visible columns
+        self.cursor = None;
+        self.instrumented(DisplayOperation::SetCursor, |lcd| {
+            lcd.device.set_ddram_address(&mut lcd.config, row, address)
+        })?;
         Ok(self)
     }
 
+    /// Returns how many columns the display is currently scrolled to the left, from 0 to 39. Scrolling to the right
+    /// from the home position wraps around to 39, since the display shift wraps around the 40 character DDRAM lines.
+    pub fn scroll_offset(&self) -> u8 {
+        self.scroll_offset
+    }
+
     /// Set the cursor visibility.
     pub fn show_cursor(
         &mut self,
         show_cursor: bool,
     ) -> Result<&mut Self, CharacterDisplayError<I2C>> {
-        self.device.show_cursor(&mut self.config, show_cursor)?;
+        self.instrumented(DisplayOperation::ShowCursor, |lcd| {
+            lcd.device.show_cursor(&mut lcd.config, show_cursor)
+        })?;
         Ok(self)
     }
 
@@ -487,7 +2551,9 @@ where
         &mut self,
         blink_cursor: bool,
     ) -> Result<&mut Self, CharacterDisplayError<I2C>> {
-        self.device.blink_cursor(&mut self.config, blink_cursor)?;
+        self.instrumented(DisplayOperation::BlinkCursor, |lcd| {
+            lcd.device.blink_cursor(&mut lcd.config, blink_cursor)
+        })?;
         Ok(self)
     }
 
@@ -496,31 +2562,125 @@ where
         &mut self,
         show_display: bool,
     ) -> Result<&mut Self, CharacterDisplayError<I2C>> {
-        self.device.show_display(&mut self.config, show_display)?;
+        self.instrumented(DisplayOperation::ShowDisplay, |lcd| {
+            lcd.device.show_display(&mut lcd.config, show_display)
+        })?;
         Ok(self)
     }
 
+    /// Turn the display output off, run `update` to make a batch of changes, and then turn the display output back
+    /// on. The display contents are retained while the output is off, so large redraws appear all at once rather
+    /// than flickering as they are drawn. The display is turned back on even when `update` fails, and the error
+    /// from `update` is returned.
+    ///
+    /// ```rust
+    /// lcd.with_display_hidden(|lcd| {
+    ///     lcd.clear()?.print("Hello")?.set_cursor(0, 1)?.print("World")?;
+    ///     Ok(())
+    /// })?;
+    /// ```
+    pub fn with_display_hidden<F>(
+        &mut self,
+        update: F,
+    ) -> Result<&mut Self, CharacterDisplayError<I2C>>
+    where
+        F: FnOnce(&mut Self) -> Result<(), CharacterDisplayError<I2C>>,
+    {
+        self.show_display(false)?;
+        let result = update(self);
+        let shown = self.show_display(true).map(|_| ());
+        result?;
+        shown?;
+        Ok(self)
+    }
+
+    /// Returns the cursor position as `( col, row )`, as tracked in software while printing and moving the cursor, or
+    /// `None` when it is not known, such as after `create_char()` or once printed text ran into DDRAM that is not
+    /// shown. In framebuffer mode, this is the position in the framebuffer that the next print writes to.
+    pub fn cursor_position(&self) -> Option<(u8, u8)> {
+        match self.framebuffer.as_ref() {
+            Some(framebuffer) => Some(framebuffer.cursor),
+            None => self.cursor,
+        }
+    }
+
+    /// Returns the position the cursor moves to when `count` characters are printed from `col` and `row` with left
+    /// to right text flow, or `None` when the controller's address counter then points to DDRAM that is not shown.
+    /// The address counter runs to the end of each 40 character DDRAM line and then on to the start of the other
+    /// line, so where text continues past the end of a row depends on the display type. For example, row 0 of a
+    /// 20x4 display continues on row 2, while on a 16x2 display the text runs into DDRAM that is not shown. On
+    /// displays with more than one controller, the text stays on the rows driven by the controller of `row`. On
+    /// controllers in 4 line mode, such as the US2066 and PCF2116, only positions within `row` are known.
+    pub fn advance(&self, col: u8, row: u8, count: usize) -> Option<(u8, u8)> {
+        let lcd_type = self.config.lcd_type;
+        if col >= lcd_type.cols() || row >= lcd_type.rows() {
+            return None;
+        }
+        if col as usize + count < lcd_type.cols() as usize {
+            return Some((col + count as u8, row));
+        }
+        let offsets = self.device.row_offsets(lcd_type);
+        if offsets
+            .iter()
+            .take(lcd_type.rows() as usize)
+            .any(|offset| offset & 0x20 != 0)
+        {
+            // the rows of the 4 line modes are 0x20 apart, with a controller specific address counter wrap
+            return None;
+        }
+        let (controller, controller_row) = self.device.controller_row(row);
+        let address = offsets[controller_row as usize] + col;
+        let position = (address & 0x3F) as usize + count;
+        // each line end crossed switches between the lines at 0x00 and 0x40
+        let line = if (position / DDRAM_LINE_LENGTH as usize) & 1 == 0 {
+            address & 0x40
+        } else {
+            (address & 0x40) ^ 0x40
+        };
+        let address = line | (position % DDRAM_LINE_LENGTH as usize) as u8;
+        (0..lcd_type.rows()).find_map(|row| {
+            let (row_controller, controller_row) = self.device.controller_row(row);
+            let offset = offsets[controller_row as usize];
+            (row_controller == controller
+                && address >= offset
+                && address - offset < lcd_type.cols())
+            .then(|| (address - offset, row))
+        })
+    }
+
     /// Scroll the display to the left.
     pub fn scroll_display_left(&mut self) -> Result<&mut Self, CharacterDisplayError<I2C>> {
-        self.device.scroll_left(&mut self.config)?;
+        self.instrumented(DisplayOperation::ScrollLeft, |lcd| {
+            lcd.device.scroll_left(&mut lcd.config)
+        })?;
+        self.scroll_offset = (self.scroll_offset + 1) % DDRAM_LINE_LENGTH;
         Ok(self)
     }
 
     /// Scroll the display to the right.
     pub fn scroll_display_right(&mut self) -> Result<&mut Self, CharacterDisplayError<I2C>> {
-        self.device.scroll_right(&mut self.config)?;
+        self.instrumented(DisplayOperation::ScrollRight, |lcd| {
+            lcd.device.scroll_right(&mut lcd.config)
+        })?;
+        self.scroll_offset = (self.scroll_offset + DDRAM_LINE_LENGTH - 1) % DDRAM_LINE_LENGTH;
         Ok(self)
     }
 
     /// Set the text flow direction to left to right.
     pub fn left_to_right(&mut self) -> Result<&mut Self, CharacterDisplayError<I2C>> {
-        self.device.left_to_right(&mut self.config)?;
+        self.cursor_advances = true;
+        self.instrumented(DisplayOperation::LeftToRight, |lcd| {
+            lcd.device.left_to_right(&mut lcd.config)
+        })?;
         Ok(self)
     }
 
     /// Set the text flow direction to right to left.
     pub fn right_to_left(&mut self) -> Result<&mut Self, CharacterDisplayError<I2C>> {
-        self.device.right_to_left(&mut self.config)?;
+        self.cursor_advances = false;
+        self.instrumented(DisplayOperation::RightToLeft, |lcd| {
+            lcd.device.right_to_left(&mut lcd.config)
+        })?;
         Ok(self)
     }
 
@@ -529,72 +2689,1273 @@ where
         &mut self,
         autoscroll: bool,
     ) -> Result<&mut Self, CharacterDisplayError<I2C>> {
-        self.device.autoscroll(&mut self.config, autoscroll)?;
+        self.instrumented(DisplayOperation::Autoscroll, |lcd| {
+            lcd.device.autoscroll(&mut lcd.config, autoscroll)
+        })?;
         Ok(self)
     }
 
-    /// Create a new custom character.
-    pub fn create_char(
+    /// Select one of the two contrast presets on adapters that switch the contrast with a GPIO pin, such as
+    /// `CharacterDisplayPCF8574TContrast`. Other adapters return `CharacterDisplayError::UnsupportedOperation`.
+    pub fn set_contrast_preset(
         &mut self,
-        location: u8,
-        charmap: [u8; 8],
+        preset: ContrastPreset,
     ) -> Result<&mut Self, CharacterDisplayError<I2C>> {
-        self.device
-            .create_char(&mut self.config, location, charmap)?;
+        self.instrumented(DisplayOperation::SetContrastPreset, |lcd| {
+            lcd.device.set_contrast_preset(&mut lcd.config, preset)
+        })?;
         Ok(self)
     }
 
-    /// Prints a string to the LCD at the current cursor position of the active device.
-    pub fn print(&mut self, text: &str) -> Result<&mut Self, CharacterDisplayError<I2C>> {
-        self.device.print(&mut self.config, text)?;
-        Ok(self)
+    /// Returns the mask of the I2C expander pins not used by the display, which can be read with
+    /// `read_input_pins()`, such as the P1 pushbutton pin of `CharacterDisplayPCF8574TButtons`. Other adapters have
+    /// no such pins.
+    pub fn input_pins(&self) -> u8 {
+        self.device.input_pins()
     }
 
-    /// Turn the backlight on or off.
-    /// Note that the AIP31068 controller does not support backlight control.
-    pub fn backlight(&mut self, on: bool) -> Result<&mut Self, CharacterDisplayError<I2C>> {
-        self.device.backlight(&mut self.config, on)?;
-        Ok(self)
+    /// Read the levels of the I2C expander pins in `input_pins()`, with a bit set for each pin that is high and the
+    /// other bits cleared. Adapters without such pins return `CharacterDisplayError::UnsupportedOperation`.
+    pub fn read_input_pins(&mut self) -> Result<u8, CharacterDisplayError<I2C>> {
+        self.instrumented(DisplayOperation::ReadInputPins, |lcd| {
+            lcd.device.read_input_pins(&mut lcd.config)
+        })
     }
-}
 
-/// Implement the `core::fmt::Write` trait, allowing it to be used with the `write!` macro.
-/// This is a convenience method for printing to the display. For multi-device, this will print to the active device as set by
-/// `set_cursor`.
-impl<I2C, DELAY, DEVICE> core::fmt::Write for BaseCharacterDisplay<I2C, DELAY, DEVICE>
-where
-    I2C: i2c::I2c,
-    DELAY: DelayNs,
-    DEVICE: driver::DriverTrait<I2C, DELAY>,
-{
-    fn write_str(&mut self, s: &str) -> Result<(), core::fmt::Error> {
-        if let Err(_e) = self.print(s) {
-            return Err(core::fmt::Error);
-        }
-        Ok(())
+    /// Read which of the pushbuttons on the I2C expander pins in `input_pins()` are pressed, with a bit set for
+    /// each pin whose pushbutton pulls it low. Adapters without such pins return
+    /// `CharacterDisplayError::UnsupportedOperation`.
+    pub fn pressed_buttons(&mut self) -> Result<u8, CharacterDisplayError<I2C>> {
+        Ok(!self.read_input_pins()? & self.input_pins())
     }
-}
 
-#[cfg(feature = "ufmt")]
-/// Implement the `ufmt::uWrite` trait, allowing it to be used with the `uwriteln!` and `uwrite!` macros.
-/// This is a convenience method for printing to the display. For multi-device, this will print to the active device as set by
-/// `set_cursor`.
-impl<I2C, DELAY, DEVICE> ufmt::uWrite for BaseCharacterDisplay<I2C, DELAY, DEVICE>
-where
-    I2C: i2c::I2c,
-    DELAY: DelayNs,
-    DEVICE: driver::DriverTrait<I2C, DELAY>,
-{
-    fn write_str(&mut self, s: &str) -> Result<(), CharacterDisplayError<I2C>> {
-        if let Err(e) = self.print(s) {
-            return Err(e);
-        }
-        Ok(())
+    /// Set the contrast on controllers with software contrast control, such as the US2066 OLED controller, where it
+    /// sets the brightness of the display. Other controllers return `CharacterDisplayError::UnsupportedOperation`.
+    pub fn set_contrast(&mut self, contrast: u8) -> Result<&mut Self, CharacterDisplayError<I2C>> {
+        self.instrumented(DisplayOperation::SetContrast, |lcd| {
+            lcd.device.set_contrast(&mut lcd.config, contrast)
+        })?;
+        self.contrast = Some(contrast);
+        Ok(self)
     }
 
-    type Error = CharacterDisplayError<I2C>;
-}
-
+    /// Fade the contrast from `from` to `to` in `steps` equal steps, waiting `step_delay_ms` milliseconds between
+    /// steps, for fade in and fade out effects on displays without a dimmable backlight. Like `set_contrast()`, this
+    /// needs a controller with software contrast control, and others return
+    /// `CharacterDisplayError::UnsupportedOperation`. With 0 steps, the contrast is set to `to` right away.
+    pub fn fade_contrast(
+        &mut self,
+        from: u8,
+        to: u8,
+        steps: u8,
+        step_delay_ms: u32,
+    ) -> Result<&mut Self, CharacterDisplayError<I2C>> {
+        let steps = steps.max(1) as i32;
+        for step in 1..=steps {
+            if step > 1 {
+                self.config.delay.delay_ms(step_delay_ms);
+            }
+            let contrast = from as i32 + (to as i32 - from as i32) * step / steps;
+            self.set_contrast(contrast as u8)?;
+        }
+        Ok(self)
+    }
+
+    /// Turn double height characters on or off on controllers that support them, such as the US2066 OLED
+    /// controller. When on, the display shows the first row in double height characters. Other controllers return
+    /// `CharacterDisplayError::UnsupportedOperation`. See `set_double_height()` for the other layouts.
+    pub fn double_height(
+        &mut self,
+        double_height: bool,
+    ) -> Result<&mut Self, CharacterDisplayError<I2C>> {
+        self.set_double_height(if double_height {
+            DoubleHeightMode::Top
+        } else {
+            DoubleHeightMode::Off
+        })
+    }
+
+    /// Select which rows are shown in double height characters on controllers that support them, such as the
+    /// US2066 OLED controller. A double height row takes the space of two rows, and the rows of `set_cursor()` are
+    /// then numbered as shown, so with `DoubleHeightMode::Top` on a 4 row display, row 1 is the third row of the
+    /// display. Controllers without double height characters and layouts the display does not support return
+    /// `CharacterDisplayError::UnsupportedOperation`.
+    pub fn set_double_height(
+        &mut self,
+        mode: DoubleHeightMode,
+    ) -> Result<&mut Self, CharacterDisplayError<I2C>> {
+        self.cursor = None;
+        self.instrumented(DisplayOperation::DoubleHeight, |lcd| {
+            lcd.device.set_double_height(&mut lcd.config, mode)
+        })?;
+        Ok(self)
+    }
+
+    /// Create a new custom character. On displays driven by more than one controller, the character is created
+    /// on every controller.
+    pub fn create_char(
+        &mut self,
+        location: u8,
+        charmap: [u8; 8],
+    ) -> Result<&mut Self, CharacterDisplayError<I2C>> {
+        // the controller's address counter now points into CGRAM
+        self.cursor = None;
+        self.fallback_chars[(location & 0x7) as usize] = None;
+        self.custom_chars[(location & 0x7) as usize] = None;
+        self.instrumented(DisplayOperation::CreateChar, |lcd| {
+            lcd.device.create_char(&mut lcd.config, location, charmap)
+        })?;
+        self.custom_chars[(location & 0x7) as usize] = Some(charmap);
+        Ok(self)
+    }
+
+    /// Upload a predefined icon from the `glyphs` module to a custom character location, and return the character
+    /// that prints it.
+    pub fn load_glyph(
+        &mut self,
+        location: u8,
+        glyph: glyphs::Glyph,
+    ) -> Result<char, CharacterDisplayError<I2C>> {
+        self.create_char(location, glyph.charmap())?;
+        Ok(char::from(location & 0x7))
+    }
+
+    /// Write the low 4 bits of `value` to a controller over the 4-bit interface of the adapter, with the RS pin set
+    /// to `rs_setting`, in a single enable pulse. This is for hardware bring-up, such as stepping through the sync
+    /// sequence of `init()` by hand, and leaves the tracked display state unknown to this object. Adapters using the
+    /// 8-bit interface and controllers with a built-in I2C interface return
+    /// `CharacterDisplayError::UnsupportedOperation`.
+    ///
+    /// ```rust
+    /// // the sync sequence that puts the controller into 4-bit mode
+    /// for nibble in [0x03, 0x03, 0x03, 0x02] {
+    ///     lcd.write_nibble_to_controller(0, false, nibble)?;
+    ///     delay.delay_ms(5);
+    /// }
+    /// // function set: 4-bit interface, 2 lines
+    /// lcd.write_byte_to_controller(0, false, 0x28)?;
+    /// ```
+    #[cfg(feature = "advanced")]
+    pub fn write_nibble_to_controller(
+        &mut self,
+        controller: usize,
+        rs_setting: bool,
+        value: u8,
+    ) -> Result<&mut Self, CharacterDisplayError<I2C>> {
+        self.cursor = None;
+        self.device
+            .write_nibble(&mut self.config, controller, rs_setting, value)?;
+        Ok(self)
+    }
+
+    /// Write a byte to a controller, as a command when `rs_setting` is `false` and as data otherwise. Like
+    /// `write_nibble_to_controller()`, this is for hardware bring-up and leaves the tracked display state unknown to
+    /// this object. Controllers with a built-in I2C interface return `CharacterDisplayError::UnsupportedOperation`.
+    #[cfg(feature = "advanced")]
+    pub fn write_byte_to_controller(
+        &mut self,
+        controller: usize,
+        rs_setting: bool,
+        value: u8,
+    ) -> Result<&mut Self, CharacterDisplayError<I2C>> {
+        self.cursor = None;
+        self.device
+            .write_byte(&mut self.config, controller, rs_setting, value)?;
+        Ok(self)
+    }
+
+    /// Draw a horizontal bar graph `width` cells wide starting at the specified column and row, filled to `percent`
+    /// (0 to 100) with a resolution of one pixel column. The bar is drawn from five custom characters of partially
+    /// filled blocks, which are created at locations 0 to 4 the first time a bar is drawn after `init()` or after a
+    /// `vbar()`, so those locations cannot hold other custom characters while progress bars are in use.
+    ///
+    /// ```rust
+    /// lcd.print_at(0, 0, "Volume")?;
+    /// lcd.progress_bar(0, 1, 16, volume_percent)?;
+    /// ```
+    pub fn progress_bar(
+        &mut self,
+        col: u8,
+        row: u8,
+        width: u8,
+        percent: u8,
+    ) -> Result<&mut Self, CharacterDisplayError<I2C>> {
+        if col as usize + width as usize > self.config.lcd_type.cols() as usize {
+            return Err(CharacterDisplayError::ColumnOutOfRange { col });
+        }
+        if row >= self.config.lcd_type.rows() {
+            return Err(CharacterDisplayError::RowOutOfRange { row });
+        }
+        if self.bar_glyphs != Some(BarGlyphs::Horizontal) {
+            for location in 0..PROGRESS_BAR_CELL_PIXELS {
+                // the glyph at each location fills one more pixel column from the left, leaving the cursor line blank
+                let columns = 0x1F & !(0x1F >> (location + 1));
+                let mut charmap = [columns; 8];
+                charmap[7] = 0;
+                self.create_char(location, charmap)?;
+            }
+            self.bar_glyphs = Some(BarGlyphs::Horizontal);
+        }
+
+        let mut filled =
+            width as usize * PROGRESS_BAR_CELL_PIXELS as usize * percent.min(100) as usize / 100;
+        let mut cells = [b' '; framebuffer::FRAMEBUFFER_COLS];
+        for cell in cells.iter_mut().take(width as usize) {
+            let pixels = filled.min(PROGRESS_BAR_CELL_PIXELS as usize);
+            if pixels > 0 {
+                *cell = pixels as u8 - 1;
+            }
+            filled -= pixels;
+        }
+        // the cells are spaces and custom character codes, all of which are valid single byte UTF-8
+        let text = core::str::from_utf8(&cells[..width as usize]).map_err(|_| core::fmt::Error)?;
+        self.print_at(col, row, text)
+    }
+
+    /// Draw a vertical bar graph in the specified column across the rows of `row_span`, filled from the bottom row up
+    /// to `percent` (0 to 100) with a resolution of one pixel row, such as for the level meters of an audio VU meter.
+    /// The bar is drawn from eight custom characters of partially filled blocks, which are created at locations 0 to
+    /// 7 the first time a bar is drawn after `init()` or after a `progress_bar()`, so no other custom characters can
+    /// be used while vertical bars are in use.
+    ///
+    /// ```rust
+    /// for (channel, level) in levels.iter().enumerate() {
+    ///     lcd.vbar(channel as u8, 0..4, *level)?;
+    /// }
+    /// ```
+    pub fn vbar(
+        &mut self,
+        col: u8,
+        row_span: core::ops::Range<u8>,
+        percent: u8,
+    ) -> Result<&mut Self, CharacterDisplayError<I2C>> {
+        if col >= self.config.lcd_type.cols() {
+            return Err(CharacterDisplayError::ColumnOutOfRange { col });
+        }
+        if row_span.is_empty() || row_span.end > self.config.lcd_type.rows() {
+            return Err(CharacterDisplayError::RowOutOfRange { row: row_span.end });
+        }
+        if self.bar_glyphs != Some(BarGlyphs::Vertical) {
+            for location in 0..VBAR_CELL_PIXELS {
+                // the glyph at each location fills one more pixel row from the bottom
+                let mut charmap = [0; 8];
+                charmap[(VBAR_CELL_PIXELS - 1 - location) as usize..].fill(0x1F);
+                self.create_char(location, charmap)?;
+            }
+            self.bar_glyphs = Some(BarGlyphs::Vertical);
+        }
+
+        let mut filled =
+            row_span.len() * VBAR_CELL_PIXELS as usize * percent.min(100) as usize / 100;
+        for row in row_span.rev() {
+            let pixels = filled.min(VBAR_CELL_PIXELS as usize);
+            // the cell is a space or a custom character code, both of which are valid single byte UTF-8
+            let cell = if pixels > 0 { pixels as u8 - 1 } else { b' ' };
+            let text =
+                core::str::from_utf8(core::slice::from_ref(&cell)).map_err(|_| core::fmt::Error)?;
+            self.print_at(col, row, text)?;
+            filled -= pixels;
+        }
+        Ok(self)
+    }
+
+    /// Create a new custom character in the CGRAM of only one controller. On displays driven by more than one
+    /// controller, such as 40x4 displays, each controller has its own 8 custom character slots, so the rows
+    /// driven by each controller can use different glyphs for the same slot. Text printed to a row uses the
+    /// glyphs of the controller driving that row, which is given by `controller_for_row()`. Displays with a
+    /// single controller only accept controller `0`.
+    pub fn create_char_for_controller(
+        &mut self,
+        controller: usize,
+        location: u8,
+        charmap: [u8; 8],
+    ) -> Result<&mut Self, CharacterDisplayError<I2C>> {
+        self.cursor = None;
+        self.fallback_chars[(location & 0x7) as usize] = None;
+        // the location no longer holds the same character on every controller
+        self.custom_chars[(location & 0x7) as usize] = None;
+        self.instrumented(DisplayOperation::CreateChar, |lcd| {
+            lcd.device
+                .create_char_for_controller(&mut lcd.config, controller, location, charmap)
+        })?;
+        Ok(self)
+    }
+
+    /// Returns the index of the controller that drives the given row.
+    pub fn controller_for_row(&self, row: u8) -> Result<usize, CharacterDisplayError<I2C>> {
+        Ok(self.row_to_controller_row(row)?.0)
+    }
+
+    /// Map a row of the display to the controller that drives it and the row within that controller. The return
+    /// tuple is `( controller, row )`. Displays with a single controller always return controller `0` and the
+    /// same row.
+    pub fn row_to_controller_row(
+        &self,
+        row: u8,
+    ) -> Result<(usize, u8), CharacterDisplayError<I2C>> {
+        if row >= self.config.lcd_type.rows() {
+            return Err(CharacterDisplayError::RowOutOfRange { row });
+        }
+        Ok(self.device.controller_row(row))
+    }
+
+    /// Prints a string to the LCD at the current cursor position of the active device. In framebuffer mode, this
+    /// writes the string to the framebuffer. An empty string, or one made up only of control characters such as
+    /// `"\r\n"`, prints nothing and leaves the cursor where it was. The character codes 0-7 of the custom
+    /// characters are not treated as control characters. On displays with more than one controller, such as 40x4
+    /// displays, text that runs past the last row driven by one controller continues on the first row of the next.
+    /// In terminal mode, `'\n'` and `'\r'` move the cursor and text wraps at the end of each row, see
+    /// `with_terminal()`. A `'\t'` prints spaces up to the next tab stop, see `with_tab_width()`.
+    pub fn print(&mut self, text: &str) -> Result<&mut Self, CharacterDisplayError<I2C>> {
+        if self.tab_width > 0 && text.contains('\t') {
+            return self.print_tabs(text);
+        }
+        if let Some(terminal) = self.terminal.take() {
+            // the rows are printed with terminal mode off, and it is turned back on even when printing fails
+            let result = self.print_terminal(text, terminal);
+            self.terminal = Some(terminal);
+            return result.map(|_| self);
+        }
+        if !text.chars().any(|c| c <= '\u{07}' || !c.is_control()) {
+            return Ok(self);
+        }
+        if self.glyph_fallback {
+            self.upload_fallback_glyphs(text)?;
+        }
+        if text.chars().any(|c| self.translate(c) != c) {
+            return self.print_translated(text);
+        }
+        self.print_text(text)
+    }
+
+    /// Prints a string with each `'\t'` replaced by spaces up to the next tab stop, without going past the end of
+    /// the row. A tab is printed as a single space when the cursor position is not known.
+    fn print_tabs(&mut self, text: &str) -> Result<&mut Self, CharacterDisplayError<I2C>> {
+        let cols = self.config.lcd_type.cols();
+        let spaces = [b' '; DDRAM_LINE_LENGTH as usize];
+        for part in text.split_inclusive('\t') {
+            let Some(line) = part.strip_suffix('\t') else {
+                self.print(part)?;
+                continue;
+            };
+            self.print(line)?;
+            let col = match self.terminal.as_ref() {
+                Some(terminal) => Some(terminal.cursor.0),
+                None => self.cursor_position().map(|(col, _)| col),
+            };
+            let count = match col {
+                Some(col) => (self.tab_width - col % self.tab_width).min(cols.saturating_sub(col)),
+                None => 1,
+            };
+            let count = (count as usize).min(spaces.len());
+            let spaces = core::str::from_utf8(&spaces[..count]).map_err(|_| core::fmt::Error)?;
+            self.print(spaces)?;
+        }
+        Ok(self)
+    }
+
+    /// Prints a string in terminal mode, printing each run of characters that stays on one row at its position and
+    /// redrawing the display whenever the lines scroll up.
+    fn print_terminal(
+        &mut self,
+        text: &str,
+        terminal: &mut TerminalBuffer,
+    ) -> Result<(), CharacterDisplayError<I2C>> {
+        let cols = self.config.lcd_type.cols();
+        let rows = self.config.lcd_type.rows();
+        if rows as usize > TERMINAL_ROWS || cols as usize > TERMINAL_COLS {
+            return Err(CharacterDisplayError::UnsupportedDisplayType);
+        }
+        let mut start = 0;
+        let mut run_cursor = terminal.cursor;
+        for (index, c) in text.char_indices() {
+            let line_break = c == '\n' || c == '\r';
+            if line_break || terminal.cursor.0 == cols {
+                if start < index {
+                    self.print_terminal_run(terminal, run_cursor, &text[start..index])?;
+                }
+                if c == '\r' {
+                    terminal.cursor.0 = 0;
+                } else if terminal.new_line(rows) {
+                    self.redraw_terminal(terminal)?;
+                }
+                start = if line_break { index + 1 } else { index };
+                run_cursor = terminal.cursor;
+            }
+            if !line_break {
+                terminal.cursor.0 += 1;
+            }
+        }
+        if start < text.len() {
+            self.print_terminal_run(terminal, run_cursor, &text[start..])?;
+        }
+        Ok(())
+    }
+
+    /// Prints a run of characters that stays on one row in terminal mode at `( col, row )`, and records the character
+    /// codes it shows in the line buffer.
+    fn print_terminal_run(
+        &mut self,
+        terminal: &mut TerminalBuffer,
+        (col, row): (u8, u8),
+        text: &str,
+    ) -> Result<(), CharacterDisplayError<I2C>> {
+        self.print_at(col, row, text)?;
+        // the codes are taken after printing, which uploads any fallback glyphs the text needs
+        for (offset, c) in text.chars().enumerate() {
+            terminal.put(col + offset as u8, row, self.translate(c) as u8);
+        }
+        Ok(())
+    }
+
+    /// Writes all the lines of the terminal to the display. The lines hold character codes, so they are printed
+    /// without translation.
+    fn redraw_terminal(
+        &mut self,
+        terminal: &TerminalBuffer,
+    ) -> Result<(), CharacterDisplayError<I2C>> {
+        let cols = self.config.lcd_type.cols() as usize;
+        // each character code is up to 2 bytes of UTF-8
+        let mut buffer = [0u8; TERMINAL_COLS * 2];
+        for (row, line) in terminal.lines.iter().enumerate() {
+            if row >= self.config.lcd_type.rows() as usize {
+                break;
+            }
+            let len = line[..cols].iter().fold(0, |len, code| {
+                len + char::from(*code).encode_utf8(&mut buffer[len..]).len()
+            });
+            let line = core::str::from_utf8(&buffer[..len]).map_err(|_| core::fmt::Error)?;
+            self.set_cursor(0, row as u8)?;
+            self.print_text(line)?;
+        }
+        Ok(())
+    }
+
+    /// Prints a string with the characters of the active glyph set replaced by their custom character codes, and the
+    /// other characters by their codes in the character ROM.
+    fn print_translated(&mut self, text: &str) -> Result<&mut Self, CharacterDisplayError<I2C>> {
+        let mut buffer = [0u8; TRANSLATION_BUFFER_SIZE];
+        let mut len = 0;
+        for c in text.chars() {
+            let c = self.translate(c);
+            if len + c.len_utf8() > buffer.len() {
+                let chunk = core::str::from_utf8(&buffer[..len]).map_err(|_| core::fmt::Error)?;
+                self.print_text(chunk)?;
+                len = 0;
+            }
+            len += c.encode_utf8(&mut buffer[len..]).len();
+        }
+        let chunk = core::str::from_utf8(&buffer[..len]).map_err(|_| core::fmt::Error)?;
+        self.print_text(chunk)
+    }
+
+    /// Prints a string as it is, to the framebuffer in framebuffer mode.
+    fn print_text(&mut self, text: &str) -> Result<&mut Self, CharacterDisplayError<I2C>> {
+        if let Some(framebuffer) = self.framebuffer.as_mut() {
+            framebuffer.write(text, self.config.lcd_type.cols() as usize);
+            return Ok(self);
+        }
+        if let (Addressing::RowMajor, true, Some(cursor)) =
+            (self.addressing, self.cursor_advances, self.cursor)
+        {
+            return self.print_row_major(text, cursor);
+        }
+        if let (true, Some((col, row))) = (self.cursor_advances, self.cursor) {
+            if let Some((end, next_row)) = self.controller_split(text, col, row) {
+                let (text, rest) = text.split_at(end);
+                self.print_text(text)?;
+                self.set_cursor(0, next_row)?;
+                return self.print_text(rest);
+            }
+        }
+        let cursor = self.cursor.take();
+        self.instrumented(DisplayOperation::Print, |lcd| lcd.write_text(text))?;
+        self.cursor = match cursor {
+            Some((col, row)) if self.cursor_advances => {
+                self.advance(col, row, text.chars().count())
+            }
+            _ => None,
+        };
+        if let Some((col, row)) = cursor {
+            self.verify_written(text, col, row)?;
+        }
+        Ok(self)
+    }
+
+    /// Reads back `text` written from `col` and `row` in write verification mode, and compares it with the text.
+    /// The cursor is then put back where the write left it, if its position is known.
+    fn verify_written(
+        &mut self,
+        text: &str,
+        col: u8,
+        row: u8,
+    ) -> Result<(), CharacterDisplayError<I2C>> {
+        if !self.verify_writes
+            || self.queue.is_some()
+            || !(self.character_rom.is_some() || text.is_ascii())
+        {
+            return Ok(());
+        }
+        if !DEVICE::supports_reads() {
+            return Err(CharacterDisplayError::ReadNotSupported);
+        }
+        let cursor = self.cursor;
+        self.set_cursor(col, row)?;
+        let mut written = text.chars().map(|c| c as u8).enumerate();
+        let mut read = [0u8; DDRAM_LINE_LENGTH as usize];
+        let mut remaining = text.chars().count();
+        while remaining > 0 {
+            // the address counter moves on with each read, so the reads continue where the last one ended
+            let len = remaining.min(read.len());
+            self.read_device_data(&mut read[..len])?;
+            for (byte, (index, expected)) in read[..len].iter().zip(written.by_ref()) {
+                if *byte != expected {
+                    let (col, row) = self.advance(col, row, index).unwrap_or((col, row));
+                    return Err(CharacterDisplayError::VerificationFailed { col, row });
+                }
+            }
+            remaining -= len;
+        }
+        if let Some((col, row)) = cursor {
+            self.set_cursor(col, row)?;
+        }
+        Ok(())
+    }
+
+    /// Returns where text printed from `col` and `row` has to be split to continue on the next controller of a
+    /// display with more than one controller, as the byte index into `text` and the row to continue on. `None` when
+    /// the text ends before the last row driven by the controller of `row`, or there is no next controller.
+    fn controller_split(&self, text: &str, col: u8, row: u8) -> Option<(usize, u8)> {
+        if self.device.controller_count() < 2 {
+            return None;
+        }
+        let rows = self.config.lcd_type.rows();
+        let cols = self.config.lcd_type.cols();
+        let (controller, _) = self.device.controller_row(row);
+        let mut room = cols.checked_sub(col)? as usize;
+        let mut last_row = row;
+        // the text runs on to the next rows of the controller where the address counter does
+        while last_row + 1 < rows
+            && self.device.controller_row(last_row + 1).0 == controller
+            && self.advance(cols - 1, last_row, 1) == Some((0, last_row + 1))
+        {
+            room += cols as usize;
+            last_row += 1;
+        }
+        let next_row = last_row + 1;
+        if next_row >= rows || self.device.controller_row(next_row).0 == controller {
+            return None;
+        }
+        text.char_indices()
+            .nth(room)
+            .map(|(end, _)| (end, next_row))
+    }
+
+    /// Writes a string to the device. With a character ROM set, the text has been encoded to characters whose values
+    /// are ROM codes, which are written as bytes rather than as UTF-8.
+    fn write_text(&mut self, text: &str) -> Result<(), CharacterDisplayError<I2C>> {
+        if self.queue.is_some()
+            && (self.character_rom.is_some() || text.is_ascii())
+            && text.chars().count() <= QUEUE_DATA
+        {
+            let mut data = [0u8; QUEUE_DATA];
+            for (byte, c) in data.iter_mut().zip(text.chars()) {
+                *byte = c as u8;
+            }
+            let data = &data[..text.chars().count()];
+            return self
+                .queue_write(QueuedCommand::Data { start: 0, len: 0 }, data)
+                .map(|_| ());
+        }
+        // text that cannot be queued is written after what was queued before it
+        self.flush_queue()?;
+        if self.character_rom.is_none() || text.is_ascii() {
+            return self.device.print(&mut self.config, text);
+        }
+        let mut buffer = [0u8; TRANSLATION_BUFFER_SIZE];
+        let mut len = 0;
+        for c in text.chars() {
+            if len == buffer.len() {
+                self.device.write_data(&mut self.config, &buffer)?;
+                len = 0;
+            }
+            buffer[len] = c as u8;
+            len += 1;
+        }
+        self.device.write_data(&mut self.config, &buffer[..len])
+    }
+
+    /// Prints a string starting at `cursor`, moving the cursor to the start of the next row whenever a row is
+    /// filled.
+    fn print_row_major(
+        &mut self,
+        text: &str,
+        cursor: (u8, u8),
+    ) -> Result<&mut Self, CharacterDisplayError<I2C>> {
+        let (mut col, mut row) = cursor;
+        let cols = self.config.lcd_type.cols();
+        let mut remaining = text;
+        while !remaining.is_empty() {
+            let (line, rest) = match remaining.char_indices().nth((cols - col) as usize) {
+                Some((end, _)) => remaining.split_at(end),
+                None => (remaining, ""),
+            };
+            self.cursor = None;
+            self.instrumented(DisplayOperation::Print, |lcd| lcd.write_text(line))?;
+            self.verify_written(line, col, row)?;
+            col += line.chars().count() as u8;
+            if col < cols {
+                self.cursor = Some((col, row));
+            } else {
+                col = 0;
+                row = (row + 1) % self.config.lcd_type.rows();
+                self.set_cursor(col, row)?;
+            }
+            remaining = rest;
+        }
+        Ok(self)
+    }
+
+    /// Prints at most `max_len` characters of a string to the LCD at the current cursor position of the active
+    /// device, regardless of the length of the string. Useful for rendering untrusted or overlong strings into
+    /// fixed size slots of a layout without overwriting what follows the slot.
+    pub fn print_clipped(
+        &mut self,
+        text: &str,
+        max_len: usize,
+    ) -> Result<&mut Self, CharacterDisplayError<I2C>> {
+        let clipped = match text.char_indices().nth(max_len) {
+            Some((end, _)) => &text[..end],
+            None => text,
+        };
+        if clipped.is_empty() {
+            return Ok(self);
+        }
+        self.print(clipped)
+    }
+
+    /// Prints a string at the current cursor position as a field of `width` characters, aligned within the field and
+    /// padded with spaces, so a status field can be overwritten without clearing it first. Text longer than the
+    /// field is truncated, ending with the character set with `with_ellipsis()`, if any. The field is written in one
+    /// print, and is at most 40 characters wide.
+    pub fn print_padded(
+        &mut self,
+        text: &str,
+        width: u8,
+        align: Align,
+    ) -> Result<&mut Self, CharacterDisplayError<I2C>> {
+        let width = width.min(DDRAM_LINE_LENGTH) as usize;
+        let len = text.chars().count();
+        let (shown, ellipsis) = match self.ellipsis {
+            Some(ellipsis) if len > width && width > 0 => (width - 1, Some(ellipsis)),
+            _ => (len.min(width), None),
+        };
+        let padding = width - shown - ellipsis.map_or(0, |_| 1);
+        let left = match align {
+            Align::Left => 0,
+            Align::Center => padding / 2,
+            Align::Right => padding,
+        };
+        let chars = core::iter::repeat(' ')
+            .take(left)
+            .chain(text.chars().take(shown))
+            .chain(ellipsis)
+            .chain(core::iter::repeat(' ').take(padding - left));
+        // each character of the field is up to 4 bytes of UTF-8
+        let mut field = [0u8; DDRAM_LINE_LENGTH as usize * 4];
+        let len = chars.fold(0, |len, c| len + c.encode_utf8(&mut field[len..]).len());
+        let field = core::str::from_utf8(&field[..len]).map_err(|_| core::fmt::Error)?;
+        self.print(field)
+    }
+
+    /// Prints a string to the LCD starting at the specified column and row. The command to set the cursor
+    /// position is skipped when the cursor is already known to be at that position, such as when updating
+    /// adjacent fields on one row, which saves the I2C traffic of one command per call.
+    pub fn print_at(
+        &mut self,
+        col: u8,
+        row: u8,
+        text: &str,
+    ) -> Result<&mut Self, CharacterDisplayError<I2C>> {
+        // in framebuffer mode, setting the cursor is free and the tracked cursor is not the framebuffer cursor
+        if self.framebuffer.is_some() || self.cursor != Some((col, row)) {
+            self.set_cursor(col, row)?;
+        }
+        self.print(text)
+    }
+
+    /// Prints a string to the LCD starting at the specified column and row, continuing at the start of the next row
+    /// whenever a row is filled, and past the end of the last row at the start of the first row. Unlike `print_at()`,
+    /// characters never run off into DDRAM that is not visible or that belongs to another row, whatever the
+    /// addressing set with `with_addressing()`. Assumes left to right text flow.
+    pub fn print_at_wrapping(
+        &mut self,
+        col: u8,
+        row: u8,
+        text: &str,
+    ) -> Result<&mut Self, CharacterDisplayError<I2C>> {
+        let cols = self.config.lcd_type.cols();
+        let (mut col, mut row) = (col, row);
+        let mut remaining = text;
+        loop {
+            let line_len = cols.saturating_sub(col) as usize;
+            let (line, rest) = match remaining.char_indices().nth(line_len) {
+                Some((end, _)) => remaining.split_at(end),
+                None => (remaining, ""),
+            };
+            self.print_at(col, row, line)?;
+            if rest.is_empty() {
+                return Ok(self);
+            }
+            col = 0;
+            row = (row + 1) % self.config.lcd_type.rows();
+            remaining = rest;
+        }
+    }
+
+    /// Prints a string to the LCD starting at the cell `index` of the display, counting the cells in row-major order
+    /// from 0 at the top left as `set_cursor_linear()` does. The text continues through the following cells as
+    /// `print_at_wrapping()` does, at the start of the next row whenever a row is filled.
+    pub fn print_at_index(
+        &mut self,
+        index: u16,
+        text: &str,
+    ) -> Result<&mut Self, CharacterDisplayError<I2C>> {
+        let (col, row) = self.linear_position(index);
+        if row >= self.config.lcd_type.rows() {
+            return Err(CharacterDisplayError::RowOutOfRange { row });
+        }
+        self.print_at_wrapping(col, row, text)
+    }
+
+    /// Prints a string across the rows of the display starting at the top left, breaking lines between words so
+    /// that no word is split across rows unless it is longer than a row. A `'\n'` starts a new row. Rows are
+    /// addressed through `set_cursor()`, so displays with multiple controllers, such as 40x4 displays with dual
+    /// HD44780 controllers, are filled in order from top to bottom. Text that does not fit on the display is dropped,
+    /// and the rest of each row is left as it is, so call `clear()` first to replace what is on the display.
+    pub fn print_wrapped(&mut self, text: &str) -> Result<&mut Self, CharacterDisplayError<I2C>> {
+        let cols = self.config.lcd_type.cols() as usize;
+        let rows = self.config.lcd_type.rows();
+        let mut row = 0;
+        for paragraph in text.split('\n') {
+            let mut remaining = paragraph.trim_start();
+            loop {
+                if row >= rows {
+                    return Ok(self);
+                }
+                let (line, rest) = split_wrapped_line(remaining, cols);
+                if !line.is_empty() {
+                    self.print_at(0, row, line)?;
+                }
+                row += 1;
+                remaining = rest;
+                if remaining.is_empty() {
+                    break;
+                }
+            }
+        }
+        Ok(self)
+    }
+
+    /// Prints formatted text to the LCD at the current cursor position of the active device. This is what the
+    /// `lcd_write!` and `lcd_print!` macros use, and unlike `write!` it returns a `CharacterDisplayError`.
+    pub fn print_fmt(
+        &mut self,
+        args: core::fmt::Arguments<'_>,
+    ) -> Result<&mut Self, CharacterDisplayError<I2C>> {
+        self.last_error = None;
+        if let Err(e) = core::fmt::Write::write_fmt(self, args) {
+            // a display error is more useful than the formatting error it was turned into
+            return Err(self
+                .last_error
+                .take()
+                .unwrap_or(CharacterDisplayError::FormattingError(e)));
+        }
+        Ok(self)
+    }
+
+    /// Prints a string written with `write!`, handling `'\n'` and `'\r'` as set with `with_line_breaks()`.
+    fn write_formatted(&mut self, text: &str) -> Result<(), CharacterDisplayError<I2C>> {
+        if self.line_breaks == LineBreaks::Print || self.terminal.is_some() {
+            self.print(text)?;
+            return Ok(());
+        }
+        for part in text.split_inclusive(['\n', '\r']) {
+            let (line, line_break) = match part.strip_suffix(['\n', '\r']) {
+                Some(line) => (line, part[line.len()..].chars().next()),
+                None => (part, None),
+            };
+            if !line.is_empty() {
+                self.print(line)?;
+            }
+            if self.line_breaks == LineBreaks::Ignore {
+                continue;
+            }
+            match (line_break, self.cursor_position()) {
+                (Some('\n'), Some((_, row))) => {
+                    self.set_cursor(0, (row + 1) % self.config.lcd_type.rows())?;
+                }
+                (Some('\r'), Some((_, row))) => {
+                    self.set_cursor(0, row)?;
+                }
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the error that caused the most recent `write!` or `writeln!` to the display to fail. The
+    /// `core::fmt::Write` trait can only return `core::fmt::Error`, so the actual `CharacterDisplayError` is kept
+    /// here until the next failed write or until it is taken with `take_last_error()`.
+    pub fn last_error(&self) -> Option<&CharacterDisplayError<I2C>> {
+        self.last_error.as_ref()
+    }
+
+    /// Takes the error that caused the most recent `write!` or `writeln!` to the display to fail, leaving `None`
+    /// in its place.
+    pub fn take_last_error(&mut self) -> Option<CharacterDisplayError<I2C>> {
+        self.last_error.take()
+    }
+
+    /// Show `len` cells of a row starting at `col` in inverse video, or back in normal video, in framebuffer mode.
+    /// Takes effect on the next `flush()`, which shows the inverse glyph preloaded with `with_inverse_glyph()` for
+    /// the characters of the cells. Characters without an inverse glyph are shown as they are. `clear()` turns off
+    /// inverse video for all cells. Returns `CharacterDisplayError::UnsupportedOperation` when framebuffer mode is
+    /// not enabled.
+    pub fn set_inverse(
+        &mut self,
+        col: u8,
+        row: u8,
+        len: u8,
+        on: bool,
+    ) -> Result<&mut Self, CharacterDisplayError<I2C>> {
+        if row >= self.config.lcd_type.rows() {
+            return Err(CharacterDisplayError::RowOutOfRange { row });
+        }
+        if col >= self.config.lcd_type.cols() {
+            return Err(CharacterDisplayError::ColumnOutOfRange { col });
+        }
+        let Some(framebuffer) = self.framebuffer.as_mut() else {
+            return Err(CharacterDisplayError::UnsupportedOperation);
+        };
+        if row as usize >= FRAMEBUFFER_ROWS {
+            return Err(CharacterDisplayError::UnsupportedDisplayType);
+        }
+        framebuffer.set_inverse(col, row, len, on);
+        Ok(self)
+    }
+
+    /// Make the text in `len` cells of a row starting at `col` blink in framebuffer mode, shown for the first half of
+    /// every `period_ms` milliseconds and blanked for the second half. The cells blink as `tick()` is called, and the
+    /// text in them can be changed by printing as usual. Marking a region starting at the same cell again replaces
+    /// it, and a `period_ms` of 0 stops it from blinking. Up to 8 regions can blink at once, and `clear()` stops all
+    /// of them. Returns `CharacterDisplayError::UnsupportedOperation` when framebuffer mode is not enabled, and
+    /// `CharacterDisplayError::BufferTooSmall` when 8 regions are already blinking.
+    pub fn blink_region(
+        &mut self,
+        col: u8,
+        row: u8,
+        len: u8,
+        period_ms: u32,
+    ) -> Result<&mut Self, CharacterDisplayError<I2C>> {
+        if row >= self.config.lcd_type.rows() {
+            return Err(CharacterDisplayError::RowOutOfRange { row });
+        }
+        if col >= self.config.lcd_type.cols() {
+            return Err(CharacterDisplayError::ColumnOutOfRange { col });
+        }
+        let Some(framebuffer) = self.framebuffer.as_mut() else {
+            return Err(CharacterDisplayError::UnsupportedOperation);
+        };
+        if row as usize >= FRAMEBUFFER_ROWS {
+            return Err(CharacterDisplayError::UnsupportedDisplayType);
+        }
+        let region = BlinkRegion {
+            col,
+            row,
+            len,
+            period_ms,
+        };
+        if !framebuffer.set_blink(region) {
+            return Err(CharacterDisplayError::BufferTooSmall);
+        }
+        Ok(self)
+    }
+
+    /// Show or blank the blinking regions as due at the current time of `clock`, and `flush()` the framebuffer. Only
+    /// the cells that changed are written, so it can be called as often as the application likes, as long as it is
+    /// called at least twice per blink period.
+    pub fn tick(
+        &mut self,
+        clock: &impl clock::Clock,
+    ) -> Result<&mut Self, CharacterDisplayError<I2C>> {
+        let Some(framebuffer) = self.framebuffer.as_mut() else {
+            return Err(CharacterDisplayError::UnsupportedOperation);
+        };
+        framebuffer.update_hidden(Some(clock.now_ms()));
+        self.flush()
+    }
+
+    /// Queue a display write in deferred mode, flushing the queue first when it is full. Returns whether the write
+    /// was queued, which it is not when deferred mode is not enabled.
+    fn queue_write(
+        &mut self,
+        command: QueuedCommand,
+        data: &[u8],
+    ) -> Result<bool, CharacterDisplayError<I2C>> {
+        let Some(queue) = self.queue.as_ref() else {
+            return Ok(false);
+        };
+        if !queue.has_room(command, data.len()) {
+            self.flush_queue()?;
+        }
+        if let Some(queue) = self.queue.as_mut() {
+            queue.push(command, data);
+        }
+        Ok(true)
+    }
+
+    /// Write the display writes queued in deferred mode to the display. The queue is emptied even when writing it
+    /// fails, since how much of it reached the display is not known.
+    fn flush_queue(&mut self) -> Result<(), CharacterDisplayError<I2C>> {
+        let Some(queue) = self.queue.take() else {
+            return Ok(());
+        };
+        if queue.is_empty() {
+            self.queue = Some(queue);
+            return Ok(());
+        }
+        let cursor = self.cursor.take();
+        let result = self.instrumented(DisplayOperation::Flush, |lcd| {
+            for command in queue.commands() {
+                match *command {
+                    QueuedCommand::SetCursor(col, row) => {
+                        lcd.device.set_cursor(&mut lcd.config, col, row)?
+                    }
+                    QueuedCommand::Data { start, len } => lcd
+                        .device
+                        .write_data(&mut lcd.config, queue.data(start, len))?,
+                    QueuedCommand::Clear => lcd.device.clear(&mut lcd.config)?,
+                    QueuedCommand::Home => lcd.device.home(&mut lcd.config)?,
+                }
+            }
+            Ok(())
+        });
+        queue.clear();
+        self.queue = Some(queue);
+        result?;
+        self.cursor = cursor;
+        Ok(())
+    }
+
+    /// Write the writes queued in deferred mode to the display. Then, in framebuffer mode, write the characters of
+    /// the framebuffer that changed since the last flush to the display. Runs of changed characters separated by a
+    /// single unchanged character are written together, since that is no more I2C traffic than the command to move
+    /// the cursor past it. Does nothing when neither deferred mode nor framebuffer mode is enabled.
+    pub fn flush(&mut self) -> Result<&mut Self, CharacterDisplayError<I2C>> {
+        self.flush_queue()?;
+        if self.framebuffer.is_none() {
+            return Ok(self);
+        }
+        if !self.cursor_advances {
+            return Err(CharacterDisplayError::UnsupportedOperation);
+        }
+        self.cursor = None;
+        self.instrumented(DisplayOperation::Flush, |lcd| {
+            let Some(framebuffer) = lcd.framebuffer.as_mut() else {
+                return Ok(());
+            };
+            let rows = (lcd.config.lcd_type.rows() as usize).min(FRAMEBUFFER_ROWS);
+            let cols = lcd.config.lcd_type.cols() as usize;
+            for row in 0..rows {
+                let shown = framebuffer.shown[row];
+                let rendered = framebuffer.rendered_row(row, &lcd.inverse_glyphs);
+                for span in diff::diff_rows(&shown[..cols], &rendered[..cols]).merge_gaps(1) {
+                    let data = &rendered[span.range()];
+                    lcd.device
+                        .set_cursor(&mut lcd.config, span.start as u8, row as u8)?;
+                    lcd.device.write_data(&mut lcd.config, data)?;
+                    // only mark what was actually written, so a failed flush is retried by the next one
+                    framebuffer.shown[row][span.range()].copy_from_slice(data);
+                }
+            }
+            Ok(())
+        })?;
+        Ok(self)
+    }
+
+    /// Returns whether the framebuffer has changes or writes are queued in deferred mode that the next `flush()`
+    /// would write to the display. Always false when neither framebuffer mode nor deferred mode is enabled.
+    pub fn has_pending_changes(&self) -> bool {
+        if self.queue.as_ref().is_some_and(|queue| !queue.is_empty()) {
+            return true;
+        }
+        let Some(framebuffer) = self.framebuffer.as_ref() else {
+            return false;
+        };
+        let rows = (self.config.lcd_type.rows() as usize).min(FRAMEBUFFER_ROWS);
+        let cols = self.config.lcd_type.cols() as usize;
+        (0..rows).any(|row| {
+            framebuffer.shown[row][..cols]
+                != framebuffer.rendered_row(row, &self.inverse_glyphs)[..cols]
+        })
+    }
+
+    /// Serialize a batch of cursor moves and prints into `buffer` as a single I2C write, for platforms that hand
+    /// writes to a DMA-driven I2C driver rather than writing through `I2c::write`. Returns the I2C address and the
+    /// bytes to write to it, which must be written before the display is used again. Supported on the AIP31068
+    /// (ST7032 compatible) controllers, which accept commands and data in one write; other displays return
+    /// `CharacterDisplayError::UnsupportedOperation`, and a buffer too small for the batch returns
+    /// `CharacterDisplayError::BufferTooSmall`. Writes queued in deferred mode are flushed first.
+    ///
+    /// ```rust
+    /// use i2c_character_display::BatchCommand;
+    ///
+    /// let mut buffer = [0u8; 64];
+    /// let (address, bytes) = lcd.serialize_batch(
+    ///     &[BatchCommand::SetCursor(0, 1), BatchCommand::Print("21.5 C")],
+    ///     &mut buffer,
+    /// )?;
+    /// i2c_dma.write(address, bytes).await?;
+    /// ```
+    pub fn serialize_batch<'b>(
+        &mut self,
+        commands: &[BatchCommand<'_>],
+        buffer: &'b mut [u8],
+    ) -> Result<(u8, &'b [u8]), CharacterDisplayError<I2C>> {
+        self.flush_queue()?;
+        let len = self
+            .device
+            .serialize_batch(&mut self.config, commands, buffer)?;
+        // the cursor is tracked as if the batch has been written
+        for command in commands {
+            self.cursor = match (*command, self.cursor) {
+                (BatchCommand::SetCursor(col, row), _) => Some((col, row)),
+                (BatchCommand::Print(text), Some((col, row))) if self.cursor_advances => {
+                    self.advance(col, row, text.chars().count())
+                }
+                (BatchCommand::Print(_), _) => None,
+            };
+        }
+        Ok((self.config.address, &buffer[..len]))
+    }
+
+    /// Reads the characters shown on a row of the display into `buffer`, returning the number of characters read,
+    /// which is the number of columns of the display. In framebuffer mode, these are the contents last flushed to the
+    /// display. Otherwise the contents are read back from the display, which requires an adapter that supports reads,
+    /// after which the cursor is put back where it was if its position is known. The characters are the codes of the
+    /// character ROM, which `CharacterRom::decode()` turns back into `char`s.
+    pub fn read_screen_row(
+        &mut self,
+        row: u8,
+        buffer: &mut [u8],
+    ) -> Result<usize, CharacterDisplayError<I2C>> {
+        if row >= self.config.lcd_type.rows() {
+            return Err(CharacterDisplayError::RowOutOfRange { row });
+        }
+        let cols = self.config.lcd_type.cols() as usize;
+        if buffer.len() < cols {
+            return Err(CharacterDisplayError::BufferTooSmall);
+        }
+        if let Some(framebuffer) = self.framebuffer.as_ref() {
+            if row as usize >= FRAMEBUFFER_ROWS {
+                return Err(CharacterDisplayError::UnsupportedDisplayType);
+            }
+            buffer[..cols].copy_from_slice(&framebuffer.shown[row as usize][..cols]);
+            return Ok(cols);
+        }
+        if !DEVICE::supports_reads() {
+            return Err(CharacterDisplayError::ReadNotSupported);
+        }
+        let cursor = self.cursor;
+        self.set_cursor(0, row)?
+            .read_device_data(&mut buffer[..cols])?;
+        if let Some((col, row)) = cursor {
+            self.set_cursor(col, row)?;
+        }
+        Ok(cols)
+    }
+
+    /// Reads the characters shown on the whole display into `screen`, one row of the display per row of `screen`,
+    /// with `read_screen_row()`. `screen` has to have at least as many rows and columns as the display, and the rest
+    /// of it is left as it is. When the contents are read back from the display, the cursor is put back where it was
+    /// once all the rows are read, if its position is known.
+    pub fn read_screen<const COLS: usize, const ROWS: usize>(
+        &mut self,
+        screen: &mut [[u8; COLS]; ROWS],
+    ) -> Result<&mut Self, CharacterDisplayError<I2C>> {
+        let rows = self.config.lcd_type.rows();
+        if ROWS < rows as usize || COLS < self.config.lcd_type.cols() as usize {
+            return Err(CharacterDisplayError::BufferTooSmall);
+        }
+        // the cursor is only put back after the last row, rather than after each row
+        let cursor = match self.framebuffer {
+            Some(_) => None,
+            None => self.cursor.take(),
+        };
+        for (row, line) in screen.iter_mut().take(rows as usize).enumerate() {
+            self.read_screen_row(row as u8, line)?;
+        }
+        if let Some((col, row)) = cursor {
+            self.set_cursor(col, row)?;
+        }
+        Ok(self)
+    }
+
+    /// Returns a 32-bit FNV-1a hash of the characters shown on the display, for supervisory code to detect that the
+    /// display contents have not changed for a long time, such as a stuck user interface. The screen contents are
+    /// obtained with `read_screen_row()`, so this requires either framebuffer mode, where it hashes the contents
+    /// last flushed to the display without any I2C traffic, or an adapter that supports reads.
+    ///
+    /// ```rust
+    /// let hash = lcd.screen_hash()?;
+    /// if hash != last_hash {
+    ///     last_hash = hash;
+    ///     last_change_ms = now_ms;
+    /// } else if now_ms - last_change_ms > STUCK_UI_TIMEOUT_MS {
+    ///     report_stuck_ui();
+    /// }
+    /// ```
+    pub fn screen_hash(&mut self) -> Result<u32, CharacterDisplayError<I2C>> {
+        let mut hash = FNV_OFFSET_BASIS;
+        let mut line = [0_u8; framebuffer::FRAMEBUFFER_COLS];
+        for row in 0..self.config.lcd_type.rows() {
+            let cols = self.read_screen_row(row, &mut line)?;
+            for c in &line[..cols] {
+                hash = (hash ^ *c as u32).wrapping_mul(FNV_PRIME);
+            }
+        }
+        Ok(hash)
+    }
+
+    /// Logs the text shown on each row of the display through `defmt`, so remote debugging sessions can see what the
+    /// display shows. The screen contents are obtained with `read_screen_row()`, so this requires either framebuffer
+    /// mode or an adapter that supports reads. Custom characters and other characters that are not printable ASCII
+    /// are logged as `?`.
+    #[cfg(feature = "defmt")]
+    pub fn log_screen(&mut self) -> Result<&mut Self, CharacterDisplayError<I2C>> {
+        let mut line = [0_u8; framebuffer::FRAMEBUFFER_COLS];
+        for row in 0..self.config.lcd_type.rows() {
+            let cols = self.read_screen_row(row, &mut line)?;
+            for c in line[..cols].iter_mut() {
+                if !c.is_ascii_graphic() && *c != b' ' {
+                    *c = b'?';
+                }
+            }
+            // the line is all ASCII now
+            let text = core::str::from_utf8(&line[..cols]).unwrap_or_default();
+            match self.label {
+                Some(label) => defmt::info!("lcd {} row {}: |{=str}|", label, row, text),
+                None => defmt::info!("lcd row {}: |{=str}|", row, text),
+            }
+        }
+        Ok(self)
+    }
+
+    /// Turn the backlight on or off.
+    /// Note that the AIP31068 controller does not support backlight control.
+    pub fn backlight(&mut self, on: bool) -> Result<&mut Self, CharacterDisplayError<I2C>> {
+        self.instrumented(DisplayOperation::Backlight, |lcd| {
+            lcd.device.backlight(&mut lcd.config, on)
+        })?;
+        self.backlight = Some(on);
+        Ok(self)
+    }
+
+    /// Set the backlight color on displays with an RGB backlight controller, such as the Grove 16x2 RGB LCD. Turning
+    /// the backlight off with `backlight(false)` and back on restores this color. Other displays return
+    /// `CharacterDisplayError::UnsupportedOperation`.
+    pub fn set_backlight_rgb(
+        &mut self,
+        red: u8,
+        green: u8,
+        blue: u8,
+    ) -> Result<&mut Self, CharacterDisplayError<I2C>> {
+        self.instrumented(DisplayOperation::BacklightRgb, |lcd| {
+            lcd.device
+                .set_backlight_rgb(&mut lcd.config, red, green, blue)
+        })?;
+        Ok(self)
+    }
+
+    /// Set the backlight brightness, from 0 (dark) to 255 (full brightness), on displays that can dim the backlight,
+    /// such as the Grove 16x2 RGB LCD, where it scales the color set with `set_backlight_rgb()`. Other displays
+    /// return `CharacterDisplayError::UnsupportedOperation`; on those with an on/off backlight, such as the PCF8574
+    /// adapters, the backlight can be dimmed in software with `backlight::BacklightPwm`.
+    pub fn set_backlight_brightness(
+        &mut self,
+        brightness: u8,
+    ) -> Result<&mut Self, CharacterDisplayError<I2C>> {
+        self.instrumented(DisplayOperation::BacklightBrightness, |lcd| {
+            lcd.device
+                .set_backlight_brightness(&mut lcd.config, brightness)
+        })?;
+        Ok(self)
+    }
+}
+
+impl<I2C, DELAY, DEVICE> Drop for BaseCharacterDisplay<I2C, DELAY, DEVICE>
+where
+    I2C: i2c::I2c,
+    DELAY: DelayNs,
+    DEVICE: driver::DriverTrait<I2C, DELAY>,
+{
+    fn drop(&mut self) {
+        self.apply_drop_behavior();
+    }
+}
+
+/// Splits off the first line of `text` that fits in `cols` columns, breaking at the last whitespace that fits, or
+/// within the word if there is none. Returns the line and the rest of the text without its leading whitespace.
+fn split_wrapped_line(text: &str, cols: usize) -> (&str, &str) {
+    let Some((end, next)) = text.char_indices().nth(cols) else {
+        return (text, "");
+    };
+    if next.is_whitespace() {
+        return (text[..end].trim_end(), text[end..].trim_start());
+    }
+    match text[..end].rfind(char::is_whitespace) {
+        Some(space) => (text[..space].trim_end(), text[space..].trim_start()),
+        None => text.split_at(end),
+    }
+}
+
+/// Implement the `core::fmt::Write` trait, allowing it to be used with the `write!` macro.
+/// This is a convenience method for printing to the display. For multi-device, this will print to the active device as set by
+/// `set_cursor`.
+impl<I2C, DELAY, DEVICE> core::fmt::Write for BaseCharacterDisplay<I2C, DELAY, DEVICE>
+where
+    I2C: i2c::I2c,
+    DELAY: DelayNs,
+    DEVICE: driver::DriverTrait<I2C, DELAY>,
+{
+    fn write_str(&mut self, s: &str) -> Result<(), core::fmt::Error> {
+        if let Err(e) = self.write_formatted(s) {
+            // keep the actual error so it can be retrieved with `last_error()`
+            self.last_error = Some(e);
+            return Err(core::fmt::Error);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "ufmt")]
+/// Implement the `ufmt::uWrite` trait, allowing it to be used with the `uwriteln!` and `uwrite!` macros.
+/// This is a convenience method for printing to the display. For multi-device, this will print to the active device as set by
+/// `set_cursor`.
+impl<I2C, DELAY, DEVICE> ufmt::uWrite for BaseCharacterDisplay<I2C, DELAY, DEVICE>
+where
+    I2C: i2c::I2c,
+    DELAY: DelayNs,
+    DEVICE: driver::DriverTrait<I2C, DELAY>,
+{
+    fn write_str(&mut self, s: &str) -> Result<(), CharacterDisplayError<I2C>> {
+        self.write_formatted(s)
+    }
+
+    type Error = CharacterDisplayError<I2C>;
+}
+
 #[cfg(test)]
 mod lib_tests {
     extern crate std;
@@ -605,235 +3966,2329 @@ mod lib_tests {
     };
 
     #[test]
-    fn test_character_display_pcf8574t_init() {
+    fn test_character_display_pcf8574t_init() {
+        let i2c_address = 0x27_u8;
+        let expected_i2c_transactions = std::vec![
+            // the PCF8574T has no adapter init sequence, so nothing to prepend
+            // the LCD init sequence
+            // write low nibble of 0x03 3 times
+            I2cTransaction::write(i2c_address, std::vec![0b0011_0100]), // low nibble, rw=0, enable=1
+            I2cTransaction::write(i2c_address, std::vec![0b0011_0000]), // low nibble, rw=0, enable=0
+            I2cTransaction::write(i2c_address, std::vec![0b0011_0100]), // low nibble, rw=0, enable=1
+            I2cTransaction::write(i2c_address, std::vec![0b0011_0000]), // low nibble, rw=0, enable=0
+            I2cTransaction::write(i2c_address, std::vec![0b0011_0100]), // low nibble, rw=0, enable=1
+            I2cTransaction::write(i2c_address, std::vec![0b0011_0000]), // low nibble, rw=0, enable=0
+            // write high nibble of 0x02 one time
+            I2cTransaction::write(i2c_address, std::vec![0b0010_0100]), // high nibble, rw=0, enable=1
+            I2cTransaction::write(i2c_address, std::vec![0b0010_0000]), // high nibble, rw=0, enable=0
+            // I2cTransaction::write(i2c_address, std::vec![0b0000_1000]),    // backlight on
+            // LCD_CMD_FUNCTIONSET | LCD_FLAG_4BITMODE | LCD_FLAG_5x8_DOTS | LCD_FLAG_2LINE
+            // = 0x20 | 0x00 | 0x00 | 0x08 = 0x28
+            I2cTransaction::write(i2c_address, std::vec![0b0010_0100]), // high nibble, rw=0, enable=1
+            I2cTransaction::write(i2c_address, std::vec![0b0010_0000]), // high nibble, rw=0, enable=0
+            I2cTransaction::write(i2c_address, std::vec![0b1000_0100]), // low nibble, rw=0, enable=1
+            I2cTransaction::write(i2c_address, std::vec![0b1000_0000]), // low nibble, rw=0, enable=0
+            // LCD_CMD_DISPLAYCONTROL | LCD_FLAG_DISPLAYON | LCD_FLAG_CURSOROFF | LCD_FLAG_BLINKOFF
+            // = 0x08 | 0x04 | 0x00 | 0x00 = 0x0C
+            I2cTransaction::write(i2c_address, std::vec![0b0000_0100]), // high nibble, rw=0, enable=1
+            I2cTransaction::write(i2c_address, std::vec![0b0000_0000]), // high nibble, rw=0, enable=0
+            I2cTransaction::write(i2c_address, std::vec![0b1100_0100]), // low nibble, rw=0, enable=1
+            I2cTransaction::write(i2c_address, std::vec![0b1100_0000]), // low nibble, rw=0, enable=0
+            // LCD_CMD_ENTRYMODESET | LCD_FLAG_ENTRYLEFT | LCD_FLAG_ENTRYSHIFTDECREMENT
+            // = 0x04 | 0x02 | 0x00 = 0x06
+            I2cTransaction::write(i2c_address, std::vec![0b0000_0100]), // high nibble, rw=0, enable=1
+            I2cTransaction::write(i2c_address, std::vec![0b0000_0000]), // high nibble, rw=0, enable=0
+            I2cTransaction::write(i2c_address, std::vec![0b0110_0100]), // low nibble, rw=0, enable=1
+            I2cTransaction::write(i2c_address, std::vec![0b0110_0000]), // low nibble, rw=0, enable=0
+            // LCD_CMD_CLEARDISPLAY
+            // = 0x01
+            I2cTransaction::write(i2c_address, std::vec![0b0000_0100]), // high nibble, rw=0, enable=1
+            I2cTransaction::write(i2c_address, std::vec![0b0000_0000]), // high nibble, rw=0, enable=0
+            I2cTransaction::write(i2c_address, std::vec![0b0001_0100]), // low nibble, rw=0, enable=1
+            I2cTransaction::write(i2c_address, std::vec![0b0001_0000]), // low nibble, rw=0, enable=0
+            // LCD_CMD_RETURNHOME
+            // = 0x02
+            I2cTransaction::write(i2c_address, std::vec![0b0000_0100]), // high nibble, rw=0, enable=1
+            I2cTransaction::write(i2c_address, std::vec![0b0000_0000]), // high nibble, rw=0, enable=0
+            I2cTransaction::write(i2c_address, std::vec![0b0010_0100]), // low nibble, rw=0, enable=1
+            I2cTransaction::write(i2c_address, std::vec![0b0010_0000]), // low nibble, rw=0, enable=0
+            // Set Backlight
+            I2cTransaction::write(i2c_address, std::vec![0b0010_1000]), // backlight on
+        ];
+
+        let i2c = I2cMock::new(&expected_i2c_transactions);
+        let mut lcd = CharacterDisplayPCF8574T::new(i2c, LcdDisplayType::Lcd16x2, NoopDelay::new());
+        let result = lcd.init();
+        assert!(result.is_ok());
+
+        // finish the i2c mock
+        lcd.i2c().done();
+    }
+
+    #[test]
+    #[allow(clippy::unusual_byte_groupings)]
+    fn test_adafruit_lcd_backpack_init() {
+        let i2c_address = 0x20_u8;
+        let expected_i2c_transactions = std::vec![
+            // the Adafruit Backpack need to init the adapter IC first
+            // write 0x00 to the MCP23008 IODIR register to set all pins as outputs
+            I2cTransaction::write(i2c_address, std::vec![0x00, 0x00]),
+            // the LCD init sequence
+            // write low nibble of 0x03 3 times
+            I2cTransaction::write(i2c_address, std::vec![0x09, 0b0_0011_100]), // low nibble, rw=0, enable=1
+            I2cTransaction::write(i2c_address, std::vec![0x09, 0b0_0011_000]), // low nibble, rw=0, enable=0
+            I2cTransaction::write(i2c_address, std::vec![0x09, 0b0_0011_100]), // low nibble, rw=0, enable=1
+            I2cTransaction::write(i2c_address, std::vec![0x09, 0b0_0011_000]), // low nibble, rw=0, enable=0
+            I2cTransaction::write(i2c_address, std::vec![0x09, 0b0_0011_100]), // low nibble, rw=0, enable=1
+            I2cTransaction::write(i2c_address, std::vec![0x09, 0b0_0011_000]), // low nibble, rw=0, enable=0
+            // write high nibble of 0x02 one time
+            I2cTransaction::write(i2c_address, std::vec![0x09, 0b0_0010_100]), // high nibble, rw=0, enable=1
+            I2cTransaction::write(i2c_address, std::vec![0x09, 0b0_0010_000]), // high nibble, rw=0, enable=0
+            // turn on the backlight
+            // I2cTransaction::write(i2c_address, std::vec![0b0000_1000]),    // backlight on
+            // LCD_CMD_FUNCTIONSET | LCD_FLAG_4BITMODE | LCD_FLAG_5x8_DOTS | LCD_FLAG_2LINE
+            // = 0x20 | 0x00 | 0x00 | 0x08 = 0x28
+            I2cTransaction::write(i2c_address, std::vec![0x09, 0b0_0010_100]), // high nibble, rw=0, enable=1
+            I2cTransaction::write(i2c_address, std::vec![0x09, 0b0_0010_000]), // high nibble, rw=0, enable=0
+            I2cTransaction::write(i2c_address, std::vec![0x09, 0b0_1000_100]), // low nibble, rw=0, enable=1
+            I2cTransaction::write(i2c_address, std::vec![0x09, 0b0_1000_000]), // low nibble, rw=0, enable=0
+            // LCD_CMD_DISPLAYCONTROL | LCD_FLAG_DISPLAYON | LCD_FLAG_CURSOROFF | LCD_FLAG_BLINKOFF
+            // = 0x08 | 0x04 | 0x00 | 0x00 = 0x0C
+            I2cTransaction::write(i2c_address, std::vec![0x09, 0b0_0000_100]), // high nibble, rw=0, enable=1
+            I2cTransaction::write(i2c_address, std::vec![0x09, 0b0_0000_000]), // high nibble, rw=0, enable=0
+            I2cTransaction::write(i2c_address, std::vec![0x09, 0b0_1100_100]), // low nibble, rw=0, enable=1
+            I2cTransaction::write(i2c_address, std::vec![0x09, 0b0_1100_000]), // low nibble, rw=0, enable=0
+            // LCD_CMD_ENTRYMODESET | LCD_FLAG_ENTRYLEFT | LCD_FLAG_ENTRYSHIFTDECREMENT
+            // = 0x04 | 0x02 | 0x00 = 0x06
+            I2cTransaction::write(i2c_address, std::vec![0x09, 0b0_0000_100]), // high nibble, rw=0, enable=1
+            I2cTransaction::write(i2c_address, std::vec![0x09, 0b0_0000_000]), // high nibble, rw=0, enable=0
+            I2cTransaction::write(i2c_address, std::vec![0x09, 0b0_0110_100]), // low nibble, rw=0, enable=1
+            I2cTransaction::write(i2c_address, std::vec![0x09, 0b0_0110_000]), // low nibble, rw=0, enable=0
+            // LCD_CMD_CLEARDISPLAY
+            // = 0x01
+            I2cTransaction::write(i2c_address, std::vec![0x09, 0b0_0000_100]), // high nibble, rw=0, enable=1
+            I2cTransaction::write(i2c_address, std::vec![0x09, 0b0_0000_000]), // high nibble, rw=0, enable=0
+            I2cTransaction::write(i2c_address, std::vec![0x09, 0b0_0001_100]), // low nibble, rw=0, enable=1
+            I2cTransaction::write(i2c_address, std::vec![0x09, 0b0_0001_000]), // low nibble, rw=0, enable=0
+            // LCD_CMD_RETURNHOME
+            // = 0x02
+            I2cTransaction::write(i2c_address, std::vec![0x09, 0b0_0000_100]), // high nibble, rw=0, enable=1
+            I2cTransaction::write(i2c_address, std::vec![0x09, 0b0_0000_000]), // high nibble, rw=0, enable=0
+            I2cTransaction::write(i2c_address, std::vec![0x09, 0b0_0010_100]), // low nibble, rw=0, enable=1
+            I2cTransaction::write(i2c_address, std::vec![0x09, 0b0_0010_000]), // low nibble, rw=0, enable=0
+            // Set Backlight
+            I2cTransaction::write(i2c_address, std::vec![0x09, 0b1_0010_000]), // backlight on
+        ];
+
+        let i2c = I2cMock::new(&expected_i2c_transactions);
+        let mut lcd = AdafruitLCDBackpack::new(i2c, LcdDisplayType::Lcd16x2, NoopDelay::new());
+
+        let result = lcd.init();
+        assert!(result.is_ok());
+        assert!(lcd.display_type() == LcdDisplayType::Lcd16x2);
+
+        // finish the i2c mock
+        lcd.i2c().done();
+    }
+
+    #[test]
+    fn test_character_display_dual_hd44780_init() {
+        let i2c_address = 0x27_u8;
+        let expected_i2c_transactions = std::vec![
+            // the PCF8574T has no adapter init sequence, so nothing to prepend
+            // *** Device 0 ***
+            // the LCD init sequence for device 0
+            // write low nibble of 0x03 3 times
+            I2cTransaction::write(i2c_address, std::vec![0b0011_0100]), // low nibble, rw=0, enable=1
+            I2cTransaction::write(i2c_address, std::vec![0b0011_0000]), // low nibble, rw=0, enable=0
+            I2cTransaction::write(i2c_address, std::vec![0b0011_0100]), // low nibble, rw=0, enable=1
+            I2cTransaction::write(i2c_address, std::vec![0b0011_0000]), // low nibble, rw=0, enable=0
+            I2cTransaction::write(i2c_address, std::vec![0b0011_0100]), // low nibble, rw=0, enable=1
+            I2cTransaction::write(i2c_address, std::vec![0b0011_0000]), // low nibble, rw=0, enable=0
+            // write high nibble of 0x02 one time
+            I2cTransaction::write(i2c_address, std::vec![0b0010_0100]), // high nibble, rw=0, enable=1
+            I2cTransaction::write(i2c_address, std::vec![0b0010_0000]), // high nibble, rw=0, enable=0
+            // turn on the backlight
+            // I2cTransaction::write(i2c_address, std::vec![0b0000_1000]),    // backlight on
+            // LCD_CMD_FUNCTIONSET | LCD_FLAG_4BITMODE | LCD_FLAG_5x8_DOTS | LCD_FLAG_2LINE
+            // = 0x20 | 0x00 | 0x00 | 0x08 = 0x28
+            I2cTransaction::write(i2c_address, std::vec![0b0010_0100]), // high nibble, rw=0, enable=1
+            I2cTransaction::write(i2c_address, std::vec![0b0010_0000]), // high nibble, rw=0, enable=0
+            I2cTransaction::write(i2c_address, std::vec![0b1000_0100]), // low nibble, rw=0, enable=1
+            I2cTransaction::write(i2c_address, std::vec![0b1000_0000]), // low nibble, rw=0, enable=0
+            // LCD_CMD_DISPLAYCONTROL | LCD_FLAG_DISPLAYON | LCD_FLAG_CURSOROFF | LCD_FLAG_BLINKOFF
+            // = 0x08 | 0x04 | 0x00 | 0x00 = 0x0C
+            I2cTransaction::write(i2c_address, std::vec![0b0000_0100]), // high nibble, rw=0, enable=1
+            I2cTransaction::write(i2c_address, std::vec![0b0000_0000]), // high nibble, rw=0, enable=0
+            I2cTransaction::write(i2c_address, std::vec![0b1100_0100]), // low nibble, rw=0, enable=1
+            I2cTransaction::write(i2c_address, std::vec![0b1100_0000]), // low nibble, rw=0, enable=0
+            // LCD_CMD_ENTRYMODESET | LCD_FLAG_ENTRYLEFT | LCD_FLAG_ENTRYSHIFTDECREMENT
+            // = 0x04 | 0x02 | 0x00 = 0x06
+            I2cTransaction::write(i2c_address, std::vec![0b0000_0100]), // high nibble, rw=0, enable=1
+            I2cTransaction::write(i2c_address, std::vec![0b0000_0000]), // high nibble, rw=0, enable=0
+            I2cTransaction::write(i2c_address, std::vec![0b0110_0100]), // low nibble, rw=0, enable=1
+            I2cTransaction::write(i2c_address, std::vec![0b0110_0000]), // low nibble, rw=0, enable=0
+            // LCD_CMD_CLEARDISPLAY
+            // = 0x01
+            I2cTransaction::write(i2c_address, std::vec![0b0000_0100]), // high nibble, rw=0, enable=1
+            I2cTransaction::write(i2c_address, std::vec![0b0000_0000]), // high nibble, rw=0, enable=0
+            I2cTransaction::write(i2c_address, std::vec![0b0001_0100]), // low nibble, rw=0, enable=1
+            I2cTransaction::write(i2c_address, std::vec![0b0001_0000]), // low nibble, rw=0, enable=0
+            // LCD_CMD_RETURNHOME
+            // = 0x02
+            I2cTransaction::write(i2c_address, std::vec![0b0000_0100]), // high nibble, rw=0, enable=1
+            I2cTransaction::write(i2c_address, std::vec![0b0000_0000]), // high nibble, rw=0, enable=0
+            I2cTransaction::write(i2c_address, std::vec![0b0010_0100]), // low nibble, rw=0, enable=1
+            I2cTransaction::write(i2c_address, std::vec![0b0010_0000]), // low nibble, rw=0, enable=0
+            // *** Device 1 ***
+            // the LCD init sequence for device 0
+            // write low nibble of 0x03 3 times
+            I2cTransaction::write(i2c_address, std::vec![0b0011_0010]), // low nibble, rw=0, enable=1
+            I2cTransaction::write(i2c_address, std::vec![0b0011_0000]), // low nibble, rw=0, enable=0
+            I2cTransaction::write(i2c_address, std::vec![0b0011_0010]), // low nibble, rw=0, enable=1
+            I2cTransaction::write(i2c_address, std::vec![0b0011_0000]), // low nibble, rw=0, enable=0
+            I2cTransaction::write(i2c_address, std::vec![0b0011_0010]), // low nibble, rw=0, enable=1
+            I2cTransaction::write(i2c_address, std::vec![0b0011_0000]), // low nibble, rw=0, enable=0
+            // write high nibble of 0x02 one time
+            I2cTransaction::write(i2c_address, std::vec![0b0010_0010]), // high nibble, rw=0, enable=1
+            I2cTransaction::write(i2c_address, std::vec![0b0010_0000]), // high nibble, rw=0, enable=0
+            // turn on the backlight
+            // I2cTransaction::write(i2c_address, std::vec![0b0000_1000]),    // backlight on
+            // LCD_CMD_FUNCTIONSET | LCD_FLAG_4BITMODE | LCD_FLAG_5x8_DOTS | LCD_FLAG_2LINE
+            // = 0x20 | 0x00 | 0x00 | 0x08 = 0x28
+            I2cTransaction::write(i2c_address, std::vec![0b0010_0010]), // high nibble, rw=0, enable=1
+            I2cTransaction::write(i2c_address, std::vec![0b0010_0000]), // high nibble, rw=0, enable=0
+            I2cTransaction::write(i2c_address, std::vec![0b1000_0010]), // low nibble, rw=0, enable=1
+            I2cTransaction::write(i2c_address, std::vec![0b1000_0000]), // low nibble, rw=0, enable=0
+            // LCD_CMD_DISPLAYCONTROL | LCD_FLAG_DISPLAYON | LCD_FLAG_CURSOROFF | LCD_FLAG_BLINKOFF
+            // = 0x08 | 0x04 | 0x00 | 0x00 = 0x0C
+            I2cTransaction::write(i2c_address, std::vec![0b0000_0010]), // high nibble, rw=0, enable=1
+            I2cTransaction::write(i2c_address, std::vec![0b0000_0000]), // high nibble, rw=0, enable=0
+            I2cTransaction::write(i2c_address, std::vec![0b1100_0010]), // low nibble, rw=0, enable=1
+            I2cTransaction::write(i2c_address, std::vec![0b1100_0000]), // low nibble, rw=0, enable=0
+            // LCD_CMD_ENTRYMODESET | LCD_FLAG_ENTRYLEFT | LCD_FLAG_ENTRYSHIFTDECREMENT
+            // = 0x04 | 0x02 | 0x00 = 0x06
+            I2cTransaction::write(i2c_address, std::vec![0b0000_0010]), // high nibble, rw=0, enable=1
+            I2cTransaction::write(i2c_address, std::vec![0b0000_0000]), // high nibble, rw=0, enable=0
+            I2cTransaction::write(i2c_address, std::vec![0b0110_0010]), // low nibble, rw=0, enable=1
+            I2cTransaction::write(i2c_address, std::vec![0b0110_0000]), // low nibble, rw=0, enable=0
+            // LCD_CMD_CLEARDISPLAY
+            // = 0x01
+            I2cTransaction::write(i2c_address, std::vec![0b0000_0010]), // high nibble, rw=0, enable=1
+            I2cTransaction::write(i2c_address, std::vec![0b0000_0000]), // high nibble, rw=0, enable=0
+            I2cTransaction::write(i2c_address, std::vec![0b0001_0010]), // low nibble, rw=0, enable=1
+            I2cTransaction::write(i2c_address, std::vec![0b0001_0000]), // low nibble, rw=0, enable=0
+            // LCD_CMD_RETURNHOME
+            // = 0x02
+            I2cTransaction::write(i2c_address, std::vec![0b0000_0010]), // high nibble, rw=0, enable=1
+            I2cTransaction::write(i2c_address, std::vec![0b0000_0000]), // high nibble, rw=0, enable=0
+            I2cTransaction::write(i2c_address, std::vec![0b0010_0010]), // low nibble, rw=0, enable=1
+            I2cTransaction::write(i2c_address, std::vec![0b0010_0000]), // low nibble, rw=0, enable=0
+            // Set Backlight
+            I2cTransaction::write(i2c_address, std::vec![0b0010_1000]), // backlight on
+        ];
+
+        let i2c = I2cMock::new(&expected_i2c_transactions);
+        let mut lcd =
+            CharacterDisplayDualHD44780::new(i2c, LcdDisplayType::Lcd40x4, NoopDelay::new());
+        let result = lcd.init();
+        assert!(result.is_ok());
+        assert!(lcd.display_type() == LcdDisplayType::Lcd40x4);
+
+        // finish the i2c mock
+        lcd.i2c().done();
+    }
+
+    #[test]
+    fn test_print_at_skips_redundant_set_cursor() {
+        let i2c_address = 0x3e_u8;
+        let expected_i2c_transactions = std::vec![
+            // print_at(0, 0, "ab") needs to set the cursor
+            I2cTransaction::write(i2c_address, std::vec![0b0000_0000, 0x80]),
+            I2cTransaction::write(i2c_address, std::vec![0b0100_0000, b'a', b'b']),
+            // print_at(2, 0, "cd") continues where the cursor already is
+            I2cTransaction::write(i2c_address, std::vec![0b0100_0000, b'c', b'd']),
+            // print_at(0, 1, "e") moves to a new row
+            I2cTransaction::write(i2c_address, std::vec![0b0000_0000, 0x80 | 0x40]),
+            I2cTransaction::write(i2c_address, std::vec![0b0100_0000, b'e']),
+            // create_char moves the address counter into CGRAM
+            I2cTransaction::write(i2c_address, std::vec![0b0000_0000, 0x40]),
+            I2cTransaction::write(i2c_address, std::vec![0b0100_0000, 0, 0, 0, 0, 0, 0, 0, 0]),
+            // so print_at(1, 1, "f") needs to set the cursor again
+            I2cTransaction::write(i2c_address, std::vec![0b0000_0000, 0x80 | 0x41]),
+            I2cTransaction::write(i2c_address, std::vec![0b0100_0000, b'f']),
+        ];
+
+        let i2c = I2cMock::new(&expected_i2c_transactions);
+        let mut lcd = CharacterDisplayAIP31068::new(i2c, LcdDisplayType::Lcd16x2, NoopDelay::new());
+        assert!(lcd.print_at(0, 0, "ab").is_ok());
+        assert!(lcd.print_at(2, 0, "cd").is_ok());
+        assert!(lcd.print_at(0, 1, "e").is_ok());
+        assert!(lcd.create_char(0, [0; 8]).is_ok());
+        assert!(lcd.print_at(1, 1, "f").is_ok());
+
+        lcd.i2c().done();
+    }
+
+    #[test]
+    fn test_create_char_for_controller() {
+        let i2c_address = 0x27_u8;
+        // the dual controller pinout has RS on P0 and the second controller's enable pin on P1
+        let second_controller_gpio = |rs: bool| {
+            move |nibble: u8, enable: bool| std::vec![nibble << 4 | (enable as u8) << 1 | rs as u8]
+        };
+        // set CGRAM address for location 1 = 0x40 | (1 << 3) = 0x48 and write the character map rows as data, on
+        // the second controller only, all packed into one I2C write
+        let expected_i2c_transactions = std::vec![testing::hd44780_packed_transaction(
+            i2c_address,
+            0x48,
+            &[0; 8],
+            second_controller_gpio(false),
+            second_controller_gpio(true),
+        )];
+
+        let i2c = I2cMock::new(&expected_i2c_transactions);
+        let mut lcd =
+            CharacterDisplayDualHD44780::new(i2c, LcdDisplayType::Lcd40x4, NoopDelay::new());
+        assert_eq!(lcd.controller_for_row(1).ok(), Some(0));
+        assert_eq!(lcd.controller_for_row(2).ok(), Some(1));
+        assert!(matches!(
+            lcd.controller_for_row(4),
+            Err(CharacterDisplayError::RowOutOfRange { .. })
+        ));
+        assert!(lcd.create_char_for_controller(1, 1, [0; 8]).is_ok());
+        assert!(matches!(
+            lcd.create_char_for_controller(2, 1, [0; 8]),
+            Err(CharacterDisplayError::BadDeviceId)
+        ));
+
+        lcd.i2c().done();
+    }
+
+    #[test]
+    fn test_with_display_hidden() {
+        let i2c_address = 0x3e_u8;
+        let expected_i2c_transactions = std::vec![
+            // display off
+            I2cTransaction::write(i2c_address, std::vec![0b0000_0000, 0x08]),
+            I2cTransaction::write(i2c_address, std::vec![0b0100_0000, b'h', b'i']),
+            // display on
+            I2cTransaction::write(i2c_address, std::vec![0b0000_0000, 0x08 | 0x04]),
+            // the display is turned back on when the update fails
+            I2cTransaction::write(i2c_address, std::vec![0b0000_0000, 0x08]),
+            I2cTransaction::write(i2c_address, std::vec![0b0000_0000, 0x08 | 0x04]),
+            // the error from the update is returned even when turning the display back on fails
+            I2cTransaction::write(i2c_address, std::vec![0b0000_0000, 0x08]),
+            I2cTransaction::write(i2c_address, std::vec![0b0000_0000, 0x08 | 0x04])
+                .with_error(embedded_hal::i2c::ErrorKind::Other),
+        ];
+
+        let i2c = I2cMock::new(&expected_i2c_transactions);
+        let mut lcd = CharacterDisplayAIP31068::new(i2c, LcdDisplayType::Lcd16x2, NoopDelay::new());
+        assert!(lcd
+            .with_display_hidden(|lcd| {
+                lcd.print("hi")?;
+                Ok(())
+            })
+            .is_ok());
+        assert!(matches!(
+            lcd.with_display_hidden(|lcd| {
+                lcd.set_cursor(0, 5)?;
+                Ok(())
+            }),
+            Err(CharacterDisplayError::RowOutOfRange { .. })
+        ));
+        assert!(matches!(
+            lcd.with_display_hidden(|lcd| {
+                lcd.set_cursor(0, 5)?;
+                Ok(())
+            }),
+            Err(CharacterDisplayError::RowOutOfRange { .. })
+        ));
+
+        lcd.i2c().done();
+    }
+
+    #[test]
+    fn test_write_keeps_last_error() {
+        let i2c_address = 0x3e_u8;
+        let expected_i2c_transactions = std::vec![
+            I2cTransaction::write(i2c_address, std::vec![0b0100_0000, b'4', b'2'])
+                .with_error(embedded_hal::i2c::ErrorKind::Other),
+            I2cTransaction::write(i2c_address, std::vec![0b0100_0000, b'4', b'2'])
+                .with_error(embedded_hal::i2c::ErrorKind::Other),
+        ];
+
+        let i2c = I2cMock::new(&expected_i2c_transactions);
+        let mut lcd = CharacterDisplayAIP31068::new(i2c, LcdDisplayType::Lcd16x2, NoopDelay::new());
+        assert!(lcd.last_error().is_none());
+        assert!(core::fmt::Write::write_fmt(&mut lcd, format_args!("{}", 42)).is_err());
+        assert!(matches!(
+            lcd.last_error(),
+            Some(CharacterDisplayError::I2cError(
+                embedded_hal::i2c::ErrorKind::Other
+            ))
+        ));
+        assert!(lcd.take_last_error().is_some());
+        assert!(lcd.last_error().is_none());
+        // print_fmt returns the display error rather than a formatting error
+        assert!(matches!(
+            lcd.print_fmt(format_args!("{}", 42)),
+            Err(CharacterDisplayError::I2cError(
+                embedded_hal::i2c::ErrorKind::Other
+            ))
+        ));
+
+        lcd.i2c().done();
+    }
+
+    #[test]
+    fn test_set_contrast_preset() {
+        let i2c_address = 0x27_u8;
+        let expected_i2c_transactions = std::vec![
+            I2cTransaction::write(i2c_address, std::vec![0b0000_0010]), // contrast pin high
+            I2cTransaction::write(i2c_address, std::vec![0b0000_0000]), // contrast pin low
+        ];
+
+        let i2c = I2cMock::new(&expected_i2c_transactions);
+        let mut lcd =
+            CharacterDisplayPCF8574TContrast::new(i2c, LcdDisplayType::Lcd16x2, NoopDelay::new());
+        assert!(lcd.set_contrast_preset(ContrastPreset::Secondary).is_ok());
+        assert!(lcd.set_contrast_preset(ContrastPreset::Primary).is_ok());
+        lcd.i2c().done();
+
+        let i2c = I2cMock::new(&[]);
+        let mut lcd = CharacterDisplayPCF8574T::new(i2c, LcdDisplayType::Lcd16x2, NoopDelay::new());
+        assert!(matches!(
+            lcd.set_contrast_preset(ContrastPreset::Secondary),
+            Err(CharacterDisplayError::UnsupportedOperation)
+        ));
+        lcd.i2c().done();
+    }
+
+    #[test]
+    fn test_pressed_buttons() {
+        let i2c_address = 0x27_u8;
+        let expected_i2c_transactions = std::vec![
+            // the button pin is held high along with the backlight
+            I2cTransaction::write(i2c_address, std::vec![0b0000_1010]),
+            I2cTransaction::read(i2c_address, std::vec![0b0000_1010]),
+            I2cTransaction::read(i2c_address, std::vec![0b0000_1000]),
+        ];
+
+        let i2c = I2cMock::new(&expected_i2c_transactions);
+        let mut lcd =
+            CharacterDisplayPCF8574TButtons::new(i2c, LcdDisplayType::Lcd16x2, NoopDelay::new());
+        assert_eq!(lcd.input_pins(), 0b0000_0010);
+        assert!(lcd.backlight(true).is_ok());
+        assert_eq!(lcd.pressed_buttons().ok(), Some(0));
+        assert_eq!(lcd.read_input_pins().ok(), Some(0));
+        lcd.i2c().done();
+
+        let i2c = I2cMock::new(&[]);
+        let mut lcd = CharacterDisplayPCF8574T::new(i2c, LcdDisplayType::Lcd16x2, NoopDelay::new());
+        assert_eq!(lcd.input_pins(), 0);
+        assert!(matches!(
+            lcd.pressed_buttons(),
+            Err(CharacterDisplayError::UnsupportedOperation)
+        ));
+        lcd.i2c().done();
+    }
+
+    #[test]
+    fn test_preloaded_chars() {
+        let i2c_address = 0x3e_u8;
+        let expected_i2c_transactions = std::vec![
+            // init
+            I2cTransaction::write(i2c_address, std::vec![0b0000_0000, 0x20 | 0x08]),
+            I2cTransaction::write(i2c_address, std::vec![0b0000_0000, 0x08 | 0x04]),
+            I2cTransaction::write(i2c_address, std::vec![0b0000_0000, 0x01]),
+            I2cTransaction::write(i2c_address, std::vec![0b0000_0000, 0x04 | 0x02]),
+            // upload the preloaded characters
+            I2cTransaction::write(i2c_address, std::vec![0b0000_0000, 0x40 | (1 << 3)]),
+            I2cTransaction::write(i2c_address, std::vec![0b0100_0000, 1, 1, 1, 1, 1, 1, 1, 1]),
+            I2cTransaction::write(i2c_address, std::vec![0b0000_0000, 0x40 | (5 << 3)]),
+            I2cTransaction::write(i2c_address, std::vec![0b0100_0000, 5, 5, 5, 5, 5, 5, 5, 5]),
+            // back to the home position
+            I2cTransaction::write(i2c_address, std::vec![0b0000_0000, 0x80]),
+            I2cTransaction::write(i2c_address, std::vec![0b0100_0000, b'a', b'b']),
+            // soft reset
+            I2cTransaction::write(i2c_address, std::vec![0b0000_0000, 0x20 | 0x08]),
+            I2cTransaction::write(i2c_address, std::vec![0b0000_0000, 0x08 | 0x04]),
+            I2cTransaction::write(i2c_address, std::vec![0b0000_0000, 0x04 | 0x02]),
+            // upload the preloaded characters again
+            I2cTransaction::write(i2c_address, std::vec![0b0000_0000, 0x40 | (1 << 3)]),
+            I2cTransaction::write(i2c_address, std::vec![0b0100_0000, 1, 1, 1, 1, 1, 1, 1, 1]),
+            I2cTransaction::write(i2c_address, std::vec![0b0000_0000, 0x40 | (5 << 3)]),
+            I2cTransaction::write(i2c_address, std::vec![0b0100_0000, 5, 5, 5, 5, 5, 5, 5, 5]),
+            // the cursor is put back after the printed text
+            I2cTransaction::write(i2c_address, std::vec![0b0000_0000, 0x80 | 0x02]),
+        ];
+
+        let i2c = I2cMock::new(&expected_i2c_transactions);
+        let mut lcd = CharacterDisplayAIP31068::new(i2c, LcdDisplayType::Lcd16x2, NoopDelay::new())
+            .with_preloaded_char(1, [1; 8])
+            .with_preloaded_char(5, [5; 8]);
+        assert!(lcd.init().is_ok());
+        assert!(lcd.print("ab").is_ok());
+        assert!(lcd.soft_reset().is_ok());
+
+        lcd.i2c().done();
+    }
+
+    #[test]
+    fn test_instrumentation() {
+        use core::sync::atomic::{AtomicU64, Ordering};
+        use instrumentation::{DisplayOperation, Instrumentation};
+        use std::sync::Mutex;
+
+        #[derive(Default)]
+        struct Recorder {
+            clock: AtomicU64,
+            events: Mutex<std::vec::Vec<(DisplayOperation, u64, u64)>>,
+        }
+
+        impl Instrumentation for Recorder {
+            fn now_us(&self) -> u64 {
+                self.clock.fetch_add(10, Ordering::Relaxed) + 10
+            }
+
+            fn operation_finished(
+                &self,
+                operation: DisplayOperation,
+                started_us: u64,
+                finished_us: u64,
+            ) {
+                self.events
+                    .lock()
+                    .unwrap()
+                    .push((operation, started_us, finished_us));
+            }
+        }
+
+        let recorder: &'static Recorder = std::boxed::Box::leak(std::boxed::Box::default());
+        let i2c_address = 0x3e_u8;
+        let expected_i2c_transactions = std::vec![
+            I2cTransaction::write(i2c_address, std::vec![0b0000_0000, 0x80 | 0x41]),
+            I2cTransaction::write(i2c_address, std::vec![0b0100_0000, b'h', b'i']),
+        ];
+
+        let i2c = I2cMock::new(&expected_i2c_transactions);
+        let mut lcd = CharacterDisplayAIP31068::new(i2c, LcdDisplayType::Lcd16x2, NoopDelay::new())
+            .with_instrumentation(recorder);
+        // the display can still be moved to another task with instrumentation attached
+        fn assert_send<T: Send>(_: &T) {}
+        assert_send(&lcd);
+        assert!(lcd.set_cursor(1, 1).is_ok());
+        assert!(lcd.print("hi").is_ok());
+        // failed operations are reported too
+        assert!(lcd.set_cursor(1, 5).is_err());
+        assert_eq!(
+            *recorder.events.lock().unwrap(),
+            [
+                (DisplayOperation::SetCursor, 10, 20),
+                (DisplayOperation::Print, 30, 40),
+                (DisplayOperation::SetCursor, 50, 60),
+            ]
+        );
+
+        lcd.i2c().done();
+    }
+
+    #[test]
+    fn test_drop_behavior() {
+        let i2c_address = 0x27_u8;
+        let expected_i2c_transactions = std::vec![
+            // LCD_CMD_DISPLAYCONTROL | LCD_FLAG_DISPLAYOFF = 0x08
+            I2cTransaction::write(i2c_address, std::vec![0b0000_0100]), // high nibble, rw=0, enable=1
+            I2cTransaction::write(i2c_address, std::vec![0b0000_0000]), // high nibble, rw=0, enable=0
+            I2cTransaction::write(i2c_address, std::vec![0b1000_0100]), // low nibble, rw=0, enable=1
+            I2cTransaction::write(i2c_address, std::vec![0b1000_0000]), // low nibble, rw=0, enable=0
+            // backlight off
+            I2cTransaction::write(i2c_address, std::vec![0b1000_0000]),
+        ];
+
+        let mut i2c = I2cMock::new(&expected_i2c_transactions);
+        let lcd =
+            CharacterDisplayPCF8574T::new(i2c.clone(), LcdDisplayType::Lcd16x2, NoopDelay::new())
+                .with_drop_behavior(DropBehavior::TurnOff);
+        drop(lcd);
+        i2c.done();
+
+        // the default leaves the display untouched
+        let mut i2c = I2cMock::new(&[]);
+        let lcd =
+            CharacterDisplayPCF8574T::new(i2c.clone(), LcdDisplayType::Lcd16x2, NoopDelay::new());
+        drop(lcd);
+        i2c.done();
+
+        // releasing the I2C peripheral carries out the drop behavior once
+        let i2c = I2cMock::new(&expected_i2c_transactions);
+        let lcd = CharacterDisplayPCF8574T::builder(i2c, LcdDisplayType::Lcd16x2, NoopDelay::new())
+            .with_drop_behavior(DropBehavior::TurnOff)
+            .build();
+        let (mut i2c, _delay) = lcd.release();
+        i2c.done();
+    }
+
+    #[test]
+    fn test_print_clipped() {
+        let i2c_address = 0x3e_u8;
+        let expected_i2c_transactions = std::vec![
+            I2cTransaction::write(i2c_address, std::vec![0b0100_0000, b'h', b'e', b'l']),
+            I2cTransaction::write(i2c_address, std::vec![0b0100_0000, b'h', b'i']),
+        ];
+
+        let i2c = I2cMock::new(&expected_i2c_transactions);
+        let mut lcd = CharacterDisplayAIP31068::new(i2c, LcdDisplayType::Lcd16x2, NoopDelay::new());
+        assert!(lcd.print_clipped("hello", 3).is_ok());
+        assert!(lcd.print_clipped("hi", 3).is_ok());
+        // nothing is written when there is no room
+        assert!(lcd.print_clipped("hello", 0).is_ok());
+
+        lcd.i2c().done();
+    }
+
+    #[test]
+    fn test_set_visible_cursor() {
+        let i2c_address = 0x3e_u8;
+        let expected_i2c_transactions = std::vec![
+            // scroll display left twice
+            I2cTransaction::write(i2c_address, std::vec![0b0000_0000, 0b0001_1000]),
+            I2cTransaction::write(i2c_address, std::vec![0b0000_0000, 0b0001_1000]),
+            // visible column 0 of row 1 is DDRAM address 0x42
+            I2cTransaction::write(i2c_address, std::vec![0b0000_0000, 0b1100_0010]),
+            // scroll display right three times
+            I2cTransaction::write(i2c_address, std::vec![0b0000_0000, 0b0001_1100]),
+            I2cTransaction::write(i2c_address, std::vec![0b0000_0000, 0b0001_1100]),
+            I2cTransaction::write(i2c_address, std::vec![0b0000_0000, 0b0001_1100]),
+            // visible column 0 of row 0 wraps around to DDRAM address 0x27
+            I2cTransaction::write(i2c_address, std::vec![0b0000_0000, 0b1010_0111]),
+            // visible column 1 of row 1 wraps around to DDRAM address 0x40
+            I2cTransaction::write(i2c_address, std::vec![0b0000_0000, 0b1100_0000]),
+            // home
+            I2cTransaction::write(i2c_address, std::vec![0b0000_0000, 0b0000_0010]),
+            // with no scrolling, visible column 2 of row 1 is DDRAM address 0x42
+            I2cTransaction::write(i2c_address, std::vec![0b0000_0000, 0b1100_0010]),
+        ];
+
+        let i2c = I2cMock::new(&expected_i2c_transactions);
+        let mut lcd = CharacterDisplayAIP31068::new(i2c, LcdDisplayType::Lcd16x2, NoopDelay::new());
+        assert!(lcd.scroll_display_left().is_ok());
+        assert!(lcd.scroll_display_left().is_ok());
+        assert_eq!(lcd.scroll_offset(), 2);
+        assert!(lcd.set_visible_cursor(0, 1).is_ok());
+        assert_eq!(lcd.cursor, None);
+        assert!(lcd.scroll_display_right().is_ok());
+        assert!(lcd.scroll_display_right().is_ok());
+        assert!(lcd.scroll_display_right().is_ok());
+        assert_eq!(lcd.scroll_offset(), 39);
+        assert!(lcd.set_visible_cursor(0, 0).is_ok());
+        assert!(lcd.set_visible_cursor(1, 1).is_ok());
+        assert!(matches!(
+            lcd.set_visible_cursor(0, 2),
+            Err(CharacterDisplayError::RowOutOfRange { .. })
+        ));
+        assert!(matches!(
+            lcd.set_visible_cursor(16, 0),
+            Err(CharacterDisplayError::ColumnOutOfRange { .. })
+        ));
+        assert!(lcd.home().is_ok());
+        assert_eq!(lcd.scroll_offset(), 0);
+        assert!(lcd.set_visible_cursor(2, 1).is_ok());
+        assert_eq!(lcd.cursor, Some((2, 1)));
+
+        lcd.i2c().done();
+    }
+
+    #[test]
+    fn test_init_step() {
+        use crate::testing::aip31068_command_transaction;
+
+        let i2c_address = 0x3e_u8;
+        let mut expected_i2c_transactions = std::vec![
+            // a failed step ends the initialization
+            aip31068_command_transaction(i2c_address, 0x28)
+                .with_error(embedded_hal::i2c::ErrorKind::Other),
+        ];
+        for command in [0x28, 0x29, 0x14, 0x78, 0x56, 0x6D, 0x28, 0x0C, 0x01, 0x06] {
+            expected_i2c_transactions.push(aip31068_command_transaction(i2c_address, command));
+        }
+
+        let i2c = I2cMock::new(&expected_i2c_transactions);
+        let mut lcd = CharacterDisplayAIP31068::new(i2c, LcdDisplayType::Lcd16x2, NoopDelay::new())
+            .with_init_profile(InitProfile::Supply3V3);
+        assert_eq!(lcd.init_step().ok(), Some(InitProgress::WaitUs(15_000)));
+        assert!(lcd.init_step().is_err());
+        // the next call starts over, with the power on wait and the wait for the LCD voltage to stabilize
+        assert_eq!(lcd.init_step().ok(), Some(InitProgress::WaitUs(15_000)));
+        assert_eq!(lcd.init_step().ok(), Some(InitProgress::WaitUs(200_000)));
+        assert_eq!(lcd.init_step().ok(), Some(InitProgress::WaitUs(1530)));
+        assert_eq!(lcd.cursor_position(), None);
+        assert_eq!(lcd.init_step().ok(), Some(InitProgress::Done));
+        assert_eq!(lcd.cursor_position(), Some((0, 0)));
+        lcd.i2c().done();
+    }
+
+    #[test]
+    fn test_init_with_address_fallback() {
+        use crate::testing::{
+            hd44780_bytes_transactions, hd44780_nibble_transactions, pcf8574_gpio,
+        };
+        use embedded_hal::i2c::{ErrorKind, NoAcknowledgeSource};
+
+        // the adapter does not acknowledge the default address, so init is retried at 0x3F
+        let mut expected_i2c_transactions =
+            std::vec![I2cTransaction::write(0x27, std::vec![0b0011_0100])
+                .with_error(ErrorKind::NoAcknowledge(NoAcknowledgeSource::Address))];
+        for nibble in [0x03, 0x03, 0x03, 0x02] {
+            expected_i2c_transactions.extend(hd44780_nibble_transactions(
+                0x3F,
+                nibble,
+                pcf8574_gpio(false, false),
+            ));
+        }
+        expected_i2c_transactions.extend(hd44780_bytes_transactions(
+            0x3F,
+            &[0x28, 0x0C, 0x06, 0x01, 0x02],
+            pcf8574_gpio(false, false),
+        ));
+        expected_i2c_transactions.push(I2cTransaction::write(0x3F, std::vec![0b0010_1000]));
+        // other errors are not retried, and the backlight is still on from the first init
+        expected_i2c_transactions
+            .push(I2cTransaction::write(0x3F, std::vec![0b0011_1100]).with_error(ErrorKind::Other));
+
+        let i2c = I2cMock::new(&expected_i2c_transactions);
+        let mut lcd = CharacterDisplayPCF8574T::new(i2c, LcdDisplayType::Lcd16x2, NoopDelay::new());
+        assert_eq!(lcd.init_with_address_fallback().ok(), Some(0x3F));
+        assert_eq!(lcd.i2c_address(), 0x3F);
+        assert!(matches!(
+            lcd.init_with_address_fallback(),
+            Err(CharacterDisplayError::I2cError(ErrorKind::Other))
+        ));
+        assert_eq!(lcd.i2c_address(), 0x3F);
+        assert_eq!(
+            lcd.error_stats(),
+            ErrorStats {
+                i2c_errors: 2,
+                retries: 1,
+                busy_timeouts: 0,
+                last_failed_operation: Some(DisplayOperation::Init),
+            }
+        );
+
+        lcd.i2c().done();
+    }
+
+    #[test]
+    fn test_framebuffer_flush() {
+        use crate::testing::{aip31068_command_transaction, aip31068_data_transaction};
+
+        let i2c_address = 0x3e_u8;
+        let expected_i2c_transactions = std::vec![
+            // first flush writes everything that is not blank
+            aip31068_command_transaction(i2c_address, 0x80),
+            aip31068_data_transaction(i2c_address, b"Hello"),
+            aip31068_command_transaction(i2c_address, 0x80 | 0x40),
+            aip31068_data_transaction(i2c_address, b"World"),
+            // only the changed characters of row 0 are written
+            aip31068_command_transaction(i2c_address, 0x80 | 0x03),
+            aip31068_data_transaction(i2c_address, b"p "),
+            // characters past the end of the row are dropped
+            aip31068_command_transaction(i2c_address, 0x80 | 0x4E),
+            aip31068_data_transaction(i2c_address, b"ab"),
+            // changes separated by one unchanged character are written together
+            aip31068_command_transaction(i2c_address, 0x80),
+            aip31068_data_transaction(i2c_address, b"XeX"),
+        ];
+
+        let i2c = I2cMock::new(&expected_i2c_transactions);
+        let mut lcd = CharacterDisplayAIP31068::new(i2c, LcdDisplayType::Lcd16x2, NoopDelay::new())
+            .with_framebuffer(std::boxed::Box::leak(std::boxed::Box::default()));
+        assert!(lcd.print_at(0, 0, "Hello").is_ok());
+        assert!(lcd.print_at(0, 1, "World").is_ok());
+        assert!(lcd.flush().is_ok());
+
+        assert!(lcd.clear().is_ok());
+        assert!(lcd.print("Help").is_ok());
+        assert!(lcd.print_at(0, 1, "World").is_ok());
+        assert!(lcd.flush().is_ok());
+
+        assert!(lcd.set_cursor(14, 1).is_ok());
+        assert!(lcd.print("abc").is_ok());
+        assert!(lcd.flush().is_ok());
+        // nothing changed
+        assert!(lcd.flush().is_ok());
+
+        assert!(lcd.print_at(0, 0, "XeX").is_ok());
+        assert!(lcd.flush().is_ok());
+        assert!(matches!(
+            lcd.set_cursor(0, 2),
+            Err(CharacterDisplayError::RowOutOfRange { .. })
+        ));
+
+        lcd.i2c().done();
+    }
+
+    #[test]
+    fn test_read_screen_row() {
+        use crate::testing::{aip31068_command_transaction, aip31068_data_transaction};
+
+        let i2c_address = 0x3e_u8;
+        let expected_i2c_transactions = std::vec![
+            aip31068_command_transaction(i2c_address, 0x80 | 0x40),
+            aip31068_data_transaction(i2c_address, b"Hello"),
+        ];
+
+        let i2c = I2cMock::new(&expected_i2c_transactions);
+        let mut lcd = CharacterDisplayAIP31068::new(i2c, LcdDisplayType::Lcd16x2, NoopDelay::new())
+            .with_framebuffer(std::boxed::Box::leak(std::boxed::Box::default()));
+        let mut buffer = [0_u8; 16];
+        assert!(lcd.print_at(0, 1, "Hello").is_ok());
+        // nothing has been flushed to the display yet
+        assert_eq!(lcd.read_screen_row(1, &mut buffer).ok(), Some(16));
+        assert_eq!(&buffer, b"                ");
+        assert!(lcd.flush().is_ok());
+        assert_eq!(lcd.read_screen_row(1, &mut buffer).ok(), Some(16));
+        assert_eq!(&buffer, b"Hello           ");
+        assert!(matches!(
+            lcd.read_screen_row(2, &mut buffer),
+            Err(CharacterDisplayError::RowOutOfRange { .. })
+        ));
+        assert!(matches!(
+            lcd.read_screen_row(0, &mut buffer[..8]),
+            Err(CharacterDisplayError::BufferTooSmall)
+        ));
+        let mut screen = [[0_u8; 20]; 4];
+        assert!(lcd.read_screen(&mut screen).is_ok());
+        assert_eq!(&screen[0][..16], b"                ");
+        assert_eq!(&screen[1][..16], b"Hello           ");
+        assert_eq!(screen[1][16..], [0; 4]);
+        assert!(matches!(
+            lcd.read_screen(&mut [[0_u8; 16]; 1]),
+            Err(CharacterDisplayError::BufferTooSmall)
+        ));
+        lcd.i2c().done();
+
+        // without a framebuffer, the AIP31068 cannot read back the display
+        let i2c = I2cMock::new(&[]);
+        let mut lcd = CharacterDisplayAIP31068::new(i2c, LcdDisplayType::Lcd16x2, NoopDelay::new());
+        assert!(matches!(
+            lcd.read_screen_row(0, &mut buffer),
+            Err(CharacterDisplayError::ReadNotSupported)
+        ));
+        lcd.i2c().done();
+    }
+
+    #[test]
+    fn test_builder_init() {
+        use crate::testing::{
+            hd44780_bytes_transactions, hd44780_nibble_transactions, pcf8574_gpio,
+        };
+
+        let i2c_address = 0x3F_u8;
+        let mut expected_i2c_transactions = std::vec![];
+        for nibble in [0x03, 0x03, 0x03, 0x02] {
+            expected_i2c_transactions.extend(hd44780_nibble_transactions(
+                i2c_address,
+                nibble,
+                pcf8574_gpio(false, false),
+            ));
+        }
+        // one line mode with the 5x10 font
+        expected_i2c_transactions.extend(hd44780_bytes_transactions(
+            i2c_address,
+            &[0x24, 0x0C, 0x06, 0x01, 0x02],
+            pcf8574_gpio(false, false),
+        ));
+        // backlight on, then off
+        expected_i2c_transactions.push(I2cTransaction::write(i2c_address, std::vec![0b0010_1000]));
+        expected_i2c_transactions.push(I2cTransaction::write(i2c_address, std::vec![0b0010_0000]));
+        // right to left entry mode, then autoscroll
+        expected_i2c_transactions.extend(hd44780_bytes_transactions(
+            i2c_address,
+            &[0x04, 0x05],
+            pcf8574_gpio(false, false),
+        ));
+
+        let i2c = I2cMock::new(&expected_i2c_transactions);
+        let lcd = CharacterDisplayPCF8574T::builder(i2c, LcdDisplayType::Lcd20x4, NoopDelay::new())
+            .with_address(i2c_address)
+            .with_display_type(LcdDisplayType::Lcd16x2)
+            .with_font_size(FontSize::Dots5x10)
+            .with_backlight(false)
+            .with_entry_mode(EntryMode {
+                left_to_right: false,
+                autoscroll: true,
+            })
+            .init();
+        let Ok(mut lcd) = lcd else {
+            panic!("builder init failed");
+        };
+        assert_eq!(lcd.i2c_address(), i2c_address);
+        assert_eq!(lcd.display_type(), LcdDisplayType::Lcd16x2);
+        assert!(!lcd.cursor_advances);
+
+        lcd.i2c().done();
+    }
+
+    #[test]
+    fn test_print_nothing() {
+        use crate::testing::aip31068_data_transaction;
+        use core::fmt::Write;
+
+        // only the custom character is written
+        let expected_i2c_transactions = std::vec![aip31068_data_transaction(0x3e, &[0x00])];
+        let i2c = I2cMock::new(&expected_i2c_transactions);
+        let mut lcd = CharacterDisplayAIP31068::new(i2c, LcdDisplayType::Lcd16x2, NoopDelay::new());
+        lcd.cursor = Some((3, 1));
+        assert!(lcd.print("").is_ok());
+        assert!(lcd.print("\r\n\u{1B}\u{7F}").is_ok());
+        assert_eq!(lcd.cursor, Some((3, 1)));
+        assert!(lcd.print("\u{00}").is_ok());
+        assert_eq!(lcd.cursor, Some((4, 1)));
+        lcd.i2c().done();
+
+        let i2c = I2cMock::new(&[]);
+        let mut lcd = CharacterDisplayPCF8574T::new(i2c, LcdDisplayType::Lcd16x2, NoopDelay::new());
+        assert!(lcd.print("").is_ok());
+        assert!(lcd.print("\n").is_ok());
+        let empty = "";
+        assert!(write!(lcd, "{empty}").is_ok());
+        lcd.i2c().done();
+
+        let i2c = I2cMock::new(&[]);
+        let mut lcd = CharacterDisplayUS2066::new(i2c, LcdDisplayType::Lcd16x2, NoopDelay::new());
+        assert!(lcd.print("").is_ok());
+        assert!(lcd.print("\r\n").is_ok());
+        lcd.i2c().done();
+    }
+
+    #[test]
+    fn test_geometry() {
+        let lcd = CharacterDisplayPCF8574T::new(
+            I2cMock::new(&[]),
+            LcdDisplayType::Lcd20x4,
+            NoopDelay::new(),
+        );
+        assert_eq!((lcd.cols(), lcd.rows()), (20, 4));
+        assert_eq!(lcd.controller_count(), 1);
+        assert_eq!(lcd.row_offsets(), [0x00, 0x40, 0x14, 0x54]);
+        let mut i2c = lcd.config.i2c.clone();
+        drop(lcd);
+        i2c.done();
+
+        let mut lcd = CharacterDisplayDualHD44780::new(
+            I2cMock::new(&[]),
+            LcdDisplayType::Lcd40x4,
+            NoopDelay::new(),
+        );
+        assert_eq!((lcd.cols(), lcd.rows()), (40, 4));
+        assert_eq!(lcd.controller_count(), 2);
+        assert_eq!(lcd.row_offsets(), [0x00, 0x40, 0x00, 0x40]);
+        lcd.i2c().done();
+
+        let mut lcd = CharacterDisplayUS2066::new(
+            I2cMock::new(&[]),
+            LcdDisplayType::Lcd20x4,
+            NoopDelay::new(),
+        );
+        assert_eq!(lcd.row_offsets(), [0x00, 0x20, 0x40, 0x60]);
+        lcd.i2c().done();
+    }
+
+    #[test]
+    fn test_print_row_major() {
+        let i2c_address = 0x3e_u8;
+        let expected_i2c_transactions = std::vec![
+            // set_cursor(18, 1)
+            I2cTransaction::write(i2c_address, std::vec![0b0000_0000, 0x80 | 0x52]),
+            // the text continues on row 2 rather than row 3, which follows row 1 in DDRAM
+            I2cTransaction::write(i2c_address, std::vec![0b0100_0000, b'a', b'b']),
+            I2cTransaction::write(i2c_address, std::vec![0b0000_0000, 0x80 | 0x14]),
+            I2cTransaction::write(i2c_address, std::vec![0b0100_0000, b'c']),
+            // set_cursor(19, 3), and the text wraps around to row 0
+            I2cTransaction::write(i2c_address, std::vec![0b0000_0000, 0x80 | 0x67]),
+            I2cTransaction::write(i2c_address, std::vec![0b0100_0000, b'd']),
+            I2cTransaction::write(i2c_address, std::vec![0b0000_0000, 0x80]),
+            I2cTransaction::write(i2c_address, std::vec![0b0100_0000, b'e']),
+            // the cursor is tracked across rows
+            I2cTransaction::write(i2c_address, std::vec![0b0100_0000, b'f']),
+        ];
+
+        let i2c = I2cMock::new(&expected_i2c_transactions);
+        let mut lcd = CharacterDisplayAIP31068::new(i2c, LcdDisplayType::Lcd20x4, NoopDelay::new())
+            .with_addressing(Addressing::RowMajor);
+        assert!(lcd.print_at(18, 1, "abc").is_ok());
+        assert_eq!(lcd.cursor, Some((1, 2)));
+        assert!(lcd.print_at(19, 3, "de").is_ok());
+        assert!(lcd.print_at(1, 0, "f").is_ok());
+
+        lcd.i2c().done();
+    }
+
+    #[test]
+    fn test_print_at_wrapping() {
+        let i2c_address = 0x3e_u8;
+        let expected_i2c_transactions = std::vec![
+            // the text continues on row 1 rather than running off into DDRAM past the visible columns
+            I2cTransaction::write(i2c_address, std::vec![0b0000_0000, 0x80 | 0x0E]),
+            I2cTransaction::write(i2c_address, std::vec![0b0100_0000, b'a', b'b']),
+            I2cTransaction::write(i2c_address, std::vec![0b0000_0000, 0x80 | 0x40]),
+            I2cTransaction::write(i2c_address, std::vec![0b0100_0000, b'c', b'd']),
+            // and wraps around from the last row to the first
+            I2cTransaction::write(i2c_address, std::vec![0b0000_0000, 0x80 | 0x4F]),
+            I2cTransaction::write(i2c_address, std::vec![0b0100_0000, b'e']),
+            I2cTransaction::write(i2c_address, std::vec![0b0000_0000, 0x80]),
+            I2cTransaction::write(i2c_address, std::vec![0b0100_0000, b'f']),
+        ];
+
+        let i2c = I2cMock::new(&expected_i2c_transactions);
+        let mut lcd = CharacterDisplayAIP31068::new(i2c, LcdDisplayType::Lcd16x2, NoopDelay::new());
+        assert!(lcd.print_at_wrapping(14, 0, "abcd").is_ok());
+        assert!(lcd.print_at_wrapping(15, 1, "ef").is_ok());
+        assert!(matches!(
+            lcd.print_at_wrapping(16, 0, "g"),
+            Err(CharacterDisplayError::ColumnOutOfRange { .. })
+        ));
+
+        lcd.i2c().done();
+    }
+
+    #[test]
+    fn test_linear_addressing() {
+        use crate::testing::{aip31068_command_transaction, aip31068_data_transaction};
+
+        let i2c_address = 0x3e_u8;
+        let expected_i2c_transactions = std::vec![
+            // cell 17 is column 1 of row 1
+            aip31068_command_transaction(i2c_address, 0x80 | 0x41),
+            // the text continues on the next row, as the cells are in row-major order
+            aip31068_command_transaction(i2c_address, 0x80 | 0x0E),
+            aip31068_data_transaction(i2c_address, b"ab"),
+            aip31068_command_transaction(i2c_address, 0x80 | 0x40),
+            aip31068_data_transaction(i2c_address, b"cd"),
+        ];
+
+        let i2c = I2cMock::new(&expected_i2c_transactions);
+        let mut lcd = CharacterDisplayAIP31068::new(i2c, LcdDisplayType::Lcd16x2, NoopDelay::new());
+        assert!(lcd.set_cursor_linear(17).is_ok());
+        assert_eq!(lcd.cursor_position(), Some((1, 1)));
+        assert!(lcd.print_at_index(14, "abcd").is_ok());
+        assert!(matches!(
+            lcd.set_cursor_linear(32),
+            Err(CharacterDisplayError::RowOutOfRange { row: 2 })
+        ));
+        assert!(matches!(
+            lcd.print_at_index(u16::MAX, "e"),
+            Err(CharacterDisplayError::RowOutOfRange { row: 255 })
+        ));
+
+        lcd.i2c().done();
+    }
+
+    #[test]
+    fn test_print_wrapped() {
+        use crate::testing::{aip31068_command_transaction, aip31068_data_transaction};
+
+        let i2c_address = 0x3e_u8;
+        let expected_i2c_transactions = std::vec![
+            // lines break between words
+            aip31068_command_transaction(i2c_address, 0x80),
+            aip31068_data_transaction(i2c_address, b"Hello wonderful"),
+            aip31068_command_transaction(i2c_address, 0x80 | 0x40),
+            aip31068_data_transaction(i2c_address, b"world of LCDs"),
+            // a word longer than a row is split, and what does not fit on the display is dropped
+            aip31068_command_transaction(i2c_address, 0x80),
+            aip31068_data_transaction(i2c_address, b"abcdefghijklmnop"),
+            aip31068_command_transaction(i2c_address, 0x80 | 0x40),
+            aip31068_data_transaction(i2c_address, b"qrst"),
+            // a new line starts a new row
+            aip31068_command_transaction(i2c_address, 0x80),
+            aip31068_data_transaction(i2c_address, b"a"),
+            aip31068_command_transaction(i2c_address, 0x80 | 0x40),
+            aip31068_data_transaction(i2c_address, b"b c"),
+        ];
+
+        let i2c = I2cMock::new(&expected_i2c_transactions);
+        let mut lcd = CharacterDisplayAIP31068::new(i2c, LcdDisplayType::Lcd16x2, NoopDelay::new());
+        assert!(lcd.print_wrapped("Hello wonderful world of LCDs").is_ok());
+        assert!(lcd.print_wrapped("abcdefghijklmnopqrst\nxyz").is_ok());
+        assert!(lcd.print_wrapped("a\n  b c").is_ok());
+
+        lcd.i2c().done();
+    }
+
+    #[test]
+    fn test_fade_contrast() {
+        let i2c_address = 0x3c_u8;
+        let contrast_transaction = |contrast: u8| {
+            I2cTransaction::write(
+                i2c_address,
+                std::vec![0x00, 0x2A, 0x79, 0x81, contrast, 0x78, 0x20],
+            )
+        };
+        let expected_i2c_transactions = std::vec![
+            // fade in and out in equal steps
+            contrast_transaction(0x3F),
+            contrast_transaction(0x7F),
+            contrast_transaction(0xBF),
+            contrast_transaction(0xFF),
+            contrast_transaction(0x80),
+            contrast_transaction(0x00),
+            // no steps sets the final contrast
+            contrast_transaction(0x10),
+        ];
+
+        let i2c = I2cMock::new(&expected_i2c_transactions);
+        let mut lcd = CharacterDisplayUS2066::new(i2c, LcdDisplayType::Lcd16x2, NoopDelay::new());
+        assert!(lcd.fade_contrast(0x00, 0xFF, 4, 50).is_ok());
+        assert!(lcd.fade_contrast(0xFF, 0x00, 2, 50).is_ok());
+        assert!(lcd.fade_contrast(0x00, 0x10, 0, 50).is_ok());
+        lcd.i2c().done();
+
+        let mut lcd = CharacterDisplayAIP31068::new(
+            I2cMock::new(&[]),
+            LcdDisplayType::Lcd16x2,
+            NoopDelay::new(),
+        );
+        assert!(matches!(
+            lcd.fade_contrast(0x00, 0xFF, 4, 50),
+            Err(CharacterDisplayError::UnsupportedOperation)
+        ));
+        lcd.i2c().done();
+    }
+
+    #[test]
+    fn test_framebuffer_inverse() {
+        use crate::testing::{aip31068_command_transaction, aip31068_data_transaction};
+
+        let i2c_address = 0x3e_u8;
+        let inverse_a = glyph::inverted(&[0x0E, 0x11, 0x11, 0x1F, 0x11, 0x11, 0x11, 0x00]);
+        let expected_i2c_transactions = std::vec![
+            // the inverse glyph is uploaded by init()
+            aip31068_command_transaction(i2c_address, 0x28),
+            aip31068_command_transaction(i2c_address, 0x0C),
+            aip31068_command_transaction(i2c_address, 0x01),
+            aip31068_command_transaction(i2c_address, 0x06),
+            aip31068_command_transaction(i2c_address, 0x40 | 1 << 3),
+            aip31068_data_transaction(i2c_address, &inverse_a),
+            aip31068_command_transaction(i2c_address, 0x80),
+            // the marked cells with an inverse glyph show the custom character
+            aip31068_command_transaction(i2c_address, 0x80 | 0x40),
+            aip31068_data_transaction(i2c_address, &[b'a', 1, b'b', 1]),
+            // and go back to normal video
+            aip31068_command_transaction(i2c_address, 0x80 | 0x41),
+            aip31068_data_transaction(i2c_address, b"a"),
+        ];
+
+        let i2c = I2cMock::new(&expected_i2c_transactions);
+        let mut lcd = CharacterDisplayAIP31068::new(i2c, LcdDisplayType::Lcd16x2, NoopDelay::new())
+            .with_framebuffer(std::boxed::Box::leak(std::boxed::Box::default()))
+            .with_inverse_glyph('a', 1, inverse_a);
+        assert!(lcd.init().is_ok());
+        assert!(lcd.print_at(0, 1, "aaba").is_ok());
+        assert!(lcd.set_inverse(1, 1, 3, true).is_ok());
+        assert!(lcd.flush().is_ok());
+        assert!(lcd.set_inverse(0, 1, 2, false).is_ok());
+        assert!(lcd.flush().is_ok());
+        assert!(matches!(
+            lcd.set_inverse(0, 2, 1, true),
+            Err(CharacterDisplayError::RowOutOfRange { .. })
+        ));
+        lcd.i2c().done();
+
+        let mut lcd = CharacterDisplayAIP31068::new(
+            I2cMock::new(&[]),
+            LcdDisplayType::Lcd16x2,
+            NoopDelay::new(),
+        );
+        assert!(matches!(
+            lcd.set_inverse(0, 1, 4, true),
+            Err(CharacterDisplayError::UnsupportedOperation)
+        ));
+        lcd.i2c().done();
+    }
+
+    #[test]
+    fn test_progress_bar() {
+        use crate::testing::{aip31068_command_transaction, aip31068_data_transaction};
+
+        let i2c_address = 0x3e_u8;
+        let mut expected_i2c_transactions = std::vec::Vec::new();
+        // the partial block glyphs are created on first use
+        for (location, columns) in [0x10, 0x18, 0x1C, 0x1E, 0x1F].into_iter().enumerate() {
+            let mut charmap = [columns; 8];
+            charmap[7] = 0;
+            expected_i2c_transactions.push(aip31068_command_transaction(
+                i2c_address,
+                0x40 | (location as u8) << 3,
+            ));
+            expected_i2c_transactions.push(aip31068_data_transaction(i2c_address, &charmap));
+        }
+        expected_i2c_transactions.extend([
+            // 45% of 4 cells is 9 pixel columns: a full cell, a cell of 4 columns and two empty cells
+            aip31068_command_transaction(i2c_address, 0x80 | 0x46),
+            aip31068_data_transaction(i2c_address, &[4, 3, b' ', b' ']),
+            aip31068_command_transaction(i2c_address, 0x80 | 0x46),
+            aip31068_data_transaction(i2c_address, &[4, 4, 4, 4]),
+            aip31068_command_transaction(i2c_address, 0x80 | 0x46),
+            aip31068_data_transaction(i2c_address, b"    "),
+        ]);
+
+        let i2c = I2cMock::new(&expected_i2c_transactions);
+        let mut lcd = CharacterDisplayAIP31068::new(i2c, LcdDisplayType::Lcd16x2, NoopDelay::new());
+        assert!(lcd.progress_bar(6, 1, 4, 45).is_ok());
+        assert!(lcd.progress_bar(6, 1, 4, 150).is_ok());
+        assert!(lcd.progress_bar(6, 1, 4, 0).is_ok());
+        assert!(matches!(
+            lcd.progress_bar(13, 1, 4, 50),
+            Err(CharacterDisplayError::ColumnOutOfRange { .. })
+        ));
+        lcd.i2c().done();
+    }
+
+    #[test]
+    fn test_vbar() {
+        use crate::testing::{aip31068_command_transaction, aip31068_data_transaction};
+
+        let i2c_address = 0x3e_u8;
+        let glyphs = |expected_i2c_transactions: &mut std::vec::Vec<_>| {
+            for location in 0..8u8 {
+                let mut charmap = [0; 8];
+                charmap[7 - location as usize..].fill(0x1F);
+                expected_i2c_transactions.push(aip31068_command_transaction(
+                    i2c_address,
+                    0x40 | location << 3,
+                ));
+                expected_i2c_transactions.push(aip31068_data_transaction(i2c_address, &charmap));
+            }
+        };
+        let cell = |address: u8, code: u8| {
+            [
+                aip31068_command_transaction(i2c_address, 0x80 | address),
+                aip31068_data_transaction(i2c_address, &[code]),
+            ]
+        };
+        let mut expected_i2c_transactions = std::vec::Vec::new();
+        // the partial block glyphs are created on first use
+        glyphs(&mut expected_i2c_transactions);
+        // 75% of 2 rows is 12 pixel rows: a full cell at the bottom and a cell of 4 rows above it
+        expected_i2c_transactions.extend(cell(0x43, 7));
+        expected_i2c_transactions.extend(cell(0x03, 3));
+        expected_i2c_transactions.extend(cell(0x44, b' '));
+        // the progress bar glyphs replace them, so they are created again
+        for (location, columns) in [0x10, 0x18, 0x1C, 0x1E, 0x1F].into_iter().enumerate() {
+            let mut charmap = [columns; 8];
+            charmap[7] = 0;
+            expected_i2c_transactions.push(aip31068_command_transaction(
+                i2c_address,
+                0x40 | (location as u8) << 3,
+            ));
+            expected_i2c_transactions.push(aip31068_data_transaction(i2c_address, &charmap));
+        }
+        expected_i2c_transactions.extend(cell(0x00, 4));
+        glyphs(&mut expected_i2c_transactions);
+        expected_i2c_transactions.extend(cell(0x43, 7));
+        expected_i2c_transactions.extend(cell(0x03, 7));
+
+        let i2c = I2cMock::new(&expected_i2c_transactions);
+        let mut lcd = CharacterDisplayAIP31068::new(i2c, LcdDisplayType::Lcd16x2, NoopDelay::new());
+        assert!(lcd.vbar(3, 0..2, 75).is_ok());
+        assert!(lcd.vbar(4, 1..2, 0).is_ok());
+        assert!(lcd.progress_bar(0, 0, 1, 100).is_ok());
+        assert!(lcd.vbar(3, 0..2, 200).is_ok());
+        assert!(matches!(
+            lcd.vbar(16, 0..2, 50),
+            Err(CharacterDisplayError::ColumnOutOfRange { .. })
+        ));
+        assert!(matches!(
+            lcd.vbar(3, 1..3, 50),
+            Err(CharacterDisplayError::RowOutOfRange { .. })
+        ));
+        assert!(matches!(
+            lcd.vbar(3, 1..1, 50),
+            Err(CharacterDisplayError::RowOutOfRange { .. })
+        ));
+        lcd.i2c().done();
+    }
+
+    #[cfg(feature = "advanced")]
+    #[test]
+    fn test_write_nibble_to_controller() {
         let i2c_address = 0x27_u8;
+        let mut expected_i2c_transactions = std::vec::Vec::new();
+        for nibble in [0x03, 0x02] {
+            expected_i2c_transactions.extend(testing::hd44780_nibble_transactions(
+                i2c_address,
+                nibble,
+                testing::pcf8574_gpio(false, false),
+            ));
+        }
+        expected_i2c_transactions.extend(testing::hd44780_bytes_transactions(
+            i2c_address,
+            &[0x28],
+            testing::pcf8574_gpio(false, false),
+        ));
+
+        let i2c = I2cMock::new(&expected_i2c_transactions);
+        let mut lcd = CharacterDisplayPCF8574T::new(i2c, LcdDisplayType::Lcd16x2, NoopDelay::new());
+        assert!(lcd.write_nibble_to_controller(0, false, 0x03).is_ok());
+        assert!(lcd.write_nibble_to_controller(0, false, 0x02).is_ok());
+        assert!(lcd.write_byte_to_controller(0, false, 0x28).is_ok());
+        assert!(matches!(
+            lcd.write_nibble_to_controller(1, false, 0x03),
+            Err(CharacterDisplayError::BadDeviceId)
+        ));
+        lcd.i2c().done();
+
+        let mut lcd = CharacterDisplayAIP31068::new(
+            I2cMock::new(&[]),
+            LcdDisplayType::Lcd16x2,
+            NoopDelay::new(),
+        );
+        assert!(matches!(
+            lcd.write_nibble_to_controller(0, false, 0x03),
+            Err(CharacterDisplayError::UnsupportedOperation)
+        ));
+        lcd.i2c().done();
+    }
+
+    #[test]
+    fn test_glyph_set() {
+        use crate::testing::{aip31068_command_transaction, aip31068_data_transaction};
+
+        let i2c_address = 0x3e_u8;
+        let accented = [('é', [0x02; 8]), ('è', [0x08; 8])];
+        let symbols = [('♥', [0x0A; 8])];
         let expected_i2c_transactions = std::vec![
-            // the PCF8574T has no adapter init sequence, so nothing to prepend
-            // the LCD init sequence
-            // write low nibble of 0x03 3 times
-            I2cTransaction::write(i2c_address, std::vec![0b0011_0100]), // low nibble, rw=0, enable=1
-            I2cTransaction::write(i2c_address, std::vec![0b0011_0000]), // low nibble, rw=0, enable=0
-            I2cTransaction::write(i2c_address, std::vec![0b0011_0100]), // low nibble, rw=0, enable=1
-            I2cTransaction::write(i2c_address, std::vec![0b0011_0000]), // low nibble, rw=0, enable=0
-            I2cTransaction::write(i2c_address, std::vec![0b0011_0100]), // low nibble, rw=0, enable=1
-            I2cTransaction::write(i2c_address, std::vec![0b0011_0000]), // low nibble, rw=0, enable=0
-            // write high nibble of 0x02 one time
-            I2cTransaction::write(i2c_address, std::vec![0b0010_0100]), // high nibble, rw=0, enable=1
-            I2cTransaction::write(i2c_address, std::vec![0b0010_0000]), // high nibble, rw=0, enable=0
-            // I2cTransaction::write(i2c_address, std::vec![0b0000_1000]),    // backlight on
-            // LCD_CMD_FUNCTIONSET | LCD_FLAG_4BITMODE | LCD_FLAG_5x8_DOTS | LCD_FLAG_2LINE
-            // = 0x20 | 0x00 | 0x00 | 0x08 = 0x28
-            I2cTransaction::write(i2c_address, std::vec![0b0010_0100]), // high nibble, rw=0, enable=1
-            I2cTransaction::write(i2c_address, std::vec![0b0010_0000]), // high nibble, rw=0, enable=0
-            I2cTransaction::write(i2c_address, std::vec![0b1000_0100]), // low nibble, rw=0, enable=1
-            I2cTransaction::write(i2c_address, std::vec![0b1000_0000]), // low nibble, rw=0, enable=0
-            // LCD_CMD_DISPLAYCONTROL | LCD_FLAG_DISPLAYON | LCD_FLAG_CURSOROFF | LCD_FLAG_BLINKOFF
-            // = 0x08 | 0x04 | 0x00 | 0x00 = 0x0C
-            I2cTransaction::write(i2c_address, std::vec![0b0000_0100]), // high nibble, rw=0, enable=1
-            I2cTransaction::write(i2c_address, std::vec![0b0000_0000]), // high nibble, rw=0, enable=0
-            I2cTransaction::write(i2c_address, std::vec![0b1100_0100]), // low nibble, rw=0, enable=1
-            I2cTransaction::write(i2c_address, std::vec![0b1100_0000]), // low nibble, rw=0, enable=0
-            // LCD_CMD_ENTRYMODESET | LCD_FLAG_ENTRYLEFT | LCD_FLAG_ENTRYSHIFTDECREMENT
-            // = 0x04 | 0x02 | 0x00 = 0x06
-            I2cTransaction::write(i2c_address, std::vec![0b0000_0100]), // high nibble, rw=0, enable=1
-            I2cTransaction::write(i2c_address, std::vec![0b0000_0000]), // high nibble, rw=0, enable=0
-            I2cTransaction::write(i2c_address, std::vec![0b0110_0100]), // low nibble, rw=0, enable=1
-            I2cTransaction::write(i2c_address, std::vec![0b0110_0000]), // low nibble, rw=0, enable=0
-            // LCD_CMD_CLEARDISPLAY
-            // = 0x01
-            I2cTransaction::write(i2c_address, std::vec![0b0000_0100]), // high nibble, rw=0, enable=1
-            I2cTransaction::write(i2c_address, std::vec![0b0000_0000]), // high nibble, rw=0, enable=0
-            I2cTransaction::write(i2c_address, std::vec![0b0001_0100]), // low nibble, rw=0, enable=1
-            I2cTransaction::write(i2c_address, std::vec![0b0001_0000]), // low nibble, rw=0, enable=0
-            // LCD_CMD_RETURNHOME
-            // = 0x02
-            I2cTransaction::write(i2c_address, std::vec![0b0000_0100]), // high nibble, rw=0, enable=1
-            I2cTransaction::write(i2c_address, std::vec![0b0000_0000]), // high nibble, rw=0, enable=0
-            I2cTransaction::write(i2c_address, std::vec![0b0010_0100]), // low nibble, rw=0, enable=1
-            I2cTransaction::write(i2c_address, std::vec![0b0010_0000]), // low nibble, rw=0, enable=0
-            // Set Backlight
-            I2cTransaction::write(i2c_address, std::vec![0b0010_1000]), // backlight on
+            // the glyphs of the first set are uploaded
+            aip31068_command_transaction(i2c_address, 0x40),
+            aip31068_data_transaction(i2c_address, &[0x02; 8]),
+            aip31068_command_transaction(i2c_address, 0x40 | 1 << 3),
+            aip31068_data_transaction(i2c_address, &[0x08; 8]),
+            // and its characters are printed as the custom characters
+            aip31068_command_transaction(i2c_address, 0x80),
+            aip31068_data_transaction(i2c_address, &[b'C', b'a', b'f', 0, 1]),
+            // switching sets uploads the new glyphs, and the old characters are no longer translated
+            aip31068_command_transaction(i2c_address, 0x40),
+            aip31068_data_transaction(i2c_address, &[0x0A; 8]),
+            aip31068_command_transaction(i2c_address, 0x80 | 0x40),
+            aip31068_data_transaction(i2c_address, &[b'I', b' ', 0, b' ', 0xC3, 0xA9]),
+        ];
+
+        let i2c = I2cMock::new(&expected_i2c_transactions);
+        let mut lcd = CharacterDisplayAIP31068::new(i2c, LcdDisplayType::Lcd16x2, NoopDelay::new());
+        assert!(lcd.set_glyph_set(&accented).is_ok());
+        assert!(lcd.print_at(0, 0, "Caféè").is_ok());
+        assert!(lcd.set_glyph_set(&symbols).is_ok());
+        assert!(lcd.print_at(0, 1, "I ♥ é").is_ok());
+        lcd.i2c().done();
+    }
+
+    #[test]
+    fn test_error_stats() {
+        let i2c_address = 0x3e_u8;
+        let expected_i2c_transactions = std::vec![
+            I2cTransaction::write(i2c_address, std::vec![0b0000_0000, 0x01])
+                .with_error(embedded_hal::i2c::ErrorKind::Other),
+            I2cTransaction::write(i2c_address, std::vec![0b0100_0000, b'4', b'2'])
+                .with_error(embedded_hal::i2c::ErrorKind::Other),
+            I2cTransaction::write(i2c_address, std::vec![0b0000_0000, 0x01]),
+        ];
+
+        let i2c = I2cMock::new(&expected_i2c_transactions);
+        let mut lcd = CharacterDisplayAIP31068::new(i2c, LcdDisplayType::Lcd16x2, NoopDelay::new());
+        assert_eq!(lcd.error_stats(), ErrorStats::default());
+        assert!(lcd.clear().is_err());
+        assert!(lcd.print("42").is_err());
+        assert!(lcd.clear().is_ok());
+        // errors that are not from the display are not counted
+        assert!(lcd.set_cursor(0, 2).is_err());
+        assert_eq!(
+            lcd.error_stats(),
+            ErrorStats {
+                i2c_errors: 2,
+                retries: 0,
+                busy_timeouts: 0,
+                last_failed_operation: Some(DisplayOperation::Print),
+            }
+        );
+        lcd.reset_error_stats();
+        assert_eq!(lcd.error_stats(), ErrorStats::default());
+        lcd.i2c().done();
+    }
+
+    #[test]
+    fn test_i2c_retries() {
+        let i2c_address = 0x27_u8;
+        let address_nack = embedded_hal::i2c::ErrorKind::NoAcknowledge(
+            embedded_hal::i2c::NoAcknowledgeSource::Address,
+        );
+        let expected_i2c_transactions = std::vec![
+            // a write that fails once goes through on its retry
+            I2cTransaction::write(i2c_address, std::vec![0b0000_0000]).with_error(address_nack),
+            I2cTransaction::write(i2c_address, std::vec![0b0000_0000]),
+            // a write that keeps failing fails the operation once the retries are used up
+            I2cTransaction::write(i2c_address, std::vec![0b0000_1000]).with_error(address_nack),
+            I2cTransaction::write(i2c_address, std::vec![0b0000_1000]).with_error(address_nack),
+            I2cTransaction::write(i2c_address, std::vec![0b0000_1000]).with_error(address_nack),
+            // a write whose data was not acknowledged is not tried again, as the adapter may have applied some of it
+            I2cTransaction::write(i2c_address, std::vec![0b0000_0000]).with_error(
+                embedded_hal::i2c::ErrorKind::NoAcknowledge(
+                    embedded_hal::i2c::NoAcknowledgeSource::Data
+                )
+            ),
+        ];
+
+        let i2c = I2cMock::new(&expected_i2c_transactions);
+        let mut lcd = CharacterDisplayPCF8574T::new(i2c, LcdDisplayType::Lcd16x2, NoopDelay::new())
+            .with_i2c_retries(2, 100);
+        assert!(lcd.backlight(false).is_ok());
+        assert_eq!(lcd.error_stats().retries, 1);
+        assert!(lcd.backlight(true).is_err());
+        assert_eq!(lcd.error_stats().retries, 3);
+        assert!(lcd.backlight(false).is_err());
+        assert_eq!(
+            lcd.error_stats(),
+            ErrorStats {
+                i2c_errors: 2,
+                retries: 3,
+                busy_timeouts: 0,
+                last_failed_operation: Some(DisplayOperation::Backlight),
+            }
+        );
+        lcd.i2c().done();
+    }
+
+    #[test]
+    fn test_timings() {
+        use crate::testing::{hd44780_bytes_transactions, pcf8574_gpio};
+
+        let i2c_address = 0x27_u8;
+        // with the enable pulses spaced, each GPIO update is its own write rather than packed with the others
+        let expected_i2c_transactions =
+            hd44780_bytes_transactions(i2c_address, b"hi", pcf8574_gpio(true, false));
+
+        let i2c = I2cMock::new(&expected_i2c_transactions);
+        let lcd = CharacterDisplayPCF8574T::new(i2c, LcdDisplayType::Lcd16x2, NoopDelay::new());
+        assert_eq!(lcd.timings().clear_home_us, 2000);
+        let timings = Timings {
+            enable_pulse_us: 10,
+            ..lcd.timings()
+        };
+        let mut lcd = lcd.with_timings(timings);
+        assert_eq!(lcd.timings(), timings);
+        assert!(lcd.print("hi").is_ok());
+        lcd.i2c().done();
+
+        let mut lcd = CharacterDisplayAIP31068::builder(
+            I2cMock::new(&[]),
+            LcdDisplayType::Lcd16x2,
+            NoopDelay::new(),
+        )
+        .with_timings(timings)
+        .build();
+        assert_eq!(lcd.timings(), timings);
+        lcd.i2c().done();
+
+        let mut lcd = CharacterDisplayUS2066::new(
+            I2cMock::new(&[]),
+            LcdDisplayType::Lcd16x2,
+            NoopDelay::new(),
+        );
+        assert_eq!(lcd.timings().power_on_us, 1000);
+        lcd.i2c().done();
+    }
+
+    #[test]
+    fn test_nibble_delay() {
+        use crate::testing::{hd44780_bytes_transactions, pcf8574_gpio};
+        use embedded_hal_mock::eh1::delay::{CheckedDelay, Transaction as DelayTransaction};
+
+        let i2c_address = 0x27_u8;
+        // each nibble is written on its own and followed by the delay, rather than packed with the others
+        let expected_i2c_transactions =
+            hd44780_bytes_transactions(i2c_address, b"hi", pcf8574_gpio(true, false));
+        let expected_delays: std::vec::Vec<_> =
+            (0..4).map(|_| DelayTransaction::delay_us(5)).collect();
+
+        let i2c = I2cMock::new(&expected_i2c_transactions);
+        let delay = CheckedDelay::new(&expected_delays);
+        let mut lcd = CharacterDisplayPCF8574T::builder(i2c, LcdDisplayType::Lcd16x2, delay)
+            .with_nibble_delay(5)
+            .build();
+        assert!(lcd.print("hi").is_ok());
+        lcd.i2c().done();
+        lcd.config.delay.done();
+    }
+
+    #[test]
+    fn test_i2c_frequency_pacing() {
+        use crate::testing::{hd44780_bytes_transactions, pcf8574_gpio};
+
+        let i2c_address = 0x27_u8;
+        // on a 1 MHz bus, the writes are unpacked so the bytes do not arrive faster than they are executed
+        let expected_i2c_transactions =
+            hd44780_bytes_transactions(i2c_address, b"hi", pcf8574_gpio(true, false));
+
+        let i2c = I2cMock::new(&expected_i2c_transactions);
+        let mut lcd =
+            CharacterDisplayPCF8574T::builder(i2c, LcdDisplayType::Lcd16x2, NoopDelay::new())
+                .with_i2c_frequency(1_000_000)
+                .build();
+        assert_eq!(lcd.i2c_pacing(), I2cPacing::Unpacked);
+        assert!(lcd.print("hi").is_ok());
+        lcd.i2c().done();
+
+        let paced = |frequency_hz| {
+            let mut lcd = CharacterDisplayPCF8574T::new(
+                I2cMock::new(&[]),
+                LcdDisplayType::Lcd16x2,
+                NoopDelay::new(),
+            )
+            .with_i2c_frequency(frequency_hz);
+            lcd.i2c().done();
+            lcd.i2c_pacing()
+        };
+        assert_eq!(paced(400_000), I2cPacing::FullSpeed);
+        assert_eq!(paced(800_000), I2cPacing::Unpacked);
+        // 40 bit times at 3.4 MHz take 11.8 us, leaving 25.2 us of the execution time to wait for
+        assert_eq!(
+            paced(3_400_000),
+            I2cPacing::Delayed {
+                nibble_delay_us: 26
+            }
+        );
+
+        // the writes of adapters that cannot pack them are slow enough on a 1 MHz bus
+        let mut lcd =
+            AdafruitLCDBackpack::new(I2cMock::new(&[]), LcdDisplayType::Lcd16x2, NoopDelay::new())
+                .with_i2c_frequency(1_000_000);
+        assert_eq!(lcd.i2c_pacing(), I2cPacing::FullSpeed);
+        lcd.i2c().done();
+    }
+
+    #[test]
+    fn test_print_character_rom() {
+        use crate::testing::aip31068_data_transaction;
+
+        let i2c_address = 0x3e_u8;
+        let expected_i2c_transactions = std::vec![
+            aip31068_data_transaction(i2c_address, &[b'2', b'1', 0xDF, b'C', b' ', b'?']),
+            aip31068_data_transaction(i2c_address, b"OK"),
+        ];
+
+        let i2c = I2cMock::new(&expected_i2c_transactions);
+        let mut lcd = CharacterDisplayAIP31068::new(i2c, LcdDisplayType::Lcd16x2, NoopDelay::new())
+            .with_character_rom(CharacterRom::A00);
+        assert!(lcd.print("21°C é").is_ok());
+        assert!(lcd.print("OK").is_ok());
+        lcd.i2c().done();
+    }
+
+    #[test]
+    fn test_screen_hash() {
+        use crate::testing::{aip31068_command_transaction, aip31068_data_transaction};
+
+        let i2c_address = 0x3e_u8;
+        let expected_i2c_transactions = std::vec![
+            aip31068_command_transaction(i2c_address, 0x80 | 0x40),
+            aip31068_data_transaction(i2c_address, b"Hi"),
+        ];
+
+        let i2c = I2cMock::new(&expected_i2c_transactions);
+        let mut lcd = CharacterDisplayAIP31068::new(i2c, LcdDisplayType::Lcd16x2, NoopDelay::new())
+            .with_framebuffer(std::boxed::Box::leak(std::boxed::Box::default()));
+        let blank = lcd.screen_hash().ok();
+        // the FNV-1a hash of 32 spaces
+        assert_eq!(blank, Some(0xF813_0445));
+        // the hash only changes once the new contents are flushed to the display
+        assert!(lcd.print_at(0, 1, "Hi").is_ok());
+        assert_eq!(lcd.screen_hash().ok(), blank);
+        assert!(lcd.flush().is_ok());
+        let hash = lcd.screen_hash().ok();
+        assert!(hash.is_some() && hash != blank);
+        assert_eq!(lcd.screen_hash().ok(), hash);
+        lcd.i2c().done();
+
+        let i2c = I2cMock::new(&[]);
+        let mut lcd = CharacterDisplayAIP31068::new(i2c, LcdDisplayType::Lcd16x2, NoopDelay::new());
+        assert!(matches!(
+            lcd.screen_hash(),
+            Err(CharacterDisplayError::ReadNotSupported)
+        ));
+        lcd.i2c().done();
+    }
+
+    #[test]
+    fn test_print_glyph_fallback() {
+        use crate::testing::{aip31068_command_transaction, aip31068_data_transaction};
+
+        let i2c_address = 0x3e_u8;
+        let degree = charset::fallback_glyph('°').unwrap();
+        let e_acute = charset::fallback_glyph('é').unwrap();
+        let expected_i2c_transactions = std::vec![
+            // the cursor position is not known, so no glyphs can be uploaded
+            aip31068_data_transaction(i2c_address, b"?"),
+            aip31068_command_transaction(i2c_address, 0x80 | 0x40),
+            // the glyphs are uploaded from the highest free location down, then the cursor is put back
+            aip31068_command_transaction(i2c_address, 0x40 | 7 << 3),
+            aip31068_data_transaction(i2c_address, &degree),
+            aip31068_command_transaction(i2c_address, 0x40 | 6 << 3),
+            aip31068_data_transaction(i2c_address, &e_acute),
+            aip31068_command_transaction(i2c_address, 0x80 | 0x40),
+            // once the free locations are taken, and for characters without a glyph, `?` is shown
+            aip31068_data_transaction(i2c_address, &[b'2', 7, b' ', 6, b'?', b'?', b'!']),
+            // uploaded glyphs are reused
+            aip31068_data_transaction(i2c_address, &[7]),
+        ];
+
+        let i2c = I2cMock::new(&expected_i2c_transactions);
+        let mut lcd = CharacterDisplayAIP31068::new(i2c, LcdDisplayType::Lcd16x2, NoopDelay::new())
+            .with_glyph_fallback();
+        for location in 0..6 {
+            lcd = lcd.with_preloaded_char(location, [0; 8]);
+        }
+        assert!(lcd.print("°").is_ok());
+        assert!(lcd.set_cursor(0, 1).is_ok());
+        assert!(lcd.print("2° é→ｱ!").is_ok());
+        assert!(lcd.print("°").is_ok());
+        lcd.i2c().done();
+    }
+
+    #[test]
+    fn test_error_display() {
+        use std::string::ToString;
+
+        let error = CharacterDisplayError::<I2cMock>::I2cError(i2c::ErrorKind::NoAcknowledge(
+            i2c::NoAcknowledgeSource::Address,
+        ));
+        assert_eq!(
+            error.to_string(),
+            std::format!(
+                "I2C error: {}",
+                i2c::ErrorKind::NoAcknowledge(i2c::NoAcknowledgeSource::Address)
+            )
+        );
+        let error = CharacterDisplayError::<I2cMock>::ColumnOutOfRange { col: 16 };
+        assert_eq!(error.to_string(), "Column 16 out of range");
+        assert_eq!(error.kind().to_string(), "Column 16 out of range");
+        let error = CharacterDisplayError::<I2cMock>::VerificationFailed { col: 3, row: 1 };
+        assert_eq!(error.to_string(), "Verification failed at column 3, row 1");
+        let msg: &'static str = From::from(&error);
+        assert_eq!(msg, "Verification failed");
+        assert_eq!(
+            CharacterDisplayError::<I2cMock>::Timeout.to_string(),
+            "Timeout"
+        );
+    }
+
+    #[test]
+    fn test_error_context() {
+        use std::string::ToString;
+
+        let i2c_address = 0x27_u8;
+        // setting the cursor on row 2 sends the command to the second controller, whose enable pin is on P1
+        let failed_write = I2cTransaction::write(i2c_address, std::vec![0b1000_0010]);
+        let expected_i2c_transactions = [failed_write.with_error(i2c::ErrorKind::Other)];
+
+        let i2c = I2cMock::new(&expected_i2c_transactions);
+        let mut lcd =
+            CharacterDisplayDualHD44780::new(i2c, LcdDisplayType::Lcd40x4, NoopDelay::new());
+        assert_eq!(lcd.error_context(), None);
+        let Err(error) = lcd.set_cursor(0, 2).map(|_| ()) else {
+            panic!("the I2C write should fail");
+        };
+        assert_eq!(
+            lcd.error_context(),
+            Some(ErrorContext {
+                operation: DisplayOperation::SetCursor,
+                controller: 1,
+            })
+        );
+        assert_eq!(
+            lcd.labeled_error(&error).to_string(),
+            std::format!(
+                "SetCursor on controller 1: I2C error: {}",
+                i2c::ErrorKind::Other
+            )
+        );
+        lcd.i2c().done();
+    }
+
+    #[test]
+    fn test_labeled_error() {
+        use std::string::ToString;
+
+        let i2c = I2cMock::new(&[]);
+        let mut lcd = CharacterDisplayAIP31068::new(i2c, LcdDisplayType::Lcd16x2, NoopDelay::new());
+        let error = CharacterDisplayError::RowOutOfRange { row: 5 };
+        assert_eq!(lcd.label(), None);
+        assert_eq!(lcd.labeled_error(&error).to_string(), "Row 5 out of range");
+        lcd.i2c().done();
+
+        let i2c = I2cMock::new(&[]);
+        let mut lcd = CharacterDisplayAIP31068::new(i2c, LcdDisplayType::Lcd16x2, NoopDelay::new())
+            .with_label("front panel");
+        assert_eq!(lcd.label(), Some(DisplayLabel::Name("front panel")));
+        assert_eq!(
+            lcd.labeled_error(&error).to_string(),
+            "front panel: Row 5 out of range"
+        );
+        lcd.i2c().done();
+
+        let i2c = I2cMock::new(&[]);
+        let mut lcd = CharacterDisplayAIP31068::new(i2c, LcdDisplayType::Lcd16x2, NoopDelay::new())
+            .with_label(3);
+        assert_eq!(
+            lcd.labeled_error(&error).to_string(),
+            "#3: Row 5 out of range"
+        );
+        lcd.i2c().done();
+    }
+
+    #[test]
+    fn test_recover() {
+        use crate::testing::{aip31068_command_transaction, aip31068_data_transaction};
+
+        let i2c_address = 0x3e_u8;
+        let expected_i2c_transactions = std::vec![
+            // init
+            aip31068_command_transaction(i2c_address, 0x20 | 0x08),
+            aip31068_command_transaction(i2c_address, 0x08 | 0x04),
+            aip31068_command_transaction(i2c_address, 0x01),
+            aip31068_command_transaction(i2c_address, 0x04 | 0x02),
+            aip31068_command_transaction(i2c_address, 0x80),
+            aip31068_data_transaction(i2c_address, b"Hi"),
+            // soft reset, then clear
+            aip31068_command_transaction(i2c_address, 0x20 | 0x08),
+            aip31068_command_transaction(i2c_address, 0x08 | 0x04),
+            aip31068_command_transaction(i2c_address, 0x04 | 0x02),
+            aip31068_command_transaction(i2c_address, 0x01),
+            // the next flush redraws the framebuffer
+            aip31068_command_transaction(i2c_address, 0x80),
+            aip31068_data_transaction(i2c_address, b"Hi"),
+        ];
+
+        let i2c = I2cMock::new(&expected_i2c_transactions);
+        let mut lcd = CharacterDisplayAIP31068::new(i2c, LcdDisplayType::Lcd16x2, NoopDelay::new())
+            .with_framebuffer(std::boxed::Box::leak(std::boxed::Box::default()));
+        assert!(lcd.init().is_ok());
+        assert!(lcd.print_at(0, 0, "Hi").is_ok());
+        assert!(lcd.flush().is_ok());
+        assert!(lcd.recover().is_ok());
+        assert!(lcd.has_pending_changes());
+        assert!(lcd.flush().is_ok());
+        lcd.i2c().done();
+    }
+
+    #[test]
+    fn test_display_state() {
+        use crate::testing::{aip31068_command_transaction, aip31068_data_transaction};
+
+        let i2c_address = 0x3e_u8;
+        let init = [
+            aip31068_command_transaction(i2c_address, 0x20 | 0x08),
+            aip31068_command_transaction(i2c_address, 0x08 | 0x04),
+            aip31068_command_transaction(i2c_address, 0x01),
+            aip31068_command_transaction(i2c_address, 0x04 | 0x02),
+        ];
+        let mut expected_i2c_transactions = std::vec::Vec::from(init.clone());
+        expected_i2c_transactions.extend([
+            aip31068_command_transaction(i2c_address, 0x08 | 0x04 | 0x02),
+            aip31068_command_transaction(i2c_address, 0x40 | (2 << 3)),
+            aip31068_data_transaction(i2c_address, &[2; 8]),
+            aip31068_command_transaction(i2c_address, 0x80 | 0x43),
+            aip31068_command_transaction(i2c_address, 0x10 | 0x08),
+        ]);
+
+        let i2c = I2cMock::new(&expected_i2c_transactions);
+        let mut lcd = CharacterDisplayAIP31068::new(i2c, LcdDisplayType::Lcd16x2, NoopDelay::new());
+        assert!(lcd.init().is_ok());
+        assert!(lcd.show_cursor(true).is_ok());
+        assert!(lcd.create_char(2, [2; 8]).is_ok());
+        assert!(lcd.set_cursor(3, 1).is_ok());
+        assert!(lcd.scroll_display_left().is_ok());
+        let state = lcd.display_state();
+        assert_eq!(state.cursor, Some((3, 1)));
+        assert_eq!(state.display_control, 0x04 | 0x02);
+        assert_eq!(state.display_mode, 0x02);
+        assert_eq!(state.backlight, None);
+        assert_eq!(state.scroll_offset, 1);
+        assert_eq!(state.custom_chars[2], Some([2; 8]));
+        lcd.i2c().done();
+
+        // a new display object after the display was powered down
+        let mut expected_i2c_transactions = std::vec::Vec::from(init);
+        expected_i2c_transactions.extend([
+            aip31068_command_transaction(i2c_address, 0x20 | 0x08),
+            aip31068_command_transaction(i2c_address, 0x08 | 0x04 | 0x02),
+            aip31068_command_transaction(i2c_address, 0x04 | 0x02),
+            aip31068_command_transaction(i2c_address, 0x40 | (2 << 3)),
+            aip31068_data_transaction(i2c_address, &[2; 8]),
+            aip31068_command_transaction(i2c_address, 0x10 | 0x08),
+            aip31068_command_transaction(i2c_address, 0x80 | 0x43),
+        ]);
+        let i2c = I2cMock::new(&expected_i2c_transactions);
+        let mut lcd = CharacterDisplayAIP31068::new(i2c, LcdDisplayType::Lcd16x2, NoopDelay::new());
+        assert!(lcd.init().is_ok());
+        assert!(lcd.restore_state(&state).is_ok());
+        assert_eq!(lcd.display_state(), state);
+        lcd.i2c().done();
+    }
+
+    #[test]
+    fn test_cursor_position() {
+        use crate::testing::{aip31068_command_transaction, aip31068_data_transaction};
+
+        let i2c_address = 0x3e_u8;
+        let expected_i2c_transactions = std::vec![
+            aip31068_command_transaction(i2c_address, 0x80 | 0x12),
+            aip31068_data_transaction(i2c_address, b"abcd"),
+            // row 0 continues on row 2, so the cursor is known to be there already
+            aip31068_data_transaction(i2c_address, b"e"),
+        ];
+
+        let i2c = I2cMock::new(&expected_i2c_transactions);
+        let mut lcd = CharacterDisplayAIP31068::new(i2c, LcdDisplayType::Lcd20x4, NoopDelay::new());
+        assert_eq!(lcd.cursor_position(), None);
+        assert!(lcd.print_at(18, 0, "abcd").is_ok());
+        assert_eq!(lcd.cursor_position(), Some((2, 2)));
+        assert!(lcd.print_at(2, 2, "e").is_ok());
+        assert_eq!(lcd.cursor_position(), Some((3, 2)));
+        // row 3 continues on row 0 after wrapping around DDRAM
+        assert_eq!(lcd.advance(19, 3, 1), Some((0, 0)));
+        assert_eq!(lcd.advance(0, 1, 20), Some((0, 3)));
+        lcd.i2c().done();
+
+        // the DDRAM past the end of a 16 column row is not shown
+        let i2c = I2cMock::new(&[]);
+        let mut lcd = CharacterDisplayAIP31068::new(i2c, LcdDisplayType::Lcd16x2, NoopDelay::new());
+        assert_eq!(lcd.advance(15, 0, 1), None);
+        assert_eq!(lcd.advance(15, 0, 25), Some((0, 1)));
+        assert_eq!(lcd.advance(10, 1, 30), Some((0, 0)));
+        lcd.i2c().done();
+
+        // the rows of each controller of a 40x4 display wrap separately
+        let i2c = I2cMock::new(&[]);
+        let mut lcd =
+            CharacterDisplayDualHD44780::new(i2c, LcdDisplayType::Lcd40x4, NoopDelay::new());
+        assert_eq!(lcd.advance(38, 1, 3), Some((1, 0)));
+        assert_eq!(lcd.advance(39, 2, 1), Some((0, 3)));
+        lcd.i2c().done();
+    }
+
+    #[test]
+    fn test_deferred_writes() {
+        use crate::testing::{aip31068_command_transaction, aip31068_data_transaction};
+
+        let i2c_address = 0x3e_u8;
+        let expected_i2c_transactions = std::vec![
+            aip31068_command_transaction(i2c_address, 0x20 | 0x08),
+            aip31068_command_transaction(i2c_address, 0x08 | 0x04),
+            aip31068_command_transaction(i2c_address, 0x01),
+            aip31068_command_transaction(i2c_address, 0x04 | 0x02),
+            // the cursor moves are merged, and so are the prints
+            aip31068_command_transaction(i2c_address, 0x80 | 0x42),
+            aip31068_data_transaction(i2c_address, b"abcd"),
+            // showing the cursor flushes the queue first
+            aip31068_data_transaction(i2c_address, b"e"),
+            aip31068_command_transaction(i2c_address, 0x08 | 0x04 | 0x02),
+            // the clear drops what was queued before it
+            aip31068_command_transaction(i2c_address, 0x01),
+            aip31068_data_transaction(i2c_address, b"f"),
         ];
 
         let i2c = I2cMock::new(&expected_i2c_transactions);
+        let mut lcd = CharacterDisplayAIP31068::new(i2c, LcdDisplayType::Lcd16x2, NoopDelay::new())
+            .with_deferred_writes(std::boxed::Box::leak(std::boxed::Box::default()));
+        assert!(lcd.init().is_ok());
+        assert!(!lcd.has_pending_changes());
+        assert!(lcd.set_cursor(0, 0).is_ok());
+        assert!(lcd.set_cursor(2, 1).is_ok());
+        assert!(lcd.print("ab").is_ok());
+        assert!(lcd.print("cd").is_ok());
+        assert!(matches!(
+            lcd.set_cursor(16, 0),
+            Err(CharacterDisplayError::ColumnOutOfRange { .. })
+        ));
+        assert_eq!(lcd.cursor_position(), Some((6, 1)));
+        assert!(lcd.has_pending_changes());
+        assert!(lcd.flush().is_ok());
+        assert!(!lcd.has_pending_changes());
+
+        assert!(lcd.print("e").is_ok());
+        assert!(lcd.show_cursor(true).is_ok());
+
+        assert!(lcd.print("zz").is_ok());
+        assert!(lcd.clear().is_ok());
+        assert!(lcd.print("f").is_ok());
+        assert_eq!(lcd.cursor_position(), Some((1, 0)));
+        assert!(lcd.flush().is_ok());
+        lcd.i2c().done();
+    }
+
+    #[test]
+    fn test_serialize_batch() {
+        let i2c = I2cMock::new(&[]);
+        let mut lcd = CharacterDisplayAIP31068::new(i2c, LcdDisplayType::Lcd16x2, NoopDelay::new());
+        let batch = [
+            BatchCommand::SetCursor(2, 1),
+            BatchCommand::Print("ab"),
+            BatchCommand::SetCursor(0, 0),
+            BatchCommand::Print("xyz"),
+        ];
+        let mut buffer = [0u8; 16];
+        let (address, bytes) = lcd.serialize_batch(&batch, &mut buffer).unwrap();
+        assert_eq!(address, 0x3e);
+        // every byte has its own control byte, except the last run of data
+        assert_eq!(bytes[..6], [0x80, 0x80 | 0x42, 0xC0, b'a', 0xC0, b'b']);
+        assert_eq!(bytes[6..], [0x80, 0x80, 0x40, b'x', b'y', b'z']);
+        assert_eq!(lcd.cursor_position(), Some((3, 0)));
+        // a batch ending with a cursor move ends with the last control byte
+        let (_, bytes) = lcd
+            .serialize_batch(&[BatchCommand::SetCursor(1, 0)], &mut buffer)
+            .unwrap();
+        assert_eq!(bytes, &[0x00, 0x81]);
+        assert!(matches!(
+            lcd.serialize_batch(&batch, &mut [0u8; 8]),
+            Err(CharacterDisplayError::BufferTooSmall)
+        ));
+        assert!(matches!(
+            lcd.serialize_batch(&[BatchCommand::SetCursor(0, 2)], &mut buffer),
+            Err(CharacterDisplayError::RowOutOfRange { .. })
+        ));
+        lcd.i2c().done();
+
+        let i2c = I2cMock::new(&[]);
         let mut lcd = CharacterDisplayPCF8574T::new(i2c, LcdDisplayType::Lcd16x2, NoopDelay::new());
-        let result = lcd.init();
-        assert!(result.is_ok());
+        assert!(matches!(
+            lcd.serialize_batch(&batch, &mut buffer),
+            Err(CharacterDisplayError::UnsupportedOperation)
+        ));
+        lcd.i2c().done();
+    }
 
-        // finish the i2c mock
+    #[test]
+    fn test_print_across_controllers() {
+        let i2c_address = 0x27_u8;
+        // the dual controller pinout has RS on P0, the second controller's enable pin on P1 and the first
+        // controller's enable pin on P2
+        let gpio = |controller: usize, rs: bool| {
+            move |nibble: u8, enable: bool| {
+                std::vec![nibble << 4 | ((enable as u8) << (2 - controller)) | rs as u8]
+            }
+        };
+        let mut expected_i2c_transactions = std::vec::Vec::new();
+        expected_i2c_transactions.extend(testing::hd44780_byte_transactions(
+            i2c_address,
+            0x80 | 0x40 | 38,
+            gpio(0, false),
+        ));
+        expected_i2c_transactions.extend(testing::hd44780_packed_data_transactions(
+            i2c_address,
+            b"ab",
+            gpio(0, true),
+        ));
+        // the text continues on row 2, which is driven by the second controller
+        expected_i2c_transactions.extend(testing::hd44780_byte_transactions(
+            i2c_address,
+            0x80,
+            gpio(1, false),
+        ));
+        expected_i2c_transactions.extend(testing::hd44780_packed_data_transactions(
+            i2c_address,
+            b"cd",
+            gpio(1, true),
+        ));
+
+        let i2c = I2cMock::new(&expected_i2c_transactions);
+        let mut lcd =
+            CharacterDisplayDualHD44780::new(i2c, LcdDisplayType::Lcd40x4, NoopDelay::new());
+        assert!(lcd.print_at(38, 1, "abcd").is_ok());
+        assert_eq!(lcd.cursor_position(), Some((2, 2)));
         lcd.i2c().done();
     }
 
     #[test]
-    fn test_adafruit_lcd_backpack_init() {
-        let i2c_address = 0x20_u8;
+    fn test_terminal() {
+        use crate::testing::{aip31068_command_transaction, aip31068_data_transaction};
+        use core::fmt::Write;
+
+        let i2c_address = 0x3e_u8;
+        let blank_row = [b' '; 16];
         let expected_i2c_transactions = std::vec![
-            // the Adafruit Backpack need to init the adapter IC first
-            // write 0x00 to the MCP23008 IODIR register to set all pins as outputs
-            I2cTransaction::write(i2c_address, std::vec![0x00, 0x00]),
-            // the LCD init sequence
-            // write low nibble of 0x03 3 times
-            I2cTransaction::write(i2c_address, std::vec![0x09, 0b0_0011_100]), // low nibble, rw=0, enable=1
-            I2cTransaction::write(i2c_address, std::vec![0x09, 0b0_0011_000]), // low nibble, rw=0, enable=0
-            I2cTransaction::write(i2c_address, std::vec![0x09, 0b0_0011_100]), // low nibble, rw=0, enable=1
-            I2cTransaction::write(i2c_address, std::vec![0x09, 0b0_0011_000]), // low nibble, rw=0, enable=0
-            I2cTransaction::write(i2c_address, std::vec![0x09, 0b0_0011_100]), // low nibble, rw=0, enable=1
-            I2cTransaction::write(i2c_address, std::vec![0x09, 0b0_0011_000]), // low nibble, rw=0, enable=0
-            // write high nibble of 0x02 one time
-            I2cTransaction::write(i2c_address, std::vec![0x09, 0b0_0010_100]), // high nibble, rw=0, enable=1
-            I2cTransaction::write(i2c_address, std::vec![0x09, 0b0_0010_000]), // high nibble, rw=0, enable=0
-            // turn on the backlight
-            // I2cTransaction::write(i2c_address, std::vec![0b0000_1000]),    // backlight on
-            // LCD_CMD_FUNCTIONSET | LCD_FLAG_4BITMODE | LCD_FLAG_5x8_DOTS | LCD_FLAG_2LINE
-            // = 0x20 | 0x00 | 0x00 | 0x08 = 0x28
-            I2cTransaction::write(i2c_address, std::vec![0x09, 0b0_0010_100]), // high nibble, rw=0, enable=1
-            I2cTransaction::write(i2c_address, std::vec![0x09, 0b0_0010_000]), // high nibble, rw=0, enable=0
-            I2cTransaction::write(i2c_address, std::vec![0x09, 0b0_1000_100]), // low nibble, rw=0, enable=1
-            I2cTransaction::write(i2c_address, std::vec![0x09, 0b0_1000_000]), // low nibble, rw=0, enable=0
-            // LCD_CMD_DISPLAYCONTROL | LCD_FLAG_DISPLAYON | LCD_FLAG_CURSOROFF | LCD_FLAG_BLINKOFF
-            // = 0x08 | 0x04 | 0x00 | 0x00 = 0x0C
-            I2cTransaction::write(i2c_address, std::vec![0x09, 0b0_0000_100]), // high nibble, rw=0, enable=1
-            I2cTransaction::write(i2c_address, std::vec![0x09, 0b0_0000_000]), // high nibble, rw=0, enable=0
-            I2cTransaction::write(i2c_address, std::vec![0x09, 0b0_1100_100]), // low nibble, rw=0, enable=1
-            I2cTransaction::write(i2c_address, std::vec![0x09, 0b0_1100_000]), // low nibble, rw=0, enable=0
-            // LCD_CMD_ENTRYMODESET | LCD_FLAG_ENTRYLEFT | LCD_FLAG_ENTRYSHIFTDECREMENT
-            // = 0x04 | 0x02 | 0x00 = 0x06
-            I2cTransaction::write(i2c_address, std::vec![0x09, 0b0_0000_100]), // high nibble, rw=0, enable=1
-            I2cTransaction::write(i2c_address, std::vec![0x09, 0b0_0000_000]), // high nibble, rw=0, enable=0
-            I2cTransaction::write(i2c_address, std::vec![0x09, 0b0_0110_100]), // low nibble, rw=0, enable=1
-            I2cTransaction::write(i2c_address, std::vec![0x09, 0b0_0110_000]), // low nibble, rw=0, enable=0
-            // LCD_CMD_CLEARDISPLAY
-            // = 0x01
-            I2cTransaction::write(i2c_address, std::vec![0x09, 0b0_0000_100]), // high nibble, rw=0, enable=1
-            I2cTransaction::write(i2c_address, std::vec![0x09, 0b0_0000_000]), // high nibble, rw=0, enable=0
-            I2cTransaction::write(i2c_address, std::vec![0x09, 0b0_0001_100]), // low nibble, rw=0, enable=1
-            I2cTransaction::write(i2c_address, std::vec![0x09, 0b0_0001_000]), // low nibble, rw=0, enable=0
-            // LCD_CMD_RETURNHOME
-            // = 0x02
-            I2cTransaction::write(i2c_address, std::vec![0x09, 0b0_0000_100]), // high nibble, rw=0, enable=1
-            I2cTransaction::write(i2c_address, std::vec![0x09, 0b0_0000_000]), // high nibble, rw=0, enable=0
-            I2cTransaction::write(i2c_address, std::vec![0x09, 0b0_0010_100]), // low nibble, rw=0, enable=1
-            I2cTransaction::write(i2c_address, std::vec![0x09, 0b0_0010_000]), // low nibble, rw=0, enable=0
-            // Set Backlight
-            I2cTransaction::write(i2c_address, std::vec![0x09, 0b1_0010_000]), // backlight on
+            aip31068_command_transaction(i2c_address, 0x20 | 0x08),
+            aip31068_command_transaction(i2c_address, 0x08 | 0x04),
+            aip31068_command_transaction(i2c_address, 0x01),
+            aip31068_command_transaction(i2c_address, 0x04 | 0x02),
+            aip31068_data_transaction(i2c_address, b"ab"),
+            aip31068_command_transaction(i2c_address, 0x80 | 0x40),
+            aip31068_data_transaction(i2c_address, b"cd"),
+            // a newline on the last row scrolls the lines up
+            aip31068_command_transaction(i2c_address, 0x80),
+            aip31068_data_transaction(i2c_address, b"cd              "),
+            aip31068_command_transaction(i2c_address, 0x80 | 0x40),
+            aip31068_data_transaction(i2c_address, &blank_row),
+            aip31068_command_transaction(i2c_address, 0x80 | 0x40),
+            aip31068_data_transaction(i2c_address, b"ef"),
+            // a carriage return goes back to the start of the row, and a full row wraps and scrolls
+            aip31068_command_transaction(i2c_address, 0x80 | 0x40),
+            aip31068_data_transaction(i2c_address, b"x123456789012345"),
+            aip31068_command_transaction(i2c_address, 0x80),
+            aip31068_data_transaction(i2c_address, b"x123456789012345"),
+            aip31068_command_transaction(i2c_address, 0x80 | 0x40),
+            aip31068_data_transaction(i2c_address, &blank_row),
+            aip31068_command_transaction(i2c_address, 0x80 | 0x40),
+            aip31068_data_transaction(i2c_address, b"z"),
+            aip31068_command_transaction(i2c_address, 0x01),
+            aip31068_data_transaction(i2c_address, b"q"),
         ];
 
         let i2c = I2cMock::new(&expected_i2c_transactions);
-        let mut lcd = AdafruitLCDBackpack::new(i2c, LcdDisplayType::Lcd16x2, NoopDelay::new());
+        let mut lcd = CharacterDisplayAIP31068::new(i2c, LcdDisplayType::Lcd16x2, NoopDelay::new())
+            .with_terminal(std::boxed::Box::leak(std::boxed::Box::default()));
+        assert!(lcd.init().is_ok());
+        assert!(lcd.print("ab\ncd").is_ok());
+        assert!(lcd.print("\nef").is_ok());
+        assert!(write!(lcd, "\rx{}z", 123456789012345_u64).is_ok());
+        assert_eq!(lcd.cursor_position(), Some((1, 1)));
+        assert!(lcd.clear().is_ok());
+        assert!(lcd.print("q").is_ok());
+        lcd.i2c().done();
+    }
 
-        let result = lcd.init();
-        assert!(result.is_ok());
-        assert!(lcd.display_type() == LcdDisplayType::Lcd16x2);
+    #[test]
+    fn test_terminal_redraws_character_codes() {
+        use crate::testing::{aip31068_command_transaction, aip31068_data_transaction};
 
-        // finish the i2c mock
+        let i2c_address = 0x3e_u8;
+        let mut scrolled_row = [b' '; 16];
+        scrolled_row[..2].copy_from_slice(&[b'1', 0xDF]);
+        let expected_i2c_transactions = std::vec![
+            aip31068_command_transaction(i2c_address, 0x80),
+            aip31068_data_transaction(i2c_address, b"a"),
+            aip31068_command_transaction(i2c_address, 0x80 | 0x40),
+            aip31068_data_transaction(i2c_address, &[b'1', 0xDF]),
+            // the scrolled line is redrawn with the ROM code it was shown with, not translated again
+            aip31068_command_transaction(i2c_address, 0x80),
+            aip31068_data_transaction(i2c_address, &scrolled_row),
+            aip31068_command_transaction(i2c_address, 0x80 | 0x40),
+            aip31068_data_transaction(i2c_address, &[b' '; 16]),
+        ];
+
+        let i2c = I2cMock::new(&expected_i2c_transactions);
+        let mut lcd = CharacterDisplayAIP31068::new(i2c, LcdDisplayType::Lcd16x2, NoopDelay::new())
+            .with_character_rom(CharacterRom::A00)
+            .with_terminal(std::boxed::Box::leak(std::boxed::Box::default()));
+        assert!(lcd.print("a\n1°\n").is_ok());
         lcd.i2c().done();
     }
 
     #[test]
-    fn test_character_display_dual_hd44780_init() {
-        let i2c_address = 0x27_u8;
+    fn test_write_line_breaks() {
+        use crate::testing::{aip31068_command_transaction, aip31068_data_transaction};
+        use core::fmt::Write;
+
+        let i2c_address = 0x3e_u8;
         let expected_i2c_transactions = std::vec![
-            // the PCF8574T has no adapter init sequence, so nothing to prepend
-            // *** Device 0 ***
-            // the LCD init sequence for device 0
-            // write low nibble of 0x03 3 times
-            I2cTransaction::write(i2c_address, std::vec![0b0011_0100]), // low nibble, rw=0, enable=1
-            I2cTransaction::write(i2c_address, std::vec![0b0011_0000]), // low nibble, rw=0, enable=0
-            I2cTransaction::write(i2c_address, std::vec![0b0011_0100]), // low nibble, rw=0, enable=1
-            I2cTransaction::write(i2c_address, std::vec![0b0011_0000]), // low nibble, rw=0, enable=0
-            I2cTransaction::write(i2c_address, std::vec![0b0011_0100]), // low nibble, rw=0, enable=1
-            I2cTransaction::write(i2c_address, std::vec![0b0011_0000]), // low nibble, rw=0, enable=0
-            // write high nibble of 0x02 one time
-            I2cTransaction::write(i2c_address, std::vec![0b0010_0100]), // high nibble, rw=0, enable=1
-            I2cTransaction::write(i2c_address, std::vec![0b0010_0000]), // high nibble, rw=0, enable=0
-            // turn on the backlight
-            // I2cTransaction::write(i2c_address, std::vec![0b0000_1000]),    // backlight on
-            // LCD_CMD_FUNCTIONSET | LCD_FLAG_4BITMODE | LCD_FLAG_5x8_DOTS | LCD_FLAG_2LINE
-            // = 0x20 | 0x00 | 0x00 | 0x08 = 0x28
-            I2cTransaction::write(i2c_address, std::vec![0b0010_0100]), // high nibble, rw=0, enable=1
-            I2cTransaction::write(i2c_address, std::vec![0b0010_0000]), // high nibble, rw=0, enable=0
-            I2cTransaction::write(i2c_address, std::vec![0b1000_0100]), // low nibble, rw=0, enable=1
-            I2cTransaction::write(i2c_address, std::vec![0b1000_0000]), // low nibble, rw=0, enable=0
-            // LCD_CMD_DISPLAYCONTROL | LCD_FLAG_DISPLAYON | LCD_FLAG_CURSOROFF | LCD_FLAG_BLINKOFF
-            // = 0x08 | 0x04 | 0x00 | 0x00 = 0x0C
-            I2cTransaction::write(i2c_address, std::vec![0b0000_0100]), // high nibble, rw=0, enable=1
-            I2cTransaction::write(i2c_address, std::vec![0b0000_0000]), // high nibble, rw=0, enable=0
-            I2cTransaction::write(i2c_address, std::vec![0b1100_0100]), // low nibble, rw=0, enable=1
-            I2cTransaction::write(i2c_address, std::vec![0b1100_0000]), // low nibble, rw=0, enable=0
-            // LCD_CMD_ENTRYMODESET | LCD_FLAG_ENTRYLEFT | LCD_FLAG_ENTRYSHIFTDECREMENT
-            // = 0x04 | 0x02 | 0x00 = 0x06
-            I2cTransaction::write(i2c_address, std::vec![0b0000_0100]), // high nibble, rw=0, enable=1
-            I2cTransaction::write(i2c_address, std::vec![0b0000_0000]), // high nibble, rw=0, enable=0
-            I2cTransaction::write(i2c_address, std::vec![0b0110_0100]), // low nibble, rw=0, enable=1
-            I2cTransaction::write(i2c_address, std::vec![0b0110_0000]), // low nibble, rw=0, enable=0
-            // LCD_CMD_CLEARDISPLAY
-            // = 0x01
-            I2cTransaction::write(i2c_address, std::vec![0b0000_0100]), // high nibble, rw=0, enable=1
-            I2cTransaction::write(i2c_address, std::vec![0b0000_0000]), // high nibble, rw=0, enable=0
-            I2cTransaction::write(i2c_address, std::vec![0b0001_0100]), // low nibble, rw=0, enable=1
-            I2cTransaction::write(i2c_address, std::vec![0b0001_0000]), // low nibble, rw=0, enable=0
-            // LCD_CMD_RETURNHOME
-            // = 0x02
-            I2cTransaction::write(i2c_address, std::vec![0b0000_0100]), // high nibble, rw=0, enable=1
-            I2cTransaction::write(i2c_address, std::vec![0b0000_0000]), // high nibble, rw=0, enable=0
-            I2cTransaction::write(i2c_address, std::vec![0b0010_0100]), // low nibble, rw=0, enable=1
-            I2cTransaction::write(i2c_address, std::vec![0b0010_0000]), // low nibble, rw=0, enable=0
-            // *** Device 1 ***
-            // the LCD init sequence for device 0
-            // write low nibble of 0x03 3 times
-            I2cTransaction::write(i2c_address, std::vec![0b0011_0010]), // low nibble, rw=0, enable=1
-            I2cTransaction::write(i2c_address, std::vec![0b0011_0000]), // low nibble, rw=0, enable=0
-            I2cTransaction::write(i2c_address, std::vec![0b0011_0010]), // low nibble, rw=0, enable=1
-            I2cTransaction::write(i2c_address, std::vec![0b0011_0000]), // low nibble, rw=0, enable=0
-            I2cTransaction::write(i2c_address, std::vec![0b0011_0010]), // low nibble, rw=0, enable=1
-            I2cTransaction::write(i2c_address, std::vec![0b0011_0000]), // low nibble, rw=0, enable=0
-            // write high nibble of 0x02 one time
-            I2cTransaction::write(i2c_address, std::vec![0b0010_0010]), // high nibble, rw=0, enable=1
-            I2cTransaction::write(i2c_address, std::vec![0b0010_0000]), // high nibble, rw=0, enable=0
-            // turn on the backlight
-            // I2cTransaction::write(i2c_address, std::vec![0b0000_1000]),    // backlight on
-            // LCD_CMD_FUNCTIONSET | LCD_FLAG_4BITMODE | LCD_FLAG_5x8_DOTS | LCD_FLAG_2LINE
-            // = 0x20 | 0x00 | 0x00 | 0x08 = 0x28
-            I2cTransaction::write(i2c_address, std::vec![0b0010_0010]), // high nibble, rw=0, enable=1
-            I2cTransaction::write(i2c_address, std::vec![0b0010_0000]), // high nibble, rw=0, enable=0
-            I2cTransaction::write(i2c_address, std::vec![0b1000_0010]), // low nibble, rw=0, enable=1
-            I2cTransaction::write(i2c_address, std::vec![0b1000_0000]), // low nibble, rw=0, enable=0
-            // LCD_CMD_DISPLAYCONTROL | LCD_FLAG_DISPLAYON | LCD_FLAG_CURSOROFF | LCD_FLAG_BLINKOFF
-            // = 0x08 | 0x04 | 0x00 | 0x00 = 0x0C
-            I2cTransaction::write(i2c_address, std::vec![0b0000_0010]), // high nibble, rw=0, enable=1
-            I2cTransaction::write(i2c_address, std::vec![0b0000_0000]), // high nibble, rw=0, enable=0
-            I2cTransaction::write(i2c_address, std::vec![0b1100_0010]), // low nibble, rw=0, enable=1
-            I2cTransaction::write(i2c_address, std::vec![0b1100_0000]), // low nibble, rw=0, enable=0
-            // LCD_CMD_ENTRYMODESET | LCD_FLAG_ENTRYLEFT | LCD_FLAG_ENTRYSHIFTDECREMENT
-            // = 0x04 | 0x02 | 0x00 = 0x06
-            I2cTransaction::write(i2c_address, std::vec![0b0000_0010]), // high nibble, rw=0, enable=1
-            I2cTransaction::write(i2c_address, std::vec![0b0000_0000]), // high nibble, rw=0, enable=0
-            I2cTransaction::write(i2c_address, std::vec![0b0110_0010]), // low nibble, rw=0, enable=1
-            I2cTransaction::write(i2c_address, std::vec![0b0110_0000]), // low nibble, rw=0, enable=0
-            // LCD_CMD_CLEARDISPLAY
-            // = 0x01
-            I2cTransaction::write(i2c_address, std::vec![0b0000_0010]), // high nibble, rw=0, enable=1
-            I2cTransaction::write(i2c_address, std::vec![0b0000_0000]), // high nibble, rw=0, enable=0
-            I2cTransaction::write(i2c_address, std::vec![0b0001_0010]), // low nibble, rw=0, enable=1
-            I2cTransaction::write(i2c_address, std::vec![0b0001_0000]), // low nibble, rw=0, enable=0
-            // LCD_CMD_RETURNHOME
-            // = 0x02
-            I2cTransaction::write(i2c_address, std::vec![0b0000_0010]), // high nibble, rw=0, enable=1
-            I2cTransaction::write(i2c_address, std::vec![0b0000_0000]), // high nibble, rw=0, enable=0
-            I2cTransaction::write(i2c_address, std::vec![0b0010_0010]), // low nibble, rw=0, enable=1
-            I2cTransaction::write(i2c_address, std::vec![0b0010_0000]), // low nibble, rw=0, enable=0
-            // Set Backlight
-            I2cTransaction::write(i2c_address, std::vec![0b0010_1000]), // backlight on
+            aip31068_command_transaction(i2c_address, 0x80),
+            aip31068_data_transaction(i2c_address, b"ab"),
+            aip31068_command_transaction(i2c_address, 0x80 | 0x40),
+            aip31068_data_transaction(i2c_address, b"cd"),
+            aip31068_command_transaction(i2c_address, 0x80 | 0x40),
+            // a newline on the last row continues on the first row
+            aip31068_data_transaction(i2c_address, b"e"),
+            aip31068_command_transaction(i2c_address, 0x80),
+            // line breaks are dropped when ignored
+            aip31068_data_transaction(i2c_address, b"x"),
+            aip31068_data_transaction(i2c_address, b"y"),
+        ];
+
+        let mut i2c = I2cMock::new(&expected_i2c_transactions);
+        let mut lcd =
+            CharacterDisplayAIP31068::new(i2c.clone(), LcdDisplayType::Lcd16x2, NoopDelay::new());
+        assert!(lcd.set_cursor(0, 0).is_ok());
+        assert!(writeln!(lcd, "ab").is_ok());
+        assert!(write!(lcd, "cd\r").is_ok());
+        assert!(writeln!(lcd, "e").is_ok());
+        assert_eq!(lcd.cursor_position(), Some((0, 0)));
+
+        let mut lcd =
+            CharacterDisplayAIP31068::new(i2c.clone(), LcdDisplayType::Lcd16x2, NoopDelay::new())
+                .with_line_breaks(LineBreaks::Ignore);
+        assert!(write!(lcd, "x\r\ny\n").is_ok());
+        i2c.done();
+    }
+
+    #[test]
+    fn test_print_tabs() {
+        use crate::testing::{aip31068_command_transaction, aip31068_data_transaction};
+        use core::fmt::Write;
+
+        let i2c_address = 0x3e_u8;
+        let expected_i2c_transactions = std::vec![
+            aip31068_command_transaction(i2c_address, 0x80),
+            aip31068_data_transaction(i2c_address, b"ab"),
+            aip31068_data_transaction(i2c_address, b"  "),
+            aip31068_data_transaction(i2c_address, b"c"),
+            aip31068_data_transaction(i2c_address, b"   "),
+            aip31068_data_transaction(i2c_address, b"d"),
+            // a tab stops at the end of the row
+            aip31068_command_transaction(i2c_address, 0x80 | 0x4E),
+            aip31068_data_transaction(i2c_address, b"  "),
         ];
 
         let i2c = I2cMock::new(&expected_i2c_transactions);
+        let mut lcd = CharacterDisplayAIP31068::new(i2c, LcdDisplayType::Lcd16x2, NoopDelay::new());
+        assert!(lcd.set_cursor(0, 0).is_ok());
+        let column = "c";
+        assert!(write!(lcd, "ab\t{column}\td").is_ok());
+        assert_eq!(lcd.cursor_position(), Some((9, 0)));
+        assert!(lcd.print_at(14, 1, "\t").is_ok());
+        lcd.i2c().done();
+    }
+
+    #[test]
+    fn test_print_padded() {
+        use crate::testing::{aip31068_command_transaction, aip31068_data_transaction};
+
+        let i2c_address = 0x3e_u8;
+        let expected_i2c_transactions = std::vec![
+            aip31068_command_transaction(i2c_address, 0x80),
+            aip31068_data_transaction(i2c_address, b"    ab"),
+            aip31068_data_transaction(i2c_address, b" ab  "),
+            aip31068_data_transaction(i2c_address, b"abcd"),
+            // truncated text ends with the ellipsis
+            aip31068_command_transaction(i2c_address, 0x80 | 0x40),
+            aip31068_data_transaction(i2c_address, b"abc~"),
+            aip31068_data_transaction(i2c_address, b"ab  "),
+        ];
+
+        let mut i2c = I2cMock::new(&expected_i2c_transactions);
         let mut lcd =
-            CharacterDisplayDualHD44780::new(i2c, LcdDisplayType::Lcd40x4, NoopDelay::new());
-        let result = lcd.init();
-        assert!(result.is_ok());
-        assert!(lcd.display_type() == LcdDisplayType::Lcd40x4);
+            CharacterDisplayAIP31068::new(i2c.clone(), LcdDisplayType::Lcd16x2, NoopDelay::new());
+        assert!(lcd.set_cursor(0, 0).is_ok());
+        assert!(lcd.print_padded("ab", 6, Align::Right).is_ok());
+        assert!(lcd.print_padded("ab", 5, Align::Center).is_ok());
+        assert!(lcd.print_padded("abcdefgh", 4, Align::Left).is_ok());
+        assert!(lcd.print_padded("", 0, Align::Left).is_ok());
+        assert_eq!(lcd.cursor_position(), Some((15, 0)));
 
-        // finish the i2c mock
+        let mut lcd =
+            CharacterDisplayAIP31068::new(i2c.clone(), LcdDisplayType::Lcd16x2, NoopDelay::new())
+                .with_ellipsis('~');
+        assert!(lcd.set_cursor(0, 1).is_ok());
+        assert!(lcd.print_padded("abcdefgh", 4, Align::Right).is_ok());
+        assert!(lcd.print_padded("ab", 4, Align::Left).is_ok());
+        i2c.done();
+    }
+
+    #[test]
+    fn test_clear_row_and_region() {
+        use crate::testing::{aip31068_command_transaction, aip31068_data_transaction};
+
+        let i2c_address = 0x3e_u8;
+        let expected_i2c_transactions = std::vec![
+            aip31068_command_transaction(i2c_address, 0x80 | 0x03),
+            // the region is cut off at the end of the row, and the cursor is moved back
+            aip31068_command_transaction(i2c_address, 0x80 | 0x4E),
+            aip31068_data_transaction(i2c_address, b"  "),
+            aip31068_command_transaction(i2c_address, 0x80 | 0x03),
+            aip31068_command_transaction(i2c_address, 0x80),
+            aip31068_data_transaction(i2c_address, &[b' '; 16]),
+            aip31068_command_transaction(i2c_address, 0x80 | 0x03),
+        ];
+
+        let i2c = I2cMock::new(&expected_i2c_transactions);
+        let mut lcd = CharacterDisplayAIP31068::new(i2c, LcdDisplayType::Lcd16x2, NoopDelay::new());
+        assert!(lcd.set_cursor(3, 0).is_ok());
+        assert!(lcd.clear_region(14, 1, 5).is_ok());
+        assert!(lcd.clear_row(0).is_ok());
+        assert_eq!(lcd.cursor_position(), Some((3, 0)));
+        assert!(matches!(
+            lcd.clear_row(2),
+            Err(CharacterDisplayError::RowOutOfRange { .. })
+        ));
+        lcd.i2c().done();
+    }
+
+    #[test]
+    fn test_write_verification() {
+        use crate::testing::{
+            hd44780_byte_transactions, hd44780_packed_data_transactions, pcf8574_gpio,
+        };
+
+        let i2c_address = 0x27_u8;
+        let command = |command: u8| {
+            hd44780_byte_transactions(i2c_address, command, pcf8574_gpio(false, false))
+        };
+        let data = |data: &[u8]| {
+            hd44780_packed_data_transactions(i2c_address, data, pcf8574_gpio(true, false))
+        };
+        // polls the busy flag once and reads one character
+        let read = |value: u8| {
+            let nibble = |nibble: u8| {
+                [
+                    I2cTransaction::write(i2c_address, std::vec![0b1111_0111]),
+                    I2cTransaction::read(i2c_address, std::vec![(nibble << 4) | 0b0111]),
+                    I2cTransaction::write(i2c_address, std::vec![0b1111_0011]),
+                ]
+            };
+            let mut transactions = std::vec![
+                I2cTransaction::write(i2c_address, std::vec![0b1111_0010]),
+                I2cTransaction::write(i2c_address, std::vec![0b1111_0110]),
+                I2cTransaction::read(i2c_address, std::vec![0b0010_0110]),
+                I2cTransaction::write(i2c_address, std::vec![0b1111_0010]),
+                I2cTransaction::write(i2c_address, std::vec![0b1111_0110]),
+                I2cTransaction::write(i2c_address, std::vec![0b1111_0010]),
+                I2cTransaction::write(i2c_address, std::vec![0b1111_0011]),
+            ];
+            transactions.extend(nibble(value >> 4));
+            transactions.extend(nibble(value & 0x0F));
+            transactions
+        };
+        let mut expected_i2c_transactions = std::vec![];
+        expected_i2c_transactions.extend(command(0x80));
+        expected_i2c_transactions.extend(data(b"A"));
+        // the character is read back, and the cursor put back after it
+        expected_i2c_transactions.extend(command(0x80));
+        expected_i2c_transactions.extend(read(b'A'));
+        expected_i2c_transactions.extend(command(0x81));
+        // a character that reads back differently fails the print
+        expected_i2c_transactions.extend(data(b"B"));
+        expected_i2c_transactions.extend(command(0x81));
+        expected_i2c_transactions.extend(read(b'C'));
+
+        let i2c = I2cMock::new(&expected_i2c_transactions);
+        let mut lcd = CharacterDisplayPCF8574T::new(i2c, LcdDisplayType::Lcd16x2, NoopDelay::new())
+            .with_write_verification();
+        assert!(lcd.set_cursor(0, 0).is_ok());
+        assert!(lcd.print("A").is_ok());
+        assert_eq!(lcd.cursor_position(), Some((1, 0)));
+        assert!(matches!(
+            lcd.print("B"),
+            Err(CharacterDisplayError::VerificationFailed { col: 1, row: 0 })
+        ));
+        lcd.i2c().done();
+
+        // adapters that cannot read cannot verify
+        let i2c = I2cMock::new(&[
+            crate::testing::aip31068_command_transaction(0x3e, 0x80),
+            crate::testing::aip31068_data_transaction(0x3e, b"A"),
+        ]);
+        let mut lcd = CharacterDisplayAIP31068::new(i2c, LcdDisplayType::Lcd16x2, NoopDelay::new())
+            .with_write_verification();
+        assert!(lcd.set_cursor(0, 0).is_ok());
+        assert!(matches!(
+            lcd.print("A"),
+            Err(CharacterDisplayError::ReadNotSupported)
+        ));
+        lcd.i2c().done();
+    }
+
+    #[test]
+    fn test_borrowed_i2c_and_delay() {
+        let backlight_off =
+            I2cTransaction::write(0x27, testing::pcf8574_gpio(false, false)(0, false));
+        let expected_i2c_transactions = std::vec![
+            backlight_off.clone(),
+            I2cTransaction::write(0x76, std::vec![0xF4, 0x27]),
+            backlight_off,
+        ];
+
+        let mut i2c = I2cMock::new(&expected_i2c_transactions);
+        let mut delay = NoopDelay::new();
+        let mut lcd = CharacterDisplayPCF8574T::new(&mut i2c, LcdDisplayType::Lcd16x2, &mut delay);
+        assert!(lcd.backlight(false).is_ok());
+        drop(lcd);
+        // the bus is used by another driver in between
+        assert!(i2c::I2c::write(&mut i2c, 0x76, &[0xF4, 0x27]).is_ok());
+        let mut lcd = CharacterDisplayPCF8574T::new(&mut i2c, LcdDisplayType::Lcd16x2, &mut delay);
+        assert!(lcd.backlight(false).is_ok());
+        drop(lcd);
+        i2c.done();
+    }
+
+    #[test]
+    fn test_blink_region() {
+        use crate::testing::{aip31068_command_transaction, aip31068_data_transaction};
+
+        let i2c_address = 0x3e_u8;
+        let expected_i2c_transactions = std::vec![
+            aip31068_command_transaction(i2c_address, 0x80 | 0x40),
+            aip31068_data_transaction(i2c_address, b"ALARM!"),
+            // the blinking cells are blanked in the second half of the period
+            aip31068_command_transaction(i2c_address, 0x80 | 0x40),
+            aip31068_data_transaction(i2c_address, b"     "),
+            // and shown again with the text printed in the meantime
+            aip31068_command_transaction(i2c_address, 0x80 | 0x40),
+            aip31068_data_transaction(i2c_address, b"ALERT"),
+        ];
+
+        let i2c = I2cMock::new(&expected_i2c_transactions);
+        let mut lcd = CharacterDisplayAIP31068::new(i2c, LcdDisplayType::Lcd16x2, NoopDelay::new())
+            .with_framebuffer(std::boxed::Box::leak(std::boxed::Box::default()));
+        let now_ms = core::cell::Cell::new(0);
+        let clock = || now_ms.get();
+        assert!(lcd.print_at(0, 1, "ALARM!").is_ok());
+        assert!(lcd.blink_region(0, 1, 5, 1000).is_ok());
+        now_ms.set(1200);
+        assert!(lcd.tick(&clock).is_ok());
+        // nothing changes within the same half of the period
+        now_ms.set(1499);
+        assert!(lcd.tick(&clock).is_ok());
+        now_ms.set(1500);
+        assert!(lcd.tick(&clock).is_ok());
+        assert!(lcd.print_at(0, 1, "ALERT").is_ok());
+        now_ms.set(1900);
+        assert!(lcd.tick(&clock).is_ok());
+        now_ms.set(2000);
+        assert!(lcd.tick(&clock).is_ok());
+        // stopping the region leaves its text shown
+        assert!(lcd.blink_region(0, 1, 5, 0).is_ok());
+        now_ms.set(2500);
+        assert!(lcd.tick(&clock).is_ok());
+        for col in 0..8 {
+            assert!(lcd.blink_region(col, 0, 1, 500).is_ok());
+        }
+        assert!(matches!(
+            lcd.blink_region(8, 0, 1, 500),
+            Err(CharacterDisplayError::BufferTooSmall)
+        ));
+        lcd.i2c().done();
+
+        let mut lcd = CharacterDisplayAIP31068::new(
+            I2cMock::new(&[]),
+            LcdDisplayType::Lcd16x2,
+            NoopDelay::new(),
+        );
+        assert!(matches!(
+            lcd.blink_region(0, 1, 4, 1000),
+            Err(CharacterDisplayError::UnsupportedOperation)
+        ));
+        lcd.i2c().done();
+    }
+
+    #[test]
+    fn test_load_glyph() {
+        use crate::testing::{aip31068_command_transaction, aip31068_data_transaction};
+
+        let i2c_address = 0x3e_u8;
+        let bell = glyphs::Glyph::Bell.charmap();
+        let expected_i2c_transactions = std::vec![
+            aip31068_command_transaction(i2c_address, 0x40 | 2 << 3),
+            aip31068_data_transaction(i2c_address, &bell),
+            aip31068_command_transaction(i2c_address, 0x80),
+            aip31068_data_transaction(i2c_address, &[2]),
+        ];
+
+        let i2c = I2cMock::new(&expected_i2c_transactions);
+        let mut lcd = CharacterDisplayAIP31068::new(i2c, LcdDisplayType::Lcd16x2, NoopDelay::new());
+        let bell = lcd.load_glyph(2, glyphs::Glyph::Bell).unwrap();
+        assert_eq!(bell, '\u{2}');
+        assert!(lcd.print_at(0, 0, bell.encode_utf8(&mut [0; 4])).is_ok());
         lcd.i2c().done();
     }
 }
@@ -0,0 +1,81 @@
+/// Formatted print at the current cursor position of the display. This is the same as using `core::fmt::Write`
+/// with the `write!` macro, except that the error type is the display's `CharacterDisplayError` rather than
+/// `core::fmt::Error`, and the display is returned in `Ok()` for chaining.
+///
+/// ```rust
+/// use i2c_character_display::lcd_write;
+///
+/// lcd_write!(lcd, "H={}%", humidity)?;
+/// ```
+#[macro_export]
+macro_rules! lcd_write {
+    ($lcd:expr, $($arg:tt)*) => {
+        $lcd.print_fmt(::core::format_args!($($arg)*))
+    };
+}
+
+/// Set the cursor to the given column and row and then do a formatted print, with a single
+/// `CharacterDisplayError` result for the whole operation.
+///
+/// ```rust
+/// use i2c_character_display::lcd_print;
+///
+/// lcd_print!(lcd, 0, 1, "T={}C", temperature)?;
+/// ```
+#[macro_export]
+macro_rules! lcd_print {
+    ($lcd:expr, $col:expr, $row:expr, $($arg:tt)*) => {
+        $lcd.set_cursor($col, $row)
+            .and_then(|lcd| lcd.print_fmt(::core::format_args!($($arg)*)))
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+    use crate::{CharacterDisplayAIP31068, CharacterDisplayError, LcdDisplayType};
+    use embedded_hal_mock::eh1::{
+        delay::NoopDelay,
+        i2c::{Mock as I2cMock, Transaction as I2cTransaction},
+    };
+
+    #[test]
+    fn test_lcd_print() {
+        let i2c_address = 0x3e;
+        let expected_i2c_transactions = std::vec![
+            // set cursor to column 2, row 1
+            I2cTransaction::write(i2c_address, std::vec![0b0000_0000, 0x80 | 0x42]),
+            // the formatted text is written in pieces
+            I2cTransaction::write(i2c_address, std::vec![0b0100_0000, b'T', b'=']),
+            I2cTransaction::write(i2c_address, std::vec![0b0100_0000, b'2', b'1']),
+            I2cTransaction::write(i2c_address, std::vec![0b0100_0000, b'C']),
+        ];
+
+        let i2c = I2cMock::new(&expected_i2c_transactions);
+        let mut lcd = CharacterDisplayAIP31068::new(i2c, LcdDisplayType::Lcd16x2, NoopDelay);
+        let temperature = 21;
+        assert!(lcd_print!(lcd, 2, 1, "T={}C", temperature).is_ok());
+        assert!(matches!(
+            lcd_print!(lcd, 2, 5, "T={}C", temperature),
+            Err(CharacterDisplayError::RowOutOfRange { .. })
+        ));
+
+        lcd.i2c().done();
+    }
+
+    #[test]
+    fn test_lcd_write() {
+        let i2c_address = 0x3e;
+        let expected_i2c_transactions = std::vec![I2cTransaction::write(
+            i2c_address,
+            std::vec![0b0100_0000, b'4', b'2']
+        ),];
+
+        let i2c = I2cMock::new(&expected_i2c_transactions);
+        let mut lcd = CharacterDisplayAIP31068::new(i2c, LcdDisplayType::Lcd16x2, NoopDelay);
+        let lcd_ref = &mut lcd;
+        assert!(lcd_write!(lcd_ref, "{}", 42).is_ok());
+
+        lcd.i2c().done();
+    }
+}
@@ -0,0 +1,176 @@
+//! A marquee that scrolls a long string through a window of one row, for text that does not fit on the display.
+//! Unlike `scroll_display_left()`, which shifts every row of the display at once, a [`Marquee`] rewrites only the
+//! characters in its window, so the rest of the display stays in place.
+//!
+//! ```rust
+//! use i2c_character_display::marquee::Marquee;
+//!
+//! lcd.print_at(0, 0, "Now playing:")?;
+//! let mut marquee = Marquee::new("Never Gonna Give You Up - Rick Astley", 1);
+//! loop {
+//!     marquee.tick(&mut lcd)?;
+//!     delay.delay_ms(300);
+//! }
+//! ```
+
+use embedded_hal::{delay::DelayNs, i2c};
+
+use crate::{driver::DriverTrait, BaseCharacterDisplay, CharacterDisplayError};
+
+/// The maximum number of characters in the window of a marquee, which is the widest supported display.
+const MAX_WINDOW_WIDTH: usize = 40;
+
+/// The default number of spaces between the end of the text and its start coming around again.
+const DEFAULT_GAP: u8 = 4;
+
+/// Scrolls a string from right to left through a window of one row, one character per call to [`Marquee::tick`].
+/// The window is the whole row unless set with [`Marquee::with_window`]. Text that fits in the window is shown
+/// without scrolling.
+#[derive(Debug, Clone, Copy)]
+pub struct Marquee<'a> {
+    text: &'a str,
+    row: u8,
+    col: u8,
+    width: Option<u8>,
+    gap: u8,
+    offset: usize,
+}
+
+impl<'a> Marquee<'a> {
+    /// Create a marquee scrolling `text` through the whole of `row`.
+    pub const fn new(text: &'a str, row: u8) -> Self {
+        Self {
+            text,
+            row,
+            col: 0,
+            width: None,
+            gap: DEFAULT_GAP,
+            offset: 0,
+        }
+    }
+
+    /// Scroll the text through the `width` columns of the row starting at column `col`, rather than the whole row.
+    /// The width is limited to 40 columns.
+    pub const fn with_window(mut self, col: u8, width: u8) -> Self {
+        self.col = col;
+        self.width = Some(width);
+        self
+    }
+
+    /// Set the number of spaces between the end of the text and its start coming around again. The default is 4.
+    pub const fn with_gap(mut self, gap: u8) -> Self {
+        self.gap = gap;
+        self
+    }
+
+    /// Replace the scrolled text, starting again from its beginning.
+    pub fn set_text(&mut self, text: &'a str) -> &mut Self {
+        self.text = text;
+        self.offset = 0;
+        self
+    }
+
+    /// Start scrolling again from the beginning of the text.
+    pub fn reset(&mut self) -> &mut Self {
+        self.offset = 0;
+        self
+    }
+
+    /// Draw the window with the text at its current scroll position, then advance the scroll position by one
+    /// character for the next call.
+    pub fn tick<I2C, DELAY, DEVICE>(
+        &mut self,
+        lcd: &mut BaseCharacterDisplay<I2C, DELAY, DEVICE>,
+    ) -> Result<(), CharacterDisplayError<I2C>>
+    where
+        I2C: i2c::I2c,
+        DELAY: DelayNs,
+        DEVICE: DriverTrait<I2C, DELAY>,
+    {
+        let width = self
+            .width
+            .unwrap_or_else(|| lcd.cols().saturating_sub(self.col))
+            .min(MAX_WINDOW_WIDTH as u8) as usize;
+        let text_len = self.text.chars().count();
+        let scrolls = text_len > width;
+
+        // each character of the window is up to 4 bytes of UTF-8
+        let mut window = [0u8; MAX_WINDOW_WIDTH * 4];
+        let len = if scrolls {
+            let gap = core::iter::repeat(' ').take(self.gap as usize);
+            let chars = self.text.chars().chain(gap).cycle().skip(self.offset);
+            fill_window(chars, width, &mut window)
+        } else {
+            let chars = self.text.chars().chain(core::iter::repeat(' '));
+            fill_window(chars, width, &mut window)
+        };
+        let text = core::str::from_utf8(&window[..len]).map_err(|_| core::fmt::Error)?;
+        lcd.print_at(self.col, self.row, text)?;
+        if scrolls {
+            self.offset = (self.offset + 1) % (text_len + self.gap as usize);
+        }
+        Ok(())
+    }
+}
+
+/// Encode the first `width` characters of `chars` into `window`, returning the number of bytes written.
+fn fill_window(chars: impl Iterator<Item = char>, width: usize, window: &mut [u8]) -> usize {
+    chars
+        .take(width)
+        .fold(0, |len, c| len + c.encode_utf8(&mut window[len..]).len())
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+    use super::*;
+    use crate::{testing::aip31068_data_transaction, CharacterDisplayAIP31068, LcdDisplayType};
+    use embedded_hal_mock::eh1::{
+        delay::NoopDelay,
+        i2c::{Mock as I2cMock, Transaction as I2cTransaction},
+    };
+
+    #[test]
+    fn test_marquee_tick() {
+        let i2c_address = 0x3e;
+        let set_cursor = |address: u8| {
+            I2cTransaction::write(i2c_address, std::vec![0b0000_0000, 0x80 | address])
+        };
+        let expected_i2c_transactions = std::vec![
+            // the window of columns 10 to 13 on row 1 scrolls through the text and the gap
+            set_cursor(0x4A),
+            aip31068_data_transaction(i2c_address, b"Hell"),
+            set_cursor(0x4A),
+            aip31068_data_transaction(i2c_address, b"ello"),
+            set_cursor(0x4A),
+            aip31068_data_transaction(i2c_address, b"llo "),
+            set_cursor(0x4A),
+            aip31068_data_transaction(i2c_address, b"lo  "),
+            set_cursor(0x4A),
+            aip31068_data_transaction(i2c_address, b"o  H"),
+            set_cursor(0x4A),
+            aip31068_data_transaction(i2c_address, b"  He"),
+            set_cursor(0x4A),
+            aip31068_data_transaction(i2c_address, b" Hel"),
+            set_cursor(0x4A),
+            aip31068_data_transaction(i2c_address, b"Hell"),
+            // text that fits the whole row is padded without scrolling
+            set_cursor(0x00),
+            aip31068_data_transaction(i2c_address, b"Hi              "),
+            set_cursor(0x00),
+            aip31068_data_transaction(i2c_address, b"Hi              "),
+        ];
+
+        let i2c = I2cMock::new(&expected_i2c_transactions);
+        let mut lcd = CharacterDisplayAIP31068::new(i2c, LcdDisplayType::Lcd16x2, NoopDelay::new());
+        let mut marquee = Marquee::new("Hello!", 1).with_window(10, 4).with_gap(2);
+        marquee.set_text("Hello");
+        for _ in 0..8 {
+            assert!(marquee.tick(&mut lcd).is_ok());
+        }
+        let mut marquee = Marquee::new("Hi", 0);
+        assert!(marquee.tick(&mut lcd).is_ok());
+        assert!(marquee.tick(&mut lcd).is_ok());
+        lcd.i2c().done();
+    }
+}
@@ -0,0 +1,286 @@
+//! A scrollable menu for devices navigated with a rotary encoder or up and down buttons. A [`Menu`] shows a list
+//! of items on the rows of the display, marks the selected item with a caret drawn as a custom character, and
+//! scrolls the list when the selection moves past the first or last row shown.
+//!
+//! ```rust
+//! use i2c_character_display::menu::Menu;
+//!
+//! let mut menu = Menu::new(&["Start", "Settings", "Network", "About"]);
+//! menu.render(&mut lcd)?;
+//! loop {
+//!     match encoder.poll() {
+//!         Some(Event::Clockwise) => menu.down(&mut lcd)?,
+//!         Some(Event::CounterClockwise) => menu.up(&mut lcd)?,
+//!         Some(Event::Pressed) => run(menu.select()),
+//!         None => continue,
+//!     };
+//! }
+//! ```
+
+use embedded_hal::{delay::DelayNs, i2c};
+
+use crate::{driver::DriverTrait, BaseCharacterDisplay, CharacterDisplayError};
+
+/// The widest menu supported, which is the width of the widest display.
+const MAX_MENU_WIDTH: usize = 40;
+
+/// The default CGRAM slot used for the caret glyph.
+const DEFAULT_CARET_SLOT: u8 = 5;
+
+/// Custom character for the caret marking the selected item, a triangle pointing right.
+const CARET_GLYPH: [u8; 8] = [
+    0b01000, 0b01100, 0b01110, 0b01111, 0b01110, 0b01100, 0b01000, 0b00000,
+];
+
+/// A list of items shown one per row, with a caret in the first column of the selected item. Items longer than
+/// the row are clipped. The menu takes up all the rows of the display unless set with [`Menu::with_rows`].
+#[derive(Debug, Clone, Copy)]
+pub struct Menu<'a> {
+    items: &'a [&'a str],
+    selected: usize,
+    /// The index of the item shown on the first row of the menu
+    top: usize,
+    first_row: u8,
+    rows: Option<u8>,
+    caret_slot: u8,
+    wrap_around: bool,
+}
+
+impl<'a> Menu<'a> {
+    /// Create a menu of `items` with the first item selected. The caret uses CGRAM slot 5.
+    pub const fn new(items: &'a [&'a str]) -> Self {
+        Self {
+            items,
+            selected: 0,
+            top: 0,
+            first_row: 0,
+            rows: None,
+            caret_slot: DEFAULT_CARET_SLOT,
+            wrap_around: false,
+        }
+    }
+
+    /// Show the menu on `rows` rows of the display starting at `first_row`, rather than on all of them, such as to
+    /// keep a title on the first row.
+    pub const fn with_rows(mut self, first_row: u8, rows: u8) -> Self {
+        self.first_row = first_row;
+        self.rows = Some(rows);
+        self
+    }
+
+    /// Set the CGRAM slot used for the caret glyph.
+    pub const fn with_caret_slot(mut self, slot: u8) -> Self {
+        self.caret_slot = slot & 0x7;
+        self
+    }
+
+    /// Move the selection from the last item to the first one and back, rather than stopping at the ends of the list.
+    pub const fn with_wrap_around(mut self) -> Self {
+        self.wrap_around = true;
+        self
+    }
+
+    /// Returns the index of the selected item, or `None` when the menu has no items.
+    pub fn select(&self) -> Option<usize> {
+        (self.selected < self.items.len()).then_some(self.selected)
+    }
+
+    /// Upload the caret glyph and draw the menu, such as when it is first shown or after the display was cleared.
+    pub fn render<I2C, DELAY, DEVICE>(
+        &mut self,
+        lcd: &mut BaseCharacterDisplay<I2C, DELAY, DEVICE>,
+    ) -> Result<&mut Self, CharacterDisplayError<I2C>>
+    where
+        I2C: i2c::I2c,
+        DELAY: DelayNs,
+        DEVICE: DriverTrait<I2C, DELAY>,
+    {
+        lcd.create_char(self.caret_slot, CARET_GLYPH)?;
+        self.draw(lcd)?;
+        Ok(self)
+    }
+
+    /// Select the previous item, scrolling the menu down when the selection moves above its first row. Nothing is
+    /// written to the display when the first item is already selected and the menu does not wrap around.
+    pub fn up<I2C, DELAY, DEVICE>(
+        &mut self,
+        lcd: &mut BaseCharacterDisplay<I2C, DELAY, DEVICE>,
+    ) -> Result<&mut Self, CharacterDisplayError<I2C>>
+    where
+        I2C: i2c::I2c,
+        DELAY: DelayNs,
+        DEVICE: DriverTrait<I2C, DELAY>,
+    {
+        let selected = match self.selected.checked_sub(1) {
+            Some(selected) => selected,
+            None if self.wrap_around => self.items.len().saturating_sub(1),
+            None => return Ok(self),
+        };
+        self.move_to(lcd, selected)
+    }
+
+    /// Select the next item, scrolling the menu up when the selection moves below its last row. Nothing is written
+    /// to the display when the last item is already selected and the menu does not wrap around.
+    pub fn down<I2C, DELAY, DEVICE>(
+        &mut self,
+        lcd: &mut BaseCharacterDisplay<I2C, DELAY, DEVICE>,
+    ) -> Result<&mut Self, CharacterDisplayError<I2C>>
+    where
+        I2C: i2c::I2c,
+        DELAY: DelayNs,
+        DEVICE: DriverTrait<I2C, DELAY>,
+    {
+        let selected = if self.selected + 1 < self.items.len() {
+            self.selected + 1
+        } else if self.wrap_around {
+            0
+        } else {
+            return Ok(self);
+        };
+        self.move_to(lcd, selected)
+    }
+
+    /// Select the item at `selected`, scrolling it into view, and redraw the menu if the selection changed.
+    fn move_to<I2C, DELAY, DEVICE>(
+        &mut self,
+        lcd: &mut BaseCharacterDisplay<I2C, DELAY, DEVICE>,
+        selected: usize,
+    ) -> Result<&mut Self, CharacterDisplayError<I2C>>
+    where
+        I2C: i2c::I2c,
+        DELAY: DelayNs,
+        DEVICE: DriverTrait<I2C, DELAY>,
+    {
+        if selected == self.selected {
+            return Ok(self);
+        }
+        let rows = self.rows(lcd)? as usize;
+        self.selected = selected;
+        if selected < self.top {
+            self.top = selected;
+        } else if selected >= self.top + rows {
+            self.top = selected + 1 - rows;
+        }
+        self.draw(lcd)?;
+        Ok(self)
+    }
+
+    /// Draw the items shown on the rows of the menu, blanking the rows past the last item.
+    fn draw<I2C, DELAY, DEVICE>(
+        &self,
+        lcd: &mut BaseCharacterDisplay<I2C, DELAY, DEVICE>,
+    ) -> Result<(), CharacterDisplayError<I2C>>
+    where
+        I2C: i2c::I2c,
+        DELAY: DelayNs,
+        DEVICE: DriverTrait<I2C, DELAY>,
+    {
+        let rows = self.rows(lcd)?;
+        let width = (lcd.cols() as usize).min(MAX_MENU_WIDTH);
+        // each character of a row is up to 4 bytes of UTF-8
+        let mut buffer = [0u8; MAX_MENU_WIDTH * 4];
+        for row in 0..rows {
+            let index = self.top + row as usize;
+            let caret = if index == self.selected {
+                char::from(self.caret_slot)
+            } else {
+                ' '
+            };
+            let item = self.items.get(index).copied().unwrap_or("");
+            let len = core::iter::once(caret)
+                .chain(item.chars())
+                .chain(core::iter::repeat(' '))
+                .take(width)
+                .fold(0, |len, c| len + c.encode_utf8(&mut buffer[len..]).len());
+            let text = core::str::from_utf8(&buffer[..len]).map_err(|_| core::fmt::Error)?;
+            lcd.print_at(0, self.first_row + row, text)?;
+        }
+        Ok(())
+    }
+
+    /// Returns the number of rows of the menu, checking that they fit on the display.
+    fn rows<I2C, DELAY, DEVICE>(
+        &self,
+        lcd: &BaseCharacterDisplay<I2C, DELAY, DEVICE>,
+    ) -> Result<u8, CharacterDisplayError<I2C>>
+    where
+        I2C: i2c::I2c,
+        DELAY: DelayNs,
+        DEVICE: DriverTrait<I2C, DELAY>,
+    {
+        let rows = self
+            .rows
+            .unwrap_or_else(|| lcd.rows().saturating_sub(self.first_row));
+        if rows == 0 || self.first_row as usize + rows as usize > lcd.rows() as usize {
+            return Err(CharacterDisplayError::RowOutOfRange {
+                row: self.first_row,
+            });
+        }
+        Ok(rows)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+    use super::*;
+    use crate::{
+        testing::{aip31068_command_transaction, aip31068_data_transaction},
+        CharacterDisplayAIP31068, LcdDisplayType,
+    };
+    use embedded_hal_mock::eh1::{delay::NoopDelay, i2c::Mock as I2cMock};
+
+    #[test]
+    fn test_menu_navigation() {
+        let i2c_address = 0x3e;
+        let expected_i2c_transactions = std::vec![
+            // the caret glyph is uploaded to CGRAM slot 5
+            aip31068_command_transaction(i2c_address, 0x40 | 5 << 3),
+            aip31068_data_transaction(i2c_address, &CARET_GLYPH),
+            aip31068_command_transaction(i2c_address, 0x80),
+            aip31068_data_transaction(i2c_address, b"\x05Start  "),
+            aip31068_command_transaction(i2c_address, 0x80 | 0x40),
+            aip31068_data_transaction(i2c_address, b" Setting"),
+            // the caret moves to the second row
+            aip31068_command_transaction(i2c_address, 0x80),
+            aip31068_data_transaction(i2c_address, b" Start  "),
+            aip31068_command_transaction(i2c_address, 0x80 | 0x40),
+            aip31068_data_transaction(i2c_address, b"\x05Setting"),
+            // the menu scrolls to show the third item
+            aip31068_command_transaction(i2c_address, 0x80),
+            aip31068_data_transaction(i2c_address, b" Setting"),
+            aip31068_command_transaction(i2c_address, 0x80 | 0x40),
+            aip31068_data_transaction(i2c_address, b"\x05About  "),
+            // wrapping around to the first item scrolls back to the top
+            aip31068_command_transaction(i2c_address, 0x80),
+            aip31068_data_transaction(i2c_address, b"\x05Start  "),
+            aip31068_command_transaction(i2c_address, 0x80 | 0x40),
+            aip31068_data_transaction(i2c_address, b" Setting"),
+        ];
+
+        let i2c = I2cMock::new(&expected_i2c_transactions);
+        let mut lcd = CharacterDisplayAIP31068::new(i2c, LcdDisplayType::Lcd8x2, NoopDelay::new());
+        let items = ["Start", "Settings", "About"];
+        let mut menu = Menu::new(&items);
+        assert!(menu.render(&mut lcd).is_ok());
+        // the first item is already selected
+        assert!(menu.up(&mut lcd).is_ok());
+        assert!(menu.down(&mut lcd).is_ok());
+        assert!(menu.down(&mut lcd).is_ok());
+        assert_eq!(menu.select(), Some(2));
+        // the last item is already selected
+        assert!(menu.down(&mut lcd).is_ok());
+        let mut menu = menu.with_wrap_around();
+        assert!(menu.down(&mut lcd).is_ok());
+        assert_eq!(menu.select(), Some(0));
+
+        // a menu that does not fit on the display is rejected
+        let mut menu = Menu::new(&items).with_rows(1, 2);
+        assert!(matches!(
+            menu.down(&mut lcd),
+            Err(CharacterDisplayError::RowOutOfRange { .. })
+        ));
+        assert_eq!(Menu::new(&[]).select(), None);
+        lcd.i2c().done();
+    }
+}
@@ -0,0 +1,220 @@
+//! Switching between several screens, or pages, such as sensor dashboards that rotate between views. The
+//! application registers a render callback for each page with [`Pages`], which clears the display and redraws the
+//! whole page when switching to it, and can cycle through the pages on its own, showing each for a dwell time
+//! measured by a [`Clock`](crate::clock::Clock).
+//!
+//! ```rust
+//! use core::cell::Cell;
+//! use i2c_character_display::{lcd_write, pages::Pages};
+//!
+//! let temperature = Cell::new(21.5);
+//! let mut climate = |lcd: &mut CharacterDisplayPCF8574T<_, _>| {
+//!     lcd_write!(lcd, "Temp {:.1}C", temperature.get()).map(|_| ())
+//! };
+//! let mut network = |lcd: &mut CharacterDisplayPCF8574T<_, _>| lcd.print("WiFi: up").map(|_| ());
+//! let clock = || timer.now().duration_since_epoch().to_millis();
+//! let mut pages = Pages::new([&mut climate, &mut network]);
+//! pages.set_auto_cycle(&clock, 5000);
+//! pages.show(&mut lcd, 0)?;
+//! loop {
+//!     temperature.set(sensor.read()?);
+//!     pages.poll(&mut lcd)?;
+//! }
+//! ```
+
+use embedded_hal::{delay::DelayNs, i2c};
+
+use crate::{clock::Clock, driver::DriverTrait, BaseCharacterDisplay, CharacterDisplayError};
+
+/// The render callback of a page, which draws the page on a cleared display.
+pub type RenderPage<'a, I2C, DELAY, DEVICE> =
+    &'a mut dyn FnMut(
+        &mut BaseCharacterDisplay<I2C, DELAY, DEVICE>,
+    ) -> Result<(), CharacterDisplayError<I2C>>;
+
+/// `N` pages, each drawn by its render callback, of which one is shown at a time.
+pub struct Pages<'a, I2C, DELAY, DEVICE, const N: usize>
+where
+    I2C: i2c::I2c,
+    DELAY: DelayNs,
+    DEVICE: DriverTrait<I2C, DELAY>,
+{
+    pages: [RenderPage<'a, I2C, DELAY, DEVICE>; N],
+    current: usize,
+    /// The clock and the time in milliseconds each page is shown for, when cycling through the pages
+    auto_cycle: Option<(&'a dyn Clock, u32)>,
+    /// When the current page was shown, in milliseconds from the clock
+    shown_ms: Option<u64>,
+}
+
+impl<'a, I2C, DELAY, DEVICE, const N: usize> Pages<'a, I2C, DELAY, DEVICE, N>
+where
+    I2C: i2c::I2c,
+    DELAY: DelayNs,
+    DEVICE: DriverTrait<I2C, DELAY>,
+{
+    /// Create a page manager from the render callbacks of the pages, with the first page current. Nothing is drawn
+    /// until a page is shown.
+    pub fn new(pages: [RenderPage<'a, I2C, DELAY, DEVICE>; N]) -> Self {
+        Self {
+            pages,
+            current: 0,
+            auto_cycle: None,
+            shown_ms: None,
+        }
+    }
+
+    /// Cycle through the pages, switching to the next page from `poll()` once the current one has been shown for
+    /// `dwell_ms` milliseconds of `clock`.
+    pub fn set_auto_cycle(&mut self, clock: &'a dyn Clock, dwell_ms: u32) -> &mut Self {
+        self.auto_cycle = Some((clock, dwell_ms));
+        self
+    }
+
+    /// Stop cycling through the pages.
+    pub fn stop_auto_cycle(&mut self) -> &mut Self {
+        self.auto_cycle = None;
+        self
+    }
+
+    /// Returns the index of the current page.
+    pub fn current(&self) -> usize {
+        self.current
+    }
+
+    /// Clear the display and draw the page at `index`, making it the current page. Returns
+    /// `CharacterDisplayError::IndexOutOfRange` when there is no page at `index`.
+    pub fn show(
+        &mut self,
+        lcd: &mut BaseCharacterDisplay<I2C, DELAY, DEVICE>,
+        index: usize,
+    ) -> Result<&mut Self, CharacterDisplayError<I2C>> {
+        if index >= N {
+            return Err(CharacterDisplayError::IndexOutOfRange { index });
+        }
+        self.current = index;
+        self.shown_ms = self.auto_cycle.map(|(clock, _)| clock.now_ms());
+        lcd.clear()?;
+        (self.pages[index])(lcd)?;
+        Ok(self)
+    }
+
+    /// Show the next page, going from the last page back to the first.
+    pub fn next(
+        &mut self,
+        lcd: &mut BaseCharacterDisplay<I2C, DELAY, DEVICE>,
+    ) -> Result<&mut Self, CharacterDisplayError<I2C>> {
+        self.show(lcd, (self.current + 1) % N.max(1))
+    }
+
+    /// Show the previous page, going from the first page back to the last.
+    pub fn previous(
+        &mut self,
+        lcd: &mut BaseCharacterDisplay<I2C, DELAY, DEVICE>,
+    ) -> Result<&mut Self, CharacterDisplayError<I2C>> {
+        self.show(lcd, (self.current + N.max(1) - 1) % N.max(1))
+    }
+
+    /// Draw the current page again without clearing the display, such as to show new values. The render callback
+    /// has to overwrite everything it drew before for this to leave no stale characters.
+    pub fn render(
+        &mut self,
+        lcd: &mut BaseCharacterDisplay<I2C, DELAY, DEVICE>,
+    ) -> Result<&mut Self, CharacterDisplayError<I2C>> {
+        if let Some(page) = self.pages.get_mut(self.current) {
+            page(lcd)?;
+        }
+        Ok(self)
+    }
+
+    /// Switch to the next page when cycling through the pages and the current page has been shown for the dwell
+    /// time. Call this regularly, such as once per main loop iteration. Returns whether the page was switched.
+    pub fn poll(
+        &mut self,
+        lcd: &mut BaseCharacterDisplay<I2C, DELAY, DEVICE>,
+    ) -> Result<bool, CharacterDisplayError<I2C>> {
+        let Some((clock, dwell_ms)) = self.auto_cycle else {
+            return Ok(false);
+        };
+        match self.shown_ms {
+            Some(shown_ms) if clock.now_ms().saturating_sub(shown_ms) < dwell_ms as u64 => {
+                Ok(false)
+            }
+            Some(_) => {
+                self.next(lcd)?;
+                Ok(true)
+            }
+            // the dwell time of the current page starts now
+            None => {
+                self.shown_ms = Some(clock.now_ms());
+                Ok(false)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+    use super::*;
+    use crate::{
+        testing::{aip31068_command_transaction, aip31068_data_transaction},
+        CharacterDisplayAIP31068, LcdDisplayType,
+    };
+    use core::cell::Cell;
+    use embedded_hal_mock::eh1::{delay::NoopDelay, i2c::Mock as I2cMock};
+
+    #[test]
+    fn test_pages() {
+        let i2c_address = 0x3e;
+        let expected_i2c_transactions = std::vec![
+            // switching pages clears the display and draws the whole page
+            aip31068_command_transaction(i2c_address, 0x01),
+            aip31068_data_transaction(i2c_address, b"T="),
+            aip31068_data_transaction(i2c_address, b"21"),
+            aip31068_data_transaction(i2c_address, b"T="),
+            aip31068_data_transaction(i2c_address, b"22"),
+            aip31068_command_transaction(i2c_address, 0x01),
+            aip31068_data_transaction(i2c_address, b"net"),
+            // cycling comes back around to the first page
+            aip31068_command_transaction(i2c_address, 0x01),
+            aip31068_data_transaction(i2c_address, b"T="),
+            aip31068_data_transaction(i2c_address, b"22"),
+            aip31068_command_transaction(i2c_address, 0x01),
+            aip31068_data_transaction(i2c_address, b"net"),
+        ];
+
+        let i2c = I2cMock::new(&expected_i2c_transactions);
+        let mut lcd = CharacterDisplayAIP31068::new(i2c, LcdDisplayType::Lcd16x2, NoopDelay::new());
+        let temperature = Cell::new(21);
+        let mut climate = |lcd: &mut CharacterDisplayAIP31068<_, _>| {
+            crate::lcd_write!(lcd, "T={}", temperature.get()).map(|_| ())
+        };
+        let mut network = |lcd: &mut CharacterDisplayAIP31068<_, _>| lcd.print("net").map(|_| ());
+        let now_ms = Cell::new(0);
+        let clock = || now_ms.get();
+        let mut pages = Pages::new([&mut climate, &mut network]);
+        pages.set_auto_cycle(&clock, 1000);
+
+        assert!(pages.show(&mut lcd, 0).is_ok());
+        temperature.set(22);
+        assert!(pages.render(&mut lcd).is_ok());
+        now_ms.set(999);
+        assert!(matches!(pages.poll(&mut lcd), Ok(false)));
+        now_ms.set(1000);
+        assert!(matches!(pages.poll(&mut lcd), Ok(true)));
+        assert_eq!(pages.current(), 1);
+        now_ms.set(2000);
+        assert!(matches!(pages.poll(&mut lcd), Ok(true)));
+        assert!(pages.previous(&mut lcd).is_ok());
+        assert_eq!(pages.current(), 1);
+        assert!(matches!(
+            pages.show(&mut lcd, 2),
+            Err(CharacterDisplayError::IndexOutOfRange { index: 2 })
+        ));
+        pages.stop_auto_cycle();
+        now_ms.set(9000);
+        assert!(matches!(pages.poll(&mut lcd), Ok(false)));
+        lcd.i2c().done();
+    }
+}
@@ -0,0 +1,122 @@
+/// The largest number of commands held by the command queue.
+pub(crate) const QUEUE_COMMANDS: usize = 16;
+/// The largest number of characters held by the command queue, enough to fill a 20x4 display.
+pub(crate) const QUEUE_DATA: usize = 80;
+
+/// A display write deferred by the command queue.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum QueuedCommand {
+    /// Move the cursor to `( col, row )`.
+    SetCursor(u8, u8),
+    /// Write the characters at `start..start + len` of the queued data.
+    Data { start: u8, len: u8 },
+    /// Clear the display.
+    Clear,
+    /// Move the cursor home.
+    Home,
+}
+
+/// Display writes deferred until the next flush when deferred mode is enabled. Commands are merged as they are
+/// queued, so the flush writes them in as few I2C transactions as possible: printed text that continues the
+/// previous text is written together, a cursor move replaces a cursor move right before it, and clearing the
+/// display drops everything queued before it.
+///
+/// The queue is supplied by the application to `BaseCharacterDisplay::with_deferred_writes()`, so displays that do
+/// not use deferred mode do not hold one.
+#[derive(Debug, Clone)]
+pub struct CommandQueue {
+    commands: [QueuedCommand; QUEUE_COMMANDS],
+    command_count: usize,
+    data: [u8; QUEUE_DATA],
+    data_len: usize,
+}
+
+impl Default for CommandQueue {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CommandQueue {
+    /// Create an empty queue.
+    pub const fn new() -> Self {
+        Self {
+            commands: [QueuedCommand::Home; QUEUE_COMMANDS],
+            command_count: 0,
+            data: [0; QUEUE_DATA],
+            data_len: 0,
+        }
+    }
+
+    /// Returns whether nothing is queued.
+    pub(crate) fn is_empty(&self) -> bool {
+        self.command_count == 0
+    }
+
+    /// Returns whether a command with `data_len` characters can be queued without flushing first.
+    pub(crate) fn has_room(&self, command: QueuedCommand, data_len: usize) -> bool {
+        let merges = matches!(
+            (command, self.commands().last()),
+            (
+                QueuedCommand::SetCursor(..),
+                Some(QueuedCommand::SetCursor(..))
+            ) | (QueuedCommand::Data { .. }, Some(QueuedCommand::Data { .. }))
+                | (QueuedCommand::Clear, _)
+        );
+        (merges || self.command_count < QUEUE_COMMANDS) && self.data_len + data_len <= QUEUE_DATA
+    }
+
+    /// Queue a command, merging it with the commands before it where possible. `data` is the characters of a
+    /// `QueuedCommand::Data` command, whose `start` and `len` are filled in. The caller checks `has_room()` first.
+    pub(crate) fn push(&mut self, command: QueuedCommand, data: &[u8]) {
+        let last = self.commands().last().copied();
+        match (command, last) {
+            (QueuedCommand::SetCursor(..), Some(QueuedCommand::SetCursor(..))) => {
+                self.commands[self.command_count - 1] = command;
+            }
+            (QueuedCommand::Data { .. }, Some(QueuedCommand::Data { start, len })) => {
+                self.data[self.data_len..self.data_len + data.len()].copy_from_slice(data);
+                self.data_len += data.len();
+                self.commands[self.command_count - 1] = QueuedCommand::Data {
+                    start,
+                    len: len + data.len() as u8,
+                };
+            }
+            (QueuedCommand::Data { .. }, _) => {
+                self.data[self.data_len..self.data_len + data.len()].copy_from_slice(data);
+                self.commands[self.command_count] = QueuedCommand::Data {
+                    start: self.data_len as u8,
+                    len: data.len() as u8,
+                };
+                self.command_count += 1;
+                self.data_len += data.len();
+            }
+            (QueuedCommand::Clear, _) => {
+                // nothing written before the clear would be seen
+                self.commands[0] = command;
+                self.command_count = 1;
+                self.data_len = 0;
+            }
+            _ => {
+                self.commands[self.command_count] = command;
+                self.command_count += 1;
+            }
+        }
+    }
+
+    /// The queued commands, in order.
+    pub(crate) fn commands(&self) -> &[QueuedCommand] {
+        &self.commands[..self.command_count]
+    }
+
+    /// The characters of a queued `QueuedCommand::Data` command.
+    pub(crate) fn data(&self, start: u8, len: u8) -> &[u8] {
+        &self.data[start as usize..start as usize + len as usize]
+    }
+
+    /// Empty the queue.
+    pub(crate) fn clear(&mut self) {
+        self.command_count = 0;
+        self.data_len = 0;
+    }
+}
@@ -0,0 +1,124 @@
+//! Limiting how often the display is refreshed, so that an application updating it in a tight loop does not saturate
+//! an I2C bus shared with other peripherals. The application draws into the framebuffer as often as it likes and
+//! calls [`RefreshLimiter::flush`] after every update. Updates to the same row or field in between flushes overwrite
+//! each other in the framebuffer, so only the latest contents are written to the display, at most once per interval.
+//!
+//! ```rust
+//! use i2c_character_display::rate_limit::RefreshLimiter;
+//!
+//! let clock = || timer.now().duration_since_epoch().to_millis();
+//! static FRAMEBUFFER: StaticCell<Framebuffer> = StaticCell::new();
+//! let mut lcd = CharacterDisplayPCF8574T::new(i2c, LcdDisplayType::Lcd16x2, delay)
+//!     .with_framebuffer(FRAMEBUFFER.init(Framebuffer::new()));
+//! let mut limiter = RefreshLimiter::new(&clock, 200);
+//! loop {
+//!     write!(lcd.set_cursor(0, 0)?, "{:5.1}C", sensor.read()?)?;
+//!     limiter.flush(&mut lcd)?;
+//! }
+//! ```
+
+use embedded_hal::{delay::DelayNs, i2c};
+
+use crate::{clock::Clock, driver::DriverTrait, BaseCharacterDisplay, CharacterDisplayError};
+
+/// Flushes the framebuffer of a display at most once per interval. A change made after the display has been idle
+/// for a whole interval is flushed right away, so the limit only delays updates that come in rapid succession.
+/// Requires framebuffer mode, without which there is nothing to flush.
+pub struct RefreshLimiter<'a> {
+    clock: &'a dyn Clock,
+    min_interval_ms: u32,
+    /// The time of the last flush that wrote to the display
+    last_refresh_ms: Option<u64>,
+}
+
+impl<'a> RefreshLimiter<'a> {
+    /// Create a limiter that flushes at most once every `min_interval_ms` milliseconds as measured by `clock`.
+    pub fn new(clock: &'a dyn Clock, min_interval_ms: u32) -> Self {
+        Self {
+            clock,
+            min_interval_ms,
+            last_refresh_ms: None,
+        }
+    }
+
+    /// Returns whether a flush would be allowed to write to the display now.
+    pub fn is_due(&self) -> bool {
+        match self.last_refresh_ms {
+            Some(last) => self.clock.now_ms().saturating_sub(last) >= self.min_interval_ms as u64,
+            None => true,
+        }
+    }
+
+    /// Flush the framebuffer of the display if it has changes and the interval since the last refresh has passed,
+    /// returning whether the display was written to. Changes that are held back stay in the framebuffer for a later
+    /// call, so call this after every update and once more when the application goes idle.
+    pub fn flush<I2C, DELAY, DEVICE>(
+        &mut self,
+        lcd: &mut BaseCharacterDisplay<I2C, DELAY, DEVICE>,
+    ) -> Result<bool, CharacterDisplayError<I2C>>
+    where
+        I2C: i2c::I2c,
+        DELAY: DelayNs,
+        DEVICE: DriverTrait<I2C, DELAY>,
+    {
+        if !lcd.has_pending_changes() || !self.is_due() {
+            return Ok(false);
+        }
+        self.last_refresh_ms = Some(self.clock.now_ms());
+        lcd.flush()?;
+        Ok(true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+    use super::*;
+    use crate::{
+        testing::{aip31068_command_transaction, aip31068_data_transaction},
+        CharacterDisplayAIP31068, LcdDisplayType,
+    };
+    use core::cell::Cell;
+    use embedded_hal_mock::eh1::{delay::NoopDelay, i2c::Mock as I2cMock};
+
+    #[test]
+    fn test_refresh_limiter() {
+        let i2c_address = 0x3e;
+        let expected_i2c_transactions = std::vec![
+            aip31068_command_transaction(i2c_address, 0x80),
+            aip31068_data_transaction(i2c_address, b"1"),
+            // the updates in between are coalesced into the latest one
+            aip31068_command_transaction(i2c_address, 0x80),
+            aip31068_data_transaction(i2c_address, b"3"),
+            // a change after an idle interval is flushed right away
+            aip31068_command_transaction(i2c_address, 0x80),
+            aip31068_data_transaction(i2c_address, b"4"),
+        ];
+
+        let i2c = I2cMock::new(&expected_i2c_transactions);
+        let mut lcd = CharacterDisplayAIP31068::new(i2c, LcdDisplayType::Lcd16x2, NoopDelay::new())
+            .with_framebuffer(std::boxed::Box::leak(std::boxed::Box::default()));
+        let now = Cell::new(0_u64);
+        let clock = || now.get();
+        let mut limiter = RefreshLimiter::new(&clock, 100);
+        assert!(!lcd.has_pending_changes());
+        assert_eq!(limiter.flush(&mut lcd).ok(), Some(false));
+
+        for (time, text, flushed) in [(0, "1", true), (10, "2", false), (50, "3", false)] {
+            now.set(time);
+            assert!(lcd.print_at(0, 0, text).is_ok());
+            assert_eq!(limiter.flush(&mut lcd).ok(), Some(flushed));
+        }
+        assert!(lcd.has_pending_changes());
+        now.set(100);
+        assert_eq!(limiter.flush(&mut lcd).ok(), Some(true));
+        assert!(!lcd.has_pending_changes());
+        now.set(150);
+        assert_eq!(limiter.flush(&mut lcd).ok(), Some(false));
+
+        now.set(300);
+        assert!(lcd.print_at(0, 0, "4").is_ok());
+        assert_eq!(limiter.flush(&mut lcd).ok(), Some(true));
+        lcd.i2c().done();
+    }
+}
@@ -0,0 +1,306 @@
+//! Constructors for displays on an I2C bus shared with other devices, such as sensors, through the bus sharing
+//! devices of [`embedded-hal-bus`](https://crates.io/crates/embedded-hal-bus), enabled by the `embedded-hal-bus`
+//! feature, or of [`embassy-embedded-hal`](https://crates.io/crates/embassy-embedded-hal), enabled by the `embassy`
+//! feature. Each device on the bus gets its own bus sharing device for the same bus, and every I2C transaction of
+//! the display is made with the bus locked, so transactions of the display and the other devices never interleave.
+//! On a bus used only from the main loop, a `RefCellDevice` shares the bus without a critical section.
+//!
+//! ```rust
+//! use core::cell::RefCell;
+//! use critical_section::Mutex;
+//! use embedded_hal_bus::i2c::CriticalSectionDevice;
+//! use i2c_character_display::{CharacterDisplayPCF8574T, LcdDisplayType};
+//!
+//! let bus = Mutex::new(RefCell::new(i2c));
+//! let mut lcd = CharacterDisplayPCF8574T::new_critical_section(&bus, LcdDisplayType::Lcd16x2, delay);
+//! let mut sensor = Bme280::new(CriticalSectionDevice::new(&bus));
+//! lcd.init()?;
+//! lcd.print_at(0, 0, "Ready")?;
+//! ```
+//!
+//! In async firmware built on Embassy, the display shares the bus through the blocking `I2cDevice` of
+//! `embassy_embedded_hal::shared_bus`. The display driver is blocking, so it cannot use the async `I2cDevice`, and
+//! the other devices on the bus share it through blocking `I2cDevice`s too. The bus is locked with the raw mutex
+//! chosen for the bus, such as a `CriticalSectionRawMutex` when it is used from more than one executor.
+//!
+//! ```rust
+//! use core::cell::RefCell;
+//! use embassy_embedded_hal::shared_bus::blocking::i2c::I2cDevice;
+//! use embassy_sync::blocking_mutex::{raw::NoopRawMutex, Mutex};
+//! use i2c_character_display::{CharacterDisplayPCF8574T, LcdDisplayType};
+//! use static_cell::StaticCell;
+//!
+//! static I2C_BUS: StaticCell<Mutex<NoopRawMutex, RefCell<Twim<TWISPI0>>>> = StaticCell::new();
+//! let bus = I2C_BUS.init(Mutex::new(RefCell::new(i2c)));
+//! let mut lcd = CharacterDisplayPCF8574T::new_embassy_shared(bus, LcdDisplayType::Lcd16x2, Delay);
+//! let mut sensor = Bme280::new(I2cDevice::new(bus));
+//! lcd.init()?;
+//! ```
+
+use core::cell::RefCell;
+
+#[cfg(feature = "embedded-hal-bus")]
+use critical_section::Mutex;
+#[cfg(feature = "embassy")]
+use embassy_embedded_hal::shared_bus::blocking::i2c::I2cDevice;
+#[cfg(feature = "embassy")]
+use embassy_sync::blocking_mutex::{raw::RawMutex, Mutex as EmbassyMutex};
+use embedded_hal::{delay::DelayNs, i2c};
+#[cfg(feature = "embedded-hal-bus")]
+use embedded_hal_bus::i2c::{CriticalSectionDevice, RefCellDevice};
+#[cfg(all(feature = "embedded-hal-bus", target_has_atomic = "8"))]
+use embedded_hal_bus::{i2c::AtomicDevice, util::AtomicCell};
+
+use crate::{driver::DriverTrait, BaseCharacterDisplay, LcdDisplayType};
+
+#[cfg(feature = "embedded-hal-bus")]
+impl<'a, BUS, DELAY, DEVICE> BaseCharacterDisplay<CriticalSectionDevice<'a, BUS>, DELAY, DEVICE>
+where
+    BUS: i2c::I2c,
+    DELAY: DelayNs,
+    DEVICE: DriverTrait<CriticalSectionDevice<'a, BUS>, DELAY>,
+{
+    /// Create a new character display object on an I2C bus shared through a critical section mutex, with the
+    /// default I2C address for the adapter. The bus can be shared with other devices, including ones used from
+    /// interrupt handlers.
+    pub fn new_critical_section(
+        bus: &'a Mutex<RefCell<BUS>>,
+        lcd_type: LcdDisplayType,
+        delay: DELAY,
+    ) -> Self {
+        Self::new(CriticalSectionDevice::new(bus), lcd_type, delay)
+    }
+
+    /// Create a new character display object on an I2C bus shared through a critical section mutex, with a
+    /// specific I2C address for the adapter.
+    pub fn new_critical_section_with_address(
+        bus: &'a Mutex<RefCell<BUS>>,
+        address: u8,
+        lcd_type: LcdDisplayType,
+        delay: DELAY,
+    ) -> Self {
+        Self::new_with_address(CriticalSectionDevice::new(bus), address, lcd_type, delay)
+    }
+}
+
+#[cfg(feature = "embedded-hal-bus")]
+impl<'a, BUS, DELAY, DEVICE> BaseCharacterDisplay<RefCellDevice<'a, BUS>, DELAY, DEVICE>
+where
+    BUS: i2c::I2c,
+    DELAY: DelayNs,
+    DEVICE: DriverTrait<RefCellDevice<'a, BUS>, DELAY>,
+{
+    /// Create a new character display object on an I2C bus shared through a `RefCell`, with the default I2C address
+    /// for the adapter. The bus can be shared with other devices used from the same thread, but not from interrupt
+    /// handlers.
+    pub fn new_ref_cell(bus: &'a RefCell<BUS>, lcd_type: LcdDisplayType, delay: DELAY) -> Self {
+        Self::new(RefCellDevice::new(bus), lcd_type, delay)
+    }
+
+    /// Create a new character display object on an I2C bus shared through a `RefCell`, with a specific I2C address
+    /// for the adapter.
+    pub fn new_ref_cell_with_address(
+        bus: &'a RefCell<BUS>,
+        address: u8,
+        lcd_type: LcdDisplayType,
+        delay: DELAY,
+    ) -> Self {
+        Self::new_with_address(RefCellDevice::new(bus), address, lcd_type, delay)
+    }
+}
+
+#[cfg(all(feature = "embedded-hal-bus", target_has_atomic = "8"))]
+impl<'a, BUS, DELAY, DEVICE> BaseCharacterDisplay<AtomicDevice<'a, BUS>, DELAY, DEVICE>
+where
+    BUS: i2c::I2c,
+    DELAY: DelayNs,
+    DEVICE: DriverTrait<AtomicDevice<'a, BUS>, DELAY>,
+{
+    /// Create a new character display object on an I2C bus shared through an atomic flag, with the default I2C
+    /// address for the adapter. Unlike a critical section, the flag does not disable interrupts while the bus is
+    /// used, but a transaction made while another one is in progress, such as from an interrupt handler, fails with
+    /// `AtomicError::Busy` rather than waiting.
+    pub fn new_atomic(bus: &'a AtomicCell<BUS>, lcd_type: LcdDisplayType, delay: DELAY) -> Self {
+        Self::new(AtomicDevice::new(bus), lcd_type, delay)
+    }
+
+    /// Create a new character display object on an I2C bus shared through an atomic flag, with a specific I2C
+    /// address for the adapter.
+    pub fn new_atomic_with_address(
+        bus: &'a AtomicCell<BUS>,
+        address: u8,
+        lcd_type: LcdDisplayType,
+        delay: DELAY,
+    ) -> Self {
+        Self::new_with_address(AtomicDevice::new(bus), address, lcd_type, delay)
+    }
+}
+
+#[cfg(feature = "embassy")]
+impl<'a, M, BUS, DELAY, DEVICE> BaseCharacterDisplay<I2cDevice<'a, M, BUS>, DELAY, DEVICE>
+where
+    M: RawMutex,
+    BUS: i2c::I2c,
+    DELAY: DelayNs,
+    DEVICE: DriverTrait<I2cDevice<'a, M, BUS>, DELAY>,
+{
+    /// Create a new character display object on an I2C bus shared through an Embassy blocking mutex, with the
+    /// default I2C address for the adapter. The bus can be shared with the other devices of Embassy firmware that
+    /// use the blocking `I2cDevice` of `embassy_embedded_hal::shared_bus`.
+    pub fn new_embassy_shared(
+        bus: &'a EmbassyMutex<M, RefCell<BUS>>,
+        lcd_type: LcdDisplayType,
+        delay: DELAY,
+    ) -> Self {
+        Self::new(I2cDevice::new(bus), lcd_type, delay)
+    }
+
+    /// Create a new character display object on an I2C bus shared through an Embassy blocking mutex, with a
+    /// specific I2C address for the adapter.
+    pub fn new_embassy_shared_with_address(
+        bus: &'a EmbassyMutex<M, RefCell<BUS>>,
+        address: u8,
+        lcd_type: LcdDisplayType,
+        delay: DELAY,
+    ) -> Self {
+        Self::new_with_address(I2cDevice::new(bus), address, lcd_type, delay)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+    use super::*;
+    use crate::{
+        testing::{hd44780_packed_data_transactions, pcf8574_gpio},
+        CharacterDisplayPCF8574T,
+    };
+    use embedded_hal::i2c::I2c;
+    use embedded_hal_mock::eh1::{
+        delay::NoopDelay,
+        i2c::{Mock as I2cMock, Transaction as I2cTransaction},
+    };
+
+    /// The transactions of turning the backlight off, with a sensor write made between them.
+    fn shared_bus_transactions() -> std::vec::Vec<I2cTransaction> {
+        let backlight_off = I2cTransaction::write(0x27, pcf8574_gpio(false, false)(0, false));
+        std::vec![
+            backlight_off.clone(),
+            I2cTransaction::write(0x76, std::vec![0xF4, 0x27]),
+            backlight_off,
+        ]
+    }
+
+    /// The transactions of printing "Hi" on a PCF8574 adapter at 0x3F.
+    fn print_transactions() -> std::vec::Vec<I2cTransaction> {
+        hd44780_packed_data_transactions(0x3F, b"Hi", pcf8574_gpio(true, false))
+    }
+
+    #[cfg(feature = "embedded-hal-bus")]
+    #[test]
+    fn test_critical_section_bus() {
+        // the mock is shared by its clones, so it can be checked after moving a clone into the bus
+        let mut i2c = I2cMock::new(&shared_bus_transactions());
+        let bus = Mutex::new(RefCell::new(i2c.clone()));
+        let mut lcd = CharacterDisplayPCF8574T::new_critical_section(
+            &bus,
+            LcdDisplayType::Lcd16x2,
+            NoopDelay::new(),
+        );
+        let mut sensor = CriticalSectionDevice::new(&bus);
+        assert!(lcd.backlight(false).is_ok());
+        assert!(sensor.write(0x76, &[0xF4, 0x27]).is_ok());
+        assert!(lcd.backlight(false).is_ok());
+        i2c.done();
+    }
+
+    #[cfg(feature = "embedded-hal-bus")]
+    #[test]
+    fn test_critical_section_bus_with_address() {
+        let mut i2c = I2cMock::new(&print_transactions());
+        let bus = Mutex::new(RefCell::new(i2c.clone()));
+        let mut lcd = CharacterDisplayPCF8574T::new_critical_section_with_address(
+            &bus,
+            0x3F,
+            LcdDisplayType::Lcd16x2,
+            NoopDelay::new(),
+        );
+        assert!(lcd.print("Hi").is_ok());
+        i2c.done();
+    }
+
+    #[cfg(feature = "embedded-hal-bus")]
+    #[test]
+    fn test_ref_cell_bus() {
+        let mut i2c = I2cMock::new(&shared_bus_transactions());
+        let bus = RefCell::new(i2c.clone());
+        // the delay provider is borrowed, so it can be shared with other drivers too
+        let mut delay = NoopDelay::new();
+        let mut lcd =
+            CharacterDisplayPCF8574T::new_ref_cell(&bus, LcdDisplayType::Lcd16x2, &mut delay);
+        let mut sensor = RefCellDevice::new(&bus);
+        assert!(lcd.backlight(false).is_ok());
+        assert!(sensor.write(0x76, &[0xF4, 0x27]).is_ok());
+        assert!(lcd.backlight(false).is_ok());
+        i2c.done();
+    }
+
+    #[cfg(feature = "embedded-hal-bus")]
+    #[test]
+    fn test_atomic_bus() {
+        let mut i2c = I2cMock::new(&shared_bus_transactions());
+        let bus = AtomicCell::new(i2c.clone());
+        let mut lcd =
+            CharacterDisplayPCF8574T::new_atomic(&bus, LcdDisplayType::Lcd16x2, NoopDelay::new());
+        let mut sensor = AtomicDevice::new(&bus);
+        assert!(lcd.backlight(false).is_ok());
+        assert!(sensor.write(0x76, &[0xF4, 0x27]).is_ok());
+        assert!(lcd.backlight(false).is_ok());
+        i2c.done();
+    }
+
+    #[cfg(feature = "embedded-hal-bus")]
+    #[test]
+    fn test_atomic_bus_with_address() {
+        let mut i2c = I2cMock::new(&print_transactions());
+        let bus = AtomicCell::new(i2c.clone());
+        let mut lcd = CharacterDisplayPCF8574T::new_atomic_with_address(
+            &bus,
+            0x3F,
+            LcdDisplayType::Lcd16x2,
+            NoopDelay::new(),
+        );
+        assert!(lcd.print("Hi").is_ok());
+        i2c.done();
+    }
+
+    #[cfg(feature = "embassy")]
+    #[test]
+    fn test_embassy_shared_bus() {
+        use embassy_sync::blocking_mutex::raw::NoopRawMutex;
+
+        let mut i2c = I2cMock::new(&shared_bus_transactions());
+        let bus = EmbassyMutex::<NoopRawMutex, _>::new(RefCell::new(i2c.clone()));
+        let mut lcd = CharacterDisplayPCF8574T::new_embassy_shared(
+            &bus,
+            LcdDisplayType::Lcd16x2,
+            NoopDelay::new(),
+        );
+        let mut sensor = I2cDevice::new(&bus);
+        assert!(lcd.backlight(false).is_ok());
+        assert!(sensor.write(0x76, &[0xF4, 0x27]).is_ok());
+        assert!(lcd.backlight(false).is_ok());
+        i2c.done();
+
+        let mut i2c = I2cMock::new(&print_transactions());
+        let bus = EmbassyMutex::<NoopRawMutex, _>::new(RefCell::new(i2c.clone()));
+        let mut lcd = CharacterDisplayPCF8574T::new_embassy_shared_with_address(
+            &bus,
+            0x3F,
+            LcdDisplayType::Lcd16x2,
+            NoopDelay::new(),
+        );
+        assert!(lcd.print("Hi").is_ok());
+        i2c.done();
+    }
+}
@@ -0,0 +1,180 @@
+//! An activity indicator for long-running operations. A [`Spinner`] shows the next frame of its animation in one
+//! cell of the display on each call to [`Spinner::tick`], so the application only has to call it regularly.
+//!
+//! ```rust
+//! use i2c_character_display::spinner::{Spinner, LINE_GLYPHS};
+//!
+//! lcd.print_at(0, 0, "Connecting")?;
+//! let mut spinner = Spinner::new(11, 0).with_glyphs(7, &LINE_GLYPHS);
+//! while !wifi.is_connected() {
+//!     spinner.tick(&mut lcd)?;
+//!     delay.delay_ms(150);
+//! }
+//! spinner.clear(&mut lcd)?;
+//! ```
+
+use embedded_hal::{delay::DelayNs, i2c};
+
+use crate::{driver::DriverTrait, BaseCharacterDisplay, CharacterDisplayError};
+
+/// The frames of a spinning line drawn with characters. The standard A00 character ROM of HD44780 compatible
+/// controllers shows a yen sign in place of the backslash, for which [`LINE_GLYPHS`] can be used instead.
+pub const LINE_FRAMES: [char; 4] = ['|', '/', '-', '\\'];
+
+/// The frames of a spinning line drawn as custom characters, which look the same on every character ROM.
+pub const LINE_GLYPHS: [[u8; 8]; 4] = [
+    [
+        0b00000, 0b00100, 0b00100, 0b00100, 0b00100, 0b00100, 0b00000, 0b00000,
+    ],
+    [
+        0b00000, 0b00001, 0b00010, 0b00100, 0b01000, 0b10000, 0b00000, 0b00000,
+    ],
+    [
+        0b00000, 0b00000, 0b00000, 0b11111, 0b00000, 0b00000, 0b00000, 0b00000,
+    ],
+    [
+        0b00000, 0b10000, 0b01000, 0b00100, 0b00010, 0b00001, 0b00000, 0b00000,
+    ],
+];
+
+/// The frames of a spinner animation.
+#[derive(Debug, Clone, Copy)]
+enum Frames<'a> {
+    /// Characters printed in the cell
+    Chars(&'a [char]),
+    /// Glyphs uploaded in turn to one custom character location, which the cell shows
+    Glyphs { slot: u8, glyphs: &'a [[u8; 8]] },
+}
+
+/// Cycles through the frames of an animation in the cell at a column and row, one frame per call to
+/// [`Spinner::tick`]. The frames are [`LINE_FRAMES`] unless set with [`Spinner::with_frames`] or
+/// [`Spinner::with_glyphs`].
+#[derive(Debug, Clone, Copy)]
+pub struct Spinner<'a> {
+    col: u8,
+    row: u8,
+    frames: Frames<'a>,
+    frame: usize,
+}
+
+impl<'a> Spinner<'a> {
+    /// Create a spinner in the cell at `col` and `row`.
+    pub const fn new(col: u8, row: u8) -> Self {
+        Self {
+            col,
+            row,
+            frames: Frames::Chars(&LINE_FRAMES),
+            frame: 0,
+        }
+    }
+
+    /// Animate the spinner with characters, such as `&['.', 'o', 'O', 'o']`.
+    pub const fn with_frames(mut self, frames: &'a [char]) -> Self {
+        self.frames = Frames::Chars(frames);
+        self
+    }
+
+    /// Animate the spinner with custom character glyphs, uploading the glyph of each frame to the custom character
+    /// location `slot` in turn, so the animation uses up only one location.
+    pub const fn with_glyphs(mut self, slot: u8, glyphs: &'a [[u8; 8]]) -> Self {
+        self.frames = Frames::Glyphs {
+            slot: slot & 0x7,
+            glyphs,
+        };
+        self
+    }
+
+    /// Start the animation again from its first frame.
+    pub fn reset(&mut self) -> &mut Self {
+        self.frame = 0;
+        self
+    }
+
+    /// Show the current frame in the cell, then advance to the next frame for the next call.
+    pub fn tick<I2C, DELAY, DEVICE>(
+        &mut self,
+        lcd: &mut BaseCharacterDisplay<I2C, DELAY, DEVICE>,
+    ) -> Result<&mut Self, CharacterDisplayError<I2C>>
+    where
+        I2C: i2c::I2c,
+        DELAY: DelayNs,
+        DEVICE: DriverTrait<I2C, DELAY>,
+    {
+        let (c, len) = match self.frames {
+            Frames::Chars(frames) => (frames.get(self.frame).copied(), frames.len()),
+            Frames::Glyphs { slot, glyphs } => match glyphs.get(self.frame) {
+                Some(glyph) => {
+                    lcd.create_char(slot, *glyph)?;
+                    (Some(char::from(slot)), glyphs.len())
+                }
+                None => (None, glyphs.len()),
+            },
+        };
+        if let Some(c) = c {
+            let mut buffer = [0u8; 4];
+            lcd.print_at(self.col, self.row, c.encode_utf8(&mut buffer))?;
+        }
+        self.frame = (self.frame + 1) % len.max(1);
+        Ok(self)
+    }
+
+    /// Blank the cell of the spinner, such as once the operation is done, and start the animation again from its
+    /// first frame.
+    pub fn clear<I2C, DELAY, DEVICE>(
+        &mut self,
+        lcd: &mut BaseCharacterDisplay<I2C, DELAY, DEVICE>,
+    ) -> Result<&mut Self, CharacterDisplayError<I2C>>
+    where
+        I2C: i2c::I2c,
+        DELAY: DelayNs,
+        DEVICE: DriverTrait<I2C, DELAY>,
+    {
+        lcd.print_at(self.col, self.row, " ")?;
+        self.frame = 0;
+        Ok(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+    use super::*;
+    use crate::{
+        testing::{aip31068_command_transaction, aip31068_data_transaction},
+        CharacterDisplayAIP31068, LcdDisplayType,
+    };
+    use embedded_hal_mock::eh1::{delay::NoopDelay, i2c::Mock as I2cMock};
+
+    #[test]
+    fn test_spinner_tick() {
+        let i2c_address = 0x3e;
+        let mut expected_i2c_transactions = std::vec::Vec::new();
+        for frame in [b"|", b"/", b"-", b"\\", b"|", b" "] {
+            expected_i2c_transactions.push(aip31068_command_transaction(i2c_address, 0x80 | 0x4F));
+            expected_i2c_transactions.push(aip31068_data_transaction(i2c_address, frame));
+        }
+        // the glyph of each frame is uploaded to the same custom character location
+        for glyph in &LINE_GLYPHS[..2] {
+            expected_i2c_transactions
+                .push(aip31068_command_transaction(i2c_address, 0x40 | 7 << 3));
+            expected_i2c_transactions.push(aip31068_data_transaction(i2c_address, glyph));
+            expected_i2c_transactions.push(aip31068_command_transaction(i2c_address, 0x80));
+            expected_i2c_transactions.push(aip31068_data_transaction(i2c_address, &[7]));
+        }
+
+        let i2c = I2cMock::new(&expected_i2c_transactions);
+        let mut lcd = CharacterDisplayAIP31068::new(i2c, LcdDisplayType::Lcd16x2, NoopDelay::new());
+        let mut spinner = Spinner::new(15, 1);
+        for _ in 0..5 {
+            assert!(spinner.tick(&mut lcd).is_ok());
+        }
+        assert!(spinner.clear(&mut lcd).is_ok());
+        let mut spinner = Spinner::new(0, 0).with_glyphs(7, &LINE_GLYPHS);
+        assert!(spinner.tick(&mut lcd).is_ok());
+        assert!(spinner.tick(&mut lcd).is_ok());
+        // an animation without frames shows nothing
+        let mut spinner = Spinner::new(0, 0).with_frames(&[]);
+        assert!(spinner.tick(&mut lcd).is_ok());
+        lcd.i2c().done();
+    }
+}
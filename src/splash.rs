@@ -0,0 +1,154 @@
+//! A startup splash that animates one cell of the display while the application initializes, such as a spinning
+//! logo. The frames of the animation are custom characters, up to one per custom character location, and a
+//! [`Splash`] shows the next frame every time the application reports progress.
+//!
+//! ```rust
+//! use i2c_character_display::splash::Splash;
+//!
+//! const SPINNER: [[u8; 8]; 4] = [SPINNER_UP, SPINNER_RIGHT, SPINNER_DOWN, SPINNER_LEFT];
+//!
+//! lcd.print_at(0, 0, "Starting")?;
+//! let mut splash = Splash::new(&SPINNER, 9, 0);
+//! splash.start(&mut lcd)?;
+//! connect_wifi(|| splash.tick(&mut lcd))?;
+//! mount_storage(|| splash.tick(&mut lcd))?;
+//! splash.finish(&mut lcd)?;
+//! ```
+
+use embedded_hal::{delay::DelayNs, i2c};
+
+use crate::{driver::DriverTrait, BaseCharacterDisplay, CharacterDisplayError};
+
+/// The most frames of an animation, which is the number of custom character locations.
+const MAX_FRAMES: usize = 8;
+
+/// Animates one cell of the display by cycling through up to 8 frames, which are uploaded to the custom character
+/// locations from 0 up by [`Splash::start`]. Frames past the eighth are ignored.
+#[derive(Debug, Clone, Copy)]
+pub struct Splash<'a> {
+    frames: &'a [[u8; 8]],
+    col: u8,
+    row: u8,
+    frame: usize,
+}
+
+impl<'a> Splash<'a> {
+    /// Create a splash animating the cell at `col` and `row` with `frames`.
+    pub fn new(frames: &'a [[u8; 8]], col: u8, row: u8) -> Self {
+        Self {
+            frames: &frames[..frames.len().min(MAX_FRAMES)],
+            col,
+            row,
+            frame: 0,
+        }
+    }
+
+    /// Upload the frames to the custom character locations and show the first frame.
+    pub fn start<I2C, DELAY, DEVICE>(
+        &mut self,
+        lcd: &mut BaseCharacterDisplay<I2C, DELAY, DEVICE>,
+    ) -> Result<(), CharacterDisplayError<I2C>>
+    where
+        I2C: i2c::I2c,
+        DELAY: DelayNs,
+        DEVICE: DriverTrait<I2C, DELAY>,
+    {
+        for (location, frame) in self.frames.iter().enumerate() {
+            lcd.create_char(location as u8, *frame)?;
+        }
+        self.frame = 0;
+        self.show(lcd)
+    }
+
+    /// Show the next frame, going back to the first frame after the last. Call this from the progress callbacks of
+    /// the application's initialization.
+    pub fn tick<I2C, DELAY, DEVICE>(
+        &mut self,
+        lcd: &mut BaseCharacterDisplay<I2C, DELAY, DEVICE>,
+    ) -> Result<(), CharacterDisplayError<I2C>>
+    where
+        I2C: i2c::I2c,
+        DELAY: DelayNs,
+        DEVICE: DriverTrait<I2C, DELAY>,
+    {
+        if self.frames.is_empty() {
+            return Ok(());
+        }
+        self.frame = (self.frame + 1) % self.frames.len();
+        self.show(lcd)
+    }
+
+    /// Blank the animated cell once initialization is done. The custom character locations can then be reused.
+    pub fn finish<I2C, DELAY, DEVICE>(
+        &mut self,
+        lcd: &mut BaseCharacterDisplay<I2C, DELAY, DEVICE>,
+    ) -> Result<(), CharacterDisplayError<I2C>>
+    where
+        I2C: i2c::I2c,
+        DELAY: DelayNs,
+        DEVICE: DriverTrait<I2C, DELAY>,
+    {
+        lcd.print_at(self.col, self.row, " ")?;
+        Ok(())
+    }
+
+    /// Print the current frame in the animated cell.
+    fn show<I2C, DELAY, DEVICE>(
+        &self,
+        lcd: &mut BaseCharacterDisplay<I2C, DELAY, DEVICE>,
+    ) -> Result<(), CharacterDisplayError<I2C>>
+    where
+        I2C: i2c::I2c,
+        DELAY: DelayNs,
+        DEVICE: DriverTrait<I2C, DELAY>,
+    {
+        if self.frames.is_empty() {
+            return Ok(());
+        }
+        // the frame is a custom character code, which is valid single byte UTF-8
+        let code = [self.frame as u8];
+        let text = core::str::from_utf8(&code).map_err(|_| core::fmt::Error)?;
+        lcd.print_at(self.col, self.row, text)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+    use super::*;
+    use crate::{
+        testing::{aip31068_command_transaction, aip31068_data_transaction},
+        CharacterDisplayAIP31068, LcdDisplayType,
+    };
+    use embedded_hal_mock::eh1::{delay::NoopDelay, i2c::Mock as I2cMock};
+
+    #[test]
+    fn test_splash() {
+        let i2c_address = 0x3e;
+        let frames = [[0x01; 8], [0x02; 8], [0x04; 8]];
+        let mut expected_i2c_transactions = std::vec::Vec::new();
+        for (location, frame) in frames.iter().enumerate() {
+            expected_i2c_transactions.push(aip31068_command_transaction(
+                i2c_address,
+                0x40 | (location as u8) << 3,
+            ));
+            expected_i2c_transactions.push(aip31068_data_transaction(i2c_address, frame));
+        }
+        // the frames cycle in the cell at column 9 of row 0
+        for code in [0, 1, 2, 0, b' '] {
+            expected_i2c_transactions.push(aip31068_command_transaction(i2c_address, 0x80 | 0x09));
+            expected_i2c_transactions.push(aip31068_data_transaction(i2c_address, &[code]));
+        }
+
+        let i2c = I2cMock::new(&expected_i2c_transactions);
+        let mut lcd = CharacterDisplayAIP31068::new(i2c, LcdDisplayType::Lcd16x2, NoopDelay::new());
+        let mut splash = Splash::new(&frames, 9, 0);
+        assert!(splash.start(&mut lcd).is_ok());
+        for _ in 0..3 {
+            assert!(splash.tick(&mut lcd).is_ok());
+        }
+        assert!(splash.finish(&mut lcd).is_ok());
+        lcd.i2c().done();
+    }
+}
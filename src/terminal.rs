@@ -0,0 +1,59 @@
+/// The largest number of rows held by the terminal line buffer.
+pub(crate) const TERMINAL_ROWS: usize = 4;
+/// The largest number of columns held by the terminal line buffer.
+pub(crate) const TERMINAL_COLS: usize = 40;
+
+/// The lines printed in terminal mode, kept to redraw the display shifted up by one row when printing runs past the
+/// last row.
+///
+/// The buffer is supplied by the application to `BaseCharacterDisplay::with_terminal()`, so displays that do not use
+/// terminal mode do not hold one.
+#[derive(Debug, Clone)]
+pub struct TerminalBuffer {
+    /// The character codes shown on each row
+    pub(crate) lines: [[u8; TERMINAL_COLS]; TERMINAL_ROWS],
+    /// The position the next character is printed to, as `( col, row )`. A column equal to the number of columns of
+    /// the display means the row is full, and the next character continues on the next row.
+    pub(crate) cursor: (u8, u8),
+}
+
+impl Default for TerminalBuffer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TerminalBuffer {
+    /// Create the line buffer of a blank display.
+    pub const fn new() -> Self {
+        Self {
+            lines: [[b' '; TERMINAL_COLS]; TERMINAL_ROWS],
+            cursor: (0, 0),
+        }
+    }
+
+    /// Blank all the lines and move the cursor home.
+    pub(crate) fn clear(&mut self) {
+        *self = Self::new();
+    }
+
+    /// Record the character code shown at `( col, row )`.
+    pub(crate) fn put(&mut self, col: u8, row: u8, code: u8) {
+        self.lines[row as usize][col as usize] = code;
+    }
+
+    /// Move the cursor to the start of the next row of a display with `rows` rows. On the last row, the lines are
+    /// shifted up by one row instead, blanking the last row, and `true` is returned, since the display has to be
+    /// redrawn.
+    pub(crate) fn new_line(&mut self, rows: u8) -> bool {
+        let (_, row) = self.cursor;
+        if row + 1 < rows {
+            self.cursor = (0, row + 1);
+            return false;
+        }
+        self.lines.copy_within(1..rows as usize, 0);
+        self.lines[rows as usize - 1] = [b' '; TERMINAL_COLS];
+        self.cursor = (0, rows - 1);
+        true
+    }
+}
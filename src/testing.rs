@@ -0,0 +1,185 @@
+//! Helpers for building the I2C transactions expected by an `embedded-hal-mock` I2C mock, for testing code that
+//! drives a character display, including custom HD44780 adapter implementations. These are the same helpers used by
+//! this crate's own tests. Enabled by the `testing` feature, which requires `std`.
+//!
+//! ```rust
+//! use i2c_character_display::testing::{hd44780_byte_transactions, pcf8574_gpio, I2cMock, NoopDelay};
+//!
+//! // set cursor to (2, 1) on a 16x2 display with a PCF8574T adapter
+//! let expected = hd44780_byte_transactions(0x27, 0x80 | 0x42, pcf8574_gpio(false, false));
+//! let mut i2c = I2cMock::new(&expected);
+//! let mut lcd = CharacterDisplayPCF8574T::new(i2c.clone(), LcdDisplayType::Lcd16x2, NoopDelay::new());
+//! lcd.set_cursor(2, 1).unwrap();
+//! i2c.done();
+//! ```
+
+extern crate std;
+use std::{vec, vec::Vec};
+
+use crate::driver::hd44780::adapter::PACKED_WRITE_BUFFER_SIZE;
+
+pub use embedded_hal_mock::eh1::{
+    delay::NoopDelay,
+    i2c::{Mock as I2cMock, Transaction as I2cTransaction},
+};
+
+/// The expected transactions for writing the low nibble of `nibble` to an HD44780 controller through a 4-bit
+/// adapter: one write with the enable pin high and one with it low. `gpio(nibble, enable)` returns the bytes the
+/// adapter writes to its GPIO expander for that nibble and enable pin state.
+pub fn hd44780_nibble_transactions<F>(address: u8, nibble: u8, mut gpio: F) -> Vec<I2cTransaction>
+where
+    F: FnMut(u8, bool) -> Vec<u8>,
+{
+    vec![
+        I2cTransaction::write(address, gpio(nibble & 0x0F, true)),
+        I2cTransaction::write(address, gpio(nibble & 0x0F, false)),
+    ]
+}
+
+/// The expected transactions for writing a byte to an HD44780 controller through a 4-bit adapter, high nibble
+/// first. See `hd44780_nibble_transactions()` for the `gpio` function.
+pub fn hd44780_byte_transactions<F>(address: u8, value: u8, mut gpio: F) -> Vec<I2cTransaction>
+where
+    F: FnMut(u8, bool) -> Vec<u8>,
+{
+    let mut transactions = hd44780_nibble_transactions(address, value >> 4, &mut gpio);
+    transactions.extend(hd44780_nibble_transactions(
+        address,
+        value & 0x0F,
+        &mut gpio,
+    ));
+    transactions
+}
+
+/// The expected transactions for writing a sequence of bytes to an HD44780 controller through a 4-bit adapter.
+/// See `hd44780_nibble_transactions()` for the `gpio` function.
+pub fn hd44780_bytes_transactions<F>(address: u8, values: &[u8], mut gpio: F) -> Vec<I2cTransaction>
+where
+    F: FnMut(u8, bool) -> Vec<u8>,
+{
+    values
+        .iter()
+        .flat_map(|value| hd44780_byte_transactions(address, *value, &mut gpio))
+        .collect()
+}
+
+/// The expected transaction for a packed write of a command followed by data bytes to an HD44780 controller
+/// through an adapter that supports packed writes, such as when uploading a custom character. The GPIO updates for
+/// all the bytes are sent in one I2C write. `command_gpio` and `data_gpio` are the `gpio` functions (see
+/// `hd44780_nibble_transactions()`) for the command and the data bytes respectively.
+pub fn hd44780_packed_transaction<C, D>(
+    address: u8,
+    command: u8,
+    data: &[u8],
+    mut command_gpio: C,
+    mut data_gpio: D,
+) -> I2cTransaction
+where
+    C: FnMut(u8, bool) -> Vec<u8>,
+    D: FnMut(u8, bool) -> Vec<u8>,
+{
+    let mut bytes = Vec::new();
+    for nibble in [command >> 4, command & 0x0F] {
+        bytes.extend(command_gpio(nibble, true));
+        bytes.extend(command_gpio(nibble, false));
+    }
+    for value in data {
+        for nibble in [value >> 4, value & 0x0F] {
+            bytes.extend(data_gpio(nibble, true));
+            bytes.extend(data_gpio(nibble, false));
+        }
+    }
+    I2cTransaction::write(address, bytes)
+}
+
+/// The expected transactions for packed writes of data bytes to an HD44780 controller through an adapter that
+/// supports packed writes, such as when printing text with a PCF8574T adapter. The GPIO updates are sent in as few
+/// I2C writes as the adapter's packed write buffer allows. See `hd44780_nibble_transactions()` for the `gpio`
+/// function.
+pub fn hd44780_packed_data_transactions<F>(
+    address: u8,
+    data: &[u8],
+    mut gpio: F,
+) -> Vec<I2cTransaction>
+where
+    F: FnMut(u8, bool) -> Vec<u8>,
+{
+    data.chunks(PACKED_WRITE_BUFFER_SIZE / 4)
+        .map(|chunk| {
+            let mut bytes = Vec::new();
+            for value in chunk {
+                for nibble in [value >> 4, value & 0x0F] {
+                    bytes.extend(gpio(nibble, true));
+                    bytes.extend(gpio(nibble, false));
+                }
+            }
+            I2cTransaction::write(address, bytes)
+        })
+        .collect()
+}
+
+/// A `gpio` function for the common PCF8574T adapter pinout: RS on P0, RW on P1, enable on P2, backlight on P3 and
+/// the data nibble on P4-P7. `rs` is `false` for commands and `true` for data.
+pub fn pcf8574_gpio(rs: bool, backlight: bool) -> impl FnMut(u8, bool) -> Vec<u8> {
+    move |nibble, enable| {
+        vec![(nibble << 4) | (backlight as u8) << 3 | (enable as u8) << 2 | rs as u8]
+    }
+}
+
+/// The expected transaction for sending a command to an AIP31068 controller.
+pub fn aip31068_command_transaction(address: u8, command: u8) -> I2cTransaction {
+    I2cTransaction::write(address, vec![0b0000_0000, command])
+}
+
+/// The expected transaction for writing data to an AIP31068 controller.
+pub fn aip31068_data_transaction(address: u8, data: &[u8]) -> I2cTransaction {
+    let mut bytes = vec![0b0100_0000];
+    bytes.extend_from_slice(data);
+    I2cTransaction::write(address, bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{CharacterDisplayAIP31068, CharacterDisplayPCF8574T, LcdDisplayType};
+
+    #[test]
+    fn test_hd44780_byte_transactions() {
+        // commands are not packed
+        let mut i2c = I2cMock::new(&hd44780_bytes_transactions(
+            0x27,
+            &[0x80 | 0x41],
+            pcf8574_gpio(false, false),
+        ));
+        let mut lcd =
+            CharacterDisplayPCF8574T::new(i2c.clone(), LcdDisplayType::Lcd16x2, NoopDelay::new());
+        assert!(lcd.set_cursor(1, 1).is_ok());
+        i2c.done();
+    }
+
+    #[test]
+    fn test_hd44780_packed_data_transactions() {
+        let expected =
+            hd44780_packed_data_transactions(0x27, b"hello world", pcf8574_gpio(true, false));
+        // 9 bytes fit in one packed write
+        assert_eq!(expected.len(), 2);
+        let mut i2c = I2cMock::new(&expected);
+        let mut lcd =
+            CharacterDisplayPCF8574T::new(i2c.clone(), LcdDisplayType::Lcd16x2, NoopDelay::new());
+        assert!(lcd.print("hello world").is_ok());
+        i2c.done();
+    }
+
+    #[test]
+    fn test_aip31068_transactions() {
+        let mut i2c = I2cMock::new(&[
+            aip31068_command_transaction(0x3e, 0x80 | 0x41),
+            aip31068_data_transaction(0x3e, b"hi"),
+        ]);
+        let mut lcd =
+            CharacterDisplayAIP31068::new(i2c.clone(), LcdDisplayType::Lcd16x2, NoopDelay::new());
+        assert!(lcd.set_cursor(1, 1).is_ok());
+        assert!(lcd.print("hi").is_ok());
+        i2c.done();
+    }
+}
@@ -0,0 +1,238 @@
+//! Several displays of the same type, each at its own I2C address or on its own bus, combined into one larger
+//! logical display, such as two 20x4 displays side by side shown as one 40x4 grid. A [`TiledDisplay`] routes the
+//! cursor and printed text to the display showing each position, so text printed across the edge of one display
+//! continues on the display next to it.
+//!
+//! ```rust
+//! use i2c_character_display::{tiled::TiledDisplay, CharacterDisplayAIP31068, LcdDisplayType};
+//!
+//! let left = CharacterDisplayAIP31068::new_with_address(left_i2c, 0x3e, LcdDisplayType::Lcd20x4, left_delay);
+//! let right = CharacterDisplayAIP31068::new_with_address(right_i2c, 0x3e, LcdDisplayType::Lcd20x4, right_delay);
+//! let mut tiled = TiledDisplay::new([left, right], 2)?;
+//! tiled.init()?;
+//! tiled.print_at(14, 0, "spans both displays")?;
+//! ```
+
+use embedded_hal::{delay::DelayNs, i2c};
+
+use crate::{driver::DriverTrait, BaseCharacterDisplay, CharacterDisplayError};
+
+/// `N` displays of the same type laid out in a grid of `columns` displays per grid row, filled left to right and
+/// then top to bottom, and used as one display of `columns` times the width and `N / columns` times the height.
+/// Characters printed past the end of a row of the grid are dropped.
+pub struct TiledDisplay<I2C, DELAY, DEVICE, const N: usize>
+where
+    I2C: i2c::I2c,
+    DELAY: DelayNs,
+    DEVICE: DriverTrait<I2C, DELAY>,
+{
+    tiles: [BaseCharacterDisplay<I2C, DELAY, DEVICE>; N],
+    columns: usize,
+    /// The cursor position in the grid, where a column past the last one means the end of the row was reached
+    cursor: (u8, u8),
+}
+
+impl<I2C, DELAY, DEVICE, const N: usize> TiledDisplay<I2C, DELAY, DEVICE, N>
+where
+    I2C: i2c::I2c,
+    DELAY: DelayNs,
+    DEVICE: DriverTrait<I2C, DELAY>,
+{
+    /// Combine `tiles` into one display with `columns` displays per grid row. Returns
+    /// `CharacterDisplayError::UnsupportedDisplayType` when the displays are not all of the same type, when `N` is
+    /// not a multiple of `columns`, or when the grid is more than 255 characters wide or high.
+    pub fn new(
+        tiles: [BaseCharacterDisplay<I2C, DELAY, DEVICE>; N],
+        columns: usize,
+    ) -> Result<Self, CharacterDisplayError<I2C>> {
+        let Some(first) = tiles.first() else {
+            return Err(CharacterDisplayError::UnsupportedDisplayType);
+        };
+        if columns == 0
+            || N % columns != 0
+            || tiles
+                .iter()
+                .any(|tile| tile.display_type() != first.display_type())
+            || first.cols() as usize * columns > u8::MAX as usize
+            || first.rows() as usize * (N / columns) > u8::MAX as usize
+        {
+            return Err(CharacterDisplayError::UnsupportedDisplayType);
+        }
+        Ok(Self {
+            tiles,
+            columns,
+            cursor: (0, 0),
+        })
+    }
+
+    /// Initialize all the displays and move the cursor to the top left corner of the grid.
+    pub fn init(&mut self) -> Result<(), CharacterDisplayError<I2C>> {
+        for tile in self.tiles.iter_mut() {
+            tile.init()?;
+        }
+        self.cursor = (0, 0);
+        Ok(())
+    }
+
+    /// Returns the number of columns of the grid.
+    pub fn cols(&self) -> u8 {
+        self.tiles[0].cols() * self.columns as u8
+    }
+
+    /// Returns the number of rows of the grid.
+    pub fn rows(&self) -> u8 {
+        self.tiles[0].rows() * (N / self.columns) as u8
+    }
+
+    /// Returns the display at `index`, counted left to right and then top to bottom, for operations on one display
+    /// such as turning its backlight on or off.
+    pub fn tile(&mut self, index: usize) -> Option<&mut BaseCharacterDisplay<I2C, DELAY, DEVICE>> {
+        self.tiles.get_mut(index)
+    }
+
+    /// Returns the displays, to use them on their own again.
+    pub fn into_tiles(self) -> [BaseCharacterDisplay<I2C, DELAY, DEVICE>; N] {
+        self.tiles
+    }
+
+    /// Clear all the displays and move the cursor to the top left corner of the grid.
+    pub fn clear(&mut self) -> Result<&mut Self, CharacterDisplayError<I2C>> {
+        for tile in self.tiles.iter_mut() {
+            tile.clear()?;
+        }
+        self.cursor = (0, 0);
+        Ok(self)
+    }
+
+    /// Set the cursor position in the grid. Columns and rows are zero-indexed. Nothing is written to the displays
+    /// until the next print.
+    pub fn set_cursor(
+        &mut self,
+        col: u8,
+        row: u8,
+    ) -> Result<&mut Self, CharacterDisplayError<I2C>> {
+        if row >= self.rows() {
+            return Err(CharacterDisplayError::RowOutOfRange { row });
+        }
+        if col >= self.cols() {
+            return Err(CharacterDisplayError::ColumnOutOfRange { col });
+        }
+        self.cursor = (col, row);
+        Ok(self)
+    }
+
+    /// Returns the cursor position in the grid as `( col, row )`. The column is `cols()` once the end of the row
+    /// has been printed to.
+    pub fn cursor(&self) -> (u8, u8) {
+        self.cursor
+    }
+
+    /// Print a string at the cursor position, continuing on the next display of the grid row where the text
+    /// crosses the edge of a display.
+    pub fn print(&mut self, text: &str) -> Result<&mut Self, CharacterDisplayError<I2C>> {
+        let tile_cols = self.tiles[0].cols();
+        let tile_rows = self.tiles[0].rows();
+        let (mut col, row) = self.cursor;
+        let mut remaining = text;
+        while !remaining.is_empty() && col < self.cols() {
+            let index = (row / tile_rows) as usize * self.columns + (col / tile_cols) as usize;
+            let tile_col = col % tile_cols;
+            let (part, rest) = match remaining
+                .char_indices()
+                .nth((tile_cols - tile_col) as usize)
+            {
+                Some((end, _)) => remaining.split_at(end),
+                None => (remaining, ""),
+            };
+            self.tiles[index].print_at(tile_col, row % tile_rows, part)?;
+            col += part.chars().count() as u8;
+            self.cursor = (col, row);
+            remaining = rest;
+        }
+        Ok(self)
+    }
+
+    /// Print a string starting at the specified column and row of the grid.
+    pub fn print_at(
+        &mut self,
+        col: u8,
+        row: u8,
+        text: &str,
+    ) -> Result<&mut Self, CharacterDisplayError<I2C>> {
+        self.set_cursor(col, row)?;
+        self.print(text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+    use super::*;
+    use crate::{
+        testing::{aip31068_command_transaction, aip31068_data_transaction},
+        CharacterDisplayAIP31068, LcdDisplayType,
+    };
+    use embedded_hal_mock::eh1::{delay::NoopDelay, i2c::Mock as I2cMock};
+
+    #[test]
+    fn test_tiled_display() {
+        let (left, right) = (0x3e_u8, 0x3f_u8);
+        let expected_i2c_transactions = std::vec![
+            aip31068_command_transaction(left, 0x80 | 0x4E),
+            aip31068_data_transaction(left, b"ab"),
+            // the text continues on the display to the right
+            aip31068_command_transaction(right, 0x80 | 0x40),
+            aip31068_data_transaction(right, b"cd"),
+            aip31068_data_transaction(right, b"e"),
+            // the text past the end of the grid row is dropped
+            aip31068_command_transaction(right, 0x80 | 0x0E),
+            aip31068_data_transaction(right, b"12"),
+        ];
+
+        // the clones of the mock share its expectations
+        let mut i2c = I2cMock::new(&expected_i2c_transactions);
+        let tiles = [left, right].map(|address| {
+            CharacterDisplayAIP31068::new_with_address(
+                i2c.clone(),
+                address,
+                LcdDisplayType::Lcd16x2,
+                NoopDelay::new(),
+            )
+        });
+        let mut tiled = TiledDisplay::new(tiles, 2).unwrap();
+        assert_eq!((tiled.cols(), tiled.rows()), (32, 2));
+        assert!(tiled.print_at(14, 1, "abcd").is_ok());
+        assert!(tiled.print("e").is_ok());
+        assert_eq!(tiled.cursor(), (19, 1));
+        assert!(tiled.print_at(30, 0, "123").is_ok());
+        assert_eq!(tiled.cursor(), (32, 0));
+        assert!(tiled.print("4").is_ok());
+        assert!(matches!(
+            tiled.set_cursor(32, 0),
+            Err(CharacterDisplayError::ColumnOutOfRange { .. })
+        ));
+        i2c.done();
+    }
+
+    #[test]
+    fn test_tiled_display_layout() {
+        let mut i2c = I2cMock::new(&[]);
+        let tiles = [LcdDisplayType::Lcd16x2, LcdDisplayType::Lcd20x2]
+            .map(|lcd_type| CharacterDisplayAIP31068::new(i2c.clone(), lcd_type, NoopDelay::new()));
+        assert!(matches!(
+            TiledDisplay::new(tiles, 2),
+            Err(CharacterDisplayError::UnsupportedDisplayType)
+        ));
+        let tiles = [LcdDisplayType::Lcd16x2; 3]
+            .map(|lcd_type| CharacterDisplayAIP31068::new(i2c.clone(), lcd_type, NoopDelay::new()));
+        assert!(matches!(
+            TiledDisplay::new(tiles, 2),
+            Err(CharacterDisplayError::UnsupportedDisplayType)
+        ));
+        let tiles = [LcdDisplayType::Lcd16x2; 4]
+            .map(|lcd_type| CharacterDisplayAIP31068::new(i2c.clone(), lcd_type, NoopDelay::new()));
+        let tiled = TiledDisplay::new(tiles, 2).unwrap();
+        assert_eq!((tiled.cols(), tiled.rows()), (32, 4));
+        i2c.done();
+    }
+}
@@ -0,0 +1,277 @@
+//! A rectangular region of the display, such as a 10x2 status box on a 20x4 display, that is printed to and
+//! scrolled on its own. A [`Window`] keeps its own cursor and a copy of its contents, wraps printed text at its
+//! right edge, and scrolls its contents up when text runs past its last row, leaving the rest of the display
+//! untouched. Several windows can be used on the same display to manage separate areas of a user interface.
+//!
+//! ```rust
+//! use i2c_character_display::window::Window;
+//!
+//! let mut log = Window::<20, 3>::new(0, 1);
+//! let mut status = Window::<8, 1>::new(12, 0);
+//! status.print(&mut lcd, "RUNNING")?;
+//! log.print(&mut lcd, "started\n")?;
+//! log.print(&mut lcd, "sensor found\n")?;
+//! ```
+
+use embedded_hal::{delay::DelayNs, i2c};
+
+use crate::{driver::DriverTrait, BaseCharacterDisplay, CharacterDisplayError};
+
+/// The widest window supported, which is the width of the widest display.
+const MAX_WINDOW_WIDTH: usize = 40;
+
+/// A window of `WIDTH` columns and `HEIGHT` rows whose top left corner is at a column and row of the display.
+/// Printed text wraps to the next row of the window at its right edge, and a newline (`'\n'`) starts the next row.
+/// Text past the last row scrolls the contents of the window up by one row.
+#[derive(Debug, Clone, Copy)]
+pub struct Window<const WIDTH: usize, const HEIGHT: usize> {
+    col: u8,
+    row: u8,
+    contents: [[char; WIDTH]; HEIGHT],
+    /// The cursor position in the window, where a column of `WIDTH` means the row is full and the next character
+    /// wraps to the next row
+    cursor: (u8, u8),
+}
+
+impl<const WIDTH: usize, const HEIGHT: usize> Window<WIDTH, HEIGHT> {
+    /// Create a window with its top left corner at `col` and `row` of the display. The window starts out blank,
+    /// with its cursor in its top left corner.
+    pub const fn new(col: u8, row: u8) -> Self {
+        Self {
+            col,
+            row,
+            contents: [[' '; WIDTH]; HEIGHT],
+            cursor: (0, 0),
+        }
+    }
+
+    /// Returns the cursor position in the window as `( col, row )`. The column is `WIDTH` once the end of the row
+    /// has been printed to.
+    pub fn cursor(&self) -> (u8, u8) {
+        self.cursor
+    }
+
+    /// Returns the contents of a row of the window, or `None` when `row` is outside the window.
+    pub fn row_contents(&self, row: u8) -> Option<&[char; WIDTH]> {
+        self.contents.get(row as usize)
+    }
+
+    /// Set the cursor position in the window. Columns and rows are zero-indexed and relative to the top left corner
+    /// of the window, and positions outside the window are moved to its nearest edge. Nothing is written to the
+    /// display until the next print.
+    pub fn set_cursor(&mut self, col: u8, row: u8) -> &mut Self {
+        let col = (col as usize).min(WIDTH.saturating_sub(1));
+        let row = (row as usize).min(HEIGHT.saturating_sub(1));
+        self.cursor = (col as u8, row as u8);
+        self
+    }
+
+    /// Blank the window on the display and move its cursor to its top left corner.
+    pub fn clear<I2C, DELAY, DEVICE>(
+        &mut self,
+        lcd: &mut BaseCharacterDisplay<I2C, DELAY, DEVICE>,
+    ) -> Result<&mut Self, CharacterDisplayError<I2C>>
+    where
+        I2C: i2c::I2c,
+        DELAY: DelayNs,
+        DEVICE: DriverTrait<I2C, DELAY>,
+    {
+        self.contents = [[' '; WIDTH]; HEIGHT];
+        self.cursor = (0, 0);
+        self.redraw(lcd)?;
+        Ok(self)
+    }
+
+    /// Write the contents of the window to the display, such as after the display was cleared or initialized.
+    pub fn redraw<I2C, DELAY, DEVICE>(
+        &self,
+        lcd: &mut BaseCharacterDisplay<I2C, DELAY, DEVICE>,
+    ) -> Result<(), CharacterDisplayError<I2C>>
+    where
+        I2C: i2c::I2c,
+        DELAY: DelayNs,
+        DEVICE: DriverTrait<I2C, DELAY>,
+    {
+        self.check_bounds(lcd)?;
+        // each character of a row is up to 4 bytes of UTF-8
+        let mut buffer = [0u8; MAX_WINDOW_WIDTH * 4];
+        for (row, contents) in self.contents.iter().enumerate() {
+            let len = contents
+                .iter()
+                .fold(0, |len, c| len + c.encode_utf8(&mut buffer[len..]).len());
+            self.draw(lcd, 0, row as u8, &buffer[..len])?;
+        }
+        Ok(())
+    }
+
+    /// Scroll the contents of the window up by one row, blanking its last row, and move its cursor to the start of
+    /// the last row.
+    pub fn scroll_up<I2C, DELAY, DEVICE>(
+        &mut self,
+        lcd: &mut BaseCharacterDisplay<I2C, DELAY, DEVICE>,
+    ) -> Result<&mut Self, CharacterDisplayError<I2C>>
+    where
+        I2C: i2c::I2c,
+        DELAY: DelayNs,
+        DEVICE: DriverTrait<I2C, DELAY>,
+    {
+        if HEIGHT > 0 {
+            self.contents.copy_within(1.., 0);
+            self.contents[HEIGHT - 1] = [' '; WIDTH];
+            self.cursor = (0, HEIGHT as u8 - 1);
+        }
+        self.redraw(lcd)?;
+        Ok(self)
+    }
+
+    /// Print a string at the cursor position of the window. Text wraps to the next row at the right edge of the
+    /// window, `'\n'` moves the cursor to the start of the next row, and `'\r'` moves it to the start of the current
+    /// row. Moving past the last row scrolls the window up.
+    pub fn print<I2C, DELAY, DEVICE>(
+        &mut self,
+        lcd: &mut BaseCharacterDisplay<I2C, DELAY, DEVICE>,
+        text: &str,
+    ) -> Result<&mut Self, CharacterDisplayError<I2C>>
+    where
+        I2C: i2c::I2c,
+        DELAY: DelayNs,
+        DEVICE: DriverTrait<I2C, DELAY>,
+    {
+        self.check_bounds(lcd)?;
+        // the characters printed on the current row of the window that are not yet written to the display
+        let mut segment = [0u8; MAX_WINDOW_WIDTH * 4];
+        let mut len = 0;
+        let mut start = self.cursor.0;
+        for c in text.chars() {
+            if c == '\n' || c == '\r' || (self.cursor.0 as usize == WIDTH) {
+                self.draw(lcd, start, self.cursor.1, &segment[..len])?;
+                len = 0;
+                if c == '\r' {
+                    self.cursor.0 = 0;
+                } else {
+                    self.new_line(lcd)?;
+                }
+                start = self.cursor.0;
+                if c == '\n' || c == '\r' {
+                    continue;
+                }
+            }
+            self.contents[self.cursor.1 as usize][self.cursor.0 as usize] = c;
+            len += c.encode_utf8(&mut segment[len..]).len();
+            self.cursor.0 += 1;
+        }
+        self.draw(lcd, start, self.cursor.1, &segment[..len])?;
+        Ok(self)
+    }
+
+    /// Move the cursor to the start of the next row, scrolling the window up when it is on the last row.
+    fn new_line<I2C, DELAY, DEVICE>(
+        &mut self,
+        lcd: &mut BaseCharacterDisplay<I2C, DELAY, DEVICE>,
+    ) -> Result<(), CharacterDisplayError<I2C>>
+    where
+        I2C: i2c::I2c,
+        DELAY: DelayNs,
+        DEVICE: DriverTrait<I2C, DELAY>,
+    {
+        if self.cursor.1 as usize + 1 < HEIGHT {
+            self.cursor = (0, self.cursor.1 + 1);
+        } else {
+            self.scroll_up(lcd)?;
+        }
+        Ok(())
+    }
+
+    /// Write UTF-8 encoded characters starting at `col` and `row` of the window, if there are any.
+    fn draw<I2C, DELAY, DEVICE>(
+        &self,
+        lcd: &mut BaseCharacterDisplay<I2C, DELAY, DEVICE>,
+        col: u8,
+        row: u8,
+        text: &[u8],
+    ) -> Result<(), CharacterDisplayError<I2C>>
+    where
+        I2C: i2c::I2c,
+        DELAY: DelayNs,
+        DEVICE: DriverTrait<I2C, DELAY>,
+    {
+        if text.is_empty() {
+            return Ok(());
+        }
+        let text = core::str::from_utf8(text).map_err(|_| core::fmt::Error)?;
+        lcd.print_at(self.col + col, self.row + row, text)?;
+        Ok(())
+    }
+
+    /// Check that the window fits on the display.
+    fn check_bounds<I2C, DELAY, DEVICE>(
+        &self,
+        lcd: &BaseCharacterDisplay<I2C, DELAY, DEVICE>,
+    ) -> Result<(), CharacterDisplayError<I2C>>
+    where
+        I2C: i2c::I2c,
+        DELAY: DelayNs,
+        DEVICE: DriverTrait<I2C, DELAY>,
+    {
+        if HEIGHT == 0 || self.row as usize + HEIGHT > lcd.rows() as usize {
+            return Err(CharacterDisplayError::RowOutOfRange { row: self.row });
+        }
+        if WIDTH == 0 || WIDTH > MAX_WINDOW_WIDTH || self.col as usize + WIDTH > lcd.cols() as usize
+        {
+            return Err(CharacterDisplayError::ColumnOutOfRange { col: self.col });
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+    use super::*;
+    use crate::{
+        testing::{aip31068_command_transaction, aip31068_data_transaction},
+        CharacterDisplayAIP31068, LcdDisplayType,
+    };
+    use embedded_hal_mock::eh1::{delay::NoopDelay, i2c::Mock as I2cMock};
+
+    #[test]
+    fn test_window_print_and_scroll() {
+        let i2c_address = 0x3e;
+        let expected_i2c_transactions = std::vec![
+            // the text wraps at the right edge of the 4x2 window at column 10
+            aip31068_command_transaction(i2c_address, 0x80 | 0x0A),
+            aip31068_data_transaction(i2c_address, b"abcd"),
+            aip31068_command_transaction(i2c_address, 0x80 | 0x4A),
+            aip31068_data_transaction(i2c_address, b"ef"),
+            // a newline on the last row scrolls the window up
+            aip31068_command_transaction(i2c_address, 0x80 | 0x0A),
+            aip31068_data_transaction(i2c_address, b"ef  "),
+            aip31068_command_transaction(i2c_address, 0x80 | 0x4A),
+            aip31068_data_transaction(i2c_address, b"    "),
+            aip31068_command_transaction(i2c_address, 0x80 | 0x4A),
+            aip31068_data_transaction(i2c_address, b"xy"),
+            aip31068_command_transaction(i2c_address, 0x80 | 0x0A),
+            aip31068_data_transaction(i2c_address, b"g"),
+        ];
+
+        let i2c = I2cMock::new(&expected_i2c_transactions);
+        let mut lcd = CharacterDisplayAIP31068::new(i2c, LcdDisplayType::Lcd16x2, NoopDelay::new());
+        let mut window = Window::<4, 2>::new(10, 0);
+        assert!(window.print(&mut lcd, "abcdef").is_ok());
+        assert_eq!(window.cursor(), (2, 1));
+        assert!(window.print(&mut lcd, "\nxy").is_ok());
+        assert_eq!(window.row_contents(0), Some(&['e', 'f', ' ', ' ']));
+        assert_eq!(window.row_contents(1), Some(&['x', 'y', ' ', ' ']));
+        window.set_cursor(2, 0);
+        assert!(window.print(&mut lcd, "\rg").is_ok());
+        assert_eq!(window.set_cursor(9, 9).cursor(), (3, 1));
+
+        // a window that does not fit on the display is rejected
+        let mut window = Window::<4, 2>::new(13, 0);
+        assert!(matches!(
+            window.print(&mut lcd, "a"),
+            Err(CharacterDisplayError::ColumnOutOfRange { .. })
+        ));
+        lcd.i2c().done();
+    }
+}
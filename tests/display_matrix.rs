@@ -0,0 +1,314 @@
+//! Runs the common high-level operations (init, print and clear) on every public display type against an
+//! `embedded-hal-mock` I2C mock, so a new adapter or controller cannot break the surface shared by all displays.
+//! Requires the `testing` feature: `cargo test --features testing`.
+
+use i2c_character_display::{
+    drivers::GenericHD44780PCF8574T,
+    testing::{
+        aip31068_command_transaction, aip31068_data_transaction, hd44780_bytes_transactions,
+        hd44780_nibble_transactions, hd44780_packed_data_transactions, pcf8574_gpio, I2cMock,
+        I2cTransaction, NoopDelay,
+    },
+    AdafruitLCDBackpack, BaseCharacterDisplay, CharacterDisplayAIP31068,
+    CharacterDisplayAIP31068RGB, CharacterDisplayDualHD44780, CharacterDisplayMCP23017,
+    CharacterDisplayMultiHD44780, CharacterDisplayPCF2116, CharacterDisplayPCF2119,
+    CharacterDisplayPCF2119R, CharacterDisplayPCF8574T, CharacterDisplayPCF8574TContrast,
+    CharacterDisplayUS2066, LcdDisplayType,
+};
+
+/// The text printed on every display
+const TEXT: &[u8] = b"Hi";
+
+/// Runs init, print and clear on a display created by `$new` from an I2C mock expecting `$expected`.
+macro_rules! check_display {
+    ($new:expr, $expected:expr) => {{
+        let mut i2c = I2cMock::new(&$expected);
+        let mut lcd = $new(i2c.clone());
+        assert!(lcd.init().is_ok());
+        assert!(lcd.print("Hi").is_ok());
+        assert!(lcd.clear().is_ok());
+        i2c.done();
+    }};
+}
+
+/// The expected transactions for init, print and clear on an HD44780 display with `controllers` controllers.
+/// `gpio(controller, rs, backlight)` returns the `gpio` function (see `hd44780_nibble_transactions()`) for
+/// writing to a controller. `packed` is whether the adapter supports packed writes, which are used for the text.
+fn hd44780_transactions<G, F>(
+    address: u8,
+    controllers: usize,
+    packed: bool,
+    mut gpio: G,
+) -> Vec<I2cTransaction>
+where
+    G: FnMut(usize, bool, bool) -> F,
+    F: FnMut(u8, bool) -> Vec<u8>,
+{
+    let mut transactions = Vec::new();
+    for controller in 0..controllers {
+        // switch to 4 bit mode, then function set, display on, entry mode, clear and home
+        for nibble in [0x03, 0x03, 0x03, 0x02] {
+            transactions.extend(hd44780_nibble_transactions(
+                address,
+                nibble,
+                gpio(controller, false, false),
+            ));
+        }
+        transactions.extend(hd44780_bytes_transactions(
+            address,
+            &[0x28, 0x0C, 0x06, 0x01, 0x02],
+            gpio(controller, false, false),
+        ));
+    }
+    // backlight on, keeping the last nibble written
+    let backlight_gpio = gpio(0, false, true)(0x02, false);
+    transactions.push(I2cTransaction::write(address, backlight_gpio));
+    if packed {
+        transactions.extend(hd44780_packed_data_transactions(
+            address,
+            TEXT,
+            gpio(0, true, true),
+        ));
+    } else {
+        transactions.extend(hd44780_bytes_transactions(
+            address,
+            TEXT,
+            gpio(0, true, true),
+        ));
+    }
+    for controller in 0..controllers {
+        transactions.extend(hd44780_bytes_transactions(
+            address,
+            &[0x01],
+            gpio(controller, false, true),
+        ));
+    }
+    transactions
+}
+
+/// Names the full display type, as user code returning a display from a function does.
+fn create_pcf8574t(
+    i2c: I2cMock,
+) -> BaseCharacterDisplay<I2cMock, NoopDelay, GenericHD44780PCF8574T<I2cMock>> {
+    CharacterDisplayPCF8574T::new(i2c, LcdDisplayType::Lcd16x2, NoopDelay::new())
+}
+
+#[test]
+fn test_pcf8574t() {
+    let expected = hd44780_transactions(0x27, 1, true, |_, rs, backlight| {
+        pcf8574_gpio(rs, backlight)
+    });
+    check_display!(create_pcf8574t, expected);
+}
+
+#[test]
+fn test_pcf8574t_contrast() {
+    let expected = hd44780_transactions(0x27, 1, true, |_, rs, backlight| {
+        pcf8574_gpio(rs, backlight)
+    });
+    check_display!(
+        |i2c| CharacterDisplayPCF8574TContrast::new(i2c, LcdDisplayType::Lcd16x2, NoopDelay::new()),
+        expected
+    );
+}
+
+#[test]
+fn test_adafruit_lcd_backpack() {
+    // MCP23008 IODIR set to output, then writes to the GPIO register
+    let mut expected = vec![I2cTransaction::write(0x20, vec![0x00, 0x00])];
+    expected.extend(hd44780_transactions(0x20, 1, false, |_, rs, backlight| {
+        move |nibble: u8, enable: bool| {
+            vec![
+                0x09,
+                (backlight as u8) << 7 | nibble << 3 | (enable as u8) << 2 | (rs as u8) << 1,
+            ]
+        }
+    }));
+    check_display!(
+        |i2c| AdafruitLCDBackpack::new(i2c, LcdDisplayType::Lcd16x2, NoopDelay::new()),
+        expected
+    );
+}
+
+#[test]
+fn test_mcp23017() {
+    // both ports set to outputs, then each byte is written in one enable pulse over the 8-bit interface
+    let address = 0x20;
+    let byte = |rs: bool, backlight: bool, value: u8| {
+        let control = (backlight as u8) << 3 | rs as u8;
+        [
+            I2cTransaction::write(address, vec![0x12, value, control | 0b100]),
+            I2cTransaction::write(address, vec![0x12, value, control]),
+        ]
+    };
+    let mut expected = vec![I2cTransaction::write(address, vec![0x00, 0x00, 0x00])];
+    for value in [0x30, 0x30, 0x30, 0x38, 0x0C, 0x06, 0x01, 0x02] {
+        expected.extend(byte(false, false, value));
+    }
+    expected.push(I2cTransaction::write(address, vec![0x12, 0x02, 0b1000]));
+    for value in TEXT {
+        expected.extend(byte(true, true, *value));
+    }
+    expected.extend(byte(false, true, 0x01));
+    check_display!(
+        |i2c| CharacterDisplayMCP23017::new(i2c, LcdDisplayType::Lcd16x2, NoopDelay::new()),
+        expected
+    );
+}
+
+#[test]
+fn test_dual_hd44780() {
+    // the first controller's enable pin is P2 and the second's is P1
+    let expected = hd44780_transactions(0x27, 2, true, |controller, rs, backlight| {
+        move |nibble: u8, enable: bool| {
+            let enable_bit = if controller == 0 { 2 } else { 1 };
+            vec![nibble << 4 | (backlight as u8) << 3 | (enable as u8) << enable_bit | rs as u8]
+        }
+    });
+    check_display!(
+        |i2c| CharacterDisplayDualHD44780::new(i2c, LcdDisplayType::Lcd40x4, NoopDelay::new()),
+        expected
+    );
+}
+
+#[test]
+fn test_multi_hd44780() {
+    // each controller's enable pin is on the second port, starting at P10
+    let expected = hd44780_transactions(0x20, 4, false, |controller, rs, backlight| {
+        move |nibble: u8, enable: bool| {
+            vec![
+                nibble << 4 | (backlight as u8) << 3 | rs as u8,
+                (enable as u8) << controller,
+            ]
+        }
+    });
+    check_display!(
+        |i2c| CharacterDisplayMultiHD44780::<_, _, 4>::new(
+            i2c,
+            LcdDisplayType::Lcd40x8,
+            NoopDelay::new()
+        ),
+        expected
+    );
+}
+
+/// The expected transactions for init, print and clear on an AIP31068 display.
+fn aip31068_transactions(address: u8) -> Vec<I2cTransaction> {
+    vec![
+        aip31068_command_transaction(address, 0x28),
+        aip31068_command_transaction(address, 0x0C),
+        aip31068_command_transaction(address, 0x01),
+        aip31068_command_transaction(address, 0x06),
+        aip31068_data_transaction(address, TEXT),
+        aip31068_command_transaction(address, 0x01),
+    ]
+}
+
+#[test]
+fn test_aip31068() {
+    check_display!(
+        |i2c| CharacterDisplayAIP31068::new(i2c, LcdDisplayType::Lcd16x2, NoopDelay::new()),
+        aip31068_transactions(0x3e)
+    );
+}
+
+#[test]
+fn test_aip31068_rgb() {
+    let mut expected = aip31068_transactions(0x3e);
+    // the PCA9633 backlight controller is set up to show white after the AIP31068 is initialized
+    expected.splice(
+        4..4,
+        [
+            I2cTransaction::write(0x62, vec![0x80, 0x00, 0x20]),
+            I2cTransaction::write(0x62, vec![0x88, 0xAA]),
+            I2cTransaction::write(0x62, vec![0x82, 0xFF, 0xFF, 0xFF]),
+        ],
+    );
+    check_display!(
+        |i2c| CharacterDisplayAIP31068RGB::new(i2c, LcdDisplayType::Lcd16x2, NoopDelay::new()),
+        expected
+    );
+}
+
+#[test]
+fn test_us2066() {
+    let address = 0x3c;
+    let command = |commands: &[u8]| {
+        let mut bytes = vec![0x00];
+        bytes.extend_from_slice(commands);
+        I2cTransaction::write(address, bytes)
+    };
+    let data = |data: &[u8]| {
+        let mut bytes = vec![0x40];
+        bytes.extend_from_slice(data);
+        I2cTransaction::write(address, bytes)
+    };
+    let expected = vec![
+        command(&[0x2A, 0x71]),
+        data(&[0x5C]),
+        command(&[0x28, 0x08]),
+        command(&[0x2A, 0x79, 0xD5, 0x70, 0x78, 0x08, 0x06, 0x72]),
+        data(&[0x00]),
+        command(&[
+            0x2A, 0x79, 0xDA, 0x10, 0xDC, 0x00, 0x81, 0x7F, 0xD9, 0xF1, 0xDB, 0x40, 0x78, 0x28,
+        ]),
+        command(&[0x01]),
+        command(&[0x80, 0x06, 0x0C]),
+        data(TEXT),
+        command(&[0x01]),
+    ];
+    check_display!(
+        |i2c| CharacterDisplayUS2066::new(i2c, LcdDisplayType::Lcd16x2, NoopDelay::new()),
+        expected
+    );
+}
+
+#[test]
+fn test_pcf211x() {
+    let address = 0x3a;
+    let command = |commands: &[u8]| {
+        let mut bytes = vec![0x00];
+        bytes.extend_from_slice(commands);
+        I2cTransaction::write(address, bytes)
+    };
+    let data = |data: &[u8]| aip31068_data_transaction(address, data);
+    check_display!(
+        |i2c| CharacterDisplayPCF2116::new(i2c, LcdDisplayType::Lcd24x2, NoopDelay::new()),
+        vec![
+            command(&[0x38]),
+            command(&[0x0C, 0x06]),
+            command(&[0x01]),
+            data(TEXT),
+            command(&[0x01]),
+        ]
+    );
+    let pcf2119_init = [
+        command(&[0x34]),
+        command(&[0x35, 0x04, 0x10, 0x42, 0x9F, 0x34]),
+        command(&[0x0C, 0x06]),
+    ];
+    let mut expected = pcf2119_init.to_vec();
+    expected.extend([command(&[0x01]), data(TEXT), command(&[0x01])]);
+    check_display!(
+        |i2c| CharacterDisplayPCF2119::new(i2c, LcdDisplayType::Lcd16x2, NoopDelay::new()),
+        expected
+    );
+    // character set R has ASCII at 0xA0 and up, so clearing writes spaces
+    let clear = [
+        command(&[0x01]),
+        command(&[0x06]),
+        command(&[0x80]),
+        data(&[0xA0; 40]),
+        command(&[0xC0]),
+        data(&[0xA0; 40]),
+        command(&[0x02, 0x06]),
+    ];
+    let mut expected = pcf2119_init.to_vec();
+    expected.extend(clear.clone());
+    expected.push(data(&[0xC8, 0xE9]));
+    expected.extend(clear);
+    check_display!(
+        |i2c| CharacterDisplayPCF2119R::new(i2c, LcdDisplayType::Lcd16x2, NoopDelay::new()),
+        expected
+    );
+}